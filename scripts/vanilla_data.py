@@ -0,0 +1,174 @@
+#!/usr/bin/env python3
+"""Generates the block and item data in assets/data from the vanilla game data.
+
+The vanilla server doesn't report block properties or tags in a form that's easy to read, so this
+uses the code azalea (https://github.com/azalea-rs/azalea) generates from the vanilla server. Pass
+the directory the `azalea-block`, `azalea-physics` and `azalea-registry` crates for the game
+version of blockstates.json are extracted to, e.g. `0.13.0+mc1.21.5`:
+
+    python3 vanilla_data.py ~/.cargo/registry/src/index.crates.io-*/
+
+Recipes are copied from the vanilla server jar of the same version when it's passed with `--jar`,
+otherwise recipes.json is left as it is.
+"""
+import argparse
+import io
+import json
+import re
+import sys
+import zipfile
+from pathlib import Path
+
+OUT_PATH = Path("../assets/data")
+
+# Tags in subdirectories have their `/` turned into `_` in the constant names
+TAG_DIRECTORIES = ["mineable", "enchantable"]
+
+
+def crate_dir(root, name):
+    dirs = sorted(root.glob(f"{name}-*"))
+    if not dirs:
+        sys.exit(f"Could not find {name} in {root}")
+    return dirs[-1] / "src"
+
+
+def registry_names(source, enum):
+    """Maps the variants of a registry enum to the names of its entries."""
+    body = re.search(r"enum " + enum + r" \{(.*?)\n\}", source, re.S).group(1)
+    return dict(re.findall(r"(\w+) => \"([a-z0-9_:/.]+)\"", body))
+
+
+def tag_name(constant):
+    name = constant.lower()
+    for directory in TAG_DIRECTORIES:
+        if name.startswith(directory + "_"):
+            name = directory + "/" + name[len(directory) + 1:]
+    return "minecraft:" + name
+
+
+def tags(source, enum, names):
+    """Reads a file of tag constants, which already have nested tags resolved."""
+    out = {}
+    for constant, body in re.findall(
+        r"^pub static (\w+): LazyLock<HashSet<" + enum + r">> =(.*?)(?=^pub static|\Z)",
+        source,
+        re.S | re.M,
+    ):
+        entries = re.findall(enum + r"::(\w+)", body)
+        out[tag_name(constant)] = sorted(names[entry] for entry in entries)
+    return dict(sorted(out.items()))
+
+
+def block_properties(source):
+    blocks = source[source.index("    Blocks => {"):]
+    out = {}
+    for name, behavior in re.findall(
+        r"^        ([a-z0-9_]+) => BlockBehavior::new\(\)((?:\.\w+\([^()]*\))*), \{", blocks, re.M
+    ):
+        hardness = 0.0
+        requires_correct_tool = False
+        force_solid = None
+        for method, args in re.findall(r"\.(\w+)\(([^()]*)\)", behavior):
+            if method in ("strength", "destroy_time"):
+                hardness = float(args.split(",")[0])
+            elif method == "requires_correct_tool_for_drops":
+                requires_correct_tool = True
+            elif method == "force_solid":
+                force_solid = args == "true"
+        out["minecraft:" + name] = {
+            "hardness": hardness,
+            "requires_correct_tool": requires_correct_tool,
+        }
+        # Blocks are solid depending on their collision shape, unless the block forces it
+        if force_solid is not None:
+            out["minecraft:" + name]["force_solid"] = force_solid
+    return out
+
+
+def block_shapes(source):
+    """Reads the boxes of every distinct shape, and which shapes each block state uses."""
+    shapes = {}
+    for index, body in re.findall(
+        r"^static SHAPE(\d+): LazyLock<VoxelShape> =(.*?)(?=^static|^impl)", source, re.S | re.M
+    ):
+        shapes[int(index)] = [
+            [float(value) for value in args.split(",")]
+            for args in re.findall(r"box_shape\(([^()]*)\)", body)
+        ]
+
+    def state_shapes(table):
+        body = re.search(r"static " + table + r": \[[^\]]*\] = \[(.*?)\];", source, re.S).group(1)
+        return [int(index) for index in re.findall(r"&SHAPE(\d+)", body)]
+
+    return {
+        "shapes": [shapes[index] for index in range(len(shapes))],
+        "collision": state_shapes("COLLISION_SHAPES_MAP"),
+        "outline": state_shapes("OUTLINE_SHAPES_MAP"),
+    }
+
+
+def recipes(jar_path):
+    """Reads the recipes from a server jar, or from the jar bundled inside it since 1.18."""
+    jar = zipfile.ZipFile(jar_path)
+    bundled = [name for name in jar.namelist() if re.match(r"META-INF/versions/.*\.jar$", name)]
+    if bundled:
+        jar = zipfile.ZipFile(io.BytesIO(jar.read(bundled[0])))
+    out = {}
+    for name in jar.namelist():
+        match = re.match(r"data/(\w+)/recipe/(.+)\.json$", name)
+        if match:
+            out[f"{match.group(1)}:{match.group(2)}"] = json.loads(jar.read(name))
+    if not out:
+        sys.exit(f"Could not find any recipes in {jar_path}")
+    return dict(sorted(out.items()))
+
+
+def main():
+    parser = argparse.ArgumentParser()
+    parser.add_argument("crates", nargs="?", default=".")
+    parser.add_argument("--jar", help="the vanilla server jar to copy recipes from")
+    args = parser.parse_args()
+    root = Path(args.crates)
+    block = crate_dir(root, "azalea-block")
+    physics = crate_dir(root, "azalea-physics")
+    registry = crate_dir(root, "azalea-registry")
+
+    registries = (registry / "lib.rs").read_text(encoding="utf-8")
+    block_names = registry_names(registries, "Block")
+    item_names = registry_names(registries, "Item")
+    properties = block_properties((block / "generated.rs").read_text(encoding="utf-8"))
+
+    blockstates = json.loads((OUT_PATH / "blockstates.json").read_text(encoding="utf-8"))
+    missing = {state["name"] for state in blockstates.values()} - properties.keys()
+    if missing:
+        sys.exit(f"The data is for a different game version, missing {sorted(missing)[:5]}")
+
+    shapes = block_shapes((physics / "collision" / "blocks.rs").read_text(encoding="utf-8"))
+    if len(shapes["collision"]) != len(blockstates) or len(shapes["outline"]) != len(blockstates):
+        sys.exit("The shapes are for a different game version")
+
+    block_tags = tags(
+        (registry / "tags" / "blocks.rs").read_text(encoding="utf-8"), "Block", block_names
+    )
+
+    item_tags = tags(
+        (registry / "tags" / "items.rs").read_text(encoding="utf-8"), "Item", item_names
+    )
+
+    outputs = [
+        ("block_properties.json", properties),
+        ("block_shapes.json", shapes),
+        ("block_tags.json", block_tags),
+        ("item_tags.json", item_tags),
+    ]
+    if args.jar:
+        outputs.append(("recipes.json", recipes(args.jar)))
+    for name, data in outputs:
+        with open(OUT_PATH / name, "w", encoding="utf-8") as f:
+            json.dump(data, f, indent=2)
+            f.write("\n")
+        print(f"Wrote {len(data)} entries to {name}")
+
+
+if __name__ == "__main__":
+    main()
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use ferrumc_anvil::writer::CompressionType;
 use tracing::Level;
 
 #[derive(Parser)]
@@ -18,6 +19,8 @@ pub enum Command {
     Setup,
     /// Import the world data
     Import(ImportArgs),
+    /// Export the world data to vanilla region files
+    Export(ExportArgs),
     /// Start the server
     Run,
 }
@@ -37,6 +40,38 @@ pub struct ImportArgs {
     pub max_concurrent_tasks: usize,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct ExportArgs {
+    /// Path to the world export folder
+    ///
+    /// Region files are written to the `region`, `DIM-1/region` and `DIM1/region` directories in it, like in a vanilla world. Those directories must be empty or not exist.
+    #[clap(long, required = true)]
+    pub export_path: String,
+    /// Compression used for the chunks in the region files
+    #[clap(long, value_enum, default_value_t = ExportCompression::Zlib)]
+    pub compression: ExportCompression,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportCompression {
+    Gzip,
+    Zlib,
+    None,
+    /// Only supported by vanilla 1.20.5 and later
+    Lz4,
+}
+
+impl From<ExportCompression> for CompressionType {
+    fn from(compression: ExportCompression) -> Self {
+        match compression {
+            ExportCompression::Gzip => CompressionType::Gzip,
+            ExportCompression::Zlib => CompressionType::Zlib,
+            ExportCompression::None => CompressionType::None,
+            ExportCompression::Lz4 => CompressionType::Lz4,
+        }
+    }
+}
+
 // Wrapper struct for the Level enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LogLevel(Level);
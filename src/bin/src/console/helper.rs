@@ -0,0 +1,42 @@
+use ferrumc_commands::{suggestions::suggest, Sender};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Completes console input with the same suggestions players get in chat.
+pub struct ConsoleHelper;
+
+impl Completer for ConsoleHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let suggestions = suggest(&line[..pos], Sender::Server, |_| true);
+        let candidates = suggestions
+            .suggestions
+            .into_iter()
+            .map(|sug| Pair {
+                display: sug.content.clone(),
+                replacement: sug.content,
+            })
+            .collect();
+
+        Ok((suggestions.start, candidates))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
@@ -0,0 +1,114 @@
+//! The interactive server console.
+//!
+//! Lines typed into stdin are read on a dedicated thread and handed to the game loop, where
+//! they are dispatched as commands with [`Sender::Server`]. Line editing, history and tab
+//! completion are provided by `rustyline`.
+
+mod helper;
+
+use crate::console::helper::ConsoleHelper;
+use bevy_ecs::prelude::*;
+use crossbeam_channel::Receiver;
+use ferrumc_commands::{
+    dispatch::resolve,
+    events::{CommandDispatchEvent, ResolvedCommandDispatchEvent},
+    Sender,
+};
+use ferrumc_general_purpose::paths::get_root_path;
+use ferrumc_state::GlobalState;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use std::sync::atomic::Ordering;
+use tracing::{debug, error, warn};
+
+const HISTORY_FILE: &str = ".console_history";
+
+/// Lines read from the console, waiting to be dispatched.
+#[derive(Resource)]
+pub struct ConsoleInput(pub Receiver<String>);
+
+/// Spawns the console thread and inserts the [`ConsoleInput`] resource.
+pub fn start_console(world: &mut World, global_state: GlobalState) {
+    let (line_send, line_recv) = crossbeam_channel::unbounded();
+    world.insert_resource(ConsoleInput(line_recv));
+
+    let spawned = std::thread::Builder::new()
+        .name("ConsoleThread".to_string())
+        .spawn(move || {
+            let mut editor = match Editor::<ConsoleHelper, DefaultHistory>::new() {
+                Ok(editor) => editor,
+                Err(e) => {
+                    error!("Failed to start console: {e}");
+                    return;
+                }
+            };
+            editor.set_helper(Some(ConsoleHelper));
+
+            let history_path = get_root_path().join(HISTORY_FILE);
+            // The history file doesn't exist on the first run
+            let _ = editor.load_history(&history_path);
+
+            while !global_state.shut_down.load(Ordering::Relaxed) {
+                match editor.readline("> ") {
+                    Ok(line) => {
+                        let line = line.trim();
+                        if line.is_empty() {
+                            continue;
+                        }
+                        let _ = editor.add_history_entry(line);
+                        let command = line.strip_prefix('/').unwrap_or(line).to_string();
+                        if line_send.send(command).is_err() {
+                            break;
+                        }
+                    }
+                    Err(ReadlineError::Interrupted) => {
+                        crate::request_shutdown(&global_state);
+                        break;
+                    }
+                    Err(ReadlineError::Eof) => {
+                        debug!("Console input closed");
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Failed to read console input: {e}");
+                        break;
+                    }
+                }
+            }
+
+            if let Err(e) = editor.save_history(&history_path) {
+                warn!("Failed to save console history: {e}");
+            }
+        });
+
+    if let Err(e) = spawned {
+        error!("Failed to spawn console thread: {e}");
+    }
+}
+
+/// Dispatches the lines read from the console as commands sent by [`Sender::Server`].
+pub fn handle_console_input(
+    input: Res<ConsoleInput>,
+    mut dispatch_events: EventWriter<CommandDispatchEvent>,
+    mut resolved_dispatch_events: EventWriter<ResolvedCommandDispatchEvent>,
+) {
+    for command in input.0.try_iter() {
+        let sender = Sender::Server;
+        dispatch_events.write(CommandDispatchEvent {
+            command: command.clone(),
+            sender,
+        });
+
+        match resolve(command, sender) {
+            Err(err) => sender.send_message(*err, false),
+            Ok((command, ctx)) => {
+                resolved_dispatch_events.write(ResolvedCommandDispatchEvent {
+                    command,
+                    ctx,
+                    sender,
+                });
+            }
+        }
+    }
+}
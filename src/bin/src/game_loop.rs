@@ -1,3 +1,4 @@
+use crate::console::start_console;
 use crate::errors::BinaryError;
 use crate::packet_handlers::{play_packets, register_player_systems};
 use crate::register_events::register_events;
@@ -11,8 +12,11 @@ use crossbeam_channel::Sender;
 use ferrumc_commands::infrastructure::register_command_systems;
 use ferrumc_config::server_config::get_global_config;
 use ferrumc_net::connection::{handle_connection, NewConnection};
+use ferrumc_net::query::run_query_listener;
+use ferrumc_net::rcon::{run_rcon_listener, RconCommand};
 use ferrumc_net::server::create_server_listener;
 use ferrumc_net::PacketSender;
+use ferrumc_plugins::{drain_registered_plugins, PluginManager};
 use ferrumc_scheduler::MissedTickBehavior;
 use ferrumc_scheduler::{drain_registered_schedules, Scheduler, TimedSchedule};
 use ferrumc_state::{GlobalState, GlobalStateResource};
@@ -33,6 +37,7 @@ pub fn start_game_loop(global_state: GlobalState) -> Result<(), BinaryError> {
     // Setup channels and stuff for new connections
     let sender_struct = Arc::new(ferrumc_net::create_packet_senders(&mut ecs_world));
     let (new_conn_send, new_conn_recv) = crossbeam_channel::unbounded();
+    let (rcon_send, rcon_recv) = crossbeam_channel::unbounded();
 
     // Setup shutdown related channels
     let (shutdown_send, shutdown_recv) = tokio::sync::oneshot::channel();
@@ -44,18 +49,39 @@ pub fn start_game_loop(global_state: GlobalState) -> Result<(), BinaryError> {
     let global_state_res = GlobalStateResource(global_state.clone());
 
     register_events(&mut ecs_world);
-    register_resources(&mut ecs_world, new_conn_recv, global_state_res);
+    register_resources(&mut ecs_world, new_conn_recv, rcon_recv, global_state_res);
 
     let mut timed = build_timed_scheduler();
 
     // Shutdown systems
     register_shutdown_systems(&mut shutdown_schedule);
 
+    // Plugins get to hook into the world and schedules before the first tick
+    #[cfg(feature = "wasm-plugins")]
+    ferrumc_plugins::wasm::register_wasm_plugins();
+    let mut plugins = PluginManager::new();
+    for plugin in drain_registered_plugins() {
+        if let Err(e) = plugins.add(plugin) {
+            error!("Failed to add plugin: {}", e);
+        }
+    }
+    plugins.load_all(&mut ecs_world, &mut timed, &mut shutdown_schedule);
+    plugins.enable_all(&mut ecs_world, &mut timed, &mut shutdown_schedule);
+
+    // Read commands from stdin
+    start_console(&mut ecs_world, global_state.clone());
+
     // Start the TCP acceptor thread
     tcp_conn_acceptor(
         global_state.clone(),
         sender_struct,
         Arc::new(new_conn_send),
+        rcon_send,
+        plugins
+            .plugin_names()
+            .into_iter()
+            .map(String::from)
+            .collect(),
         shutdown_recv,
         shutdown_response_send,
     )?;
@@ -127,6 +153,7 @@ pub fn start_game_loop(global_state: GlobalState) -> Result<(), BinaryError> {
         }
     }
 
+    plugins.disable_all(&mut ecs_world, &mut timed, &mut shutdown_schedule);
     shutdown_schedule.run(&mut ecs_world);
 
     // tell the TCP connection acceptor to shut down
@@ -164,6 +191,7 @@ fn build_timed_scheduler() -> Scheduler {
 
     // World sync
     let build_world_sync = |s: &mut Schedule| {
+        s.add_systems(crate::systems::player_data::save_player_data);
         s.add_systems(crate::systems::world_sync::sync_world);
     };
     timed.register(
@@ -206,6 +234,8 @@ fn tcp_conn_acceptor(
     state: GlobalState,
     packet_sender: Arc<PacketSender>,
     sender: Arc<Sender<NewConnection>>,
+    rcon_sender: Sender<RconCommand>,
+    plugin_names: Vec<String>,
     mut shutdown_notify: tokio::sync::oneshot::Receiver<()>,
     shutdown_response: Sender<()>,
 ) -> Result<(), BinaryError> {
@@ -227,6 +257,22 @@ fn tcp_conn_acceptor(
                     sleep(Duration::from_millis(1500)).await;
                 }
             });
+            async_runtime.spawn({
+                let state = Arc::clone(&state);
+                async move {
+                    if let Err(e) = run_rcon_listener(state, rcon_sender).await {
+                        error!("RCON listener failed: {}", e);
+                    }
+                }
+            });
+            async_runtime.spawn({
+                let state = Arc::clone(&state);
+                async move {
+                    if let Err(e) = run_query_listener(state, plugin_names).await {
+                        error!("Query listener failed: {}", e);
+                    }
+                }
+            });
             async_runtime.block_on({
                 let state = Arc::clone(&state);
                 async move {
@@ -1,9 +1,10 @@
-#![feature(try_blocks)]
+#![feature(try_blocks_heterogeneous)]
 
 use crate::errors::BinaryError;
 use clap::Parser;
 use ferrumc_config::server_config::get_global_config;
 use ferrumc_config::whitelist::create_whitelist;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_general_purpose::paths::get_root_path;
 use ferrumc_state::player_list::PlayerList;
 use ferrumc_state::{GlobalState, ServerState};
@@ -15,9 +16,10 @@ use std::time::Instant;
 use tracing::{error, info};
 
 pub(crate) mod errors;
-use crate::cli::{CLIArgs, Command, ImportArgs};
+use crate::cli::{CLIArgs, Command, ExportArgs, ImportArgs};
 mod chunk_sending;
 mod cli;
+mod console;
 mod game_loop;
 mod packet_handlers;
 mod register_events;
@@ -55,6 +57,14 @@ fn main() {
                 info!("Import completed successfully.");
             }
         }
+        Some(Command::Export(export_args)) => {
+            info!("Starting export...");
+            if let Err(e) = handle_export(export_args) {
+                error!("Export failed with the following error: {}", e.to_string());
+            } else {
+                info!("Export completed successfully.");
+            }
+        }
         Some(Command::Run) | None => {
             info!("Starting server...");
             if let Err(e) = ferrumc_config::setup::setup() {
@@ -88,7 +98,7 @@ fn generate_chunks(state: GlobalState) -> Result<(), BinaryError> {
         batch.execute(move || {
             let chunk = state_clone
                 .terrain_generator
-                .generate_chunk(x, z)
+                .generate_chunk(x, z, Dimension::Overworld)
                 .map(Arc::new);
             if let Err(e) = chunk {
                 error!("Error generating chunk ({}, {}): {:?}", x, z, e);
@@ -109,22 +119,17 @@ fn entry(start_time: Instant) -> Result<(), BinaryError> {
     let state = create_state(start_time)?;
     let global_state = Arc::new(state);
     create_whitelist();
-    if !global_state.world.chunk_exists(0, 0, "overworld")? {
+    info!("Loaded {} recipes", ferrumc_recipes::RECIPES.len());
+    if !global_state
+        .world
+        .chunk_exists(0, 0, Dimension::Overworld.name())?
+    {
         generate_chunks(global_state.clone())?;
     }
 
     ctrlc::set_handler({
         let global_state = global_state.clone();
-        move || {
-            info!("Shutting down server...");
-            global_state
-                .shut_down
-                .store(true, std::sync::atomic::Ordering::Relaxed);
-            global_state
-                .world
-                .sync()
-                .expect("Failed to sync world before shutdown")
-        }
+        move || request_shutdown(&global_state)
     })
     .expect("Error setting Ctrl-C handler");
 
@@ -133,6 +138,18 @@ fn entry(start_time: Instant) -> Result<(), BinaryError> {
     Ok(())
 }
 
+/// Signals the game loop to stop and flushes the world to disk.
+pub(crate) fn request_shutdown(global_state: &GlobalState) {
+    info!("Shutting down server...");
+    global_state
+        .shut_down
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    global_state
+        .world
+        .sync()
+        .expect("Failed to sync world before shutdown")
+}
+
 fn handle_import(import_args: ImportArgs) -> Result<(), BinaryError> {
     //! Handles the import of the world.
     info!("Importing world...");
@@ -154,6 +171,22 @@ fn handle_import(import_args: ImportArgs) -> Result<(), BinaryError> {
     Ok(())
 }
 
+fn handle_export(export_args: ExportArgs) -> Result<(), BinaryError> {
+    //! Handles the export of the world.
+    info!("Exporting world...");
+
+    let world = World::new(&get_global_config().database.db_path);
+
+    let export_path = get_root_path().join(export_args.export_path);
+
+    if let Err(e) = world.export(export_path, export_args.compression.into()) {
+        error!("Could not export world: {}", e.to_string());
+        return Err(BinaryError::Custom("Could not export world.".to_string()));
+    }
+
+    Ok(())
+}
+
 fn create_state(start_time: Instant) -> Result<ServerState, BinaryError> {
     Ok(ServerState {
         world: World::new(&get_global_config().database.db_path),
@@ -0,0 +1,201 @@
+use crate::errors::BinaryError;
+use crate::systems::containers::{self, ContainerWindow, OpenContainers};
+use crate::systems::entities;
+use bevy_ecs::prelude::{Commands, Entity, Query, Res, ResMut};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_inventories::window::ClickMode;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::incoming::click_container::{ClickContainer, HashedItem};
+use ferrumc_net::packets::outgoing::set_container_content::SetContainerContent;
+use ferrumc_net::packets::outgoing::set_container_slot::SetContainerSlot;
+use ferrumc_net::ClickContainerReceiver;
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalStateResource;
+use tracing::{debug, error, trace};
+
+/// How far above a player's feet the items they throw out of a window appear.
+const THROW_HEIGHT: f64 = 1.3;
+
+type Clickers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static StreamWriter,
+        &'static mut Inventory,
+        &'static mut ContainerWindow,
+        &'static Position,
+        &'static Dimension,
+    ),
+>;
+
+pub fn handle(
+    events: Res<ClickContainerReceiver>,
+    state: Res<GlobalStateResource>,
+    mut containers: ResMut<OpenContainers>,
+    mut clickers: Clickers,
+    mut commands: Commands,
+) {
+    for (event, eid) in events.0.try_iter() {
+        if !state.0.players.is_connected(eid) {
+            trace!("Entity {:?} is not connected", eid);
+            continue;
+        }
+        if let Err(err) = click(
+            &event,
+            eid,
+            &state,
+            &mut containers,
+            &mut clickers,
+            &mut commands,
+        ) {
+            error!("Failed to handle container click: {:?}", err);
+        }
+    }
+}
+
+fn click(
+    event: &ClickContainer,
+    player: Entity,
+    state: &GlobalStateResource,
+    containers: &mut OpenContainers,
+    clickers: &mut Clickers,
+    commands: &mut Commands,
+) -> Result<(), BinaryError> {
+    let Ok((conn, mut inventory, mut window, position, &dimension)) = clickers.get_mut(player)
+    else {
+        trace!("Player {:?} clicked without a window", player);
+        return Ok(());
+    };
+    if event.window_id.0 != i32::from(window.window_id) {
+        trace!("Player {:?} clicked in a window that isn't open", player);
+        return Ok(());
+    }
+    let Some(mode) = ClickMode::from_id(event.mode.0) else {
+        debug!("Invalid click mode: {}", event.mode.0);
+        return Ok(());
+    };
+    let container = window.container();
+    let before = container
+        .and_then(|pos| containers.containers.get(&pos))
+        .map(|container| container.slots.clone());
+
+    let Some(mut view) = window.view(containers, &mut inventory) else {
+        return Ok(());
+    };
+    let contents_before = view.contents();
+    let (valid, dropped) = match view.click(event.slot, event.button, mode) {
+        Ok(dropped) => (true, dropped),
+        Err(err) => {
+            debug!("Invalid click from {:?}: {}", player, err);
+            (false, Vec::new())
+        }
+    };
+    let (contents, carried) = (view.contents(), view.carried());
+
+    // The client already shows what it thinks happened, so it only needs telling where it was
+    // wrong, or about what it couldn't know, like a crafting result
+    let mispredicted = (0..contents.len())
+        .filter(|&slot| {
+            let changed = event
+                .changed_slots
+                .data
+                .iter()
+                .find(|changed| usize::try_from(changed.slot) == Ok(slot));
+            match changed {
+                Some(changed) => !matches(&changed.item, &contents[slot]),
+                None => contents_before[slot] != contents[slot],
+            }
+        })
+        .collect::<Vec<_>>();
+    if !valid || event.state_id.0 != window.state_id || !matches(&event.carried_item, &carried) {
+        let state_id = window.next_state_id();
+        conn.send_packet(SetContainerContent {
+            window_id: VarInt::new(window.window_id.into()),
+            state_id,
+            slots: LengthPrefixedVec::new(contents),
+            carried_item: carried,
+        })?;
+    } else if !mispredicted.is_empty() {
+        let state_id = window.next_state_id();
+        for slot in mispredicted {
+            conn.send_packet(SetContainerSlot {
+                window_id: VarInt::new(window.window_id.into()),
+                state_id,
+                slot_index: slot as i16,
+                slot: contents[slot].clone(),
+            })?;
+        }
+    }
+
+    for item in dropped {
+        let position = Position::new(position.x, position.y + THROW_HEIGHT, position.z);
+        entities::spawn_item(commands, item, position, Velocity::default(), dimension);
+    }
+
+    // Anyone else looking into the same container needs to see the change
+    let (Some(pos), Some(before)) = (container, before) else {
+        return Ok(());
+    };
+    let Some(container) = containers.containers.get(&pos) else {
+        return Ok(());
+    };
+    let changed = (0..before.len())
+        .filter(|&slot| before[slot] != container.slots[slot])
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    containers::save(&state.0, pos, &container.slots)?;
+    for &viewer in &container.viewers {
+        if viewer == player {
+            continue;
+        }
+        let Ok((conn, _, mut window, _, _)) = clickers.get_mut(viewer) else {
+            continue;
+        };
+        let state_id = window.next_state_id();
+        for &slot in &changed {
+            conn.send_packet(SetContainerSlot {
+                window_id: VarInt::new(window.window_id.into()),
+                state_id,
+                slot_index: slot as i16,
+                slot: container.slots[slot].clone().unwrap_or_default(),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the item the client sent is the one the server has. Component data is only sent as
+/// hashes, so only which components the item has is compared.
+fn matches(hashed: &PrefixedOptional<HashedItem>, item: &InventorySlot) -> bool {
+    match hashed {
+        PrefixedOptional::None => item.is_empty(),
+        PrefixedOptional::Some(hashed) => {
+            let mut added = item
+                .components()
+                .iter()
+                .filter_map(|component| component.type_id())
+                .collect::<Vec<_>>();
+            let mut hashed_added = hashed
+                .components_to_add
+                .data
+                .iter()
+                .map(|component| component.component_type)
+                .collect::<Vec<_>>();
+            added.sort();
+            hashed_added.sort();
+            !item.is_empty()
+                && item.item_id == Some(ItemID(hashed.item_id))
+                && item.count == hashed.count
+                && added == hashed_added
+        }
+    }
+}
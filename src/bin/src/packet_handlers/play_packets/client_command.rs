@@ -0,0 +1,85 @@
+use crate::errors::BinaryError;
+use crate::systems::change_dimension;
+use bevy_ecs::prelude::{Query, Res};
+use bevy_ecs::query::QueryData;
+use ferrumc_core::chunks::chunk_receiver::ChunkReceiver;
+use ferrumc_core::digging::Digging;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::health::{Breath, FallDistance, Food, Health};
+use ferrumc_core::movement::MovementValidator;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::incoming::client_command::ClientCommandAction;
+use ferrumc_net::packets::outgoing::respawn::RespawnPacket;
+use ferrumc_net::packets::outgoing::set_default_spawn_position::DEFAULT_SPAWN_POSITION;
+use ferrumc_net::ClientCommandPacketReceiver;
+use ferrumc_state::GlobalStateResource;
+use tracing::error;
+
+/// Everything that's reset when a player respawns.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct Respawning {
+    health: &'static mut Health,
+    food: &'static mut Food,
+    breath: &'static mut Breath,
+    fall_distance: &'static mut FallDistance,
+    dimension: &'static mut Dimension,
+    position: &'static mut Position,
+    rotation: &'static Rotation,
+    receiver: &'static mut ChunkReceiver,
+    conn: &'static mut StreamWriter,
+    digging: &'static mut Digging,
+    validator: &'static mut MovementValidator,
+    game_mode: &'static GameMode,
+}
+
+pub fn handle(
+    events: Res<ClientCommandPacketReceiver>,
+    mut players: Query<Respawning>,
+    state: Res<GlobalStateResource>,
+) {
+    for (event, eid) in events.0.try_iter() {
+        if !matches!(event.action, ClientCommandAction::PerformRespawn)
+            || !state.0.players.is_connected(eid)
+        {
+            continue;
+        }
+        let Ok(mut player) = players.get_mut(eid) else {
+            continue;
+        };
+        // Clients also ask to respawn once they join
+        if !player.health.is_dead() {
+            continue;
+        }
+
+        // Players respawn at the spawn point they were sent when they joined
+        let spawn = (
+            f64::from(DEFAULT_SPAWN_POSITION.x) + 0.5,
+            f64::from(DEFAULT_SPAWN_POSITION.z) + 0.5,
+        );
+        let res = try bikeshed Result<(), BinaryError> {
+            *player.position = change_dimension::respawn(
+                &state.0,
+                &mut player.conn,
+                RespawnPacket::after_death(Dimension::Overworld, *player.game_mode),
+                Dimension::Overworld,
+                spawn,
+                player.rotation,
+                &mut player.validator,
+            )?;
+            *player.dimension = Dimension::Overworld;
+            *player.receiver = ChunkReceiver::new();
+            player.digging.0 = None;
+            player.fall_distance.reset();
+            *player.health = Health::default();
+            *player.food = Food::default();
+            *player.breath = Breath::default();
+        };
+        if let Err(e) = res {
+            error!("Failed to respawn player {:?}: {:?}", eid, e);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::systems::containers::{self, ContainerWindow, OpenContainers};
+use crate::systems::entities;
+use bevy_ecs::prelude::{Commands, Query, Res, ResMut};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::CloseContainerReceiver;
+use tracing::{error, trace};
+
+pub fn handle(
+    events: Res<CloseContainerReceiver>,
+    mut containers: ResMut<OpenContainers>,
+    mut query: Query<(
+        &StreamWriter,
+        &mut Inventory,
+        &mut ContainerWindow,
+        &Position,
+        &Dimension,
+    )>,
+    mut commands: Commands,
+) {
+    for (event, eid) in events.0.try_iter() {
+        let Ok((conn, mut inventory, mut window, position, &dimension)) = query.get_mut(eid) else {
+            trace!("Player {:?} closed a window without having one", eid);
+            continue;
+        };
+        if event.window_id.0 != i32::from(window.window_id) {
+            continue;
+        }
+        let slots_before = inventory.slots.clone();
+        let rest = containers::close(&mut containers, eid, &mut window, &mut inventory);
+        // Carried and crafting items went back in the inventory without the client knowing
+        if inventory.slots != slots_before {
+            if let Err(err) = containers::send_inventory(conn, &inventory) {
+                error!("Failed to send inventory after closing a window: {:?}", err);
+            }
+        }
+        for item in rest {
+            let position = Position::new(position.x, position.y, position.z);
+            entities::spawn_item(
+                &mut commands,
+                item,
+                position,
+                Velocity::default(),
+                dimension,
+            );
+        }
+    }
+}
@@ -1,46 +1,19 @@
-use std::sync::Arc;
-
 use bevy_ecs::prelude::*;
 use ferrumc_commands::{
+    dispatch::resolve,
     events::{CommandDispatchEvent, ResolvedCommandDispatchEvent},
-    infrastructure, Command, CommandContext, CommandInput, Sender,
+    Sender,
 };
+use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_core::mq;
 use ferrumc_net::ChatCommandPacketReceiver;
-use ferrumc_text::{NamedColor, TextComponent, TextComponentBuilder};
-
-fn resolve(
-    input: String,
-    sender: Sender,
-) -> Result<(Arc<Command>, CommandContext), Box<TextComponent>> {
-    let command = infrastructure::find_command(&input);
-    if command.is_none() {
-        return Err(Box::new(
-            TextComponentBuilder::new("Unknown command")
-                .color(NamedColor::Red)
-                .build(),
-        ));
-    }
-
-    let command = command.unwrap();
-    let input = input
-        .strip_prefix(command.name)
-        .unwrap_or(&input)
-        .trim_start();
-    let input = CommandInput::of(input.to_string());
-    let ctx = CommandContext {
-        input: input.clone(),
-        command: command.clone(),
-        sender,
-    };
-
-    Ok((command, ctx))
-}
+use ferrumc_text::{NamedColor, TextComponentBuilder};
 
 pub fn handle(
     events: Res<ChatCommandPacketReceiver>,
     mut dispatch_events: EventWriter<CommandDispatchEvent>,
     mut resolved_dispatch_events: EventWriter<ResolvedCommandDispatchEvent>,
+    players: Query<&PlayerIdentity>,
 ) {
     for (event, entity) in events.0.try_iter() {
         let sender = Sender::Player(entity);
@@ -55,6 +28,16 @@ pub fn handle(
                 mq::queue(*err, false, entity);
             }
 
+            Ok((command, _)) if !sender.can_use(&command, &players) => {
+                mq::queue(
+                    TextComponentBuilder::new("You don't have permission to use this command")
+                        .color(NamedColor::Red)
+                        .build(),
+                    false,
+                    entity,
+                );
+            }
+
             Ok((command, ctx)) => {
                 resolved_dispatch_events.write(ResolvedCommandDispatchEvent {
                     command,
@@ -1,3 +1,14 @@
-pub fn handle() {
-    // TODO
+use bevy_ecs::prelude::{Query, Res};
+use ferrumc_core::movement::MovementValidator;
+use ferrumc_net::ConfirmPlayerTeleportReceiver;
+
+pub fn handle(
+    events: Res<ConfirmPlayerTeleportReceiver>,
+    mut query: Query<&mut MovementValidator>,
+) {
+    for (event, eid) in events.0.try_iter() {
+        if let Ok(mut validator) = query.get_mut(eid) {
+            validator.confirm_teleport(event.teleport_id.0);
+        }
+    }
 }
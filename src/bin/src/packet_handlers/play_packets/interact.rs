@@ -0,0 +1,93 @@
+use bevy_ecs::prelude::{Entity, EventWriter, Query, Res};
+use bevy_ecs::query::QueryData;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::health::combat::{AttackCooldown, ATTACK_REACH, FIST_DAMAGE};
+use ferrumc_core::health::damage_event::{DamageEvent, DamageSource};
+use ferrumc_core::health::Health;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_net::packets::incoming::interact::InteractAction;
+use ferrumc_net::InteractPacketReceiver;
+use ferrumc_state::GlobalStateResource;
+use tracing::debug;
+
+/// Something a player can hit.
+#[derive(QueryData)]
+pub struct Target {
+    entity: Entity,
+    position: &'static Position,
+    dimension: &'static Dimension,
+    health: &'static Health,
+    player: Option<&'static PlayerIdentity>,
+    game_mode: Option<&'static GameMode>,
+    identity: Option<&'static EntityIdentity>,
+}
+
+impl TargetItem<'_> {
+    fn network_id(&self) -> Option<i32> {
+        self.player
+            .map(|player| player.entity_id)
+            .or(self.identity.map(|identity| identity.entity_id))
+    }
+}
+
+pub fn handle(
+    events: Res<InteractPacketReceiver>,
+    mut attackers: Query<(
+        &Position,
+        &Dimension,
+        &Health,
+        &GameMode,
+        &mut AttackCooldown,
+    )>,
+    targets: Query<Target>,
+    mut damage: EventWriter<DamageEvent>,
+    state: Res<GlobalStateResource>,
+) {
+    for (event, eid) in events.0.try_iter() {
+        // Right clicking entities doesn't do anything yet
+        if event.action != InteractAction::Attack || !state.0.players.is_connected(eid) {
+            continue;
+        }
+        let Ok((position, dimension, health, game_mode, mut cooldown)) = attackers.get_mut(eid)
+        else {
+            continue;
+        };
+        // Spectators can't touch anything
+        if health.is_dead() || *game_mode == GameMode::Spectator {
+            continue;
+        }
+        let Some(target) = targets
+            .iter()
+            .find(|target| target.network_id() == Some(event.entity_id.0))
+        else {
+            debug!(
+                "Player {} attacked unknown entity {}",
+                eid, event.entity_id.0
+            );
+            continue;
+        };
+        let distance = ((target.position.x - position.x).powi(2)
+            + (target.position.y - position.y).powi(2)
+            + (target.position.z - position.z).powi(2))
+        .sqrt();
+        if target.entity == eid
+            || target.health.is_dead()
+            || target.game_mode == Some(&GameMode::Spectator)
+            || target.dimension != dimension
+            || distance > ATTACK_REACH
+        {
+            continue;
+        }
+
+        let amount = FIST_DAMAGE * cooldown.strength();
+        cooldown.reset();
+        damage.write(DamageEvent::new(
+            target.entity,
+            amount,
+            DamageSource::Attack(eid),
+        ));
+    }
+}
@@ -2,11 +2,16 @@ use bevy_ecs::schedule::Schedule;
 
 mod chat_message;
 mod chunk_batch_ack;
+mod click_container;
+mod client_command;
+mod close_container;
 mod command;
 mod command_suggestions;
 mod confirm_player_teleport;
+mod interact;
 mod keep_alive;
 mod place_block;
+mod place_recipe;
 mod player_action;
 mod player_command;
 mod player_loaded;
@@ -34,6 +39,11 @@ pub fn register_packet_handlers(schedule: &mut Schedule) {
     schedule.add_systems(chat_message::handle);
     schedule.add_systems(set_creative_mode_slot::handle);
     schedule.add_systems(set_held_item::handle);
+    schedule.add_systems(click_container::handle);
+    schedule.add_systems(close_container::handle);
+    schedule.add_systems(place_recipe::handle);
+    schedule.add_systems(interact::handle);
+    schedule.add_systems(client_command::handle);
 }
 
 pub mod set_creative_mode_slot;
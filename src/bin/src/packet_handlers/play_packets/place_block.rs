@@ -1,14 +1,16 @@
-use std::sync::Arc;
-
-use bevy_ecs::prelude::{Entity, Query, Res};
+use crate::systems::containers::{self, ContainerPos, ContainerWindow, OpenContainers};
+use crate::systems::world_edits::{self, Viewers};
+use bevy_ecs::prelude::{Entity, EventWriter, Query, Res, ResMut};
+use ferrumc_core::abilities::Abilities;
+use ferrumc_core::chunks::block_change_event::BlockChangeEvent;
+use ferrumc_core::collisions::blocks::EPSILON;
 use ferrumc_core::collisions::bounds::CollisionBounds;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_core::transform::position::Position;
 use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::packets::outgoing::block_change_ack::BlockChangeAck;
-use ferrumc_net::packets::outgoing::block_update::BlockUpdate;
 use ferrumc_net::PlaceBlockReceiver;
 use ferrumc_net_codec::net_types::network_position::NetworkPosition;
-use ferrumc_net_codec::net_types::var_int::VarInt;
 use ferrumc_state::GlobalStateResource;
 use tracing::{debug, error, trace};
 
@@ -33,11 +35,24 @@ static ITEM_TO_BLOCK_MAPPING: Lazy<HashMap<i32, i32>> = Lazy::new(|| {
 pub fn handle(
     events: Res<PlaceBlockReceiver>,
     state: Res<GlobalStateResource>,
-    query: Query<(Entity, &StreamWriter, &Inventory, &Hotbar)>,
-    pos_q: Query<(&Position, &CollisionBounds)>,
+    mut query: Query<(
+        Entity,
+        &StreamWriter,
+        &mut Inventory,
+        &Hotbar,
+        &Dimension,
+        &mut ContainerWindow,
+        &Abilities,
+    )>,
+    pos_q: Query<(&Position, &CollisionBounds, &Dimension)>,
+    viewers: Viewers,
+    mut containers: ResMut<OpenContainers>,
+    mut block_changes: EventWriter<BlockChangeEvent>,
 ) {
     'ev_loop: for (event, eid) in events.0.try_iter() {
-        let Ok((entity, conn, inventory, hotbar)) = query.get(eid) else {
+        let Ok((entity, conn, mut inventory, hotbar, &dimension, mut window, abilities)) =
+            query.get_mut(eid)
+        else {
             debug!("Could not get connection for entity {:?}", eid);
             continue;
         };
@@ -45,6 +60,53 @@ pub fn handle(
             trace!("Entity {:?} is not connected", entity);
             continue;
         }
+        // The client closes any other window before using a block. It still waits for the ack
+        // to roll back what it predicted.
+        if window.window_id != 0 {
+            let ack_packet = BlockChangeAck {
+                sequence: event.sequence,
+            };
+            if let Err(err) = conn.send_packet_ref(&ack_packet) {
+                error!("Failed to send block change ack packet: {:?}", err);
+            }
+            continue;
+        }
+        // Using a container or crafting table opens it instead of placing against it
+        let container = ContainerPos::new(&event.position, dimension);
+        match containers::open(
+            &state.0,
+            &mut containers,
+            entity,
+            conn,
+            &mut window,
+            &mut inventory,
+            container,
+        ) {
+            Ok(false) => {}
+            Ok(true) => {
+                let ack_packet = BlockChangeAck {
+                    sequence: event.sequence,
+                };
+                if let Err(err) = conn.send_packet_ref(&ack_packet) {
+                    error!("Failed to send block change ack packet: {:?}", err);
+                }
+                continue 'ev_loop;
+            }
+            Err(err) => {
+                error!("Failed to open container: {:?}", err);
+                continue 'ev_loop;
+            }
+        }
+        if !abilities.may_build {
+            // Acknowledging without placing anything makes the client take its block back
+            let ack_packet = BlockChangeAck {
+                sequence: event.sequence,
+            };
+            if let Err(err) = conn.send_packet_ref(&ack_packet) {
+                error!("Failed to send block change ack packet: {:?}", err);
+            }
+            continue 'ev_loop;
+        }
         match event.hand.0 {
             0 => {
                 let slot_index = hotbar.selected_slot as usize;
@@ -66,14 +128,12 @@ pub fn handle(
                         "Placing block with item ID: {}, mapped to block state ID: {}",
                         item_id.0, mapped_block_state_id
                     );
-                    let mut chunk = match state.0.world.load_chunk_owned(
-                        event.position.x >> 4,
-                        event.position.z >> 4,
-                        "overworld",
-                    ) {
+                    let (chunk_x, chunk_z) = (event.position.x >> 4, event.position.z >> 4);
+                    let chunk = match world_edits::load_chunk(&state.0, chunk_x, chunk_z, dimension)
+                    {
                         Ok(chunk) => chunk,
                         Err(e) => {
-                            debug!("Failed to load chunk: {:?}", e);
+                            debug!("Failed to generate chunk: {:?}", e);
                             continue 'ev_loop;
                         }
                     };
@@ -101,21 +161,18 @@ pub fn handle(
                         event.position.y + y_block_offset,
                         event.position.z + z_block_offset,
                     );
-                    // Check if the block collides with any entities
+                    // Check if the block's shape collides with any entities
+                    let block = BlockStateId(*mapped_block_state_id as u32);
                     let does_collide = {
-                        pos_q.into_iter().any(|(pos, bounds)| {
-                            bounds.collides(
-                                (pos.x, pos.y, pos.z),
-                                &CollisionBounds {
-                                    x_offset_start: 0.0,
-                                    x_offset_end: 1.0,
-                                    y_offset_start: 0.0,
-                                    y_offset_end: 1.0,
-                                    z_offset_start: 0.0,
-                                    z_offset_end: 1.0,
-                                },
-                                (x as f64, y as f64, z as f64),
-                            )
+                        pos_q.into_iter().any(|(pos, bounds, &entity_dimension)| {
+                            entity_dimension == dimension
+                                && block.collision_shape().iter().any(|shape| {
+                                    bounds.shrink(EPSILON).collides(
+                                        (pos.x, pos.y, pos.z),
+                                        shape,
+                                        (x as f64, y as f64, z as f64),
+                                    )
+                                })
                         })
                     };
                     if does_collide {
@@ -130,37 +187,26 @@ pub fn handle(
                         continue 'ev_loop;
                     }
 
-                    if let Err(err) = chunk.set_block(
-                        x & 0xF,
-                        y as i32,
-                        z & 0xF,
-                        BlockStateId(*mapped_block_state_id as u32),
-                    ) {
-                        error!("Failed to set block: {:?}", err);
+                    let location = NetworkPosition { x, y, z };
+                    if let Err(err) =
+                        world_edits::set_block(&state.0, &viewers, &location, dimension, block)
+                    {
+                        error!("Failed to place block: {:?}", err);
                         continue 'ev_loop;
                     }
+                    block_changes.write(BlockChangeEvent {
+                        location,
+                        dimension,
+                    });
                     let ack_packet = BlockChangeAck {
                         sequence: event.sequence,
                     };
-
-                    let chunk_packet = BlockUpdate {
-                        location: NetworkPosition { x, y, z },
-                        block_state_id: VarInt::from(*mapped_block_state_id),
-                    };
-                    if let Err(err) = conn.send_packet_ref(&chunk_packet) {
-                        error!("Failed to send block update packet: {:?}", err);
-                        continue 'ev_loop;
-                    }
                     if let Err(err) = conn.send_packet_ref(&ack_packet) {
                         error!("Failed to send block change ack packet: {:?}", err);
                         continue 'ev_loop;
                     }
 
-                    if let Err(err) = state.0.world.save_chunk(Arc::from(chunk)) {
-                        error!("Failed to save chunk after block placement: {:?}", err);
-                    } else {
-                        trace!("Block placed at ({}, {}, {})", x, y, z);
-                    }
+                    trace!("Block placed at ({}, {}, {})", x, y, z);
                 }
             }
             1 => {
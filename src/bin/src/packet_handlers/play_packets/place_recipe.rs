@@ -0,0 +1,84 @@
+use crate::errors::BinaryError;
+use crate::systems::containers::{ContainerWindow, Menu, OpenContainers};
+use bevy_ecs::prelude::{Query, Res, ResMut};
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::incoming::place_recipe::PlaceRecipe;
+use ferrumc_net::packets::outgoing::place_ghost_recipe::PlaceGhostRecipe;
+use ferrumc_net::packets::outgoing::set_container_content::SetContainerContent;
+use ferrumc_net::PlaceRecipeReceiver;
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_recipes::RECIPES;
+use ferrumc_state::GlobalStateResource;
+use tracing::{debug, error, trace};
+
+pub fn handle(
+    events: Res<PlaceRecipeReceiver>,
+    state: Res<GlobalStateResource>,
+    mut containers: ResMut<OpenContainers>,
+    mut query: Query<(&StreamWriter, &mut Inventory, &mut ContainerWindow)>,
+) {
+    for (event, eid) in events.0.try_iter() {
+        if !state.0.players.is_connected(eid) {
+            trace!("Entity {:?} is not connected", eid);
+            continue;
+        }
+        let Ok((conn, mut inventory, mut window)) = query.get_mut(eid) else {
+            continue;
+        };
+        if let Err(err) = place(&event, conn, &mut inventory, &mut window, &mut containers) {
+            error!("Failed to place recipe: {:?}", err);
+        }
+    }
+}
+
+/// Fills the crafting grid from the player's inventory, or shows them the recipe as a ghost in
+/// it if they don't have the items.
+fn place(
+    event: &PlaceRecipe,
+    conn: &StreamWriter,
+    inventory: &mut Inventory,
+    window: &mut ContainerWindow,
+    containers: &mut OpenContainers,
+) -> Result<(), BinaryError> {
+    if event.window_id.0 != i32::from(window.window_id) {
+        return Ok(());
+    }
+    let grid_width = match window.menu {
+        Menu::Inventory => 2,
+        Menu::Crafting(_) => 3,
+        Menu::Container(_) => return Ok(()),
+    };
+    let Some(recipe) = usize::try_from(event.recipe_id.0)
+        .ok()
+        .and_then(|id| RECIPES.get(id))
+    else {
+        debug!("Unknown recipe id: {}", event.recipe_id.0);
+        return Ok(());
+    };
+    let Some((pattern, width)) = recipe.placement(grid_width) else {
+        return Ok(());
+    };
+    let Some(mut view) = window.view(containers, inventory) else {
+        return Ok(());
+    };
+    let placed = view.place_recipe(&pattern, width, event.make_all);
+    let (contents, carried) = (view.contents(), view.carried());
+
+    let window_id = VarInt::new(window.window_id.into());
+    let state_id = window.next_state_id();
+    conn.send_packet(SetContainerContent {
+        window_id,
+        state_id,
+        slots: LengthPrefixedVec::new(contents),
+        carried_item: carried,
+    })?;
+    if !placed {
+        conn.send_packet(PlaceGhostRecipe {
+            window_id,
+            recipe: recipe.display(),
+        })?;
+    }
+    Ok(())
+}
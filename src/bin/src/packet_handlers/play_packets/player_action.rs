@@ -1,73 +1,209 @@
-use std::sync::Arc;
-
 use crate::errors::BinaryError;
-use bevy_ecs::prelude::{Entity, Query, Res};
-use ferrumc_net::connection::StreamWriter;
+use crate::systems::containers::{self, ContainerPos, OpenContainers};
+use crate::systems::digging;
+use crate::systems::world_edits::{self, Viewers};
+use bevy_ecs::prelude::{Commands, EventWriter, Query, Res, ResMut};
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::abilities::Abilities;
+use ferrumc_core::chunks::block_change_event::BlockChangeEvent;
+use ferrumc_core::digging::{DigProgress, Digging};
+use ferrumc_core::health::Health;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_inventories::hotbar::Hotbar;
+use ferrumc_inventories::inventory::Inventory;
 use ferrumc_net::packets::outgoing::block_change_ack::BlockChangeAck;
+use ferrumc_net::packets::outgoing::block_destruction::BlockDestructionPacket;
 use ferrumc_net::packets::outgoing::block_update::BlockUpdate;
 use ferrumc_net::PlayerActionReceiver;
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use ferrumc_state::GlobalStateResource;
 use ferrumc_world::block_state_id::BlockStateId;
-use tracing::{debug, error, trace};
+use tracing::{debug, error};
+
+/// The furthest a player can reach a block from their eyes: the creative interaction range plus
+/// a block of leeway for latency.
+const MAX_REACH: f64 = 6.0;
+/// The height of a player's eyes above their feet.
+const EYE_HEIGHT: f64 = 1.62;
+/// How much of a block has to be broken when the client says it's done, to allow for latency
+/// between the client and server tick counts.
+const MIN_FINISH_PROGRESS: f32 = 0.7;
 
 pub fn handle(
+    mut commands: Commands,
     events: Res<PlayerActionReceiver>,
     state: Res<GlobalStateResource>,
-    query: Query<(Entity, &StreamWriter)>,
+    mut diggers: Query<(
+        &PlayerIdentity,
+        &OnGround,
+        &Abilities,
+        &Health,
+        &Inventory,
+        &Hotbar,
+        &mut Digging,
+    )>,
+    viewers: Viewers,
+    mut containers: ResMut<OpenContainers>,
+    mut block_changes: EventWriter<BlockChangeEvent>,
 ) {
     // https://minecraft.wiki/w/Minecraft_Wiki:Projects/wiki.vg_merge/Protocol?oldid=2773393#Player_Action
     for (event, trigger_eid) in events.0.try_iter() {
-        let res: Result<(), BinaryError> = try {
+        let res = try bikeshed Result<(), BinaryError> {
+            let Ok((_, conn, &dimension, position)) = viewers.get(trigger_eid) else {
+                continue;
+            };
+            let Ok((identity, on_ground, abilities, health, inventory, hotbar, mut digging)) =
+                diggers.get_mut(trigger_eid)
+            else {
+                continue;
+            };
+            let location = event.location.clone();
+            let (x, y, z) = (location.x, location.y as i32, location.z);
+            let container = ContainerPos::new(&location, dimension);
+
             match event.status.0 {
+                // Started digging
                 0 => {
-                    let mut chunk = match state.0.clone().world.load_chunk_owned(
-                        event.location.x >> 4,
-                        event.location.z >> 4,
-                        "overworld",
-                    ) {
-                        Ok(chunk) => chunk,
-                        Err(e) => {
-                            trace!("Chunk not found, generating new chunk: {:?}", e);
-                            state
-                                .0
-                                .clone()
-                                .terrain_generator
-                                .generate_chunk(event.location.x >> 4, event.location.z >> 4)?
-                        }
-                    };
-                    let (relative_x, relative_y, relative_z) = (
-                        event.location.x.abs() % 16,
-                        event.location.y as i32,
-                        event.location.z.abs() % 16,
-                    );
-                    chunk.set_block(relative_x, relative_y, relative_z, BlockStateId::default())?;
-                    // Save the chunk to disk
-                    state.0.world.save_chunk(Arc::new(chunk))?;
-                    for (eid, conn) in query {
-                        if !state.0.players.is_connected(eid) {
-                            continue;
-                        }
-                        // If the player is the one who placed the block, send the BlockChangeAck packet
-                        let block_update_packet = BlockUpdate {
-                            location: event.location.clone(),
-                            block_state_id: VarInt::from(BlockStateId::default()),
-                        };
-                        conn.send_packet_ref(&block_update_packet)?;
-                        if eid == trigger_eid {
-                            let ack_packet = BlockChangeAck {
-                                sequence: event.sequence,
-                            };
-                            conn.send_packet_ref(&ack_packet)?;
+                    let chunk = world_edits::load_chunk(&state.0, x >> 4, z >> 4, dimension)?;
+                    let block = chunk.get_block(x & 0xF, y, z & 0xF)?;
+                    let center = (x as f64 + 0.5, y as f64 + 0.5, z as f64 + 0.5);
+                    let distance_squared = (position.x - center.0).powi(2)
+                        + (position.y + EYE_HEIGHT - center.1).powi(2)
+                        + (position.z - center.2).powi(2);
+                    if !abilities.may_build || health.is_dead() {
+                        // Adventure and spectator players and dead players can't break blocks, so
+                        // put back the block the client may have started breaking
+                        conn.send_packet(BlockUpdate {
+                            location,
+                            block_state_id: VarInt::from(block),
+                        })?;
+                    } else if distance_squared > MAX_REACH * MAX_REACH {
+                        debug!("{} tried to break a block out of reach", identity.username);
+                        conn.send_packet(BlockUpdate {
+                            location,
+                            block_state_id: VarInt::from(block),
+                        })?;
+                    } else if abilities.instant_build {
+                        containers::drop_contents(
+                            &mut commands,
+                            &state.0,
+                            &mut containers,
+                            container,
+                        )?;
+                        world_edits::set_block(
+                            &state.0,
+                            &viewers,
+                            &location,
+                            dimension,
+                            BlockStateId::default(),
+                        )?;
+                        block_changes.write(BlockChangeEvent {
+                            location: location.clone(),
+                            dimension,
+                        });
+                    } else {
+                        let tool = digging::held_tool(inventory, hotbar);
+                        let progress = block.properties().destroy_progress(tool, on_ground.0);
+                        if progress >= 1.0 {
+                            // The client breaks instant blocks without finishing
+                            containers::drop_contents(
+                                &mut commands,
+                                &state.0,
+                                &mut containers,
+                                container,
+                            )?;
+                            world_edits::set_block(
+                                &state.0,
+                                &viewers,
+                                &location,
+                                dimension,
+                                BlockStateId::default(),
+                            )?;
+                            block_changes.write(BlockChangeEvent {
+                                location: location.clone(),
+                                dimension,
+                            });
+                            digging::drop_items(&mut commands, block, tool, &location, dimension);
+                        } else if progress > 0.0 {
+                            digging.0 = Some(DigProgress::new(x, y, z, progress));
+                        } else {
+                            conn.send_packet(BlockUpdate {
+                                location,
+                                block_state_id: VarInt::from(block),
+                            })?;
                         }
                     }
                 }
-
+                // Cancelled digging
                 1 => {
-                    debug!("You shouldn't be seeing this in creative mode.");
+                    if let Some(dig) = digging.0.take() {
+                        let packet = BlockDestructionPacket::clear(identity.entity_id, location);
+                        digging::send_nearby(
+                            &state.0,
+                            &viewers,
+                            Some(trigger_eid),
+                            dimension,
+                            &dig,
+                            &packet,
+                        );
+                    }
+                }
+                // Finished digging
+                2 => {
+                    let chunk = world_edits::load_chunk(&state.0, x >> 4, z >> 4, dimension)?;
+                    let block = chunk.get_block(x & 0xF, y, z & 0xF)?;
+                    let finished = digging.0.take().filter(|dig| {
+                        let ticks = dig.ticks(get_global_config().tps);
+                        (dig.x, dig.y, dig.z) == (x, y, z)
+                            && dig.progress(ticks + 1.0) >= MIN_FINISH_PROGRESS
+                    });
+                    match finished {
+                        Some(dig) => {
+                            let packet =
+                                BlockDestructionPacket::clear(identity.entity_id, location.clone());
+                            digging::send_nearby(
+                                &state.0,
+                                &viewers,
+                                Some(trigger_eid),
+                                dimension,
+                                &dig,
+                                &packet,
+                            );
+                            let tool = digging::held_tool(inventory, hotbar);
+                            containers::drop_contents(
+                                &mut commands,
+                                &state.0,
+                                &mut containers,
+                                container,
+                            )?;
+                            world_edits::set_block(
+                                &state.0,
+                                &viewers,
+                                &location,
+                                dimension,
+                                BlockStateId::default(),
+                            )?;
+                            block_changes.write(BlockChangeEvent {
+                                location: location.clone(),
+                                dimension,
+                            });
+                            digging::drop_items(&mut commands, block, tool, &location, dimension);
+                        }
+                        None => {
+                            debug!("{} finished digging too early", identity.username);
+                            conn.send_packet(BlockUpdate {
+                                location,
+                                block_state_id: VarInt::from(block),
+                            })?;
+                        }
+                    }
                 }
-                _ => {}
+                _ => continue,
             };
+            conn.send_packet(BlockChangeAck {
+                sequence: event.sequence,
+            })?;
         };
         if res.is_err() {
             error!("Error handling player action: {:?}", res);
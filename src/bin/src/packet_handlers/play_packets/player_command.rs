@@ -1,17 +1,40 @@
-use bevy_ecs::prelude::{Entity, Query, Res};
-use ferrumc_net::connection::StreamWriter;
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
+use bevy_ecs::prelude::{Query, Res};
+use ferrumc_core::movement::MovementState;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_inventories::defined_slots::player::CHEST_SLOT;
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_inventories::item::ItemID;
 use ferrumc_net::packets::incoming::player_command::PlayerCommandAction;
 use ferrumc_net::packets::outgoing::entity_metadata::{EntityMetadata, EntityMetadataPacket};
 use ferrumc_net::PlayerCommandPacketReceiver;
 use ferrumc_state::GlobalStateResource;
-use tracing::error;
 
 pub fn handle(
     events: Res<PlayerCommandPacketReceiver>,
-    query: Query<(Entity, &StreamWriter)>,
+    mut movement: Query<(&mut MovementState, &Inventory, &OnGround)>,
+    trackers: Trackers,
     state: Res<GlobalStateResource>,
 ) {
-    for (event, _) in events.0.try_iter() {
+    for (event, eid) in events.0.try_iter() {
+        if let Ok((mut movement, inventory, on_ground)) = movement.get_mut(eid) {
+            match event.action {
+                PlayerCommandAction::StartSneaking => movement.sneaking = true,
+                PlayerCommandAction::StopSneaking => movement.sneaking = false,
+                PlayerCommandAction::StartSprinting => movement.sprinting = true,
+                PlayerCommandAction::StopSprinting => movement.sprinting = false,
+                PlayerCommandAction::StartFlyingWithElytra => {
+                    let has_elytra = inventory
+                        .get_item(CHEST_SLOT as usize)
+                        .ok()
+                        .flatten()
+                        .and_then(|slot| slot.item_id)
+                        .is_some_and(|id| Some(id) == ItemID::from_name("minecraft:elytra"));
+                    movement.start_gliding(has_elytra, on_ground.0);
+                }
+                _ => {}
+            }
+        }
         match event.action {
             PlayerCommandAction::StartSneaking => {
                 let packet = EntityMetadataPacket::new(
@@ -21,29 +44,12 @@ pub fn handle(
                         EntityMetadata::entity_sneaking_pressed(),
                     ],
                 );
-
-                // TODO: Don't clone
-                for (entity, conn) in query {
-                    if !state.0.players.is_connected(entity) {
-                        continue;
-                    }
-                    if let Err(err) = conn.send_packet_ref(&packet) {
-                        error!("Failed to send start sneaking packet: {:?}", err);
-                    }
-                }
+                send_to_trackers(&state.0, &trackers, eid, &packet);
             }
             PlayerCommandAction::StopSneaking => {
                 let packet =
                     EntityMetadataPacket::new(event.entity_id, [EntityMetadata::entity_standing()]);
-
-                for (entity, conn) in query {
-                    if !state.0.players.is_connected(entity) {
-                        continue;
-                    }
-                    if let Err(err) = conn.send_packet_ref(&packet) {
-                        error!("Failed to send stop sneaking packet: {:?}", err);
-                    }
-                }
+                send_to_trackers(&state.0, &trackers, eid, &packet);
             }
             _ => {}
         }
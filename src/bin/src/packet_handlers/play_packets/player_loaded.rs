@@ -1,8 +1,11 @@
 use bevy_ecs::prelude::{Entity, Query, Res};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::movement::MovementValidator;
 use ferrumc_core::transform::position::Position;
 use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket;
 use ferrumc_net::PlayerLoadedReceiver;
+use ferrumc_net_codec::net_types::var_int::VarInt;
 use ferrumc_state::GlobalStateResource;
 use ferrumc_world::block_state_id::BlockStateId;
 use tracing::warn;
@@ -10,10 +13,17 @@ use tracing::warn;
 pub fn handle(
     ev: Res<PlayerLoadedReceiver>,
     state: Res<GlobalStateResource>,
-    query: Query<(Entity, &Position, &StreamWriter)>,
+    mut query: Query<(
+        Entity,
+        &mut Position,
+        &Dimension,
+        &StreamWriter,
+        &mut MovementValidator,
+    )>,
 ) {
     for (_, player) in ev.0.try_iter() {
-        let Ok((entity, player_pos, conn)) = query.get(player) else {
+        let Ok((entity, mut player_pos, dimension, conn, mut validator)) = query.get_mut(player)
+        else {
             warn!("Player position not found in query.");
             continue;
         };
@@ -28,7 +38,7 @@ pub fn handle(
             player_pos.x as i32,
             player_pos.y as i32,
             player_pos.z as i32,
-            "overworld",
+            dimension.name(),
         );
         if let Ok(head_block) = head_block {
             if head_block == BlockStateId(0) {
@@ -49,7 +59,10 @@ pub fn handle(
                     head_block
                 );
                 // Teleport the player to the world center if their head block is not air
-                let packet = SynchronizePlayerPositionPacket::default();
+                let mut packet = SynchronizePlayerPositionPacket::default();
+                let spawn = (packet.x, packet.y, packet.z);
+                packet.teleport_id = VarInt::new(validator.teleport(spawn));
+                *player_pos = spawn.into();
                 if let Err(e) = conn.send_packet_ref(&packet) {
                     tracing::error!(
                         "Failed to send synchronize player position packet for player {}: {:?}",
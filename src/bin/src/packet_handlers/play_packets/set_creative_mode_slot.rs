@@ -1,16 +1,17 @@
 use bevy_ecs::prelude::{Query, Res};
+use ferrumc_core::game_mode::GameMode;
 use ferrumc_inventories::defined_slots::player::HOTBAR_SLOT_6;
 use ferrumc_inventories::inventory::Inventory;
 use ferrumc_inventories::item::ItemID;
 use ferrumc_inventories::slot::InventorySlot;
 use ferrumc_net::SetCreativeModeSlotReceiver;
 use ferrumc_state::GlobalStateResource;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 pub fn handle(
     events: Res<SetCreativeModeSlotReceiver>,
     state: Res<GlobalStateResource>,
-    mut query: Query<&mut Inventory>,
+    mut query: Query<(&mut Inventory, &GameMode)>,
 ) {
     for (event, entity) in events.0.try_iter() {
         debug!(
@@ -18,7 +19,15 @@ pub fn handle(
             event.slot, event.slot_index, entity
         );
         if state.0.players.is_connected(entity) {
-            if let Ok(mut inventory) = query.get_mut(entity) {
+            if let Ok((mut inventory, game_mode)) = query.get_mut(entity) {
+                // Only creative players can take items out of thin air
+                if *game_mode != GameMode::Creative {
+                    warn!(
+                        "Player {} tried to set a creative slot in {}",
+                        entity, game_mode
+                    );
+                    continue;
+                }
                 if event.slot.count.0 == 0 {
                     // Clear the slot if the count is zero
                     if let Err(e) = inventory.remove_item(event.slot_index as usize) {
@@ -9,7 +9,8 @@ pub fn handle(
     for (event, eid) in events.0.try_iter() {
         let transform_event = TransformEvent::new(eid)
             .position((event.x, event.feet_y, event.z).into())
-            .rotation((event.yaw, event.pitch).into());
+            .rotation((event.yaw, event.pitch).into())
+            .on_ground(event.flags & 0x01 != 0);
         transform_event_writer.write(transform_event);
     }
 }
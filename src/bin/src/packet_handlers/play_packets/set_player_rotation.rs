@@ -7,7 +7,9 @@ pub fn handle(
     mut event_writer: EventWriter<TransformEvent>,
 ) {
     for (event, eid) in events.0.try_iter() {
-        let transform_event = TransformEvent::new(eid).rotation((event.yaw, event.pitch).into());
+        let transform_event = TransformEvent::new(eid)
+            .rotation((event.yaw, event.pitch).into())
+            .on_ground(event.flags & 0x01 != 0);
         event_writer.write(transform_event);
     }
 }
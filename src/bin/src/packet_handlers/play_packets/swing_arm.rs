@@ -1,16 +1,15 @@
-use bevy_ecs::prelude::{Entity, Query, Res};
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
+use bevy_ecs::prelude::{Query, Res};
 use ferrumc_core::identity::player_identity::PlayerIdentity;
-use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::packets::outgoing::entity_animation::EntityAnimationPacket;
 use ferrumc_net::SwingArmPacketReceiver;
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use ferrumc_state::GlobalStateResource;
-use tracing::error;
 
 pub fn handle(
     events: Res<SwingArmPacketReceiver>,
     query: Query<&PlayerIdentity>,
-    conn_query: Query<(Entity, &StreamWriter)>,
+    trackers: Trackers,
     state: Res<GlobalStateResource>,
 ) {
     for (event, eid) in events.0.try_iter() {
@@ -22,17 +21,7 @@ pub fn handle(
             }
         };
         let game_id = query.get(eid).expect("Game ID not found");
-        let packet = EntityAnimationPacket::new(VarInt::new(game_id.short_uuid), animation);
-        for (entity, conn) in conn_query.iter() {
-            if entity == eid {
-                continue; // Skip sending to the player who triggered the event
-            }
-            if !state.0.players.is_connected(entity) {
-                continue; // Skip if the player is not connected
-            }
-            if let Err(e) = conn.send_packet_ref(&packet) {
-                error!("Failed to send packet: {}", e);
-            }
-        }
+        let packet = EntityAnimationPacket::new(VarInt::new(game_id.entity_id), animation);
+        send_to_trackers(&state.0, &trackers, eid, &packet);
     }
 }
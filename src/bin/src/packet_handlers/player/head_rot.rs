@@ -1,41 +1,35 @@
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
 use bevy_ecs::event::EventReader;
-use bevy_ecs::prelude::Query;
+use bevy_ecs::prelude::{Query, Res};
 use ferrumc_core::identity::player_identity::PlayerIdentity;
-use ferrumc_core::transform::rotation::Rotation;
-use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::packets::outgoing::set_head_rotation::SetHeadRotationPacket;
 use ferrumc_net::packets::packet_events::TransformEvent;
 use ferrumc_net_codec::net_types::angle::NetAngle;
-use tracing::error;
+use ferrumc_state::GlobalStateResource;
 
 pub fn handle_player_move(
     mut events: EventReader<TransformEvent>,
-    query: Query<(&Rotation, &PlayerIdentity)>,
-    broadcast_query: Query<&StreamWriter>,
+    query: Query<&PlayerIdentity>,
+    trackers: Trackers,
+    state: Res<GlobalStateResource>,
 ) {
     for event in events.read() {
         let entity = event.entity;
 
-        let Ok((rot, identity)) = query.get(entity) else {
+        let Some(rot) = &event.rotation else {
+            continue;
+        };
+        let Ok(identity) = query.get(entity) else {
             continue;
         };
 
-        let head_rot_packet = SetHeadRotationPacket::new(
-            identity.uuid.as_u128() as i32,
-            NetAngle::from_degrees(rot.yaw as f64),
-        );
+        let head_rot_packet =
+            SetHeadRotationPacket::new(identity.entity_id, NetAngle::from_degrees(rot.yaw as f64));
 
         #[cfg(debug_assertions)]
         let start = std::time::Instant::now();
 
-        for writer in broadcast_query.iter() {
-            if !writer.running.load(std::sync::atomic::Ordering::Relaxed) {
-                continue;
-            }
-            if let Err(err) = writer.send_packet_ref(&head_rot_packet) {
-                error!("Failed to send head rotation packet: {:?}", err);
-            }
-        }
+        send_to_trackers(&state.0, &trackers, entity, &head_rot_packet);
 
         #[cfg(debug_assertions)]
         tracing::trace!("broadcasting entity move took {:?}", start.elapsed());
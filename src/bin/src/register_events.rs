@@ -1,8 +1,13 @@
 use bevy_ecs::event::EventRegistry;
 use bevy_ecs::prelude::World;
 use ferrumc_commands::events::{CommandDispatchEvent, ResolvedCommandDispatchEvent};
+use ferrumc_core::chunks::block_change_event::BlockChangeEvent;
 use ferrumc_core::chunks::cross_chunk_boundary_event::CrossChunkBoundaryEvent;
 use ferrumc_core::conn::force_player_recount_event::ForcePlayerRecountEvent;
+use ferrumc_core::dimension::change_dimension_event::ChangeDimensionEvent;
+use ferrumc_core::game_mode::change_game_mode_event::ChangeGameModeEvent;
+use ferrumc_core::health::damage_event::DamageEvent;
+use ferrumc_core::movement::flag_event::MovementFlagEvent;
 use ferrumc_net::packets::packet_events::TransformEvent;
 
 pub fn register_events(world: &mut World) {
@@ -11,4 +16,9 @@ pub fn register_events(world: &mut World) {
     EventRegistry::register_event::<ForcePlayerRecountEvent>(world);
     EventRegistry::register_event::<CommandDispatchEvent>(world);
     EventRegistry::register_event::<ResolvedCommandDispatchEvent>(world);
+    EventRegistry::register_event::<ChangeDimensionEvent>(world);
+    EventRegistry::register_event::<BlockChangeEvent>(world);
+    EventRegistry::register_event::<DamageEvent>(world);
+    EventRegistry::register_event::<MovementFlagEvent>(world);
+    EventRegistry::register_event::<ChangeGameModeEvent>(world);
 }
@@ -1,17 +1,24 @@
+use crate::systems::containers::OpenContainers;
 use crate::systems::new_connections::NewConnectionRecv;
+use crate::systems::rcon::PendingRconResponses;
 use bevy_ecs::prelude::World;
 use crossbeam_channel::Receiver;
 use ferrumc_core::chunks::world_sync_tracker::WorldSyncTracker;
 use ferrumc_core::conn::player_count_update_cooldown::PlayerCountUpdateCooldown;
 use ferrumc_net::connection::NewConnection;
+use ferrumc_net::rcon::{RconCommand, RconCommandReceiver};
 use ferrumc_state::GlobalStateResource;
 
 pub fn register_resources(
     world: &mut World,
     new_conn_recv: Receiver<NewConnection>,
+    rcon_recv: Receiver<RconCommand>,
     global_state: GlobalStateResource,
 ) {
     world.insert_resource(NewConnectionRecv(new_conn_recv));
+    world.insert_resource(RconCommandReceiver(rcon_recv));
+    world.insert_resource(PendingRconResponses::default());
+    world.insert_resource(OpenContainers::default());
     world.insert_resource(global_state);
     world.insert_resource(PlayerCountUpdateCooldown {
         last_update: std::time::Instant::now(),
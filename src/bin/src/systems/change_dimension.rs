@@ -0,0 +1,163 @@
+use crate::errors::BinaryError;
+use crate::systems::send_chunks::send_chunks;
+use bevy_ecs::prelude::{EventReader, Mut, Query, Res};
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::chunks::chunk_receiver::ChunkReceiver;
+use ferrumc_core::digging::Digging;
+use ferrumc_core::dimension::change_dimension_event::ChangeDimensionEvent;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::health::FallDistance;
+use ferrumc_core::movement::MovementValidator;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_macros::block;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::game_event::GameEventPacket;
+use ferrumc_net::packets::outgoing::respawn::RespawnPacket;
+use ferrumc_net::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::{GlobalState, GlobalStateResource};
+use ferrumc_world::block_state_id::BlockStateId;
+use tracing::{debug, error};
+
+type Traveller = (
+    &'static mut Dimension,
+    &'static mut Position,
+    &'static Rotation,
+    &'static mut ChunkReceiver,
+    &'static mut StreamWriter,
+    &'static mut Digging,
+    &'static mut FallDistance,
+    &'static mut MovementValidator,
+    &'static GameMode,
+);
+
+pub fn change_dimension(
+    mut events: EventReader<ChangeDimensionEvent>,
+    mut query: Query<Traveller>,
+    state: Res<GlobalStateResource>,
+) {
+    for event in events.read() {
+        if !state.0.players.is_connected(event.player) {
+            continue;
+        }
+        let Ok((
+            mut dimension,
+            mut position,
+            rotation,
+            mut receiver,
+            mut conn,
+            mut digging,
+            mut fall_distance,
+            mut validator,
+            game_mode,
+        )) = query.get_mut(event.player)
+        else {
+            debug!("Player {:?} can't change dimension", event.player);
+            continue;
+        };
+
+        let res = try bikeshed Result<(), BinaryError> {
+            // The End only has the main island, so travelling to or from it lands at the origin
+            let (x, z) = if *dimension == Dimension::End || event.dimension == Dimension::End {
+                (0.5, 0.5)
+            } else {
+                let scale = dimension.coordinate_scale() / event.dimension.coordinate_scale();
+                (position.x * scale, position.z * scale)
+            };
+            *position = respawn(
+                &state.0,
+                &mut conn,
+                RespawnPacket::new(event.dimension, *game_mode),
+                event.dimension,
+                (x, z),
+                rotation,
+                &mut validator,
+            )?;
+            *dimension = event.dimension;
+            *receiver = ChunkReceiver::new();
+            digging.0 = None;
+            fall_distance.reset();
+        };
+        if let Err(e) = res {
+            error!(
+                "Failed to move player {:?} to {}: {:?}",
+                event.player, event.dimension, e
+            );
+        }
+    }
+}
+
+/// Sends a player through the respawn `packet` into `dimension`, on the ground at `x`, `z`, and
+/// resends the chunks around them. Returns where they ended up, which their moves are checked
+/// from once the client confirms the teleport.
+pub fn respawn(
+    state: &GlobalState,
+    conn: &mut Mut<StreamWriter>,
+    packet: RespawnPacket,
+    dimension: Dimension,
+    (x, z): (f64, f64),
+    rotation: &Rotation,
+    validator: &mut MovementValidator,
+) -> Result<Position, BinaryError> {
+    let y = spawn_height(state, dimension, x.floor() as i32, z.floor() as i32)?;
+
+    conn.send_packet(packet)?;
+    conn.send_packet(GameEventPacket::start_waiting_for_level_chunks())?;
+    conn.send_packet(SynchronizePlayerPositionPacket::new(
+        (x, y, z),
+        (0.0, 0.0, 0.0),
+        rotation.yaw,
+        rotation.pitch,
+        0,
+        VarInt::new(validator.teleport((x, y, z))),
+    ))?;
+
+    // The client drops all chunks when it respawns, so the whole view has to be resent
+    let radius = get_global_config().chunk_render_distance as i32;
+    let (center_x, center_z) = (x.floor() as i32 >> 4, z.floor() as i32 >> 4);
+    let mut chunks = Vec::new();
+    for chunk_x in center_x - radius..=center_x + radius {
+        for chunk_z in center_z - radius..=center_z + radius {
+            chunks.push((chunk_x, chunk_z, dimension.name().to_string()));
+        }
+    }
+    send_chunks(state.clone(), chunks, conn, (center_x, center_z))?;
+    Ok(Position::new(x, y, z))
+}
+
+/// Finds the lowest spot in the column at `x`, `z` with two blocks of air above solid ground,
+/// falling back to the top of the dimension.
+fn spawn_height(
+    state: &GlobalState,
+    dimension: Dimension,
+    x: i32,
+    z: i32,
+) -> Result<f64, BinaryError> {
+    let (chunk_x, chunk_z) = (x >> 4, z >> 4);
+    let chunk = if state
+        .world
+        .chunk_exists(chunk_x, chunk_z, dimension.name())?
+    {
+        state
+            .world
+            .load_chunk_owned(chunk_x, chunk_z, dimension.name())?
+    } else {
+        state
+            .terrain_generator
+            .generate_chunk(chunk_x, chunk_z, dimension)?
+    };
+
+    let air = BlockStateId::default();
+    let lava = block!("lava", {level: 0});
+    let column = (dimension.min_y()..dimension.min_y() + dimension.height())
+        .map(|y| chunk.get_block(x & 0xF, y, z & 0xF))
+        .collect::<Result<Vec<_>, _>>()?;
+    let height = column
+        .windows(3)
+        .position(|blocks| blocks[0] != air && blocks[0] != lava && blocks[1..] == [air, air])
+        .map(|index| index as i32 + 1 + dimension.min_y())
+        .unwrap_or(dimension.min_y() + dimension.height());
+    Ok(height as f64)
+}
@@ -1,15 +1,36 @@
-use crate::systems::system_messages;
-use bevy_ecs::prelude::{Commands, Entity, Query, Res};
+use crate::systems::containers::{self, ContainerWindow, OpenContainers};
+use crate::systems::{entities, system_messages};
+use bevy_ecs::prelude::{Commands, Entity, Query, Res, ResMut};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::game_mode::GameMode;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_inventories::hotbar::Hotbar;
+use ferrumc_inventories::inventory::Inventory;
 use ferrumc_net::connection::StreamWriter;
 use ferrumc_state::GlobalStateResource;
 use ferrumc_text::TextComponent;
-use tracing::{info, trace, warn};
+use tracing::{error, info, trace, warn};
+
+/// What gets saved of a player when they leave, and the window they might still have open.
+type SavedPlayerData<'a> = (
+    &'a Dimension,
+    &'a Position,
+    &'a Rotation,
+    &'a mut Inventory,
+    &'a Hotbar,
+    &'a GameMode,
+    Option<&'a mut ContainerWindow>,
+);
 
 pub fn connection_killer(
     query: Query<(Entity, &StreamWriter, &PlayerIdentity)>,
+    mut player_data: Query<SavedPlayerData>,
     mut cmd: Commands,
     state: Res<GlobalStateResource>,
+    mut open_containers: ResMut<OpenContainers>,
 ) {
     while let Some((disconnecting_entity, reason)) = state.0.players.disconnection_queue.pop() {
         let disconnecting_player_identity = query
@@ -55,6 +76,50 @@ pub fn connection_killer(
                         player_identity.username
                     );
                 }
+                if let Ok((
+                    dimension,
+                    position,
+                    rotation,
+                    mut inventory,
+                    hotbar,
+                    game_mode,
+                    window,
+                )) = player_data.get_mut(entity)
+                {
+                    // Whatever they were carrying or crafting with goes back in their
+                    // inventory, or on the ground
+                    if let Some(mut window) = window {
+                        let rest = containers::close(
+                            &mut open_containers,
+                            entity,
+                            &mut window,
+                            &mut inventory,
+                        );
+                        for item in rest {
+                            let position = Position::new(position.x, position.y, position.z);
+                            entities::spawn_item(
+                                &mut cmd,
+                                item,
+                                position,
+                                Velocity::default(),
+                                *dimension,
+                            );
+                        }
+                    }
+                    let data = crate::systems::player_data::snapshot(
+                        dimension, position, rotation, &inventory, hotbar, game_mode,
+                    );
+                    if let Err(e) = state
+                        .0
+                        .world
+                        .save_player_data(player_identity.uuid.as_u128(), &data)
+                    {
+                        error!(
+                            "Failed to save player data for {}: {}",
+                            player_identity.username, e
+                        );
+                    }
+                }
                 cmd.entity(entity).despawn();
             } else {
                 system_messages::player_leave::handle(&disconnecting_player_identity, entity);
@@ -0,0 +1,405 @@
+//! Player windows, on their own inventory, a crafting table, or a container shared between every
+//! player looking into it.
+//!
+//! A container's items are kept in memory while anyone has it open, and every change is written
+//! back to its block entity straight away. Breaking a container spills its items and closes the
+//! window of everyone looking into it.
+
+use crate::errors::BinaryError;
+use crate::systems::{entities, world_edits};
+use bevy_ecs::prelude::{Commands, Component, Entity, Query, ResMut, Resource};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_inventories::container::{self, ContainerKind};
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_inventories::window::{Drag, Window, CRAFTING_SLOTS};
+use ferrumc_macros::block;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::container_close::ContainerClose;
+use ferrumc_net::packets::outgoing::open_screen::OpenScreen;
+use ferrumc_net::packets::outgoing::set_container_content::SetContainerContent;
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_recipes::RECIPES;
+use ferrumc_state::GlobalState;
+use ferrumc_text::ComponentBuilder;
+use ferrumc_world::block_state_id::BlockStateId;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+/// Window ids go from 1 up to this, 0 being the player's own inventory.
+const MAX_WINDOW_ID: u8 = 100;
+/// State ids wrap around like vanilla's do.
+const STATE_ID_MASK: i32 = 0x7FFF;
+
+/// Where a container is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContainerPos {
+    pub dimension: Dimension,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl ContainerPos {
+    pub fn new(location: &NetworkPosition, dimension: Dimension) -> Self {
+        Self {
+            dimension,
+            x: location.x,
+            y: location.y as i32,
+            z: location.z,
+        }
+    }
+}
+
+pub struct OpenContainer {
+    pub kind: ContainerKind,
+    pub slots: Vec<Option<InventorySlot>>,
+    /// The players with the container open.
+    pub viewers: Vec<Entity>,
+}
+
+/// The containers players have open.
+#[derive(Resource, Default)]
+pub struct OpenContainers {
+    pub containers: HashMap<ContainerPos, OpenContainer>,
+    last_window_id: u8,
+}
+
+impl OpenContainers {
+    fn next_window_id(&mut self) -> u8 {
+        self.last_window_id = self.last_window_id % MAX_WINDOW_ID + 1;
+        self.last_window_id
+    }
+
+    /// Stops `player` viewing any container, forgetting containers nobody is viewing any more.
+    pub fn remove_viewer(&mut self, player: Entity) {
+        self.containers.retain(|_, container| {
+            container.viewers.retain(|&viewer| viewer != player);
+            !container.viewers.is_empty()
+        });
+    }
+}
+
+/// What a player's window is open on.
+pub enum Menu {
+    /// The player's own inventory, open whenever nothing else is.
+    Inventory,
+    /// A container block, which other players can have open too.
+    Container(ContainerPos),
+    /// A crafting table, whose result and grid belong to the window.
+    Crafting(Vec<Option<InventorySlot>>),
+}
+
+/// The window a player has open, their own inventory when it's nothing else.
+#[derive(Component)]
+pub struct ContainerWindow {
+    pub window_id: u8,
+    pub menu: Menu,
+    /// The last state id the player was sent, which their clicks say they've seen.
+    pub state_id: i32,
+    /// The items held by the cursor.
+    pub carried: Option<InventorySlot>,
+    pub drag: Drag,
+}
+
+impl Default for ContainerWindow {
+    fn default() -> Self {
+        Self {
+            window_id: 0,
+            menu: Menu::Inventory,
+            state_id: 0,
+            carried: None,
+            drag: Drag::default(),
+        }
+    }
+}
+
+impl ContainerWindow {
+    /// Moves on to the next state id, for sending the player a change.
+    pub fn next_state_id(&mut self) -> VarInt {
+        self.state_id = (self.state_id + 1) & STATE_ID_MASK;
+        VarInt::new(self.state_id)
+    }
+
+    /// Where the container the window is open on is, if it's on one.
+    pub fn container(&self) -> Option<ContainerPos> {
+        match self.menu {
+            Menu::Container(pos) => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// The window's slots and what clicking in them does, or `None` if its container has gone.
+    pub fn view<'a>(
+        &'a mut self,
+        containers: &'a mut OpenContainers,
+        inventory: &'a mut Inventory,
+    ) -> Option<Window<'a>> {
+        let Self {
+            menu,
+            carried,
+            drag,
+            ..
+        } = self;
+        Some(match menu {
+            Menu::Inventory => Window::inventory(inventory, carried, drag, &*RECIPES),
+            Menu::Container(pos) => Window::new(
+                &mut containers.containers.get_mut(pos)?.slots,
+                inventory,
+                carried,
+                drag,
+            ),
+            Menu::Crafting(slots) => Window::crafting(slots, inventory, carried, drag, &*RECIPES),
+        })
+    }
+}
+
+/// Opens the container or crafting table at `pos` for `player`, returning `false` if there isn't
+/// one there.
+///
+/// Sneaking players should place blocks against them instead, but sneaking isn't tracked yet so
+/// they always open.
+pub fn open(
+    state: &GlobalState,
+    containers: &mut OpenContainers,
+    player: Entity,
+    conn: &StreamWriter,
+    window: &mut ContainerWindow,
+    inventory: &mut Inventory,
+    pos: ContainerPos,
+) -> Result<bool, BinaryError> {
+    // Forget containers whose block was replaced without being broken
+    if let Some(open) = containers.containers.get(&pos) {
+        let chunk = world_edits::load_chunk(state, pos.x >> 4, pos.z >> 4, pos.dimension)?;
+        let block = chunk.get_block(pos.x, pos.y, pos.z)?;
+        if block
+            .block_entity_type()
+            .and_then(ContainerKind::from_block_entity)
+            != Some(open.kind)
+        {
+            containers.containers.remove(&pos);
+        }
+    }
+    let menu = match containers.containers.entry(pos) {
+        Entry::Occupied(_) => Menu::Container(pos),
+        Entry::Vacant(entry) => {
+            let chunk = world_edits::load_chunk(state, pos.x >> 4, pos.z >> 4, pos.dimension)?;
+            let container = chunk
+                .get_block_entity(pos.x, pos.y, pos.z)
+                .and_then(|block_entity| {
+                    let kind = ContainerKind::from_block_entity(&block_entity.id)?;
+                    Some((kind, block_entity.items(kind.size())))
+                });
+            match container {
+                Some((kind, slots)) => {
+                    entry.insert(OpenContainer {
+                        kind,
+                        slots,
+                        viewers: Vec::new(),
+                    });
+                    Menu::Container(pos)
+                }
+                None if chunk.get_block(pos.x, pos.y, pos.z)? == block!("crafting_table") => {
+                    Menu::Crafting(vec![None; CRAFTING_SLOTS])
+                }
+                None => return Ok(false),
+            }
+        }
+    };
+    let (window_type, title) = match &menu {
+        Menu::Container(pos) => {
+            let Some(container) = containers.containers.get_mut(pos) else {
+                return Ok(false);
+            };
+            if !container.viewers.contains(&player) {
+                container.viewers.push(player);
+            }
+            (container.kind.menu_id(), container.kind.title_key())
+        }
+        Menu::Crafting(_) => (
+            container::menu_id("minecraft:crafting"),
+            "container.crafting",
+        ),
+        Menu::Inventory => return Ok(false),
+    };
+    let window_type =
+        window_type.ok_or_else(|| BinaryError::Custom(format!("No menu for {title}")))?;
+    let window_id = containers.next_window_id();
+    *window = ContainerWindow {
+        window_id,
+        menu,
+        ..ContainerWindow::default()
+    };
+
+    conn.send_packet(OpenScreen {
+        window_id: VarInt::new(window_id.into()),
+        window_type,
+        title: ComponentBuilder::translate(title, vec![]),
+    })?;
+    let Some(view) = window.view(containers, inventory) else {
+        return Ok(false);
+    };
+    conn.send_packet(SetContainerContent {
+        window_id: VarInt::new(window_id.into()),
+        state_id: VarInt::new(0),
+        slots: LengthPrefixedVec::new(view.contents()),
+        carried_item: InventorySlot::default(),
+    })?;
+    Ok(true)
+}
+
+/// Closes `player`'s window, putting what they were carrying and what was left in the crafting
+/// grid back in their inventory, and returns what didn't fit. Their own inventory is what's open
+/// afterwards.
+pub fn close(
+    containers: &mut OpenContainers,
+    player: Entity,
+    window: &mut ContainerWindow,
+    inventory: &mut Inventory,
+) -> Vec<InventorySlot> {
+    let rest = match window.view(containers, inventory) {
+        Some(mut view) => view.close(),
+        None => Window::new(&mut [], inventory, &mut window.carried, &mut window.drag).close(),
+    };
+    containers.remove_viewer(player);
+    *window = ContainerWindow::default();
+    rest
+}
+
+/// Spills the items of the container at `pos` before its block is broken, if there is one there.
+///
+/// The container is forgotten, so [`close_broken`] closes the windows of the players that had it
+/// open.
+pub fn drop_contents(
+    commands: &mut Commands,
+    state: &GlobalState,
+    containers: &mut OpenContainers,
+    pos: ContainerPos,
+) -> Result<(), BinaryError> {
+    // What players have open is never behind the block entity
+    let items = match containers.containers.remove(&pos) {
+        Some(open) => open.slots,
+        None => {
+            let chunk = world_edits::load_chunk(state, pos.x >> 4, pos.z >> 4, pos.dimension)?;
+            let Some(block_entity) = chunk.get_block_entity(pos.x, pos.y, pos.z) else {
+                return Ok(());
+            };
+            let Some(kind) = ContainerKind::from_block_entity(&block_entity.id) else {
+                return Ok(());
+            };
+            block_entity.items(kind.size())
+        }
+    };
+    for item in items.into_iter().flatten() {
+        let position = Position::new(pos.x as f64 + 0.5, pos.y as f64 + 0.5, pos.z as f64 + 0.5);
+        // Scattered like vanilla's
+        let velocity = Velocity::new(
+            rand::random_range(-0.1..0.1),
+            0.2,
+            rand::random_range(-0.1..0.1),
+        );
+        entities::spawn_item(commands, item, position, velocity, pos.dimension);
+    }
+    Ok(())
+}
+
+/// The players whose window might need closing.
+type WindowQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static StreamWriter,
+        &'static mut ContainerWindow,
+        &'static mut Inventory,
+        &'static Position,
+        &'static Dimension,
+    ),
+>;
+
+/// Closes the windows of players whose container has gone, putting what they were carrying back
+/// in their inventory.
+pub fn close_broken(
+    mut commands: Commands,
+    mut containers: ResMut<OpenContainers>,
+    mut players: WindowQuery,
+) {
+    for (player, conn, mut window, mut inventory, position, &dimension) in &mut players {
+        let Some(pos) = window.container() else {
+            continue;
+        };
+        // A container put back in the same place is a different one
+        if containers
+            .containers
+            .get(&pos)
+            .is_some_and(|open| open.viewers.contains(&player))
+        {
+            continue;
+        }
+        if let Err(err) = conn.send_packet(ContainerClose {
+            window_id: VarInt::new(window.window_id.into()),
+        }) {
+            error!(
+                "Failed to close the window of a broken container: {:?}",
+                err
+            );
+        }
+        let slots_before = inventory.slots.clone();
+        let rest = close(&mut containers, player, &mut window, &mut inventory);
+        if inventory.slots != slots_before {
+            if let Err(err) = send_inventory(conn, &inventory) {
+                error!("Failed to send inventory after closing a window: {:?}", err);
+            }
+        }
+        for item in rest {
+            let position = Position::new(position.x, position.y, position.z);
+            entities::spawn_item(
+                &mut commands,
+                item,
+                position,
+                Velocity::default(),
+                dimension,
+            );
+        }
+    }
+}
+
+/// Writes a container's items back to its block entity and saves the chunk.
+pub fn save(
+    state: &GlobalState,
+    pos: ContainerPos,
+    slots: &[Option<InventorySlot>],
+) -> Result<(), BinaryError> {
+    let mut chunk = world_edits::load_chunk(state, pos.x >> 4, pos.z >> 4, pos.dimension)?;
+    let Some(block_entity) = chunk.get_block_entity_mut(pos.x, pos.y, pos.z) else {
+        warn!("Container at {:?} has no block entity any more", pos);
+        return Ok(());
+    };
+    block_entity.set_items(slots)?;
+    state.world.save_chunk(Arc::new(chunk))?;
+    Ok(())
+}
+
+/// Sends the whole of a player's own inventory, after the server changed it without them
+/// clicking.
+pub fn send_inventory(conn: &StreamWriter, inventory: &Inventory) -> Result<(), BinaryError> {
+    conn.send_packet(SetContainerContent {
+        window_id: VarInt::new(0),
+        state_id: VarInt::new(0),
+        slots: LengthPrefixedVec::new(
+            inventory
+                .slots
+                .iter()
+                .map(|slot| slot.clone().unwrap_or_default())
+                .collect(),
+        ),
+        carried_item: InventorySlot::default(),
+    })?;
+    Ok(())
+}
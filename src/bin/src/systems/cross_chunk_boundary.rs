@@ -2,13 +2,14 @@ use crate::systems::send_chunks::send_chunks;
 use bevy_ecs::prelude::{EventReader, Query, Res};
 use ferrumc_config::server_config::get_global_config;
 use ferrumc_core::chunks::cross_chunk_boundary_event::CrossChunkBoundaryEvent;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_net::connection::StreamWriter;
 use ferrumc_state::GlobalStateResource;
 use std::collections::HashSet;
 
 pub fn cross_chunk_boundary(
     mut events: EventReader<CrossChunkBoundaryEvent>,
-    mut query: Query<&mut StreamWriter>,
+    mut query: Query<(&mut StreamWriter, &Dimension)>,
     state: Res<GlobalStateResource>,
 ) {
     if events.is_empty() {
@@ -32,16 +33,16 @@ pub fn cross_chunk_boundary(
                 new_chunk_seen.insert((x, z));
             }
         }
+        let (mut conn, dimension) = query.get_mut(event.player).expect("Player does not exist");
         let needed_chunks: Vec<_> = new_chunk_seen
             .iter()
             .filter(|chunk| !old_chunk_seen.contains(chunk))
             .map(|chunk| {
                 let (x, z) = *chunk;
-                (x, z, "overworld".to_string())
+                (x, z, dimension.name().to_string())
             })
             .collect();
         let center_chunk = (event.new_chunk.0, event.new_chunk.1);
-        let mut conn = query.get_mut(event.player).expect("Player does not exist");
         send_chunks(state.0.clone(), needed_chunks, &mut conn, center_chunk)
             .expect("Failed to send chunks")
     }
@@ -0,0 +1,137 @@
+use crate::systems::entities;
+use crate::systems::world_edits::Viewers;
+use bevy_ecs::prelude::{Commands, Entity, Query, Res};
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::digging::{DigProgress, Digging};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_inventories::defined_slots::player::HOTBAR_SLOT_1;
+use ferrumc_inventories::dropped_item::DroppedItem;
+use ferrumc_inventories::hotbar::Hotbar;
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_net::packets::outgoing::block_destruction::BlockDestructionPacket;
+use ferrumc_net_codec::encode::NetEncode;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::{GlobalState, GlobalStateResource};
+use ferrumc_world::block_properties::{BlockDrop, Tool};
+use ferrumc_world::block_state_id::BlockStateId;
+use std::time::Duration;
+use tracing::error;
+
+/// How far away other players can see a block being broken, in blocks.
+const ANIMATION_RANGE: f64 = 32.0;
+/// How long dropped items stay on the ground before they despawn, like vanilla's 6000 ticks.
+const ITEM_DESPAWN_TIME: Duration = Duration::from_secs(5 * 60);
+
+/// Sends the break animation of every block being dug to the players near it whenever it
+/// reaches the next stage.
+pub fn update_dig_animations(
+    mut diggers: Query<(Entity, &PlayerIdentity, &Dimension, &mut Digging)>,
+    viewers: Viewers,
+    state: Res<GlobalStateResource>,
+) {
+    let tps = get_global_config().tps;
+    for (digger, identity, &dimension, mut digging) in &mut diggers {
+        let Some(dig) = digging.0.as_mut() else {
+            continue;
+        };
+        let stage = DigProgress::stage(dig.progress(dig.ticks(tps)));
+        if stage == dig.last_stage {
+            continue;
+        }
+        dig.last_stage = stage;
+
+        let location = NetworkPosition::new(dig.x, dig.y as i16, dig.z);
+        let packet = BlockDestructionPacket::new(identity.entity_id, location, stage);
+        send_nearby(&state.0, &viewers, Some(digger), dimension, dig, &packet);
+    }
+}
+
+/// Despawns dropped items that have been lying around for too long.
+pub fn despawn_dropped_items(mut commands: Commands, items: Query<(Entity, &DroppedItem)>) {
+    for (entity, item) in &items {
+        if item.spawned_at.elapsed() >= ITEM_DESPAWN_TIME {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Sends `packet` to the connected players in `dimension` that are close enough to the block
+/// being dug to see it, except for `except`.
+pub fn send_nearby(
+    state: &GlobalState,
+    viewers: &Viewers,
+    except: Option<Entity>,
+    dimension: Dimension,
+    dig: &DigProgress,
+    packet: &(impl NetEncode + Send),
+) {
+    let center = (dig.x as f64 + 0.5, dig.y as f64 + 0.5, dig.z as f64 + 0.5);
+    for (player, conn, &player_dimension, position) in viewers {
+        if Some(player) == except
+            || player_dimension != dimension
+            || !state.players.is_connected(player)
+        {
+            continue;
+        }
+        let distance_squared = (position.x - center.0).powi(2)
+            + (position.y - center.1).powi(2)
+            + (position.z - center.2).powi(2);
+        if distance_squared > ANIMATION_RANGE * ANIMATION_RANGE {
+            continue;
+        }
+        if let Err(e) = conn.send_packet_ref(packet) {
+            error!("Failed to send dig animation to {:?}: {:?}", player, e);
+        }
+    }
+}
+
+/// The tool in the player's selected hotbar slot, if they're holding one.
+pub fn held_tool(inventory: &Inventory, hotbar: &Hotbar) -> Option<Tool> {
+    let slot = HOTBAR_SLOT_1 as usize + hotbar.selected_slot as usize;
+    let item = inventory.get_item(slot).ok()??.item_id?;
+    Tool::from_item_name(&item.to_name()?)
+}
+
+/// Spawns the items `block` drops when broken with `tool` as item entities at `location`.
+pub fn drop_items(
+    commands: &mut Commands,
+    block: BlockStateId,
+    tool: Option<Tool>,
+    location: &NetworkPosition,
+    dimension: Dimension,
+) {
+    let properties = block.properties();
+    if !properties.can_harvest(tool) {
+        return;
+    }
+    let BlockDrop::Item { item, min, max } = properties.drop else {
+        return;
+    };
+    let count = rand::random_range(min..=max);
+    if count == 0 {
+        return;
+    }
+
+    let slot = InventorySlot {
+        count: VarInt::new(count as i32),
+        item_id: Some(item),
+        ..Default::default()
+    };
+    let position = Position::new(
+        location.x as f64 + 0.5,
+        location.y as f64 + 0.25,
+        location.z as f64 + 0.5,
+    );
+    // Pops out of the block in a random direction, like vanilla
+    let velocity = Velocity::new(
+        rand::random_range(-0.1..0.1),
+        0.2,
+        rand::random_range(-0.1..0.1),
+    );
+    entities::spawn_item(commands, slot, position, velocity, dimension);
+}
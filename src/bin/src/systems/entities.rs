@@ -0,0 +1,272 @@
+//! Keeps clients in sync with the entities that aren't players, and moves them.
+//!
+//! Gameplay code only spawns an [`EntityBundle`] and changes its components. The entity tracker
+//! spawns and removes it for players as it comes in and out of their range, and these systems
+//! send its movement and item changes to the players that can see it.
+
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
+use crate::systems::world_edits;
+use bevy_ecs::change_detection::DetectChanges;
+use bevy_ecs::prelude::{Changed, Commands, Component, Entity, EventReader, Query, Ref, Res};
+use ferrumc_core::chunks::block_change_event::BlockChangeEvent;
+use ferrumc_core::collisions::blocks as collisions;
+use ferrumc_core::collisions::bounds::CollisionBounds;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::kind::EntityKind;
+use ferrumc_core::entities::physics::Physics;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::entities::EntityBundle;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_inventories::dropped_item::DroppedItem;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_net::packets::outgoing::entity_metadata::{EntityMetadata, EntityMetadataPacket};
+use ferrumc_net::packets::outgoing::entity_position_sync::TeleportEntityPacket;
+use ferrumc_net::packets::outgoing::set_entity_motion::SetEntityMotionPacket;
+use ferrumc_net::packets::outgoing::update_entity_position::UpdateEntityPositionPacket;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::{GlobalState, GlobalStateResource};
+use ferrumc_world::block_state_id::BlockStateId;
+use ferrumc_world::chunk_format::Chunk;
+use std::collections::HashMap;
+
+/// Relative moves are sent in 1/4096 of a block and can't be further than 8 blocks, anything
+/// further is sent as a teleport.
+const MAX_DELTA: f64 = 7.5 * 4096.0;
+/// How far under the bottom of the world entities fall before they're removed, like vanilla.
+pub const VOID_DEPTH: i32 = 64;
+
+/// Where clients last saw an entity and how fast they were told it's moving.
+#[derive(Component)]
+pub struct Synced {
+    x: f64,
+    y: f64,
+    z: f64,
+    velocity: Velocity,
+    on_ground: bool,
+}
+
+/// An entity and what clients were last told about it, if anything.
+type SyncedEntity = (
+    Entity,
+    &'static EntityIdentity,
+    &'static Position,
+    &'static Rotation,
+    &'static Velocity,
+    &'static OnGround,
+    Option<&'static mut Synced>,
+);
+
+/// Spawns `slot` as an item entity at `position`.
+pub fn spawn_item(
+    commands: &mut Commands,
+    slot: InventorySlot,
+    position: Position,
+    velocity: Velocity,
+    dimension: Dimension,
+) {
+    commands.spawn((
+        EntityBundle::new(EntityKind::ITEM, position, dimension).with_velocity(velocity),
+        DroppedItem::new(slot),
+    ));
+}
+
+/// Sends the item a dropped item shows to clients when it changes.
+pub fn sync_entity_metadata(
+    items: Query<(Entity, &EntityIdentity, Ref<DroppedItem>), Changed<DroppedItem>>,
+    trackers: Trackers,
+    state: Res<GlobalStateResource>,
+) {
+    for (entity, identity, item) in &items {
+        // New items get their metadata with the spawn
+        if item.is_added() {
+            continue;
+        }
+        let packet = EntityMetadataPacket::new(
+            VarInt::new(identity.entity_id),
+            [EntityMetadata::item(item.item.clone())],
+        );
+        send_to_trackers(&state.0, &trackers, entity, &packet);
+    }
+}
+
+/// Sends entities that moved or changed speed to the players that can see them.
+pub fn sync_entity_movement(
+    mut commands: Commands,
+    mut entities: Query<SyncedEntity>,
+    trackers: Trackers,
+    state: Res<GlobalStateResource>,
+) {
+    for (entity, identity, position, rotation, velocity, on_ground, synced) in &mut entities {
+        // New entities were spawned where they are
+        let Some(mut synced) = synced else {
+            commands.entity(entity).insert(Synced {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+                velocity: *velocity,
+                on_ground: on_ground.0,
+            });
+            continue;
+        };
+        if *velocity != synced.velocity {
+            let packet = SetEntityMotionPacket::new(identity, velocity);
+            send_to_trackers(&state.0, &trackers, entity, &packet);
+            synced.velocity = *velocity;
+        }
+
+        let delta = [
+            ((position.x - synced.x) * 4096.0).round(),
+            ((position.y - synced.y) * 4096.0).round(),
+            ((position.z - synced.z) * 4096.0).round(),
+        ];
+        if delta == [0.0; 3] && on_ground.0 == synced.on_ground {
+            continue;
+        }
+        if delta.iter().any(|delta| delta.abs() > MAX_DELTA) {
+            let packet =
+                TeleportEntityPacket::entity(identity, position, rotation, velocity, on_ground.0);
+            send_to_trackers(&state.0, &trackers, entity, &packet);
+            (synced.x, synced.y, synced.z) = (position.x, position.y, position.z);
+        } else {
+            let packet = UpdateEntityPositionPacket::entity(
+                identity,
+                (delta[0] as i16, delta[1] as i16, delta[2] as i16),
+                on_ground.0,
+            );
+            send_to_trackers(&state.0, &trackers, entity, &packet);
+            // Only what the client was told, so rounding doesn't add up over time
+            synced.x += delta[0] / 4096.0;
+            synced.y += delta[1] / 4096.0;
+            synced.z += delta[2] / 4096.0;
+        }
+        synced.on_ground = on_ground.0;
+    }
+}
+
+/// Applies gravity and drag to entities and moves them by their velocity, stopping them at the
+/// blocks in their way. Entities lying still are left alone until the block under them changes.
+pub fn move_entities(
+    mut commands: Commands,
+    mut entities: Query<(
+        Entity,
+        &mut Position,
+        &mut Velocity,
+        &mut OnGround,
+        &Physics,
+        &Dimension,
+    )>,
+    mut block_changes: EventReader<BlockChangeEvent>,
+    state: Res<GlobalStateResource>,
+) {
+    for event in block_changes.read() {
+        let location = &event.location;
+        for (_, position, _, mut on_ground, _, &dimension) in &mut entities {
+            if dimension == event.dimension
+                && position.x.floor() as i32 == location.x
+                && position.z.floor() as i32 == location.z
+                && (position.y - (f64::from(location.y) + 1.0)).abs() < 0.5
+            {
+                on_ground.0 = false;
+            }
+        }
+    }
+
+    let mut blocks = BlockCache::new(&state.0);
+    for (entity, mut position, mut velocity, mut on_ground, physics, &dimension) in &mut entities {
+        if on_ground.0 && velocity.is_zero() {
+            continue;
+        }
+        if position.y < f64::from(dimension.min_y() - VOID_DEPTH) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        physics.fall(&mut velocity);
+        // Entities in chunks that can't be loaded stay where they are
+        let Some(landed) = blocks.step(dimension, &physics.bounds, &mut position, &mut velocity)
+        else {
+            continue;
+        };
+        on_ground.0 = landed;
+        physics.slow_down(&mut velocity, landed);
+    }
+}
+
+/// The chunks entities have looked at this tick, so each is only loaded once per tick.
+pub struct BlockCache<'a> {
+    state: &'a GlobalState,
+    chunks: HashMap<(i32, i32, Dimension), Option<Chunk>>,
+}
+
+impl<'a> BlockCache<'a> {
+    pub fn new(state: &'a GlobalState) -> Self {
+        Self {
+            state,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// The block at a position, or `None` if its chunk can't be loaded. Everything above and
+    /// below the world is air.
+    pub fn block(&mut self, dimension: Dimension, x: f64, y: f64, z: f64) -> Option<BlockStateId> {
+        let (x, y, z) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        if y < dimension.min_y() || y >= dimension.min_y() + dimension.height() {
+            return Some(BlockStateId::default());
+        }
+        let state = self.state;
+        let chunk = self
+            .chunks
+            .entry((x >> 4, z >> 4, dimension))
+            .or_insert_with(|| world_edits::load_chunk(state, x >> 4, z >> 4, dimension).ok())
+            .as_ref()?;
+        chunk.get_block(x, y, z).ok()
+    }
+
+    /// Moves an entity by its velocity for a tick, stopping it at the blocks in its way, and
+    /// stops it along the axes it ran into something. Returns whether the entity landed on the
+    /// ground.
+    fn step(
+        &mut self,
+        dimension: Dimension,
+        bounds: &CollisionBounds,
+        position: &mut Position,
+        velocity: &mut Velocity,
+    ) -> Option<bool> {
+        let motion = (velocity.x, velocity.y, velocity.z);
+        let mut unloaded = false;
+        let mut world = |x: i32, y: i32, z: i32| {
+            let block = self.block(dimension, f64::from(x), f64::from(y), f64::from(z));
+            unloaded |= block.is_none();
+            block.map(|block| block.collision_shape())
+        };
+        let moved = collisions::sweep(
+            &mut world,
+            bounds,
+            (position.x, position.y, position.z),
+            motion,
+        );
+        // Entities next to unloaded chunks wait for them instead of flying through
+        if unloaded {
+            return None;
+        }
+
+        *position = Position::new(
+            position.x + moved.0,
+            position.y + moved.1,
+            position.z + moved.2,
+        );
+        let landed = motion.1 < 0.0 && moved.1 > motion.1;
+        for (speed, moved) in [
+            (&mut velocity.x, moved.0),
+            (&mut velocity.y, moved.1),
+            (&mut velocity.z, moved.2),
+        ] {
+            if moved != *speed {
+                *speed = 0.0;
+            }
+        }
+        Some(landed)
+    }
+}
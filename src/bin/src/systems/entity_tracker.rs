@@ -0,0 +1,255 @@
+//! Decides which players and entities each player can see.
+//!
+//! Every player has an [`EntityTracker`] with the set of things they've been sent. Whenever a
+//! player crosses into another chunk, or an entity moves into another chunk, the sets are updated
+//! and players are sent spawns for what came into range and removals for what left it. Entity
+//! updates are only sent to the players that can see the entity, through [`send_to_trackers`].
+
+use bevy_ecs::change_detection::DetectChanges;
+use bevy_ecs::prelude::{Entity, EventReader, Local, Or, Query, Ref, RemovedComponents, Res, With};
+use bevy_ecs::query::QueryData;
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::chunks::cross_chunk_boundary_event::CrossChunkBoundaryEvent;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::kind::EntityKind;
+use ferrumc_core::entities::tracker::{chunk_of, in_range, EntityTracker};
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::entities::SpawnData;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_inventories::dropped_item::DroppedItem;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::outgoing::entity_metadata::{EntityMetadata, EntityMetadataPacket};
+use ferrumc_net::packets::outgoing::player_info_update::PlayerInfoUpdatePacket;
+use ferrumc_net::packets::outgoing::remove_entities::RemoveEntitiesPacket;
+use ferrumc_net::packets::outgoing::spawn_entity::SpawnEntityPacket;
+use ferrumc_net_codec::encode::NetEncode;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::{GlobalState, GlobalStateResource};
+use std::collections::{HashMap, HashSet};
+use tracing::error;
+
+/// The players that get an entity's updates, see [`send_to_trackers`].
+pub type Trackers<'w, 's> = Query<'w, 's, (Entity, &'static StreamWriter, &'static EntityTracker)>;
+
+/// Everything players could see, which is other players and entities.
+type Trackables<'w, 's> =
+    Query<'w, 's, Trackable, Or<(With<PlayerIdentity>, With<EntityIdentity>)>>;
+
+/// Where everything that can be seen was last tracked.
+#[derive(Default)]
+pub struct Tracked {
+    /// Network IDs, so things can still be removed from players after they're despawned.
+    ids: HashMap<Entity, i32>,
+    /// The dimension and chunk each thing is in.
+    chunks: HashMap<Entity, (Dimension, (i32, i32))>,
+}
+
+/// What players need to know to see something.
+#[derive(QueryData)]
+pub struct Trackable {
+    entity: Entity,
+    position: &'static Position,
+    rotation: &'static Rotation,
+    dimension: Ref<'static, Dimension>,
+    player: Option<&'static PlayerIdentity>,
+    game_mode: Option<&'static GameMode>,
+    identity: Option<&'static EntityIdentity>,
+    kind: Option<&'static EntityKind>,
+    velocity: Option<&'static Velocity>,
+    data: Option<&'static SpawnData>,
+    item: Option<&'static DroppedItem>,
+}
+
+impl TrackableItem<'_> {
+    fn network_id(&self) -> Option<i32> {
+        match (self.player, self.identity) {
+            (Some(player), _) => Some(player.entity_id),
+            (None, Some(identity)) => Some(identity.entity_id),
+            (None, None) => None,
+        }
+    }
+
+    /// Sends everything a player needs to see this for the first time.
+    fn send_spawn(&self, conn: &StreamWriter) -> Result<(), NetError> {
+        if let Some(player) = self.player {
+            // Clients won't spawn a player they don't have the profile of
+            conn.send_packet(PlayerInfoUpdatePacket::new_player_join_packet(
+                player.clone(),
+                self.game_mode.copied().unwrap_or_default(),
+            ))?;
+            return conn.send_packet(SpawnEntityPacket::player(
+                player,
+                self.position,
+                self.rotation,
+            ));
+        }
+        let (Some(identity), Some(kind)) = (self.identity, self.kind) else {
+            return Ok(());
+        };
+        conn.send_packet(SpawnEntityPacket::entity(
+            identity,
+            *kind,
+            self.position,
+            self.rotation,
+            self.velocity.unwrap_or(&Velocity::default()),
+            self.data.map_or(0, |data| data.0),
+        ))?;
+        if let Some(item) = self.item {
+            conn.send_packet(EntityMetadataPacket::new(
+                VarInt::new(identity.entity_id),
+                [EntityMetadata::item(item.item.clone())],
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+/// Updates what every player can see and sends them the spawns and removals for what changed.
+pub fn track_entities(
+    mut crossings: EventReader<CrossChunkBoundaryEvent>,
+    mut viewers: Query<(Entity, &StreamWriter, Ref<Dimension>, &mut EntityTracker)>,
+    trackables: Trackables,
+    mut removed_players: RemovedComponents<PlayerIdentity>,
+    mut removed_entities: RemovedComponents<EntityIdentity>,
+    mut tracked: Local<Tracked>,
+    state: Res<GlobalStateResource>,
+) {
+    let Tracked { ids, chunks } = &mut *tracked;
+    let config = get_global_config();
+    let radius = config
+        .entity_tracking_distance
+        .min(config.chunk_render_distance) as i32;
+
+    // Despawned things are removed from everyone who could see them
+    let gone: HashSet<Entity> = removed_players
+        .read()
+        .chain(removed_entities.read())
+        .collect();
+    let mut removals: HashMap<Entity, Vec<i32>> = HashMap::new();
+    for &entity in &gone {
+        chunks.remove(&entity);
+        let Some(id) = ids.remove(&entity) else {
+            continue;
+        };
+        for (viewer, _, _, mut tracker) in &mut viewers {
+            if tracker.visible.remove(&entity) {
+                removals.entry(viewer).or_default().push(id);
+            }
+        }
+    }
+
+    // Players move chunks through their crossing events, entities are checked every tick
+    let crossed: HashMap<Entity, (i32, i32)> = crossings
+        .read()
+        .map(|event| (event.player, event.new_chunk))
+        .collect();
+    let mut moved = HashSet::new();
+    for trackable in &trackables {
+        let entity = trackable.entity;
+        let respawned = trackable.dimension.is_changed() && !trackable.dimension.is_added();
+        if respawned {
+            // Clients forget things that respawn, so they're removed and spawned again
+            for (viewer, _, _, mut tracker) in &mut viewers {
+                if tracker.visible.remove(&entity) {
+                    removals.entry(viewer).or_default().extend(ids.get(&entity));
+                }
+            }
+        }
+        let known = chunks.get(&entity).copied();
+        let chunk = match known {
+            Some((_, chunk)) if trackable.player.is_some() && !respawned => {
+                crossed.get(&entity).copied().unwrap_or(chunk)
+            }
+            _ => chunk_of(trackable.position),
+        };
+        let location = (*trackable.dimension, chunk);
+        if known == Some(location) && !respawned {
+            continue;
+        }
+        if known.is_none() {
+            ids.extend(trackable.network_id().map(|id| (entity, id)));
+        }
+        chunks.insert(entity, location);
+        moved.insert(entity);
+    }
+
+    let visible_to = |viewer: Entity, entity: Entity| {
+        viewer != entity
+            && match (chunks.get(&viewer), chunks.get(&entity)) {
+                (Some(&(viewer_dimension, viewer_chunk)), Some(&(dimension, chunk))) => {
+                    viewer_dimension == dimension && in_range(viewer_chunk, chunk, radius)
+                }
+                _ => false,
+            }
+    };
+    for (viewer, conn, dimension, mut tracker) in &mut viewers {
+        if !state.0.players.is_connected(viewer) {
+            continue;
+        }
+        // The client forgets every entity when it respawns
+        let respawned = dimension.is_changed() && !dimension.is_added();
+        if respawned {
+            tracker.visible.clear();
+        }
+        // A player that moved needs everything checked, otherwise only what moved
+        let candidates: Vec<Entity> = if respawned || moved.contains(&viewer) {
+            trackables
+                .iter()
+                .map(|trackable| trackable.entity)
+                .collect()
+        } else {
+            moved.iter().copied().collect()
+        };
+        let mut remove = removals.remove(&viewer).unwrap_or_default();
+        let mut spawn = Vec::new();
+        for entity in candidates {
+            let visible = visible_to(viewer, entity);
+            if visible == tracker.can_see(entity) {
+                continue;
+            }
+            if visible {
+                tracker.visible.insert(entity);
+                spawn.push(entity);
+            } else {
+                tracker.visible.remove(&entity);
+                remove.extend(ids.get(&entity));
+            }
+        }
+        // Removals go first, as something that respawned is removed and spawned again
+        if !remove.is_empty() {
+            if let Err(e) = conn.send_packet(RemoveEntitiesPacket::from_ids(remove)) {
+                error!("Failed to remove entities for {:?}: {:?}", viewer, e);
+            }
+        }
+        for trackable in trackables.iter_many(spawn) {
+            if let Err(e) = trackable.send_spawn(conn) {
+                error!(
+                    "Failed to spawn {:?} for {:?}: {:?}",
+                    trackable.entity, viewer, e
+                );
+            }
+        }
+    }
+}
+
+/// Sends `packet` to every connected player that can see `entity`.
+pub fn send_to_trackers(
+    state: &GlobalState,
+    trackers: &Trackers,
+    entity: Entity,
+    packet: &(impl NetEncode + Send),
+) {
+    for (player, conn, tracker) in trackers {
+        if !tracker.can_see(entity) || !state.players.is_connected(player) {
+            continue;
+        }
+        if let Err(e) = conn.send_packet_ref(packet) {
+            error!("Failed to send entity update to {:?}: {:?}", player, e);
+        }
+    }
+}
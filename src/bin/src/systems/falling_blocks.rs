@@ -0,0 +1,130 @@
+use crate::errors::BinaryError;
+use crate::systems::entities;
+use crate::systems::world_edits::{self, Viewers};
+use bevy_ecs::prelude::{Commands, Entity, EventReader, Query, Res};
+use ferrumc_core::chunks::block_change_event::BlockChangeEvent;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::kind::EntityKind;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::entities::EntityBundle;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::{GlobalState, GlobalStateResource};
+use ferrumc_world::block_properties::BlockDrop;
+use ferrumc_world::block_state_id::BlockStateId;
+use ferrumc_world::falling_block::{FallingBlock, MAX_FALL_TICKS};
+use tracing::error;
+
+/// Turns blocks like sand into falling blocks when they're placed on nothing or the block under
+/// them is removed, along with the rest of the column above them.
+pub fn start_falling(
+    mut commands: Commands,
+    mut block_changes: EventReader<BlockChangeEvent>,
+    viewers: Viewers,
+    state: Res<GlobalStateResource>,
+) {
+    for event in block_changes.read() {
+        let location = &event.location;
+        let mut y = location.y;
+        loop {
+            let location = NetworkPosition::new(location.x, y, location.z);
+            match fall(
+                &mut commands,
+                &state.0,
+                &viewers,
+                &location,
+                event.dimension,
+            ) {
+                Ok(true) => {}
+                // The changed block itself might not fall, but the one on top of it could
+                Ok(false) if y == event.location.y => {}
+                Ok(false) => break,
+                Err(e) => {
+                    error!("Failed to make a block fall: {:?}", e);
+                    break;
+                }
+            }
+            y += 1;
+        }
+    }
+}
+
+/// Makes the block at `location` fall if it's a falling block with nothing under it. Returns
+/// whether it started falling.
+fn fall(
+    commands: &mut Commands,
+    state: &GlobalState,
+    viewers: &Viewers,
+    location: &NetworkPosition,
+    dimension: Dimension,
+) -> Result<bool, BinaryError> {
+    let (x, y, z) = (location.x, i32::from(location.y), location.z);
+    if y <= dimension.min_y() || y >= dimension.min_y() + dimension.height() {
+        return Ok(false);
+    }
+    let chunk = world_edits::load_chunk(state, x >> 4, z >> 4, dimension)?;
+    let block = chunk.get_block(x, y, z)?;
+    if !block.falls() || chunk.get_block(x, y - 1, z)?.blocks_motion() {
+        return Ok(false);
+    }
+
+    world_edits::set_block(state, viewers, location, dimension, BlockStateId::default())?;
+    let position = Position::new(f64::from(x) + 0.5, f64::from(y), f64::from(z) + 0.5);
+    commands.spawn((
+        EntityBundle::new(EntityKind::FALLING_BLOCK, position, dimension)
+            .with_spawn_data(block.0 as i32),
+        FallingBlock::new(block),
+    ));
+    Ok(true)
+}
+
+/// Places falling blocks back in the world once they land. Blocks that land somewhere they
+/// can't be placed, or fall for too long, break into their item instead.
+pub fn land_falling_blocks(
+    mut commands: Commands,
+    mut blocks: Query<(Entity, &mut FallingBlock, &Position, &OnGround, &Dimension)>,
+    viewers: Viewers,
+    state: Res<GlobalStateResource>,
+) {
+    for (entity, mut falling, position, on_ground, &dimension) in &mut blocks {
+        falling.ticks += 1;
+        if !on_ground.0 && falling.ticks < MAX_FALL_TICKS {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        let location = NetworkPosition::new(
+            position.x.floor() as i32,
+            position.y.floor() as i16,
+            position.z.floor() as i32,
+        );
+        let res = try bikeshed Result<(), BinaryError> {
+            let chunk =
+                world_edits::load_chunk(&state.0, location.x >> 4, location.z >> 4, dimension)?;
+            let replaced = chunk.get_block(location.x, i32::from(location.y), location.z)?;
+            if on_ground.0 && !replaced.blocks_motion() {
+                world_edits::set_block(&state.0, &viewers, &location, dimension, falling.block)?;
+            } else if let BlockDrop::Item { item, .. } = falling.block.properties().drop {
+                let slot = InventorySlot {
+                    count: VarInt::new(1),
+                    item_id: Some(item),
+                    ..Default::default()
+                };
+                let position = Position::new(position.x, position.y, position.z);
+                entities::spawn_item(
+                    &mut commands,
+                    slot,
+                    position,
+                    Velocity::default(),
+                    dimension,
+                );
+            }
+        };
+        if let Err(e) = res {
+            error!("Failed to land a falling block: {:?}", e);
+        }
+    }
+}
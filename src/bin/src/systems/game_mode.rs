@@ -0,0 +1,67 @@
+use bevy_ecs::prelude::{EventReader, Query, Res};
+use ferrumc_core::abilities::Abilities;
+use ferrumc_core::digging::Digging;
+use ferrumc_core::game_mode::change_game_mode_event::ChangeGameModeEvent;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::game_event::GameEventPacket;
+use ferrumc_net::packets::outgoing::player_abilities::PlayerAbilitiesPacket;
+use ferrumc_net::packets::outgoing::player_info_update::{
+    PlayerInfoUpdatePacket, PlayerWithActions,
+};
+use ferrumc_state::GlobalStateResource;
+use tracing::{debug, error};
+
+type Switching = (
+    &'static mut GameMode,
+    &'static mut Abilities,
+    &'static mut Digging,
+    &'static PlayerIdentity,
+    &'static StreamWriter,
+);
+
+/// Switches players to the game mode they were given, and tells everyone about it so the tab
+/// list and spectators show up right.
+pub fn change_game_mode(
+    mut events: EventReader<ChangeGameModeEvent>,
+    mut players: Query<Switching>,
+    connections: Query<&StreamWriter>,
+    state: Res<GlobalStateResource>,
+) {
+    for event in events.read() {
+        if !state.0.players.is_connected(event.player) {
+            continue;
+        }
+        let Ok((mut game_mode, mut abilities, mut digging, identity, conn)) =
+            players.get_mut(event.player)
+        else {
+            debug!("Player {:?} can't change game mode", event.player);
+            continue;
+        };
+
+        *game_mode = event.game_mode;
+        *abilities = event.game_mode.abilities();
+        digging.0 = None;
+        if let Err(e) = conn
+            .send_packet(GameEventPacket::change_game_mode(event.game_mode))
+            .and_then(|_| conn.send_packet(PlayerAbilitiesPacket::new(&abilities)))
+        {
+            error!(
+                "Failed to switch {} to {}: {:?}",
+                identity.username, event.game_mode, e
+            );
+        }
+
+        let update =
+            PlayerInfoUpdatePacket::with_players(vec![PlayerWithActions::update_game_mode(
+                identity.uuid.as_u128(),
+                event.game_mode,
+            )]);
+        for conn in &connections {
+            if let Err(e) = conn.send_packet_ref(&update) {
+                error!("Failed to send game mode update: {:?}", e);
+            }
+        }
+    }
+}
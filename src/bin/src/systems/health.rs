@@ -0,0 +1,270 @@
+//! Hurts players and entities, heals players and handles them dying.
+//!
+//! Anything can be hurt by writing a [`DamageEvent`] for it, which [`apply_damage`] checks
+//! against the target's health, invulnerability and hurt cooldown before showing it to clients.
+//! Players that die stay dead until they respawn from the death screen. They keep their
+//! inventory.
+
+use crate::systems::entities::{BlockCache, VOID_DEPTH};
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
+use bevy_ecs::change_detection::{DetectChanges, DetectChangesMut};
+use bevy_ecs::prelude::{
+    Changed, Commands, Component, Entity, EventReader, EventWriter, Or, Query, Ref, Res, With,
+};
+use bevy_ecs::query::QueryData;
+use ferrumc_core::abilities::Abilities;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::health::combat::{knockback, AttackCooldown, KNOCKBACK_STRENGTH};
+use ferrumc_core::health::damage_event::{DamageEvent, DamageSource};
+use ferrumc_core::health::{Breath, FallDistance, Food, Health, REGENERATION_TICKS};
+use ferrumc_core::identity::entity_identity::EntityIdentity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::mq;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::damage_event::DamageEventPacket;
+use ferrumc_net::packets::outgoing::entity_event::EntityEventPacket;
+use ferrumc_net::packets::outgoing::player_combat_kill::PlayerCombatKillPacket;
+use ferrumc_net::packets::outgoing::registry_data::damage_type_id;
+use ferrumc_net::packets::outgoing::set_entity_motion::SetEntityMotionPacket;
+use ferrumc_net::packets::outgoing::set_health::SetHealthPacket;
+use ferrumc_state::GlobalStateResource;
+use tracing::error;
+
+/// Damage taken every tick under the bottom of the world, which the hurt cooldown spreads out.
+const VOID_DAMAGE: f32 = 4.0;
+/// Damage taken each second without air under water.
+const DROWNING_DAMAGE: f32 = 2.0;
+/// How far above their feet players breathe from.
+const EYE_HEIGHT: f64 = 1.62;
+/// How long dead entities lie around for their death animation before they're removed.
+const DEATH_TICKS: u32 = 20;
+/// Exhaustion from taking a hit.
+const DAMAGE_EXHAUSTION: f32 = 0.1;
+/// Exhaustion from regaining a point of health.
+const REGENERATION_EXHAUSTION: f32 = 6.0;
+
+/// An entity that died and is playing its death animation.
+#[derive(Component, Default)]
+pub struct Dying {
+    ticks: u32,
+}
+
+/// Counts down hurt and attack cooldowns, and slowly heals players that have eaten enough.
+pub fn tick_health(
+    mut living: Query<(&mut Health, Option<&mut Food>, Option<&mut AttackCooldown>)>,
+) {
+    for (mut health, food, cooldown) in &mut living {
+        if let Some(mut cooldown) = cooldown {
+            cooldown.ticks = cooldown.ticks.saturating_add(1);
+        }
+        if health.hurt_cooldown > 0 {
+            health.bypass_change_detection().hurt_cooldown -= 1;
+        }
+
+        let Some(mut food) = food else {
+            continue;
+        };
+        if health.is_dead() || health.is_full() || !food.can_regenerate() {
+            food.bypass_change_detection().regeneration_timer = 0;
+            continue;
+        }
+        food.bypass_change_detection().regeneration_timer += 1;
+        if food.regeneration_timer >= REGENERATION_TICKS {
+            health.heal(1.0);
+            food.exhaust(REGENERATION_EXHAUSTION);
+            food.regeneration_timer = 0;
+        }
+    }
+}
+
+type Surroundings = (
+    Entity,
+    Ref<'static, Position>,
+    &'static OnGround,
+    &'static Dimension,
+    &'static Health,
+    &'static mut Breath,
+    &'static mut FallDistance,
+);
+
+/// Hurts players that land after a long fall, are under the world or are out of air.
+pub fn environmental_damage(
+    mut players: Query<Surroundings, With<PlayerIdentity>>,
+    mut damage: EventWriter<DamageEvent>,
+    state: Res<GlobalStateResource>,
+) {
+    let mut blocks = BlockCache::new(&state.0);
+    for (entity, position, on_ground, &dimension, health, mut breath, mut fall) in &mut players {
+        if health.is_dead() || !state.0.players.is_connected(entity) {
+            continue;
+        }
+        let mut in_water = |y: f64| {
+            blocks
+                .block(dimension, position.x, y, position.z)
+                .is_some_and(|block| block.is_water())
+        };
+
+        if position.y < f64::from(dimension.min_y() - VOID_DEPTH) {
+            damage.write(DamageEvent::new(entity, VOID_DAMAGE, DamageSource::Void));
+        }
+        if position.is_changed() {
+            if let Some(amount) = fall.update(position.y, on_ground.0, in_water(position.y)) {
+                damage.write(DamageEvent::new(entity, amount, DamageSource::Fall));
+            }
+        }
+        if breath.tick(in_water(position.y + EYE_HEIGHT)) {
+            damage.write(DamageEvent::new(
+                entity,
+                DROWNING_DAMAGE,
+                DamageSource::Drowning,
+            ));
+        }
+    }
+}
+
+/// Anything that can be hurt.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct Victim {
+    health: &'static mut Health,
+    food: Option<&'static mut Food>,
+    velocity: Option<&'static mut Velocity>,
+    on_ground: Option<&'static mut OnGround>,
+    player: Option<&'static PlayerIdentity>,
+    identity: Option<&'static EntityIdentity>,
+    abilities: Option<&'static Abilities>,
+    conn: Option<&'static StreamWriter>,
+}
+
+/// Applies damage, shows it to clients, and handles whatever it killed.
+pub fn apply_damage(
+    mut commands: Commands,
+    mut events: EventReader<DamageEvent>,
+    mut victims: Query<Victim>,
+    attackers: Query<(&Rotation, &PlayerIdentity)>,
+    trackers: Trackers,
+    state: Res<GlobalStateResource>,
+) {
+    for event in events.read() {
+        let Ok(mut victim) = victims.get_mut(event.entity) else {
+            continue;
+        };
+        let invulnerable = victim
+            .abilities
+            .is_some_and(|abilities| abilities.invulnerable);
+        if victim.health.is_dead()
+            || victim.health.hurt_cooldown > 0
+            || (invulnerable && !event.source.bypasses_invulnerability())
+        {
+            continue;
+        }
+        let Some(id) = victim
+            .player
+            .map(|player| player.entity_id)
+            .or(victim.identity.map(|identity| identity.entity_id))
+        else {
+            continue;
+        };
+        let attacker = event
+            .source
+            .attacker()
+            .and_then(|attacker| attackers.get(attacker).ok());
+
+        victim.health.hurt(event.amount);
+        if let Some(food) = victim.food.as_mut() {
+            food.exhaust(DAMAGE_EXHAUSTION);
+        }
+
+        if let Some(damage_type) = damage_type_id(event.source.damage_type()) {
+            let packet = DamageEventPacket::new(
+                id,
+                damage_type,
+                attacker.map(|(_, attacker)| attacker.entity_id),
+            );
+            send_to_trackers(&state.0, &trackers, event.entity, &packet);
+            if let Some(Err(e)) = victim.conn.map(|conn| conn.send_packet_ref(&packet)) {
+                error!("Failed to send damage to {:?}: {:?}", event.entity, e);
+            }
+        }
+
+        if let Some((rotation, _)) = attacker {
+            let on_ground = victim
+                .on_ground
+                .as_ref()
+                .is_some_and(|on_ground| on_ground.0);
+            if let Some(velocity) = victim.velocity.as_mut() {
+                **velocity = knockback(**velocity, rotation.yaw, KNOCKBACK_STRENGTH, on_ground);
+                // Wakes resting entities up so they fly off
+                if let Some(on_ground) = victim.on_ground.as_mut() {
+                    on_ground.0 = false;
+                }
+            } else if let (Some(player), Some(conn)) = (victim.player, victim.conn) {
+                // Players move themselves, so only their own client is told
+                let velocity = knockback(
+                    Velocity::default(),
+                    rotation.yaw,
+                    KNOCKBACK_STRENGTH,
+                    on_ground,
+                );
+                if let Err(e) = conn.send_packet(SetEntityMotionPacket::player(player, &velocity)) {
+                    error!("Failed to knock back {:?}: {:?}", event.entity, e);
+                }
+            }
+        }
+
+        if !victim.health.is_dead() {
+            continue;
+        }
+        let packet = EntityEventPacket::new(id, EntityEventPacket::DEATH);
+        send_to_trackers(&state.0, &trackers, event.entity, &packet);
+        let Some(player) = victim.player else {
+            commands.entity(event.entity).insert(Dying::default());
+            continue;
+        };
+        let message = event.source.death_message(
+            &player.username,
+            attacker.map(|(_, attacker)| attacker.username.as_str()),
+        );
+        if let Some(conn) = victim.conn {
+            let packet = PlayerCombatKillPacket::new(player.entity_id, message.clone());
+            if let Err(e) = conn.send_packet(packet) {
+                error!(
+                    "Failed to show the death screen to {:?}: {:?}",
+                    event.entity, e
+                );
+            }
+        }
+        mq::broadcast(message, false);
+    }
+}
+
+/// Removes dead entities once their death animation is over.
+pub fn remove_dead(mut commands: Commands, mut dying: Query<(Entity, &mut Dying)>) {
+    for (entity, mut dying) in &mut dying {
+        dying.ticks += 1;
+        if dying.ticks >= DEATH_TICKS {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+type HealthChanged = Or<(Changed<Health>, Changed<Food>)>;
+
+/// Sends players their health and hunger when they change.
+pub fn send_health(
+    players: Query<(Entity, &StreamWriter, &Health, &Food), HealthChanged>,
+    state: Res<GlobalStateResource>,
+) {
+    for (entity, conn, health, food) in &players {
+        if !state.0.players.is_connected(entity) {
+            continue;
+        }
+        if let Err(e) = conn.send_packet(SetHealthPacket::new(health, food)) {
+            error!("Failed to send health to {:?}: {:?}", entity, e);
+        }
+    }
+}
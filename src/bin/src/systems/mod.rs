@@ -1,13 +1,28 @@
+use bevy_ecs::schedule::IntoScheduleConfigs;
+use ferrumc_commands::infrastructure::CommandSystems;
+
+pub mod change_dimension;
 pub mod connection_killer;
+pub mod containers;
 mod cross_chunk_boundary;
+pub mod digging;
+pub mod entities;
+pub mod entity_tracker;
+mod falling_blocks;
+mod game_mode;
+mod health;
 pub mod keep_alive_system;
 pub mod lan_pinger;
+pub mod movement;
 mod mq;
 pub mod new_connections;
 pub mod player_count_update;
+pub mod player_data;
+pub mod rcon;
 pub mod send_chunks;
 pub mod shutdown_systems;
 mod system_messages;
+pub mod world_edits;
 pub mod world_sync;
 
 pub fn register_game_systems(schedule: &mut bevy_ecs::schedule::Schedule) {
@@ -15,6 +30,44 @@ pub fn register_game_systems(schedule: &mut bevy_ecs::schedule::Schedule) {
     schedule.add_systems(new_connections::accept_new_connections);
     schedule.add_systems(cross_chunk_boundary::cross_chunk_boundary);
     schedule.add_systems(mq::process);
+    schedule.add_systems(crate::console::handle_console_input.before(CommandSystems));
+    schedule.add_systems(rcon::dispatch_rcon_commands.before(CommandSystems));
+    schedule.add_systems(rcon::respond_rcon_commands.after(CommandSystems));
+    schedule.add_systems(change_dimension::change_dimension.after(CommandSystems));
+    schedule.add_systems(game_mode::change_game_mode.after(CommandSystems));
+    schedule.add_systems(digging::update_dig_animations);
+    schedule.add_systems(digging::despawn_dropped_items);
+    schedule.add_systems(containers::close_broken);
+    schedule.add_systems(movement::tick_movement.before(movement::move_players));
+    schedule.add_systems(
+        movement::move_players
+            .after(change_dimension::change_dimension)
+            .before(health::tick_health),
+    );
+    schedule.add_systems(
+        (
+            health::tick_health,
+            health::environmental_damage,
+            health::apply_damage,
+            health::remove_dead,
+            health::send_health,
+        )
+            .chain()
+            .after(change_dimension::change_dimension)
+            .before(entities::move_entities),
+    );
+    schedule.add_systems(
+        (
+            entities::move_entities,
+            falling_blocks::land_falling_blocks,
+            falling_blocks::start_falling,
+            entity_tracker::track_entities,
+            entities::sync_entity_metadata,
+            entities::sync_entity_movement,
+        )
+            .chain()
+            .after(change_dimension::change_dimension),
+    );
 
     // Should always be last
     schedule.add_systems(connection_killer::connection_killer);
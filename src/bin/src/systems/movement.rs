@@ -0,0 +1,225 @@
+//! Moves players to where their clients say they went, once the move passes the movement checks.
+//!
+//! Every movement packet becomes a [`TransformEvent`]. Rotations are always taken as they are,
+//! but positions go through the player's [`MovementValidator`] first. Rejected moves send the
+//! player back to their last valid position, coordinates that aren't numbers get them kicked, and
+//! every failed check fires a [`MovementFlagEvent`] for anything that wants to deal with cheaters.
+
+use crate::systems::entities::BlockCache;
+use crate::systems::entity_tracker::{send_to_trackers, Trackers};
+use bevy_ecs::prelude::{EventReader, EventWriter, Query, Res};
+use bevy_ecs::query::QueryData;
+use ferrumc_core::abilities::Abilities;
+use ferrumc_core::chunks::cross_chunk_boundary_event::CrossChunkBoundaryEvent;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::tracker::chunk_of;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::health::Health;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::movement::flag_event::MovementFlagEvent;
+use ferrumc_core::movement::{BlockInfo, MovementState, MovementValidator};
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_macros::NetEncode;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::entity_position_sync::TeleportEntityPacket;
+use ferrumc_net::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket;
+use ferrumc_net::packets::outgoing::update_entity_position::UpdateEntityPositionPacket;
+use ferrumc_net::packets::outgoing::update_entity_position_and_rotation::UpdateEntityPositionAndRotationPacket;
+use ferrumc_net::packets::outgoing::update_entity_rotation::UpdateEntityRotationPacket;
+use ferrumc_net::packets::packet_events::TransformEvent;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalStateResource;
+use ferrumc_world::block_state_id::BlockStateId;
+use tracing::{debug, error};
+
+/// A player moving themselves.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct Mover {
+    position: &'static mut Position,
+    rotation: &'static mut Rotation,
+    on_ground: &'static mut OnGround,
+    validator: &'static mut MovementValidator,
+    state: &'static mut MovementState,
+    abilities: &'static Abilities,
+    game_mode: &'static GameMode,
+    dimension: &'static Dimension,
+    health: &'static Health,
+    identity: &'static PlayerIdentity,
+    conn: &'static StreamWriter,
+}
+
+#[derive(NetEncode, Clone)]
+enum BroadcastMovementPacket {
+    UpdateEntityPosition(UpdateEntityPositionPacket),
+    UpdateEntityPositionAndRotation(UpdateEntityPositionAndRotationPacket),
+    UpdateEntityRotation(UpdateEntityRotationPacket),
+    TeleportEntity(TeleportEntityPacket),
+}
+
+/// Lets every player move another tick's worth, however many moves they send in it.
+pub fn tick_movement(mut validators: Query<&mut MovementValidator>) {
+    for mut validator in &mut validators {
+        validator.tick();
+    }
+}
+
+/// Checks and applies the moves players send, and shows them to the players tracking them.
+pub fn move_players(
+    mut events: EventReader<TransformEvent>,
+    mut players: Query<Mover>,
+    trackers: Trackers,
+    mut crossings: EventWriter<CrossChunkBoundaryEvent>,
+    mut flags: EventWriter<MovementFlagEvent>,
+    state: Res<GlobalStateResource>,
+) {
+    let mut blocks = BlockCache::new(&state.0);
+    for event in events.read() {
+        let entity = event.entity;
+        if !state.0.players.is_connected(entity) {
+            continue;
+        }
+        let Ok(mut player) = players.get_mut(entity) else {
+            continue;
+        };
+
+        let mut delta = None;
+        // Moves sent before the client saw a teleport would undo it, and the dead can't move
+        let position = event
+            .position
+            .as_ref()
+            .filter(|_| !player.validator.is_teleporting() && !player.health.is_dead());
+        if let Some(position) = position {
+            let to = (position.x, position.y, position.z);
+            let dimension = *player.dimension;
+            // Spectators fly through blocks
+            let spectating = *player.game_mode == GameMode::Spectator;
+            let mut view = |x: i32, y: i32, z: i32| {
+                if spectating {
+                    return Some(block_info(BlockStateId::default()));
+                }
+                blocks
+                    .block(dimension, f64::from(x), f64::from(y), f64::from(z))
+                    .map(block_info)
+            };
+            let on_ground = event.on_ground.unwrap_or(player.on_ground.0);
+            let may_fly = player.abilities.may_fly;
+            let checked =
+                player
+                    .validator
+                    .check(to, on_ground, &mut player.state, may_fly, &mut view);
+            if let Some(violation) = checked.violation {
+                debug!(
+                    "{} failed a movement check: {:?}",
+                    player.identity.username, violation
+                );
+                flags.write(MovementFlagEvent {
+                    player: entity,
+                    violation,
+                });
+            }
+
+            if checked.violation.is_some_and(|violation| violation.kicks()) {
+                state.0.players.disconnect(
+                    entity,
+                    Some("Invalid move player packet received".to_string()),
+                );
+                continue;
+            }
+            if checked
+                .violation
+                .is_some_and(|violation| violation.rejects_move())
+            {
+                let id = player.validator.reject();
+                let packet = SynchronizePlayerPositionPacket::new(
+                    player.validator.last_valid(),
+                    (0.0, 0.0, 0.0),
+                    player.rotation.yaw,
+                    player.rotation.pitch,
+                    0,
+                    VarInt::new(id),
+                );
+                if let Err(e) = player.conn.send_packet(packet) {
+                    error!("Failed to send {:?} back: {:?}", entity, e);
+                }
+            } else {
+                let old_chunk = chunk_of(&player.position);
+                delta = Some((
+                    ((position.x - player.position.x) * 4096.0) as i16,
+                    ((position.y - player.position.y) * 4096.0) as i16,
+                    ((position.z - player.position.z) * 4096.0) as i16,
+                ));
+                *player.position = Position::new(position.x, position.y, position.z);
+                player.on_ground.0 = checked.on_ground;
+
+                let new_chunk = chunk_of(&player.position);
+                if old_chunk != new_chunk {
+                    crossings.write(CrossChunkBoundaryEvent {
+                        player: entity,
+                        old_chunk,
+                        new_chunk,
+                    });
+                }
+            }
+        }
+        if let Some(rotation) = &event.rotation {
+            *player.rotation = Rotation::new(rotation.yaw, rotation.pitch);
+        }
+
+        if let Some(packet) = movement_packet(&player, delta, event.rotation.is_some()) {
+            send_to_trackers(&state.0, &trackers, entity, &packet);
+        }
+    }
+}
+
+/// What the movement checks need to know about a block.
+fn block_info(block: BlockStateId) -> BlockInfo {
+    BlockInfo {
+        shape: block.collision_shape(),
+        solid: block.blocks_motion(),
+        fluid: block.is_fluid(),
+        climbable: block.is_climbable(),
+    }
+}
+
+/// The packet that shows others a player moving by `delta` and turning if they `rotated`.
+fn movement_packet(
+    player: &MoverItem,
+    delta: Option<(i16, i16, i16)>,
+    rotated: bool,
+) -> Option<BroadcastMovementPacket> {
+    let (identity, on_ground) = (player.identity, player.on_ground.0);
+    // Moves of more than 8 blocks don't fit in the deltas of the update packets, and
+    // anything that wraps around shows up as `i16::MIN` or `i16::MAX`
+    const MAX_DELTA: i16 = (7.5 * 4096f32) as i16;
+    let too_far = delta.is_some_and(|(x, y, z)| {
+        [x, y, z]
+            .iter()
+            .any(|&delta| delta == i16::MIN || delta.abs() > MAX_DELTA)
+    });
+    if too_far {
+        return Some(BroadcastMovementPacket::TeleportEntity(
+            TeleportEntityPacket::new(identity, &player.position, &player.rotation, on_ground),
+        ));
+    }
+
+    Some(match (delta, rotated) {
+        (Some(delta), true) => BroadcastMovementPacket::UpdateEntityPositionAndRotation(
+            UpdateEntityPositionAndRotationPacket::new(
+                identity,
+                delta,
+                &player.rotation,
+                on_ground,
+            ),
+        ),
+        (Some(delta), false) => BroadcastMovementPacket::UpdateEntityPosition(
+            UpdateEntityPositionPacket::new(identity, delta, on_ground),
+        ),
+        (None, true) => BroadcastMovementPacket::UpdateEntityRotation(
+            UpdateEntityRotationPacket::new(identity, &player.rotation, on_ground),
+        ),
+        (None, false) => return None,
+    })
+}
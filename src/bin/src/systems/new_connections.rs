@@ -1,14 +1,23 @@
-use crate::systems::system_messages;
+use crate::systems::containers::{self, ContainerWindow};
+use crate::systems::{player_data, system_messages};
 use bevy_ecs::prelude::{Commands, Res, Resource};
 use crossbeam_channel::Receiver;
 use ferrumc_core::chunks::chunk_receiver::ChunkReceiver;
+use ferrumc_core::collisions::bounds::CollisionBounds;
 use ferrumc_core::conn::keepalive::KeepAliveTracker;
+use ferrumc_core::digging::Digging;
+use ferrumc_core::entities::tracker::EntityTracker;
+use ferrumc_core::health::HealthBundle;
+use ferrumc_core::movement::{MovementState, MovementValidator};
 use ferrumc_core::transform::grounded::OnGround;
-use ferrumc_core::transform::position::Position;
-use ferrumc_core::transform::rotation::Rotation;
 use ferrumc_inventories::hotbar::Hotbar;
 use ferrumc_inventories::inventory::Inventory;
-use ferrumc_net::connection::{DisconnectHandle, NewConnection};
+use ferrumc_net::connection::{DisconnectHandle, NewConnection, StreamWriter};
+use ferrumc_net::packets::outgoing::player_abilities::PlayerAbilitiesPacket;
+use ferrumc_net::packets::outgoing::recipe_book_add::RecipeBookAdd;
+use ferrumc_net::packets::outgoing::set_held_slot::SetHeldSlot;
+use ferrumc_net::packets::outgoing::update_recipes::UpdateRecipes;
+use ferrumc_recipes::RECIPES;
 use ferrumc_state::GlobalStateResource;
 use std::time::Instant;
 use tracing::{error, trace};
@@ -26,14 +35,29 @@ pub fn accept_new_connections(
     }
     while let Ok(new_connection) = new_connections.0.try_recv() {
         let return_sender = new_connection.entity_return;
+        let (dimension, position, rotation, inventory, hotbar, game_mode) =
+            player_data::restore(new_connection.player_data.as_ref());
+        let abilities = game_mode.abilities();
+        if new_connection.player_data.is_some() {
+            send_inventory(&new_connection.stream, &inventory, &hotbar);
+        }
+        send_recipes(&new_connection.stream);
+        if let Err(err) = new_connection
+            .stream
+            .send_packet(PlayerAbilitiesPacket::new(&abilities))
+        {
+            error!("Failed to send abilities: {:?}", err);
+        }
+        let validator = MovementValidator::new((position.x, position.y, position.z));
         let entity = cmd.spawn((
             new_connection.stream,
             DisconnectHandle {
                 sender: Some(new_connection.disconnect_handle),
             },
-            Position::default(),
+            dimension,
+            position,
             ChunkReceiver::default(),
-            Rotation::default(),
+            rotation,
             OnGround::default(),
             new_connection.player_identity.clone(),
             KeepAliveTracker {
@@ -41,8 +65,12 @@ pub fn accept_new_connections(
                 last_received_keep_alive: Instant::now(),
                 has_received_keep_alive: true,
             },
-            Inventory::new(46),
-            Hotbar::default(),
+            (inventory, hotbar, ContainerWindow::default()),
+            (game_mode, abilities),
+            Digging::default(),
+            EntityTracker::default(),
+            HealthBundle::default(),
+            (CollisionBounds::PLAYER, MovementState::default(), validator),
         ));
 
         state.0.players.player_list.insert(
@@ -80,3 +108,23 @@ pub fn accept_new_connections(
         }
     }
 }
+
+/// Sends every recipe, all of which everyone has unlocked.
+fn send_recipes(conn: &StreamWriter) {
+    if let Err(err) = conn.send_packet(UpdateRecipes::new(&RECIPES)) {
+        error!("Failed to send recipes: {:?}", err);
+    }
+    if let Err(err) = conn.send_packet(RecipeBookAdd::all(&RECIPES)) {
+        error!("Failed to send recipe book: {:?}", err);
+    }
+}
+
+/// Sends a returning player's restored inventory and selected hotbar slot.
+fn send_inventory(conn: &StreamWriter, inventory: &Inventory, hotbar: &Hotbar) {
+    if let Err(err) = containers::send_inventory(conn, inventory) {
+        error!("Failed to send restored inventory: {:?}", err);
+    }
+    if let Err(err) = conn.send_packet(SetHeldSlot::new(hotbar.selected_slot)) {
+        error!("Failed to send restored held slot: {:?}", err);
+    }
+}
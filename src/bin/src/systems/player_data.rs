@@ -0,0 +1,133 @@
+use bevy_ecs::prelude::{Query, Res};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_inventories::components::ComponentPatch;
+use ferrumc_inventories::hotbar::Hotbar;
+use ferrumc_inventories::inventory::Inventory;
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_state::GlobalStateResource;
+use ferrumc_world::player_data::{self, PlayerData, SavedItem};
+use tracing::{error, trace};
+
+/// The number of slots in the player inventory window.
+pub const PLAYER_INVENTORY_SIZE: usize = 46;
+
+/// What a joining player starts with.
+pub type Restored = (Dimension, Position, Rotation, Inventory, Hotbar, GameMode);
+
+/// Builds the components of a joining player from their saved state, or the defaults for a
+/// new player.
+pub fn restore(data: Option<&PlayerData>) -> Restored {
+    let game_mode = player_data::game_mode(data);
+    let Some(data) = data else {
+        return (
+            Dimension::default(),
+            Position::default(),
+            Rotation::default(),
+            Inventory::new(PLAYER_INVENTORY_SIZE),
+            Hotbar::default(),
+            game_mode,
+        );
+    };
+
+    let mut inventory = Inventory::new(PLAYER_INVENTORY_SIZE);
+    for item in &data.inventory {
+        let mut slot = InventorySlot::new(ItemID::new(item.item_id), item.count);
+        if !item.components.is_empty() {
+            match ComponentPatch::from_bytes(&item.components) {
+                Ok(components) => slot = slot.with_component_patch(components),
+                Err(e) => error!("Failed to restore the components of a saved item: {}", e),
+            }
+        }
+        if let Err(e) = inventory.set_item(item.slot as usize, slot) {
+            error!("Failed to restore saved item: {}", e);
+        }
+    }
+
+    (
+        Dimension::from_name(&data.dimension).unwrap_or_default(),
+        Position::from(data.position),
+        Rotation::new(data.rotation.0, data.rotation.1),
+        inventory,
+        Hotbar {
+            selected_slot: data.selected_slot,
+        },
+        game_mode,
+    )
+}
+
+/// Captures the state of a player so it can be saved.
+pub fn snapshot(
+    dimension: &Dimension,
+    position: &Position,
+    rotation: &Rotation,
+    inventory: &Inventory,
+    hotbar: &Hotbar,
+    game_mode: &GameMode,
+) -> PlayerData {
+    let inventory = inventory
+        .slots
+        .iter()
+        .enumerate()
+        .filter_map(|(index, slot)| {
+            let slot = slot.as_ref()?;
+            let item_id = slot.item_id?;
+            (slot.count.0 > 0).then_some(SavedItem {
+                slot: index as u8,
+                item_id: item_id.0 .0,
+                count: slot.count.0,
+                components: Some(slot.component_patch())
+                    .filter(|components| !components.is_empty())
+                    .map(|components| components.to_bytes())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    PlayerData {
+        dimension: dimension.name().to_string(),
+        position: (position.x, position.y, position.z),
+        rotation: (rotation.yaw, rotation.pitch),
+        selected_slot: hotbar.selected_slot,
+        inventory,
+        game_mode: Some(game_mode.id()),
+    }
+}
+
+/// What gets saved of every connected player.
+type Saved<'a> = (
+    &'a PlayerIdentity,
+    &'a Dimension,
+    &'a Position,
+    &'a Rotation,
+    &'a Inventory,
+    &'a Hotbar,
+    &'a GameMode,
+);
+
+/// Saves the state of every connected player.
+pub fn save_player_data(query: Query<Saved>, state: Res<GlobalStateResource>) {
+    let data = query
+        .iter()
+        .map(
+            |(identity, dimension, position, rotation, inventory, hotbar, game_mode)| {
+                (
+                    identity.uuid.as_u128(),
+                    snapshot(dimension, position, rotation, inventory, hotbar, game_mode),
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+    if data.is_empty() {
+        return;
+    }
+
+    trace!("Saving data of {} players", data.len());
+    if let Err(e) = state.0.world.save_player_data_batch(data) {
+        error!("Failed to save player data: {}", e);
+    }
+}
@@ -0,0 +1,57 @@
+use bevy_ecs::prelude::*;
+use ferrumc_commands::{
+    clear_rcon_output,
+    dispatch::resolve,
+    events::{CommandDispatchEvent, ResolvedCommandDispatchEvent},
+    take_rcon_output, Sender,
+};
+use ferrumc_net::rcon::RconCommandReceiver;
+use tokio::sync::oneshot;
+
+/// RCON commands dispatched this tick, waiting for their output.
+#[derive(Resource, Default)]
+pub struct PendingRconResponses(pub Vec<(u32, oneshot::Sender<String>)>);
+
+/// Dispatches the RCON commands received since the last tick. Command systems handle every
+/// dispatch in the tick it was made in, so each command's output is complete by the time
+/// [`respond_rcon_commands`] runs.
+pub fn dispatch_rcon_commands(
+    commands: Res<RconCommandReceiver>,
+    mut pending: ResMut<PendingRconResponses>,
+    mut next_id: Local<u32>,
+    mut dispatch_events: EventWriter<CommandDispatchEvent>,
+    mut resolved_dispatch_events: EventWriter<ResolvedCommandDispatchEvent>,
+) {
+    for request in commands.0.try_iter() {
+        let id = *next_id;
+        *next_id = next_id.wrapping_add(1);
+        let sender = Sender::Rcon(id);
+
+        dispatch_events.write(CommandDispatchEvent {
+            command: request.command.clone(),
+            sender,
+        });
+
+        match resolve(request.command, sender) {
+            Err(err) => sender.send_message(*err, false),
+            Ok((command, ctx)) => {
+                resolved_dispatch_events.write(ResolvedCommandDispatchEvent {
+                    command,
+                    ctx,
+                    sender,
+                });
+            }
+        }
+
+        pending.0.push((id, request.response));
+    }
+}
+
+/// Sends the captured output of the commands dispatched this tick back to their clients.
+pub fn respond_rcon_commands(mut pending: ResMut<PendingRconResponses>) {
+    for (id, response) in pending.0.drain(..) {
+        // The client may have disconnected in the meantime
+        let _ = response.send(take_rcon_output(id));
+    }
+    clear_rcon_output();
+}
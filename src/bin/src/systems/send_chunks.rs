@@ -1,5 +1,6 @@
 use crate::errors::BinaryError;
 use bevy_ecs::prelude::Mut;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_net::compression::compress_packet;
 use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::errors::NetError;
@@ -60,7 +61,7 @@ pub fn send_chunks(
                 // Don't bother saving the chunk if it hasn't been edited yet
                 let chunk = state_clone
                     .terrain_generator
-                    .generate_chunk(x, z)
+                    .generate_chunk(x, z, Dimension::from_name(&dim).unwrap_or_default())
                     .map_err(|err| NetError::Misc(err.to_string()))?;
                 Ok((ChunkAndLightData::from_chunk(&chunk), x, z))
             }?;
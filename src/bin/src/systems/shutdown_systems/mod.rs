@@ -2,4 +2,5 @@ mod send_shutdown_packet;
 
 pub fn register_shutdown_systems(schedule: &mut bevy_ecs::schedule::Schedule) {
     schedule.add_systems(send_shutdown_packet::handle);
+    schedule.add_systems(crate::systems::player_data::save_player_data);
 }
@@ -0,0 +1,124 @@
+use crate::errors::BinaryError;
+use bevy_ecs::prelude::{Entity, Query};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::transform::position::Position;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::block_entity_data::BlockEntityData;
+use ferrumc_net::packets::outgoing::block_update::BlockUpdate;
+use ferrumc_net::packets::outgoing::update_light::UpdateLightPacket;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalState;
+use ferrumc_world::block_entities::BlockEntity;
+use ferrumc_world::block_state_id::BlockStateId;
+use ferrumc_world::chunk_format::Chunk;
+use ferrumc_world::lighting::LightEngine;
+use std::sync::Arc;
+use tracing::trace;
+
+/// The connected players that block changes and entities are sent to.
+pub type Viewers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static StreamWriter,
+        &'static Dimension,
+        &'static Position,
+    ),
+>;
+
+/// Loads a chunk, generating it if it hasn't been saved yet.
+pub fn load_chunk(
+    state: &GlobalState,
+    chunk_x: i32,
+    chunk_z: i32,
+    dimension: Dimension,
+) -> Result<Chunk, BinaryError> {
+    match state
+        .world
+        .load_chunk_owned(chunk_x, chunk_z, dimension.name())
+    {
+        Ok(chunk) => Ok(chunk),
+        Err(e) => {
+            // Chunks are only saved once they're edited
+            trace!("Chunk not found, generating new chunk: {:?}", e);
+            Ok(state
+                .terrain_generator
+                .generate_chunk(chunk_x, chunk_z, dimension)?)
+        }
+    }
+}
+
+/// Sets the block at `location`, relights it and the chunks around it, saves the chunks that
+/// changed and sends the changes to every player in the dimension.
+///
+/// Blocks that have a block entity get an empty one, replacing the block entity of the old block.
+pub fn set_block(
+    state: &GlobalState,
+    viewers: &Viewers,
+    location: &NetworkPosition,
+    dimension: Dimension,
+    block: BlockStateId,
+) -> Result<(), BinaryError> {
+    let (chunk_x, chunk_z) = (location.x >> 4, location.z >> 4);
+    let (x, y, z) = (location.x, location.y as i32, location.z);
+    let mut centre = load_chunk(state, chunk_x, chunk_z, dimension)?;
+    // Light travels at most 15 blocks, so it can't reach past the surrounding chunks. They're
+    // only loaded, and generated if they haven't been saved, when the light can change at all.
+    let mut neighbours = Vec::new();
+    if centre.get_block(x, y, z)?.light() != block.light() {
+        for dx in -1..=1 {
+            for dz in -1..=1 {
+                if (dx, dz) != (0, 0) {
+                    neighbours.push(load_chunk(state, chunk_x + dx, chunk_z + dz, dimension)?);
+                }
+            }
+        }
+    }
+    let relit = {
+        let chunks = std::iter::once(&mut centre)
+            .chain(&mut neighbours)
+            .collect();
+        let mut engine = LightEngine::new(chunks);
+        engine.set_block(x, y, z, block)?;
+        engine.changed_chunks().collect::<Vec<_>>()
+    };
+    let block_entity = block.block_entity_type().map(BlockEntity::empty);
+    if let Some(block_entity) = &block_entity {
+        centre.set_block_entity(x, y, z, block_entity.clone());
+    }
+
+    let mut packets = Vec::new();
+    if relit.contains(&(chunk_x, chunk_z)) {
+        packets.push(UpdateLightPacket::from_chunk(&centre));
+    }
+    state.world.save_chunk(Arc::new(centre))?;
+    // Neighbours the light didn't reach are left as they were, so generated ones aren't stored
+    for chunk in neighbours {
+        if relit.contains(&(chunk.x, chunk.z)) {
+            packets.push(UpdateLightPacket::from_chunk(&chunk));
+            state.world.save_chunk(Arc::new(chunk))?;
+        }
+    }
+
+    let block_update = BlockUpdate {
+        location: location.clone(),
+        block_state_id: VarInt::from(block),
+    };
+    let block_entity_data =
+        block_entity.and_then(|block_entity| BlockEntityData::new(location.clone(), &block_entity));
+    for (player, conn, &player_dimension, _) in viewers {
+        if player_dimension != dimension || !state.players.is_connected(player) {
+            continue;
+        }
+        conn.send_packet_ref(&block_update)?;
+        if let Some(block_entity_data) = &block_entity_data {
+            conn.send_packet_ref(block_entity_data)?;
+        }
+        for packet in &packets {
+            conn.send_packet_ref(packet)?;
+        }
+    }
+    Ok(())
+}
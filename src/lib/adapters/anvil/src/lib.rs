@@ -1,4 +1,6 @@
 pub mod errors;
+mod lz4;
+pub mod writer;
 
 use crate::errors::AnvilError;
 use memmap2::Mmap;
@@ -147,14 +149,17 @@ impl LoadedAnvilFile {
     /// 3: None
     /// 4: LZ4
     ///
-    /// The next 4 bytes are the uncompressed size of the chunk
-    ///
-    /// The rest of the data is the compressed chunk data
+    /// The chunk data starts with the length of the compressed data, followed by the compression
+    /// type and the compressed data itself
     ///
     /// This function will return the decompressed chunk data, or an error if the data reading
     /// fails, the compression type is unknown, the checksum is missing, the checksum is invalid,
-    /// or the decompression fails.
+    /// or the decompression fails. A location of 0 means the chunk hasn't been generated, in
+    /// which case `None` is returned.
     pub fn get_chunk_from_location(&self, location: u32) -> Result<Option<Vec<u8>>, AnvilError> {
+        if location == 0 {
+            return Ok(None);
+        }
         let offset = (location >> 8) & 0xFFFFFF;
         if u64::from(offset) * 4096 >= u64::from(u32::MAX) {
             error!("Invalid offset: {}", offset);
@@ -163,14 +168,24 @@ impl LoadedAnvilFile {
         let offset = offset * 4096;
         let size = (location & 0xFF) * 4096;
         let chunk_data = self.get_data_from_file(offset, size)?;
-        let chunk_compressed_data = &chunk_data[5..];
+        if chunk_data.len() < 5 {
+            return Err(AnvilError::InvalidOffsetOrSize);
+        }
+        // The length includes the compression type, the rest of the last sector is padding
+        let length =
+            u32::from_be_bytes([chunk_data[0], chunk_data[1], chunk_data[2], chunk_data[3]]);
+        let chunk_compressed_data = chunk_data
+            .get(5..4 + length as usize)
+            .ok_or(AnvilError::InvalidOffsetOrSize)?;
         let compression_type = chunk_data[4];
 
         match compression_type {
             1 => {
                 let mut decompressed_data = Vec::new();
                 let mut decoder = flate2::read::GzDecoder::new(chunk_compressed_data);
-                decoder.read_to_end(&mut decompressed_data).unwrap();
+                decoder
+                    .read_to_end(&mut decompressed_data)
+                    .map_err(|_| AnvilError::DecompressionError)?;
                 Ok(Some(decompressed_data))
             }
             2 => {
@@ -190,11 +205,7 @@ impl LoadedAnvilFile {
                 }
             }
             3 => Ok(Some(chunk_compressed_data.to_vec())),
-            4 => {
-                let mut decompressed_data = vec![];
-                lzzzz::lz4::decompress(chunk_compressed_data, &mut decompressed_data)?;
-                Ok(Some(decompressed_data))
-            }
+            4 => Ok(Some(lz4::decompress(chunk_compressed_data)?)),
             _ => {
                 error!("Unknown compression type: {}", compression_type);
                 Err(AnvilError::DecompressionError)
@@ -209,7 +220,7 @@ impl LoadedAnvilFile {
     /// This function will return the decompressed chunk data, or an error if the data reading
     /// fails for any reason.
     pub fn get_chunk(&self, x: u32, z: u32) -> Result<Option<Vec<u8>>, AnvilError> {
-        let index = u64::from(4 * ((x & 31) + (z & 31) * 32));
+        let index = (x & 31) + (z & 31) * 32;
         let base_index = index as usize * 4;
         let chunk_data = [
             u32::from(self.table[base_index]),
@@ -0,0 +1,145 @@
+//! The LZ4 block stream format written by `lz4-java`'s `LZ4BlockOutputStream`, which is what
+//! vanilla uses for compression type 4.
+//!
+//! The stream is a sequence of blocks, each with a 21 byte header:
+//!
+//! * the magic `LZ4Block`
+//! * a token, the compression method OR'd with the compression level
+//! * the compressed length, the original length and a checksum of the original data, all
+//!   little endian 32-bit integers
+//!
+//! The stream ends with an empty block.
+
+use crate::errors::AnvilError;
+use xxhash_rust::xxh32::xxh32;
+
+const MAGIC: &[u8; 8] = b"LZ4Block";
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + 4 + 4 + 4;
+const METHOD_RAW: u8 = 0x10;
+const METHOD_LZ4: u8 = 0x20;
+/// The default block size of `LZ4BlockOutputStream`.
+const BLOCK_SIZE: usize = 64 * 1024;
+/// The level is derived from the block size, `log2(BLOCK_SIZE) - 10`.
+const COMPRESSION_LEVEL: u8 = 6;
+const CHECKSUM_SEED: u32 = 0x9747_b28c;
+
+/// `lz4-java` only keeps the lower 28 bits of the hash.
+fn checksum(data: &[u8]) -> u32 {
+    xxh32(data, CHECKSUM_SEED) & 0x0FFF_FFFF
+}
+
+fn write_header(out: &mut Vec<u8>, method: u8, compressed: usize, original: usize, check: u32) {
+    out.extend_from_slice(MAGIC);
+    out.push(method | COMPRESSION_LEVEL);
+    out.extend_from_slice(&(compressed as u32).to_le_bytes());
+    out.extend_from_slice(&(original as u32).to_le_bytes());
+    out.extend_from_slice(&check.to_le_bytes());
+}
+
+pub(crate) fn compress(data: &[u8]) -> Result<Vec<u8>, AnvilError> {
+    let mut out = Vec::with_capacity(data.len() / 2 + HEADER_LENGTH);
+    let mut compressed = Vec::new();
+    for block in data.chunks(BLOCK_SIZE) {
+        compressed.clear();
+        lzzzz::lz4::compress_to_vec(block, &mut compressed, lzzzz::lz4::ACC_LEVEL_DEFAULT)
+            .map_err(|_| AnvilError::CompressionError)?;
+        // Incompressible blocks are stored as is, like lz4-java does
+        if compressed.len() < block.len() {
+            write_header(
+                &mut out,
+                METHOD_LZ4,
+                compressed.len(),
+                block.len(),
+                checksum(block),
+            );
+            out.extend_from_slice(&compressed);
+        } else {
+            write_header(
+                &mut out,
+                METHOD_RAW,
+                block.len(),
+                block.len(),
+                checksum(block),
+            );
+            out.extend_from_slice(block);
+        }
+    }
+    write_header(&mut out, METHOD_RAW, 0, 0, 0);
+    Ok(out)
+}
+
+pub(crate) fn decompress(mut data: &[u8]) -> Result<Vec<u8>, AnvilError> {
+    let mut out = Vec::new();
+    while data.len() >= HEADER_LENGTH {
+        if !data.starts_with(MAGIC) {
+            return Err(AnvilError::DecompressionError);
+        }
+        let read_u32 =
+            |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let method = data[MAGIC.len()] & 0xF0;
+        let compressed_length = read_u32(9) as usize;
+        let original_length = read_u32(13) as usize;
+        let check = read_u32(17);
+        data = &data[HEADER_LENGTH..];
+
+        if original_length == 0 {
+            break;
+        }
+        let block = data
+            .get(..compressed_length)
+            .ok_or(AnvilError::DecompressionError)?;
+        let start = out.len();
+        match method {
+            METHOD_RAW => out.extend_from_slice(block),
+            METHOD_LZ4 => {
+                out.resize(start + original_length, 0);
+                lzzzz::lz4::decompress(block, &mut out[start..])?;
+            }
+            _ => return Err(AnvilError::DecompressionError),
+        }
+        if checksum(&out[start..]) != check {
+            return Err(AnvilError::ChecksumMismatch);
+        }
+        data = &data[compressed_length..];
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        // Spans several blocks, with a mix of compressible and random-ish data
+        let data = (0..200_000u32)
+            .map(|i| {
+                if i < 100_000 {
+                    (i % 7) as u8
+                } else {
+                    (i.wrapping_mul(2_654_435_761) >> 24) as u8
+                }
+            })
+            .collect::<Vec<_>>();
+        let compressed = compress(&data).unwrap();
+        assert!(compressed.starts_with(MAGIC));
+        assert_eq!(decompress(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_empty() {
+        let compressed = compress(&[]).unwrap();
+        assert_eq!(compressed.len(), HEADER_LENGTH);
+        assert!(decompress(&compressed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let mut compressed = compress(&[1; 100]).unwrap();
+        compressed[17] ^= 1;
+        assert!(matches!(
+            decompress(&compressed),
+            Err(AnvilError::ChecksumMismatch)
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::errors::AnvilError;
+use crate::lz4;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The size of a sector in a region file. Chunks and the header tables are aligned to sectors.
+const SECTOR_SIZE: usize = 4096;
+/// The location and timestamp tables take up the first two sectors.
+const HEADER_SECTORS: usize = 2;
+/// The sector count of a chunk is stored in a single byte.
+const MAX_CHUNK_SECTORS: usize = u8::MAX as usize;
+
+/// The compression types a chunk can be stored with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionType {
+    Gzip = 1,
+    #[default]
+    Zlib = 2,
+    None = 3,
+    /// The `lz4-java` block stream format, supported by vanilla since 1.20.5.
+    Lz4 = 4,
+}
+
+impl CompressionType {
+    fn compress(self, data: &[u8]) -> Result<Vec<u8>, AnvilError> {
+        match self {
+            CompressionType::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(data)
+                    .map_err(|_| AnvilError::CompressionError)?;
+                encoder.finish().map_err(|_| AnvilError::CompressionError)
+            }
+            CompressionType::Zlib => {
+                yazi::compress(data, yazi::Format::Zlib, yazi::CompressionLevel::Default)
+                    .map_err(|_| AnvilError::CompressionError)
+            }
+            CompressionType::None => Ok(data.to_vec()),
+            CompressionType::Lz4 => lz4::compress(data),
+        }
+    }
+}
+
+struct RegionChunk {
+    compressed: Vec<u8>,
+    timestamp: u32,
+}
+
+impl RegionChunk {
+    /// The number of sectors the chunk takes up, including the length and compression type.
+    fn sectors(&self) -> usize {
+        (self.compressed.len() + 5).div_ceil(SECTOR_SIZE)
+    }
+}
+
+/// Builds a region file in memory.
+///
+/// Chunks are compressed as they're added, and laid out in sectors in the order of their index
+/// when the region is written, so the resulting file has no gaps.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ferrumc_anvil::writer::{AnvilRegionWriter, CompressionType};
+/// use std::path::PathBuf;
+///
+/// let mut writer = AnvilRegionWriter::new(CompressionType::Zlib);
+/// writer.insert_chunk(0, 0, b"some nbt", 0).unwrap();
+/// writer.save(PathBuf::from("r.0.0.mca")).unwrap();
+/// ```
+pub struct AnvilRegionWriter {
+    compression: CompressionType,
+    chunks: Vec<Option<RegionChunk>>,
+}
+
+impl AnvilRegionWriter {
+    pub fn new(compression: CompressionType) -> Self {
+        Self {
+            compression,
+            chunks: (0..1024).map(|_| None).collect(),
+        }
+    }
+
+    /// Compresses and adds the uncompressed NBT `data` of a chunk, replacing any previous data.
+    ///
+    /// The x and z coordinates are the chunk coordinates, only their position within the region
+    /// is used. `timestamp` is the time of the last modification in seconds since the epoch.
+    ///
+    /// Fails with [`AnvilError::ChunkTooLarge`] if the compressed chunk doesn't fit in 255 sectors,
+    /// vanilla stores those in separate `.mcc` files which aren't supported.
+    pub fn insert_chunk(
+        &mut self,
+        x: u32,
+        z: u32,
+        data: &[u8],
+        timestamp: u32,
+    ) -> Result<(), AnvilError> {
+        let chunk = RegionChunk {
+            compressed: self.compression.compress(data)?,
+            timestamp,
+        };
+        if chunk.sectors() > MAX_CHUNK_SECTORS {
+            return Err(AnvilError::ChunkTooLarge(x, z));
+        }
+        self.chunks[((x & 31) + (z & 31) * 32) as usize] = Some(chunk);
+        Ok(())
+    }
+
+    /// The number of chunks in the region.
+    pub fn len(&self) -> usize {
+        self.chunks.iter().filter(|chunk| chunk.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the region file to `out`.
+    pub fn write(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut locations = [0u8; SECTOR_SIZE];
+        let mut timestamps = [0u8; SECTOR_SIZE];
+        let mut next_sector = HEADER_SECTORS;
+        for (index, chunk) in self.chunks.iter().enumerate() {
+            let Some(chunk) = chunk else {
+                continue;
+            };
+            let sectors = chunk.sectors();
+            let location = ((next_sector as u32) << 8) | sectors as u32;
+            locations[index * 4..index * 4 + 4].copy_from_slice(&location.to_be_bytes());
+            timestamps[index * 4..index * 4 + 4].copy_from_slice(&chunk.timestamp.to_be_bytes());
+            next_sector += sectors;
+        }
+        out.write_all(&locations)?;
+        out.write_all(&timestamps)?;
+
+        for chunk in self.chunks.iter().flatten() {
+            // The length includes the compression type
+            out.write_all(&(chunk.compressed.len() as u32 + 1).to_be_bytes())?;
+            out.write_all(&[self.compression as u8])?;
+            out.write_all(&chunk.compressed)?;
+            let padding = chunk.sectors() * SECTOR_SIZE - chunk.compressed.len() - 5;
+            out.write_all(&[0; SECTOR_SIZE][..padding])?;
+        }
+        Ok(())
+    }
+
+    /// Writes the region file to `file_path`, replacing it if it exists.
+    ///
+    /// The file is written next to the destination first and then renamed, so a crash won't leave
+    /// a half written region behind.
+    pub fn save(&self, file_path: PathBuf) -> Result<(), AnvilError> {
+        let temp_path = file_path.with_extension("mca.tmp");
+        let write = || -> std::io::Result<()> {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(&temp_path)?);
+            self.write(&mut file)?;
+            file.into_inner()?.sync_all()?;
+            std::fs::rename(&temp_path, &file_path)
+        };
+        write().map_err(|e| AnvilError::UnableToWriteFile(file_path.clone(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_anvil_file;
+
+    #[test]
+    fn test_roundtrip() {
+        let path = std::env::temp_dir().join(format!("ferrumc-anvil-{}.mca", std::process::id()));
+        let big_chunk = (0..20_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+
+        for compression in [
+            CompressionType::Gzip,
+            CompressionType::Zlib,
+            CompressionType::None,
+            CompressionType::Lz4,
+        ] {
+            let mut writer = AnvilRegionWriter::new(compression);
+            writer.insert_chunk(0, 0, b"first", 10).unwrap();
+            writer.insert_chunk(33, 5, &big_chunk, 20).unwrap();
+            writer.insert_chunk(31, 31, b"last", 30).unwrap();
+            assert_eq!(writer.len(), 3);
+            writer.save(path.clone()).unwrap();
+
+            let loaded = load_anvil_file(path.clone()).unwrap();
+            assert_eq!(loaded.get_chunk(0, 0).unwrap().unwrap(), b"first");
+            assert_eq!(loaded.get_chunk(1, 5).unwrap().unwrap(), big_chunk);
+            assert_eq!(loaded.get_chunk(31, 31).unwrap().unwrap(), b"last");
+            assert_eq!(loaded.get_chunk(2, 2).unwrap(), None);
+            assert_eq!(loaded.get_locations().len(), 3);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_sector_layout() {
+        let mut writer = AnvilRegionWriter::new(CompressionType::None);
+        writer.insert_chunk(1, 0, &[0; 5000], 1234).unwrap();
+        writer.insert_chunk(0, 0, &[0; 10], 0).unwrap();
+        let mut out = Vec::new();
+        writer.write(&mut out).unwrap();
+
+        assert_eq!(out.len(), SECTOR_SIZE * 5);
+        // Chunk 0 comes first, chunk 1 spans two sectors after it
+        assert_eq!(&out[0..4], &[0, 0, 2, 1]);
+        assert_eq!(&out[4..8], &[0, 0, 3, 2]);
+        assert_eq!(
+            &out[SECTOR_SIZE + 4..SECTOR_SIZE + 8],
+            &1234u32.to_be_bytes()
+        );
+        assert_eq!(
+            &out[SECTOR_SIZE * 2..SECTOR_SIZE * 2 + 5],
+            &[0, 0, 0, 11, 3]
+        );
+    }
+
+    #[test]
+    fn test_chunk_too_large() {
+        let mut writer = AnvilRegionWriter::new(CompressionType::None);
+        assert!(matches!(
+            writer.insert_chunk(3, 4, &vec![0; SECTOR_SIZE * MAX_CHUNK_SECTORS], 0),
+            Err(AnvilError::ChunkTooLarge(3, 4))
+        ));
+        assert!(writer.is_empty());
+    }
+}
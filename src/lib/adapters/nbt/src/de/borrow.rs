@@ -197,6 +197,18 @@ impl<'a> NbtTape<'a> {
         res.flatten()
     }
 
+    /// Writes an element of this tape back out as NBT, for keeping data that isn't read.
+    pub fn write_element(
+        &self,
+        element: &NbtTapeElement<'a>,
+        writer: &mut Vec<u8>,
+        opts: &NBTSerializeOptions,
+    ) -> Result<(), NetEncodeError> {
+        // Lists are parsed again while writing, which moves the tape around, so use a copy
+        let mut tape = NbtTape::new(self.data);
+        element.serialize_as_network(&mut tape, writer, opts)
+    }
+
     pub fn unpack_list<T: FromNbt<'a>>(&self, element: &NbtTapeElement<'a>) -> Option<Vec<T>> {
         match element {
             NbtTapeElement::List {
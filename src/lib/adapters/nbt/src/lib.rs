@@ -1,6 +1,7 @@
 #![allow(unsafe_code)]
 pub mod de;
 pub mod errors;
+pub mod network;
 pub mod ser;
 
 pub type Result<T> = std::result::Result<T, NBTError>;
@@ -8,4 +9,5 @@ pub type Result<T> = std::result::Result<T, NBTError>;
 pub use de::borrow::{NbtTape, NbtTapeElement};
 pub use de::converter::FromNbt;
 pub use errors::NBTError;
+pub use network::NetworkNbt;
 pub use ser::{NBTSerializable, NBTSerializeOptions};
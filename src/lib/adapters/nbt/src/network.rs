@@ -0,0 +1,364 @@
+//! NBT values as they're sent over the network: a tag type followed by the payload, without the
+//! name a root tag has on disk.
+
+use crate::de::borrow::{NbtTape, NbtTapeElement};
+use crate::de::converter::FromNbt;
+use crate::{NBTError, NBTSerializable, NBTSerializeOptions};
+use ferrumc_net_codec::decode::errors::NetDecodeError;
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
+use ferrumc_net_codec::encode::errors::NetEncodeError;
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use std::io::{Read, Write};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const TAG_END: u8 = 0;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+
+/// The most NBT a client may send in one value, the same limit vanilla uses.
+const MAX_SIZE: usize = 2 * 1024 * 1024;
+/// How deep compounds and lists may nest.
+const MAX_DEPTH: usize = 512;
+
+/// An NBT value kept as its network bytes, for data that is passed along rather than read,
+/// like the text components in item names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkNbt(pub Vec<u8>);
+
+impl NetworkNbt {
+    /// A string tag, which is also the simplest text component.
+    pub fn string(value: &str) -> Self {
+        let mut bytes = Vec::new();
+        value.serialize(&mut bytes, &NBTSerializeOptions::Network);
+        Self(bytes)
+    }
+
+    /// The value, if it's a string tag.
+    pub fn as_string(&self) -> Option<&str> {
+        let (&tag, rest) = self.0.split_first()?;
+        if tag != TAG_STRING {
+            return None;
+        }
+        std::str::from_utf8(rest.get(2..)?).ok()
+    }
+
+    /// The type of the tag.
+    pub fn tag(&self) -> u8 {
+        self.0.first().copied().unwrap_or(TAG_END)
+    }
+
+    /// Writes the value as a named tag, as it appears in a compound.
+    pub fn write_named(&self, name: &str, buf: &mut Vec<u8>) {
+        let Some((&tag, payload)) = self.0.split_first() else {
+            return;
+        };
+        buf.push(tag);
+        name.serialize(buf, &NBTSerializeOptions::None);
+        buf.extend_from_slice(payload);
+    }
+
+    /// Writes the payload on its own, as it appears in a list.
+    pub fn write_payload(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.0.get(1..).unwrap_or_default());
+    }
+
+    /// Reads a value, which has to be done a tag at a time since its length isn't sent.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, NetDecodeError> {
+        let mut scanner = Scanner::new();
+        while let Some(len) = scanner.next_read()? {
+            let mut bytes = vec![0; len];
+            reader.read_exact(&mut bytes)?;
+            scanner.feed(&bytes)?;
+        }
+        Ok(Self(scanner.bytes))
+    }
+
+    pub async fn read_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Self, NetDecodeError> {
+        let mut scanner = Scanner::new();
+        while let Some(len) = scanner.next_read()? {
+            let mut bytes = vec![0; len];
+            reader.read_exact(&mut bytes).await?;
+            scanner.feed(&bytes)?;
+        }
+        Ok(Self(scanner.bytes))
+    }
+}
+
+impl<'a> FromNbt<'a> for NetworkNbt {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> crate::Result<Self> {
+        let mut bytes = Vec::new();
+        tapes
+            .write_element(element, &mut bytes, &NBTSerializeOptions::Network)
+            .map_err(|_| NBTError::InvalidNBTData)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl NetDecode for NetworkNbt {
+    fn decode<R: Read>(reader: &mut R, _: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Self::read(reader)
+    }
+
+    async fn decode_async<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        _: &NetDecodeOpts,
+    ) -> Result<Self, NetDecodeError> {
+        Self::read_async(reader).await
+    }
+}
+
+impl NetEncode for NetworkNbt {
+    fn encode<W: Write>(&self, writer: &mut W, _: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        writer.write_all(&self.0)?;
+        Ok(())
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        _: &NetEncodeOpts,
+    ) -> Result<(), NetEncodeError> {
+        writer.write_all(&self.0).await?;
+        Ok(())
+    }
+}
+
+enum Frame {
+    Compound,
+    List { tag: u8, remaining: i32 },
+}
+
+/// What the scanner reads next.
+enum Want {
+    RootTag,
+    /// The tag of the next entry of a compound, or the end of it.
+    EntryTag,
+    EntryNameLength(u8),
+    /// Bytes that are only copied, followed by the payload of a tag if there is one.
+    Bytes {
+        len: usize,
+        then: Option<u8>,
+    },
+    Payload(u8),
+    StringLength,
+    /// The length of an array whose elements are this many bytes.
+    ArrayLength(usize),
+    ListHeader,
+}
+
+/// Works out how many bytes one NBT value takes while it's being read, so the same walk can
+/// be used for blocking and async readers.
+struct Scanner {
+    bytes: Vec<u8>,
+    stack: Vec<Frame>,
+    want: Option<Want>,
+}
+
+fn invalid(message: &str) -> NetDecodeError {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string()).into()
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            stack: Vec::new(),
+            want: Some(Want::RootTag),
+        }
+    }
+
+    /// How many bytes to read next, or `None` once the value is complete.
+    fn next_read(&mut self) -> Result<Option<usize>, NetDecodeError> {
+        loop {
+            let len = match self.want {
+                None => return Ok(None),
+                Some(Want::Payload(tag)) => {
+                    self.start_payload(tag)?;
+                    continue;
+                }
+                Some(Want::RootTag | Want::EntryTag) => 1,
+                Some(Want::EntryNameLength(_) | Want::StringLength) => 2,
+                Some(Want::ArrayLength(_)) => 4,
+                Some(Want::ListHeader) => 5,
+                Some(Want::Bytes { len, .. }) => len,
+            };
+            if len > MAX_SIZE - self.bytes.len() {
+                return Err(invalid("NBT value is too large"));
+            }
+            if len == 0 {
+                self.feed(&[])?;
+                continue;
+            }
+            return Ok(Some(len));
+        }
+    }
+
+    fn start_payload(&mut self, tag: u8) -> Result<(), NetDecodeError> {
+        let fixed = |len| Want::Bytes { len, then: None };
+        self.want = Some(match tag {
+            1 => fixed(1),
+            2 => fixed(2),
+            3 | 5 => fixed(4),
+            4 | 6 => fixed(8),
+            7 => Want::ArrayLength(1),
+            TAG_STRING => Want::StringLength,
+            TAG_LIST => Want::ListHeader,
+            TAG_COMPOUND => {
+                self.push(Frame::Compound)?;
+                Want::EntryTag
+            }
+            11 => Want::ArrayLength(4),
+            12 => Want::ArrayLength(8),
+            _ => return Err(invalid("Invalid NBT tag")),
+        });
+        Ok(())
+    }
+
+    fn push(&mut self, frame: Frame) -> Result<(), NetDecodeError> {
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(invalid("NBT is nested too deeply"));
+        }
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    /// Moves on to whatever follows a complete value.
+    fn finish_value(&mut self) {
+        loop {
+            match self.stack.last_mut() {
+                None => self.want = None,
+                Some(Frame::Compound) => self.want = Some(Want::EntryTag),
+                Some(Frame::List { tag, remaining }) if *remaining > 0 => {
+                    *remaining -= 1;
+                    self.want = Some(Want::Payload(*tag));
+                }
+                Some(Frame::List { .. }) => {
+                    self.stack.pop();
+                    continue;
+                }
+            }
+            return;
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) -> Result<(), NetDecodeError> {
+        self.bytes.extend_from_slice(bytes);
+        let length = |bytes: &[u8]| -> Result<usize, NetDecodeError> {
+            let length = match bytes.len() {
+                2 => i64::from(u16::from_be_bytes([bytes[0], bytes[1]])),
+                _ => i64::from(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+            };
+            usize::try_from(length).map_err(|_| invalid("Negative NBT length"))
+        };
+        match self.want.take() {
+            None | Some(Want::Payload(_)) => unreachable!("nothing to read"),
+            Some(Want::RootTag) if bytes[0] == TAG_END => self.want = None,
+            Some(Want::RootTag) => self.want = Some(Want::Payload(bytes[0])),
+            Some(Want::EntryTag) if bytes[0] == TAG_END => {
+                self.stack.pop();
+                self.finish_value();
+            }
+            Some(Want::EntryTag) => self.want = Some(Want::EntryNameLength(bytes[0])),
+            Some(Want::EntryNameLength(tag)) => {
+                self.want = Some(Want::Bytes {
+                    len: length(bytes)?,
+                    then: Some(tag),
+                })
+            }
+            Some(Want::Bytes {
+                then: Some(tag), ..
+            }) => self.want = Some(Want::Payload(tag)),
+            Some(Want::Bytes { then: None, .. }) => self.finish_value(),
+            Some(Want::StringLength) => {
+                self.want = Some(Want::Bytes {
+                    len: length(bytes)?,
+                    then: None,
+                })
+            }
+            Some(Want::ArrayLength(size)) => {
+                self.want = Some(Want::Bytes {
+                    len: length(bytes)?.saturating_mul(size),
+                    then: None,
+                })
+            }
+            Some(Want::ListHeader) => {
+                let remaining = length(&bytes[1..])? as i32;
+                self.push(Frame::List {
+                    tag: bytes[0],
+                    remaining,
+                })?;
+                self.finish_value();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(bytes: &[u8]) -> Result<NetworkNbt, NetDecodeError> {
+        NetworkNbt::read(&mut &bytes[..])
+    }
+
+    #[test]
+    fn test_read_string() {
+        let nbt = NetworkNbt::string("Excalibur");
+        let mut bytes = nbt.0.clone();
+        bytes.push(42);
+        let mut reader = &bytes[..];
+        assert_eq!(NetworkNbt::read(&mut reader).unwrap(), nbt);
+        // Only the value is read
+        assert_eq!(reader, &[42]);
+        assert_eq!(nbt.as_string(), Some("Excalibur"));
+    }
+
+    #[test]
+    fn test_read_compound() {
+        let mut bytes = vec![TAG_COMPOUND];
+        "text".serialize(&mut bytes, &NBTSerializeOptions::WithHeader("text"));
+        vec![1i32, 2, 3].serialize(&mut bytes, &NBTSerializeOptions::WithHeader("ints"));
+        vec!["a", "b"].serialize(&mut bytes, &NBTSerializeOptions::WithHeader("list"));
+        bytes.extend_from_slice(&[TAG_LIST, 0, 0, TAG_END, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&[TAG_COMPOUND, 0, 1, b'c', TAG_END]);
+        bytes.push(TAG_END);
+        assert_eq!(read(&bytes).unwrap().0, bytes);
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_async() {
+        let nbt = NetworkNbt::string("Excalibur");
+        let decoded = NetworkNbt::read_async(&mut &nbt.0[..]).await.unwrap();
+        assert_eq!(decoded, nbt);
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        assert!(read(&[13]).is_err());
+        // A string claiming to be longer than it is
+        assert!(read(&[TAG_STRING, 0xFF, 0xFF]).is_err());
+        // A list of a negative number of ints
+        assert!(read(&[TAG_LIST, 3, 0xFF, 0xFF, 0xFF, 0xFF]).is_err());
+
+        let mut nested = vec![TAG_COMPOUND];
+        for _ in 0..MAX_DEPTH {
+            nested.extend_from_slice(&[TAG_COMPOUND, 0, 0]);
+        }
+        let Err(NetDecodeError::IoError(error)) = read(&nested) else {
+            panic!("Nesting should be limited");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_write_named() {
+        let mut bytes = Vec::new();
+        NetworkNbt::string("a").write_named("b", &mut bytes);
+        let mut expected = Vec::new();
+        "a".serialize(&mut expected, &NBTSerializeOptions::WithHeader("b"));
+        assert_eq!(bytes, expected);
+    }
+}
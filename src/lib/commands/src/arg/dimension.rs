@@ -0,0 +1,31 @@
+use ferrumc_core::dimension::Dimension;
+
+use crate::{CommandContext, Suggestion};
+
+use super::{primitive::PrimitiveArgument, utils::parser_error, CommandArgument, ParserResult};
+
+impl CommandArgument for Dimension {
+    fn parse(ctx: &mut CommandContext) -> ParserResult<Self> {
+        let name = ctx.input.read_string();
+        Dimension::from_name(&name)
+            .ok_or_else(|| parser_error(&format!("unknown dimension: {name}")))
+    }
+
+    fn primitive() -> PrimitiveArgument {
+        PrimitiveArgument::dimension()
+    }
+
+    fn suggest(ctx: &mut CommandContext) -> Vec<Suggestion> {
+        let input = ctx.input.read_string();
+
+        Dimension::ALL
+            .into_iter()
+            .map(Dimension::identifier)
+            .filter(|identifier| {
+                identifier.starts_with(&input)
+                    || identifier["minecraft:".len()..].starts_with(&input)
+            })
+            .map(Suggestion::of)
+            .collect()
+    }
+}
@@ -0,0 +1,31 @@
+use ferrumc_core::entities::kind::EntityKind;
+
+use crate::{CommandContext, Suggestion};
+
+use super::{primitive::PrimitiveArgument, utils::parser_error, CommandArgument, ParserResult};
+
+impl CommandArgument for EntityKind {
+    fn parse(ctx: &mut CommandContext) -> ParserResult<Self> {
+        let name = ctx.input.read_string();
+        EntityKind::from_name(&name)
+            .ok_or_else(|| parser_error(&format!("unknown entity type: {name}")))
+    }
+
+    fn primitive() -> PrimitiveArgument {
+        // The client only knows entity types through the resource parser, which isn't supported
+        // yet, so they're sent as plain words
+        PrimitiveArgument::word()
+    }
+
+    fn suggest(ctx: &mut CommandContext) -> Vec<Suggestion> {
+        let input = ctx.input.read_string();
+
+        let mut names = EntityKind::names()
+            .filter(|name| {
+                name.starts_with(&input) || name["minecraft:".len()..].starts_with(&input)
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.into_iter().map(Suggestion::of).collect()
+    }
+}
@@ -0,0 +1,28 @@
+use ferrumc_core::game_mode::GameMode;
+
+use crate::{CommandContext, Suggestion};
+
+use super::{primitive::PrimitiveArgument, utils::parser_error, CommandArgument, ParserResult};
+
+impl CommandArgument for GameMode {
+    fn parse(ctx: &mut CommandContext) -> ParserResult<Self> {
+        let name = ctx.input.read_string();
+        GameMode::from_name(&name)
+            .ok_or_else(|| parser_error(&format!("unknown game mode: {name}")))
+    }
+
+    fn primitive() -> PrimitiveArgument {
+        PrimitiveArgument::game_mode()
+    }
+
+    fn suggest(ctx: &mut CommandContext) -> Vec<Suggestion> {
+        let input = ctx.input.read_string();
+
+        GameMode::ALL
+            .into_iter()
+            .map(GameMode::name)
+            .filter(|name| name.starts_with(&input))
+            .map(Suggestion::of)
+            .collect()
+    }
+}
@@ -5,7 +5,11 @@ use primitive::PrimitiveArgument;
 
 use crate::{ctx::CommandContext, Suggestion};
 
+pub mod dimension;
 pub mod duration;
+pub mod entity_kind;
+pub mod game_mode;
+pub mod player;
 pub mod primitive;
 
 pub type ParserResult<T> = Result<T, Box<TextComponent>>;
@@ -50,6 +54,10 @@ where
     fn primitive() -> PrimitiveArgument {
         T::primitive()
     }
+
+    fn suggest(ctx: &mut CommandContext) -> Vec<Suggestion> {
+        T::suggest(ctx)
+    }
 }
 
 /// An instance of a command argument node consisting of a name, optionality and the
@@ -0,0 +1,63 @@
+use bevy_ecs::prelude::Entity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+
+use crate::{CommandContext, Sender, Suggestion};
+
+use super::{primitive::PrimitiveArgument, utils::parser_error, CommandArgument, ParserResult};
+
+/// A single online player, given by name or as `@s` for the sender.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlayerTarget {
+    Sender,
+    Name(String),
+}
+
+impl PlayerTarget {
+    /// Finds the targeted player among the online `players`. `None` if they aren't online, or
+    /// if the sender is targeted but isn't a player.
+    pub fn resolve<'a>(
+        &self,
+        sender: Sender,
+        players: impl IntoIterator<Item = (Entity, &'a PlayerIdentity)>,
+    ) -> Option<Entity> {
+        match self {
+            PlayerTarget::Sender => match sender {
+                Sender::Player(entity) => Some(entity),
+                _ => None,
+            },
+            PlayerTarget::Name(name) => players
+                .into_iter()
+                .find(|(_, identity)| identity.username.eq_ignore_ascii_case(name))
+                .map(|(entity, _)| entity),
+        }
+    }
+}
+
+impl CommandArgument for PlayerTarget {
+    fn parse(ctx: &mut CommandContext) -> ParserResult<Self> {
+        let input = ctx.input.read_string();
+        match input.as_str() {
+            "" => Err(parser_error("expected a player")),
+            "@s" => Ok(PlayerTarget::Sender),
+            selector if selector.starts_with('@') => {
+                Err(parser_error(&format!("unsupported selector: {selector}")))
+            }
+            name => Ok(PlayerTarget::Name(name.to_string())),
+        }
+    }
+
+    fn primitive() -> PrimitiveArgument {
+        PrimitiveArgument::player()
+    }
+
+    fn suggest(ctx: &mut CommandContext) -> Vec<Suggestion> {
+        let input = ctx.input.read_string();
+
+        // Online players aren't known here, so only the sender can be suggested
+        if "@s".starts_with(&input) {
+            vec![Suggestion::of("@s")]
+        } else {
+            vec![]
+        }
+    }
+}
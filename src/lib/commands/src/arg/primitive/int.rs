@@ -55,7 +55,7 @@ impl NetEncode for IntArgumentFlags {
 /// An integer, limited in size by the type arguments.
 pub struct Integer<const MIN: i32 = { i32::MIN }, const MAX: i32 = { i32::MAX }>(i32);
 
-impl Deref for Integer {
+impl<const MIN: i32, const MAX: i32> Deref for Integer<MIN, MAX> {
     type Target = i32;
 
     fn deref(&self) -> &Self::Target {
@@ -77,9 +77,9 @@ impl<const MIN: i32, const MAX: i32> CommandArgument for Integer<MIN, MAX> {
             )));
         }
 
-        if int > MIN {
+        if int > MAX {
             return Err(parser_error(&format!(
-                "integer too large: {int}, expected at most {MIN}"
+                "integer too large: {int}, expected at most {MAX}"
             )));
         }
 
@@ -87,6 +87,43 @@ impl<const MIN: i32, const MAX: i32> CommandArgument for Integer<MIN, MAX> {
     }
 
     fn primitive() -> PrimitiveArgument {
-        PrimitiveArgument::int(Some(MIN), Some(MIN))
+        PrimitiveArgument::int(Some(MIN), Some(MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arg::primitive::PrimitiveArgumentFlags;
+    use crate::{CommandInput, Sender, ROOT_COMMAND};
+
+    fn parse<const MIN: i32, const MAX: i32>(input: &str) -> ParserResult<Integer<MIN, MAX>> {
+        let mut ctx = CommandContext {
+            input: CommandInput::of(input.to_string()),
+            command: ROOT_COMMAND.clone(),
+            sender: Sender::Server,
+        };
+        Integer::parse(&mut ctx)
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert!(parse::<1, 4>("1").is_ok_and(|int| *int == 1));
+        assert!(parse::<1, 4>("4").is_ok_and(|int| *int == 4));
+        assert!(parse::<1, 4>("0").is_err());
+        assert!(parse::<1, 4>("5").is_err());
+        assert!(parse::<1, 4>("four").is_err());
+        assert!(parse::<{ i32::MIN }, { i32::MAX }>("-2147483648").is_ok());
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert_eq!(
+            Integer::<1, 4>::primitive().flags,
+            Some(PrimitiveArgumentFlags::Int(IntArgumentFlags {
+                min: Some(1),
+                max: Some(4),
+            }))
+        );
     }
 }
@@ -55,7 +55,7 @@ impl NetEncode for LongArgumentFlags {
 /// A 64-bit integer, limited in size by the type arguments.
 pub struct Long<const MIN: i64 = { i64::MIN }, const MAX: i64 = { i64::MAX }>(i64);
 
-impl Deref for Long {
+impl<const MIN: i64, const MAX: i64> Deref for Long<MIN, MAX> {
     type Target = i64;
 
     fn deref(&self) -> &Self::Target {
@@ -77,9 +77,9 @@ impl<const MIN: i64, const MAX: i64> CommandArgument for Long<MIN, MAX> {
             )));
         }
 
-        if long > MIN {
+        if long > MAX {
             return Err(parser_error(&format!(
-                "integer too large: {long}, expected at most {MIN}"
+                "integer too large: {long}, expected at most {MAX}"
             )));
         }
 
@@ -87,6 +87,42 @@ impl<const MIN: i64, const MAX: i64> CommandArgument for Long<MIN, MAX> {
     }
 
     fn primitive() -> PrimitiveArgument {
-        PrimitiveArgument::long(Some(MIN), Some(MIN))
+        PrimitiveArgument::long(Some(MIN), Some(MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arg::primitive::PrimitiveArgumentFlags;
+    use crate::{CommandInput, Sender, ROOT_COMMAND};
+
+    fn parse<const MIN: i64, const MAX: i64>(input: &str) -> ParserResult<Long<MIN, MAX>> {
+        let mut ctx = CommandContext {
+            input: CommandInput::of(input.to_string()),
+            command: ROOT_COMMAND.clone(),
+            sender: Sender::Server,
+        };
+        Long::parse(&mut ctx)
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert!(parse::<-10, 10>("-10").is_ok_and(|long| *long == -10));
+        assert!(parse::<-10, 10>("10").is_ok_and(|long| *long == 10));
+        assert!(parse::<-10, 10>("-11").is_err());
+        assert!(parse::<-10, 10>("11").is_err());
+        assert!(parse::<{ i64::MIN }, { i64::MAX }>("9223372036854775807").is_ok());
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert_eq!(
+            Long::<-10, 10>::primitive().flags,
+            Some(PrimitiveArgumentFlags::Long(LongArgumentFlags {
+                min: Some(-10),
+                max: Some(10),
+            }))
+        );
     }
 }
@@ -1,7 +1,6 @@
 //! Primitive command argument types.
 
 // TODO:
-// * Entity
 // * Score Holder
 // * Resource or Tag
 // * Resource or Tag Key
@@ -87,10 +86,34 @@ impl PrimitiveArgument {
             flags: None,
         }
     }
+
+    pub fn dimension() -> PrimitiveArgument {
+        PrimitiveArgument {
+            argument_type: PrimitiveArgumentType::Dimension,
+            flags: None,
+        }
+    }
+
+    pub fn game_mode() -> PrimitiveArgument {
+        PrimitiveArgument {
+            argument_type: PrimitiveArgumentType::GameMode,
+            flags: None,
+        }
+    }
+
+    /// A single player, by name or selector.
+    pub fn player() -> PrimitiveArgument {
+        PrimitiveArgument {
+            argument_type: PrimitiveArgumentType::Entity,
+            // Single entity, players only
+            flags: Some(PrimitiveArgumentFlags::Entity(0x01 | 0x02)),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, NetEncode)]
 pub enum PrimitiveArgumentFlags {
+    Entity(u8),
     Float(FloatArgumentFlags),
     Int(IntArgumentFlags),
     Long(LongArgumentFlags),
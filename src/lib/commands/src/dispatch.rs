@@ -0,0 +1,37 @@
+//! Command resolution.
+
+use std::sync::Arc;
+
+use ferrumc_text::{NamedColor, TextComponent, TextComponentBuilder};
+
+use crate::{infrastructure, Command, CommandContext, CommandInput, Sender};
+
+/// Resolves a command `input` (without the leading slash) into the matching command and a
+/// context ready to be parsed, or an error message suitable for the sender.
+pub fn resolve(
+    input: String,
+    sender: Sender,
+) -> Result<(Arc<Command>, CommandContext), Box<TextComponent>> {
+    let command = infrastructure::find_command(&input);
+    if command.is_none() {
+        return Err(Box::new(
+            TextComponentBuilder::new("Unknown command")
+                .color(NamedColor::Red)
+                .build(),
+        ));
+    }
+
+    let command = command.unwrap();
+    let input = input
+        .strip_prefix(command.name)
+        .unwrap_or(&input)
+        .trim_start();
+    let input = CommandInput::of(input.to_string());
+    let ctx = CommandContext {
+        input: input.clone(),
+        command: command.clone(),
+        sender,
+    };
+
+    Ok((command, ctx))
+}
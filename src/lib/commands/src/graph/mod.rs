@@ -104,7 +104,8 @@ impl CommandGraph {
                 suggestions_type: Some("ask_server".to_string()),
             };
 
-            if is_last {
+            // The command can stop here if the remaining arguments are optional
+            if is_last || command.args.get(idx + 1).is_some_and(|next| !next.required) {
                 arg_node.flags |= CommandNodeFlag::Executable.bitmask();
             }
 
@@ -261,3 +262,42 @@ impl CommandGraph {
             .map(|(node_idx, _remaining)| self.get_command_name(*node_idx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arg::primitive::PrimitiveArgument;
+    use crate::arg::CommandArgumentNode;
+
+    fn arg(name: &str, required: bool) -> CommandArgumentNode {
+        CommandArgumentNode {
+            name: name.to_string(),
+            required,
+            primitive: PrimitiveArgument::word(),
+            suggester: |_| Vec::new(),
+        }
+    }
+
+    fn executable(graph: &CommandGraph, name: &str) -> bool {
+        graph
+            .nodes
+            .iter()
+            .find(|node| node.name.as_deref() == Some(name))
+            .is_some_and(|node| node.is_executable())
+    }
+
+    #[test]
+    fn test_optional_arguments_executable() {
+        let mut graph = CommandGraph::default();
+        graph.push(Arc::new(Command {
+            name: "give",
+            args: vec![arg("player", true), arg("item", true), arg("count", false)],
+            permission: None,
+        }));
+
+        assert!(!executable(&graph, "give"));
+        assert!(!executable(&graph, "player"));
+        assert!(executable(&graph, "item"));
+        assert!(executable(&graph, "count"));
+    }
+}
@@ -17,6 +17,11 @@ thread_local! {
     static SYSTEMS_TO_BE_REGISTERED: RefCell<Vec<ScheduleConfigs<ScheduleSystem>>> = RefCell::new(Vec::new());
 }
 
+/// The system set every command system runs in. Order systems against it to
+/// run before or after commands are executed.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandSystems;
+
 /// Internal function. Adds a command system.
 #[doc(hidden)]
 pub fn add_system<M>(system: impl IntoScheduleConfigs<ScheduleSystem, M>) {
@@ -31,7 +36,7 @@ pub fn register_command_systems(schedule: &mut Schedule) {
     SYSTEMS_TO_BE_REGISTERED.with(|systems| {
         let mut systems = systems.borrow_mut();
         while let Some(sys) = systems.pop() {
-            schedule.add_systems(sys);
+            schedule.add_systems(sys.in_set(CommandSystems));
         }
     });
 }
@@ -53,6 +58,24 @@ pub fn get_graph() -> CommandGraph {
     }
 }
 
+/// Gets the command graph of the commands the player with the given `uuid` may use.
+pub fn get_player_graph(uuid: u128) -> CommandGraph {
+    let mut graph = CommandGraph::default();
+    for name in get_command_names() {
+        if let Some(command) = get_command_by_name(name).filter(|cmd| cmd.is_permitted(uuid)) {
+            graph.push(command);
+        }
+    }
+    graph
+}
+
+/// Gets the names of all registered commands, sorted alphabetically.
+pub fn get_command_names() -> Vec<&'static str> {
+    let mut names = COMMANDS.iter().map(|cmd| *cmd.key()).collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
 /// Attempts to find a command by its `name`.
 pub fn get_command_by_name(name: &str) -> Option<Arc<Command>> {
     COMMANDS.get(name).map(|cmd_ref| Arc::clone(&cmd_ref))
@@ -4,15 +4,18 @@
 use std::sync::{Arc, LazyLock};
 
 use arg::CommandArgumentNode;
+use ferrumc_config::permissions;
 
 pub mod arg;
 mod ctx;
+pub mod dispatch;
 pub mod errors;
 pub mod events;
 pub mod graph;
 pub mod infrastructure;
 mod input;
 mod sender;
+pub mod suggestions;
 
 // Re-export under main module to avoid clutter.
 pub use ctx::*;
@@ -29,6 +32,17 @@ pub struct Command {
 
     /// All possible arguments this command can take.
     pub args: Vec<CommandArgumentNode>,
+
+    /// The permission node needed to use this command, if any.
+    pub permission: Option<&'static str>,
+}
+
+impl Command {
+    /// Whether the player with the given `uuid` may use this command.
+    pub fn is_permitted(&self, uuid: u128) -> bool {
+        self.permission
+            .is_none_or(|node| permissions::has_permission(uuid, node))
+    }
 }
 
 /// A command suggestion.
@@ -56,5 +70,6 @@ pub static ROOT_COMMAND: LazyLock<Arc<Command>> = LazyLock::new(|| {
     Arc::new(Command {
         name: "",
         args: Vec::new(),
+        permission: None,
     })
 });
@@ -1,10 +1,20 @@
 //! Command senders.
 
+use std::sync::LazyLock;
+
 use bevy_ecs::prelude::*;
+use dashmap::DashMap;
+use ferrumc_config::permissions;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_core::mq;
 use ferrumc_text::TextComponent;
 use tracing::info;
 
+use crate::Command;
+
+/// Output captured for RCON requests, keyed by request id.
+static RCON_OUTPUT: LazyLock<DashMap<u32, Vec<String>>> = LazyLock::new(DashMap::new);
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 /// A possible command sender.
 pub enum Sender {
@@ -13,6 +23,10 @@ pub enum Sender {
 
     /// The server console has sent a command.
     Server,
+
+    /// A remote console client has sent a command. Messages are captured under
+    /// the given request id instead of being logged, see [`take_rcon_output`].
+    Rcon(u32),
 }
 
 impl Sender {
@@ -22,8 +36,45 @@ impl Sender {
         match self {
             Sender::Player(entity) => mq::queue(message, actionbar, *entity),
             Sender::Server => {
-                info!("{message}"); // TODO: serialize into ANSI?
+                info!("{}", message.to_ansi());
             }
+            Sender::Rcon(id) => RCON_OUTPUT
+                .entry(*id)
+                .or_default()
+                .push(message.to_plain_text()),
+        }
+    }
+
+    /// Whether this sender has the permission node `permission`. The console and remote
+    /// consoles have every permission.
+    pub fn has_permission(&self, permission: &str, players: &Query<&PlayerIdentity>) -> bool {
+        match self {
+            Sender::Player(entity) => players.get(*entity).is_ok_and(|identity| {
+                permissions::has_permission(identity.uuid.as_u128(), permission)
+            }),
+            Sender::Server | Sender::Rcon(_) => true,
         }
     }
+
+    /// Whether this sender may use the given `command`.
+    pub fn can_use(&self, command: &Command, players: &Query<&PlayerIdentity>) -> bool {
+        command
+            .permission
+            .is_none_or(|permission| self.has_permission(permission, players))
+    }
+}
+
+/// Takes all messages sent to [`Sender::Rcon`] with the given request `id`,
+/// joined by newlines.
+pub fn take_rcon_output(id: u32) -> String {
+    RCON_OUTPUT
+        .remove(&id)
+        .map(|(_, lines)| lines.join("\n"))
+        .unwrap_or_default()
+}
+
+/// Drops the output of every RCON request. Anything still here once all requests of a tick have
+/// been answered came too late to be sent, and would otherwise never be taken.
+pub fn clear_rcon_output() {
+    RCON_OUTPUT.clear();
 }
@@ -0,0 +1,110 @@
+//! Command suggestions, shared by in-game tab completion and the server console.
+
+use std::sync::Arc;
+
+use crate::{
+    infrastructure, Command, CommandContext, CommandInput, Sender, Suggestion, ROOT_COMMAND,
+};
+
+/// Suggestions for the token currently being typed.
+pub struct CommandSuggestions {
+    /// Byte offset into the input where the current token starts.
+    pub start: usize,
+
+    /// Suggestions that start with the current token.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Finds the command being typed in a partial `input`, dropping trailing words
+/// until a command matches.
+pub fn find_partial_command(input: &str) -> Option<Arc<Command>> {
+    let mut input = input.strip_prefix('/').unwrap_or(input).to_string();
+
+    if let Some(command) = infrastructure::get_command_by_name(&input) {
+        return Some(command);
+    }
+
+    if let Some(command) = infrastructure::find_command(&input) {
+        return Some(command);
+    }
+
+    while !input.is_empty() {
+        // remove the last word and retry
+        if let Some(pos) = input.rfind(char::is_whitespace) {
+            input.truncate(pos);
+
+            if let Some(command) = infrastructure::get_command_by_name(&input) {
+                return Some(command);
+            }
+
+            if let Some(command) = infrastructure::find_command(&input) {
+                return Some(command);
+            }
+        } else {
+            break; // string does not have any further words, meaning it's just whitespace?
+        }
+    }
+
+    None
+}
+
+fn create_ctx(input: &str, command: Option<Arc<Command>>, sender: Sender) -> CommandContext {
+    let input = input
+        .strip_prefix(command.clone().map(|c| c.name).unwrap_or_default())
+        .unwrap_or(input)
+        .trim_start();
+
+    CommandContext {
+        input: CommandInput::of(input.to_string()),
+        command: command.unwrap_or(ROOT_COMMAND.clone()),
+        sender,
+    }
+}
+
+/// Suggests completions for a partial command `input`, with or without a leading slash.
+///
+/// Arguments are completed with their [`CommandArgument::suggest`](crate::arg::CommandArgument::suggest)
+/// implementation. While the first word is still being typed, command names are suggested instead.
+/// Only commands that are `permitted` for the sender are completed.
+pub fn suggest(
+    input: &str,
+    sender: Sender,
+    permitted: impl Fn(&Command) -> bool,
+) -> CommandSuggestions {
+    let unprefixed = input.strip_prefix('/').unwrap_or(input);
+    let current_token = unprefixed.rsplit(' ').next().unwrap_or_default();
+    let start = input.len() - current_token.len();
+
+    let command = find_partial_command(input);
+    let mut suggestions = Vec::new();
+
+    match command {
+        Some(command) if unprefixed.contains(' ') && permitted(&command) => {
+            let command_arg = unprefixed
+                .strip_prefix(&format!("{} ", command.name))
+                .unwrap_or(unprefixed);
+            let mut ctx = create_ctx(command_arg, Some(command.clone()), sender);
+
+            for arg in command.args.clone() {
+                let arg_suggestions = (arg.suggester)(&mut ctx);
+                ctx.input.skip_whitespace(u32::MAX, true);
+                if !ctx.input.has_remaining_input() {
+                    suggestions = arg_suggestions;
+                    break;
+                }
+            }
+        }
+        _ if !unprefixed.contains(' ') => {
+            suggestions = infrastructure::get_command_names()
+                .into_iter()
+                .filter_map(infrastructure::get_command_by_name)
+                .filter(|command| permitted(command))
+                .map(|command| Suggestion::of(command.name))
+                .collect();
+        }
+        _ => {}
+    }
+
+    suggestions.retain(|sug| sug.content.starts_with(current_token));
+    CommandSuggestions { start, suggestions }
+}
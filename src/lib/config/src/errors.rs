@@ -24,6 +24,11 @@ pub enum ConfigError {
     #[error("Failed to set configuration file.")]
     ConfigSetError,
 
+    /// Error when permissions are changed while the permissions file couldn't be read, which
+    /// would be overwritten otherwise. Contains why it couldn't be read.
+    #[error("The permissions file couldn't be read, so changes aren't saved over it: {0}")]
+    PermissionsUnreadable(String),
+
     /// Error when the root path cannot be found.
     #[error("Failed to get the root path.")]
     RootPathError(#[from] ferrumc_general_purpose::paths::RootPathError),
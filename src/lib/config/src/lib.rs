@@ -6,10 +6,12 @@
 //!
 //! The crate is organized into the following modules:
 //! - [errors](errors/index.html): Error types for the config module.
+//! - [permissions](permissions/index.html): Operator levels and permission nodes.
 //! - [server_config](server_config/index.html): Server configuration struct and functions.
 
 pub mod errors;
 pub mod favicon;
+pub mod permissions;
 pub mod server_config;
 pub mod setup;
 pub mod whitelist;
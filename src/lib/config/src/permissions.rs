@@ -0,0 +1,291 @@
+//! Operator levels and permission nodes, stored in `permissions.toml` next to `whitelist.txt`.
+//!
+//! A player has a permission node if it was granted to them, either directly or through a
+//! wildcard like `ferrumc.command.*`, or if their op level is at least the level the node needs.
+//!
+//! The file is read the first time a permission is checked and kept in memory after that, so
+//! edits to it while the server is running only take effect after [`reload_permissions`]. If it
+//! can't be read, nobody has any permissions beyond the defaults and changes aren't saved, so a
+//! typo doesn't get the whole file overwritten.
+
+use crate::errors::ConfigError;
+use ferrumc_general_purpose::paths::get_root_path;
+use once_cell::sync::OnceCell;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::sync::{PoisonError, RwLock};
+use tracing::error;
+use uuid::Uuid;
+
+/// The op level needed for nodes without a level of their own, vanilla's level for game commands.
+pub const DEFAULT_OP_LEVEL: u8 = 2;
+/// The highest op level, which vanilla gives to server operators.
+pub const MAX_OP_LEVEL: u8 = 4;
+
+const HEADER: &str = "\
+# This is the permissions file.
+#
+# [ops] holds the op level of players by UUID, from 1 to 4:
+# \"00000000-0000-0000-0000-000000000000\" = 4
+#
+# [players] holds the permission nodes granted to players by UUID, on top of their op level.
+# Wildcards grant every node below them:
+# \"11111111-1111-1111-1111-111111111111\" = [\"ferrumc.command.gamemode\", \"ferrumc.command.*\"]
+#
+# [levels] holds the op level a node needs, 2 if it isn't listed:
+# \"ferrumc.command.summon\" = 3
+";
+
+static PERMISSIONS: OnceCell<RwLock<Loaded>> = OnceCell::new();
+
+/// The contents of the permissions file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Permissions {
+    /// The op level of each operator.
+    pub ops: BTreeMap<Uuid, u8>,
+    /// The nodes granted to players regardless of their op level.
+    pub players: BTreeMap<Uuid, BTreeSet<String>>,
+    /// The op level needed for nodes that don't need [`DEFAULT_OP_LEVEL`].
+    pub levels: BTreeMap<String, u8>,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        // Like in vanilla, only ops of level 3 and up can make others ops
+        let levels = ["ferrumc.command.op", "ferrumc.command.deop"]
+            .map(|node| (node.to_string(), 3))
+            .into();
+        Self {
+            ops: BTreeMap::new(),
+            players: BTreeMap::new(),
+            levels,
+        }
+    }
+}
+
+impl Permissions {
+    /// The op level of the player with the given `uuid`, 0 if they aren't an operator.
+    pub fn op_level(&self, uuid: u128) -> u8 {
+        self.ops.get(&Uuid::from_u128(uuid)).copied().unwrap_or(0)
+    }
+
+    /// The op level needed to have `node` without it being granted.
+    pub fn required_level(&self, node: &str) -> u8 {
+        self.levels.get(node).copied().unwrap_or(DEFAULT_OP_LEVEL)
+    }
+
+    /// Whether the player with the given `uuid` has the permission `node`.
+    pub fn has_permission(&self, uuid: u128, node: &str) -> bool {
+        let granted = self
+            .players
+            .get(&Uuid::from_u128(uuid))
+            .is_some_and(|nodes| nodes.iter().any(|granted| covers(granted, node)));
+        granted || self.op_level(uuid) >= self.required_level(node)
+    }
+}
+
+/// Whether the `granted` node, which may be a wildcard, includes `node`.
+fn covers(granted: &str, node: &str) -> bool {
+    match granted.strip_suffix('*') {
+        Some(prefix) => prefix.is_empty() || (prefix.ends_with('.') && node.starts_with(prefix)),
+        None => granted == node,
+    }
+}
+
+/// The permissions in memory, and whether they came from the file.
+struct Loaded {
+    permissions: Permissions,
+    /// Why the file couldn't be read, if it couldn't.
+    error: Option<String>,
+}
+
+impl Loaded {
+    fn new(file: Result<Permissions, ConfigError>) -> Self {
+        match file {
+            Ok(permissions) => Self {
+                permissions,
+                error: None,
+            },
+            Err(e) => {
+                error!(
+                    "Could not load permissions file, changes won't be saved until it's fixed: {e}"
+                );
+                Self {
+                    permissions: Permissions::default(),
+                    error: Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Applies `change` and returns the permissions to save, unless the file couldn't be read.
+    fn change(
+        &mut self,
+        change: impl FnOnce(&mut Permissions),
+    ) -> Result<&Permissions, ConfigError> {
+        if let Some(e) = &self.error {
+            return Err(ConfigError::PermissionsUnreadable(e.clone()));
+        }
+        change(&mut self.permissions);
+        Ok(&self.permissions)
+    }
+}
+
+fn permissions() -> &'static RwLock<Loaded> {
+    PERMISSIONS.get_or_init(|| RwLock::new(Loaded::new(load_permissions())))
+}
+
+/// Reads the permissions file, creating it if it doesn't exist yet.
+pub fn load_permissions() -> Result<Permissions, ConfigError> {
+    let location = get_root_path().join("permissions.toml");
+    if !location.exists() {
+        create_blank_permissions_file();
+        return Ok(Permissions::default());
+    }
+    let contents = std::fs::read_to_string(&location)?;
+    Ok(toml::from_str(&contents)?)
+}
+
+/// Reads the permissions file again, for edits made to it while the server is running. If it
+/// can't be read, the permissions stay as they were.
+pub fn reload_permissions() -> Result<(), ConfigError> {
+    let permissions = load_permissions()?;
+    *self::permissions()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Loaded::new(Ok(permissions));
+    Ok(())
+}
+
+/// Whether the player with the given `uuid` has the permission `node`.
+pub fn has_permission(uuid: u128, node: &str) -> bool {
+    permissions()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .permissions
+        .has_permission(uuid, node)
+}
+
+/// The op level of the player with the given `uuid`, 0 if they aren't an operator.
+pub fn op_level(uuid: u128) -> u8 {
+    permissions()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .permissions
+        .op_level(uuid)
+}
+
+/// Sets the op level of the player with the given `uuid`, clamped to [`MAX_OP_LEVEL`]. Level 0
+/// takes their operator status away.
+pub fn set_op_level(uuid: u128, level: u8) -> Result<(), ConfigError> {
+    update(|permissions| {
+        let uuid = Uuid::from_u128(uuid);
+        if level == 0 {
+            permissions.ops.remove(&uuid);
+        } else {
+            permissions.ops.insert(uuid, level.min(MAX_OP_LEVEL));
+        }
+    })
+}
+
+/// Grants the permission `node` to the player with the given `uuid`.
+pub fn grant_permission(uuid: u128, node: &str) -> Result<(), ConfigError> {
+    update(|permissions| {
+        permissions
+            .players
+            .entry(Uuid::from_u128(uuid))
+            .or_default()
+            .insert(node.to_string());
+    })
+}
+
+/// Takes the permission `node` away from the player with the given `uuid`. This doesn't
+/// affect nodes they have through their op level or a wildcard.
+pub fn revoke_permission(uuid: u128, node: &str) -> Result<(), ConfigError> {
+    update(|permissions| {
+        let uuid = Uuid::from_u128(uuid);
+        if let Some(nodes) = permissions.players.get_mut(&uuid) {
+            nodes.remove(node);
+            if nodes.is_empty() {
+                permissions.players.remove(&uuid);
+            }
+        }
+    })
+}
+
+/// Applies `change` to the permissions and writes them back to the file.
+fn update(change: impl FnOnce(&mut Permissions)) -> Result<(), ConfigError> {
+    let mut loaded = permissions()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    save(loaded.change(change)?)
+}
+
+fn save(permissions: &Permissions) -> Result<(), ConfigError> {
+    let contents = toml::to_string(permissions)?;
+    let mut file = File::create(get_root_path().join("permissions.toml"))?;
+    write!(file, "{HEADER}\n{contents}")?;
+    Ok(())
+}
+
+pub fn create_blank_permissions_file() {
+    if let Err(e) = save(&Permissions::default()) {
+        error!("Failed to save permissions: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_permission() {
+        let op = Uuid::from_u128(1);
+        let player = Uuid::from_u128(2);
+        let mut permissions = Permissions::default();
+        permissions.ops.insert(op, 2);
+        permissions
+            .players
+            .insert(player, BTreeSet::from(["ferrumc.command.*".to_string()]));
+
+        assert!(permissions.has_permission(op.as_u128(), "ferrumc.command.gamemode"));
+        assert!(!permissions.has_permission(op.as_u128(), "ferrumc.command.op"));
+        assert!(permissions.has_permission(player.as_u128(), "ferrumc.command.op"));
+        assert!(!permissions.has_permission(player.as_u128(), "ferrumc.commands"));
+        assert!(!permissions.has_permission(3, "ferrumc.command.gamemode"));
+    }
+
+    #[test]
+    fn test_permissions_file_roundtrip() {
+        let mut permissions = Permissions::default();
+        permissions.ops.insert(Uuid::from_u128(1), 4);
+        permissions.players.insert(
+            Uuid::from_u128(2),
+            BTreeSet::from(["ferrumc.command.summon".to_string()]),
+        );
+
+        let contents = format!("{HEADER}\n{}", toml::to_string(&permissions).unwrap());
+        assert_eq!(
+            toml::from_str::<Permissions>(&contents).unwrap(),
+            permissions
+        );
+    }
+
+    #[test]
+    fn test_unreadable_file_isnt_overwritten() {
+        let grant = |permissions: &mut Permissions| {
+            permissions.ops.insert(Uuid::from_u128(1), 4);
+        };
+        let mut broken = Loaded::new(toml::from_str("ops = [").map_err(ConfigError::from));
+        assert!(matches!(
+            broken.change(grant),
+            Err(ConfigError::PermissionsUnreadable(_))
+        ));
+        assert!(broken.permissions.ops.is_empty());
+
+        let mut loaded = Loaded::new(Ok(Permissions::default()));
+        assert_eq!(loaded.change(grant).unwrap().op_level(1), 4);
+    }
+}
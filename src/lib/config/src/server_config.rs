@@ -35,8 +35,17 @@ pub fn set_global_config(config: ServerConfig) {
 /// - `world`: The name of the world that the server will load.
 /// - `network_compression_threshold`: The threshold at which the server will compress network packets.
 /// - `whitelist`: Whether the server whitelist is enabled or not.
+/// - `online_mode`: Whether players are authenticated with the session server and the connection
+///   is encrypted.
+/// - `session_server`: The base URL of the session server used to authenticate players in online mode.
 /// - `chunk_render_distance`: The render distance of the chunks. This is the number of chunks that will be
 ///   loaded around the player.
+/// - `entity_tracking_distance`: How far away players can see entities, in chunks. It's capped at
+///   the chunk render distance.
+/// - `rcon` - [RconConfig]: The configuration for the remote console.
+/// - `query` - [QueryConfig]: The configuration for the UDP query protocol.
+/// - `default_gamemode`: The game mode new players start in: `survival`, `creative`, `adventure`
+///   or `spectator`.
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct ServerConfig {
     pub host: String,
@@ -49,7 +58,13 @@ pub struct ServerConfig {
     pub network_compression_threshold: i32, // Can be negative
     pub verify_decompressed_packets: bool,
     pub whitelist: bool,
+    pub online_mode: bool,
+    pub session_server: String,
     pub chunk_render_distance: u32,
+    pub entity_tracking_distance: u32,
+    pub rcon: RconConfig,
+    pub query: QueryConfig,
+    pub default_gamemode: String,
 }
 
 /// The database configuration section from [ServerConfig].
@@ -72,6 +87,30 @@ pub struct DatabaseConfig {
     pub cache_capacity: u64,
 }
 
+/// The remote console (RCON) configuration section from [ServerConfig].
+///
+/// Fields:
+/// - `enabled`: Whether the RCON listener is started.
+/// - `port`: The port that the RCON listener will bind to, on the same host as the server.
+/// - `password`: The password clients have to authenticate with. RCON won't start if this is empty.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct RconConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub password: String,
+}
+
+/// The UDP query (GameSpy4) configuration section from [ServerConfig].
+///
+/// Fields:
+/// - `enabled`: Whether the query listener is started.
+/// - `port`: The UDP port that the query listener will bind to, on the same host as the server.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct QueryConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
 fn create_config() -> ServerConfig {
     let config_location = get_root_path().join("configs");
     let main_config_file = config_location.join("config.toml");
@@ -1,3 +1,4 @@
+use crate::permissions::create_blank_permissions_file;
 use crate::server_config::DEFAULT_CONFIG;
 use crate::whitelist::create_blank_whitelist_file;
 use ferrumc_general_purpose::paths::get_root_path;
@@ -30,6 +31,12 @@ pub fn setup() -> Result<(), SetupError> {
     if !std::fs::exists(get_root_path().join("whitelist.txt"))? {
         create_blank_whitelist_file();
     }
+    if !std::fs::exists(get_root_path().join("permissions.toml"))? {
+        create_blank_permissions_file();
+    }
+    if !std::fs::exists(get_root_path().join("recipes"))? {
+        std::fs::create_dir(get_root_path().join("recipes"))?;
+    }
     if !std::fs::exists(get_root_path().join("configs"))? {
         std::fs::create_dir(get_root_path().join("configs"))?;
     }
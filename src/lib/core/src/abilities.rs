@@ -0,0 +1,48 @@
+use bevy_ecs::prelude::Component;
+use typename::TypeName;
+
+/// What a player is allowed to do, mirroring the flags of the player abilities packet.
+#[derive(TypeName, Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct Abilities {
+    /// Only the void can hurt the player.
+    pub invulnerable: bool,
+    /// The player is flying, which spectators always are.
+    pub flying: bool,
+    /// The player can start flying by jumping twice.
+    pub may_fly: bool,
+    /// Blocks break instantly and don't drop anything.
+    pub instant_build: bool,
+    /// The player can place and break blocks. This one isn't sent to the client.
+    pub may_build: bool,
+}
+
+impl Abilities {
+    /// The flags of the player abilities packet.
+    pub fn flags(&self) -> u8 {
+        [
+            self.invulnerable,
+            self.flying,
+            self.may_fly,
+            self.instant_build,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(0, |flags, (bit, set)| flags | (u8::from(set) << bit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags() {
+        let abilities = Abilities {
+            invulnerable: true,
+            may_fly: true,
+            may_build: true,
+            ..Default::default()
+        };
+        assert_eq!(abilities.flags(), 0x01 | 0x04);
+    }
+}
@@ -0,0 +1,10 @@
+use crate::dimension::Dimension;
+use bevy_ecs::prelude::Event;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+
+// Fired after a block in the world changes, so the blocks around it can react
+#[derive(Event)]
+pub struct BlockChangeEvent {
+    pub location: NetworkPosition,
+    pub dimension: Dimension,
+}
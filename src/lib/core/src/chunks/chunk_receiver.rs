@@ -1,3 +1,4 @@
+use crate::dimension::Dimension;
 use bevy_ecs::prelude::Component;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicBool;
@@ -25,7 +26,7 @@ impl ChunkReceiver {
         Self {
             needs_reload: HashSet::new(),
             seen: HashSet::new(),
-            last_chunk: (0, 0, "overworld".to_string()),
+            last_chunk: (0, 0, Dimension::default().name().to_string()),
             chunks_per_tick: 0.0,
             has_loaded: AtomicBool::new(false),
         }
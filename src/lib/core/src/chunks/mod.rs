@@ -1,3 +1,4 @@
+pub mod block_change_event;
 pub mod chunk_receiver;
 pub mod cross_chunk_boundary_event;
 pub mod world_sync_tracker;
@@ -0,0 +1,242 @@
+//! Collision queries of boxes against the blocks of a world.
+//!
+//! Blocks can have any number of boxes in their collision shape, which can reach out of the block
+//! they belong to, like the 1.5 block tall collision of fences. Anything that moves or places
+//! things in the world checks it through the same few queries here.
+
+use crate::collisions::bounds::CollisionBounds;
+
+/// How far boxes can be inside each other before they count as colliding, for rounding errors.
+pub const EPSILON: f64 = 1e-7;
+
+/// The collision shapes of the blocks of a world.
+pub trait BlockShapes {
+    /// The collision boxes of the block at a position, relative to its lowest corner, or `None`
+    /// if its chunk isn't loaded. Blocks that aren't loaded don't collide with anything.
+    fn collision_shape(&mut self, x: i32, y: i32, z: i32) -> Option<&'static [CollisionBounds]>;
+}
+
+impl<F: FnMut(i32, i32, i32) -> Option<&'static [CollisionBounds]>> BlockShapes for F {
+    fn collision_shape(&mut self, x: i32, y: i32, z: i32) -> Option<&'static [CollisionBounds]> {
+        self(x, y, z)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+/// Where a box at `position` starts and ends along `axis`.
+fn span(bounds: &CollisionBounds, position: (f64, f64, f64), axis: Axis) -> (f64, f64) {
+    match axis {
+        Axis::X => (
+            position.0 + bounds.x_offset_start,
+            position.0 + bounds.x_offset_end,
+        ),
+        Axis::Y => (
+            position.1 + bounds.y_offset_start,
+            position.1 + bounds.y_offset_end,
+        ),
+        Axis::Z => (
+            position.2 + bounds.z_offset_start,
+            position.2 + bounds.z_offset_end,
+        ),
+    }
+}
+
+/// The blocks whose shapes could overlap a box at `position`. The blocks below it are included
+/// for shapes that are taller than a block.
+pub fn blocks_in(
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+) -> impl Iterator<Item = (i32, i32, i32)> {
+    let range = |axis| {
+        let (start, end) = span(bounds, position, axis);
+        (start.floor() as i32, end.floor() as i32)
+    };
+    let (x_start, x_end) = range(Axis::X);
+    let (y_start, y_end) = range(Axis::Y);
+    let (z_start, z_end) = range(Axis::Z);
+    (x_start..=x_end).flat_map(move |x| {
+        (y_start - 1..=y_end).flat_map(move |y| (z_start..=z_end).map(move |z| (x, y, z)))
+    })
+}
+
+/// The collision boxes of the blocks around a box at `position`, with the block positions they
+/// belong to.
+fn nearby_shapes(
+    world: &mut impl BlockShapes,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+) -> Vec<(CollisionBounds, (f64, f64, f64))> {
+    blocks_in(bounds, position)
+        .flat_map(|(x, y, z)| {
+            let origin = (f64::from(x), f64::from(y), f64::from(z));
+            world
+                .collision_shape(x, y, z)
+                .unwrap_or_default()
+                .iter()
+                .map(move |shape| (*shape, origin))
+        })
+        .collect()
+}
+
+/// Whether a box at `position` overlaps the collision shape of any block.
+pub fn collides(
+    world: &mut impl BlockShapes,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+) -> bool {
+    let inner = bounds.shrink(EPSILON);
+    blocks_in(&inner, position).any(|(x, y, z)| {
+        let origin = (f64::from(x), f64::from(y), f64::from(z));
+        world.collision_shape(x, y, z).is_some_and(|shapes| {
+            shapes
+                .iter()
+                .any(|shape| inner.collides(position, shape, origin))
+        })
+    })
+}
+
+/// How far a box at `position` can move along `axis` by up to `distance` before it runs into a
+/// block, like vanilla's `Shapes.collide`. Blocks the box is already inside of aren't in its way.
+pub fn sweep_axis(
+    world: &mut impl BlockShapes,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+    axis: Axis,
+    distance: f64,
+) -> f64 {
+    if distance == 0.0 {
+        return 0.0;
+    }
+    // The whole space the box passes through
+    let mut swept = *bounds;
+    match axis {
+        Axis::X if distance > 0.0 => swept.x_offset_end += distance,
+        Axis::X => swept.x_offset_start += distance,
+        Axis::Y if distance > 0.0 => swept.y_offset_end += distance,
+        Axis::Y => swept.y_offset_start += distance,
+        Axis::Z if distance > 0.0 => swept.z_offset_end += distance,
+        Axis::Z => swept.z_offset_start += distance,
+    }
+
+    let (start, end) = span(bounds, position, axis);
+    let others = [Axis::X, Axis::Y, Axis::Z]
+        .into_iter()
+        .filter(|&other| other != axis)
+        .map(|other| (other, span(bounds, position, other)))
+        .collect::<Vec<_>>();
+    let mut allowed = distance;
+    for (shape, origin) in nearby_shapes(world, &swept, position) {
+        let in_line = others.iter().all(|&(other, (own_start, own_end))| {
+            let (shape_start, shape_end) = span(&shape, origin, other);
+            own_start < shape_end - EPSILON && own_end > shape_start + EPSILON
+        });
+        if !in_line {
+            continue;
+        }
+        let (shape_start, shape_end) = span(&shape, origin, axis);
+        if allowed > 0.0 && shape_start >= end - EPSILON {
+            allowed = allowed.min(shape_start - end);
+        } else if allowed < 0.0 && shape_end <= start + EPSILON {
+            allowed = allowed.max(shape_end - start);
+        }
+    }
+    allowed
+}
+
+/// Moves a box at `position` by `motion` one axis at a time, vertically first and then the
+/// longer horizontal axis, stopping each at the first block in its way like vanilla entities do.
+/// Returns how far it actually moved.
+pub fn sweep(
+    world: &mut impl BlockShapes,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+    motion: (f64, f64, f64),
+) -> (f64, f64, f64) {
+    let order = if motion.0.abs() < motion.2.abs() {
+        [Axis::Y, Axis::Z, Axis::X]
+    } else {
+        [Axis::Y, Axis::X, Axis::Z]
+    };
+    sweep_in_order(world, bounds, position, motion, order)
+}
+
+/// Like [`sweep`], but moving along the axes in `order`.
+pub fn sweep_in_order(
+    world: &mut impl BlockShapes,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+    motion: (f64, f64, f64),
+    order: [Axis; 3],
+) -> (f64, f64, f64) {
+    let requested = [motion.0, motion.1, motion.2];
+    let mut at = [position.0, position.1, position.2];
+    let mut moved = [0.0; 3];
+    for axis in order {
+        let i = axis.index();
+        moved[i] = sweep_axis(world, bounds, (at[0], at[1], at[2]), axis, requested[i]);
+        at[i] += moved[i];
+    }
+    (moved[0], moved[1], moved[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLAB: CollisionBounds = CollisionBounds {
+        y_offset_end: 0.5,
+        ..CollisionBounds::FULL_BLOCK
+    };
+
+    /// Stone up to y = 0, a bottom slab at x = 2 and a wall at x = 5.
+    fn world(x: i32, y: i32, _z: i32) -> Option<&'static [CollisionBounds]> {
+        Some(match (x, y) {
+            (_, ..0) | (5, _) => &[CollisionBounds::FULL_BLOCK],
+            (2, 0) => &[SLAB],
+            _ => &[],
+        })
+    }
+
+    #[test]
+    fn test_collides() {
+        let player = CollisionBounds::PLAYER;
+        assert!(!collides(&mut world, &player, (0.5, 0.0, 0.5)));
+        assert!(collides(&mut world, &player, (0.5, -0.1, 0.5)));
+        assert!(collides(&mut world, &player, (2.5, 0.4, 0.5)));
+        assert!(!collides(&mut world, &player, (2.5, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_sweep() {
+        let player = CollisionBounds::PLAYER;
+        // Falling onto the ground and onto a slab
+        let moved = sweep(&mut world, &player, (0.5, 3.0, 0.5), (0.0, -5.0, 0.0));
+        assert!((moved.1 + 3.0).abs() < 1e-9);
+        let moved = sweep(&mut world, &player, (2.5, 3.0, 0.5), (0.0, -5.0, 0.0));
+        assert!((moved.1 + 2.5).abs() < 1e-9);
+
+        // Walking into the slab, and over it and into the wall
+        let moved = sweep(&mut world, &player, (0.5, 0.0, 0.5), (3.0, 0.0, 0.0));
+        assert!((moved.0 - 1.2).abs() < 1e-9);
+        let moved = sweep(&mut world, &player, (0.5, 0.5, 0.5), (10.0, 0.0, 0.0));
+        assert!((moved.0 - 4.2).abs() < 1e-9);
+        let moved = sweep_axis(&mut world, &player, (4.5, 0.0, 0.5), Axis::X, -3.0);
+        assert!((moved + 1.2).abs() < 1e-9);
+    }
+}
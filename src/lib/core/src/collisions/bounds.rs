@@ -1,7 +1,7 @@
 use bevy_ecs::prelude::Component;
 use typename::TypeName;
 
-#[derive(TypeName, Component)]
+#[derive(TypeName, Debug, Clone, Copy, PartialEq, Component)]
 pub struct CollisionBounds {
     // Given a start position, where the bounding box starts on the x-axis.
     pub x_offset_start: f64,
@@ -31,6 +31,44 @@ impl Default for CollisionBounds {
 }
 
 impl CollisionBounds {
+    /// A whole block, starting at its lowest corner.
+    pub const FULL_BLOCK: CollisionBounds = CollisionBounds {
+        x_offset_start: 0.0,
+        x_offset_end: 1.0,
+        y_offset_start: 0.0,
+        y_offset_end: 1.0,
+        z_offset_start: 0.0,
+        z_offset_end: 1.0,
+    };
+
+    /// A standing player, centered on their feet.
+    pub const PLAYER: CollisionBounds = CollisionBounds::centered(0.6, 1.8);
+
+    /// A box `width` blocks wide and `height` tall, centered on a position at its bottom, like
+    /// the bounds of entities.
+    pub const fn centered(width: f64, height: f64) -> Self {
+        Self {
+            x_offset_start: -width / 2.0,
+            x_offset_end: width / 2.0,
+            y_offset_start: 0.0,
+            y_offset_end: height,
+            z_offset_start: -width / 2.0,
+            z_offset_end: width / 2.0,
+        }
+    }
+
+    /// These bounds made smaller by `amount` on every side.
+    pub fn shrink(&self, amount: f64) -> Self {
+        Self {
+            x_offset_start: self.x_offset_start + amount,
+            x_offset_end: self.x_offset_end - amount,
+            y_offset_start: self.y_offset_start + amount,
+            y_offset_end: self.y_offset_end - amount,
+            z_offset_start: self.z_offset_start + amount,
+            z_offset_end: self.z_offset_end - amount,
+        }
+    }
+
     #[inline]
     pub fn collides(
         &self,
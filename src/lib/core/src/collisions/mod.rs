@@ -1 +1,2 @@
+pub mod blocks;
 pub mod bounds;
@@ -0,0 +1,66 @@
+use bevy_ecs::prelude::Component;
+use std::time::Instant;
+use typename::TypeName;
+
+/// The number of break animation stages the client has textures for.
+pub const DESTROY_STAGES: i8 = 10;
+
+/// The block a player is currently breaking, if any.
+#[derive(TypeName, Debug, Default, Component)]
+pub struct Digging(pub Option<DigProgress>);
+
+#[derive(Debug, Clone)]
+pub struct DigProgress {
+    /// The position of the block being broken.
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub started: Instant,
+    /// The fraction of the block that is broken each tick.
+    pub progress_per_tick: f32,
+    /// The last break animation stage sent to other players.
+    pub last_stage: i8,
+}
+
+impl DigProgress {
+    pub fn new(x: i32, y: i32, z: i32, progress_per_tick: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            started: Instant::now(),
+            progress_per_tick,
+            last_stage: -1,
+        }
+    }
+
+    /// The number of game ticks since digging started, at `tps` ticks per second.
+    pub fn ticks(&self, tps: u32) -> f32 {
+        self.started.elapsed().as_secs_f32() * tps as f32
+    }
+
+    /// The fraction of the block broken after `ticks` ticks, capped at 1.
+    pub fn progress(&self, ticks: f32) -> f32 {
+        (self.progress_per_tick * ticks).min(1.0)
+    }
+
+    /// The break animation stage for `progress`, from 0 to 9.
+    pub fn stage(progress: f32) -> i8 {
+        ((progress * DESTROY_STAGES as f32) as i8).clamp(0, DESTROY_STAGES - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage() {
+        let dig = DigProgress::new(0, 0, 0, 0.05);
+        assert_eq!(dig.progress(10.0), 0.5);
+        assert_eq!(dig.progress(40.0), 1.0);
+        assert_eq!(DigProgress::stage(0.0), 0);
+        assert_eq!(DigProgress::stage(0.55), 5);
+        assert_eq!(DigProgress::stage(1.0), 9);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::dimension::Dimension;
+use bevy_ecs::prelude::{Entity, Event};
+
+// Fired to move a player to another dimension
+#[derive(Event)]
+pub struct ChangeDimensionEvent {
+    pub player: Entity,
+    pub dimension: Dimension,
+}
@@ -0,0 +1,121 @@
+use bevy_ecs::prelude::Component;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use typename::TypeName;
+
+pub mod change_dimension_event;
+
+/// The dimension an entity is in.
+#[derive(
+    TypeName, Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub enum Dimension {
+    #[default]
+    Overworld,
+    Nether,
+    End,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 3] = [Dimension::Overworld, Dimension::Nether, Dimension::End];
+
+    /// The name of the dimension, which is also the key of its `dimension_type` registry entry
+    /// and the dimension chunks are stored under.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Dimension::Overworld => "overworld",
+            Dimension::Nether => "the_nether",
+            Dimension::End => "the_end",
+        }
+    }
+
+    /// The namespaced identifier of the dimension, as sent to the client.
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            Dimension::Overworld => "minecraft:overworld",
+            Dimension::Nether => "minecraft:the_nether",
+            Dimension::End => "minecraft:the_end",
+        }
+    }
+
+    /// Parses a dimension from its name, with or without the `minecraft:` namespace.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        Self::ALL
+            .into_iter()
+            .find(|dimension| dimension.name() == name)
+    }
+
+    /// The lowest block y coordinate.
+    pub fn min_y(self) -> i32 {
+        match self {
+            Dimension::Overworld => -64,
+            Dimension::Nether | Dimension::End => 0,
+        }
+    }
+
+    /// The number of blocks between the lowest and highest y coordinates.
+    pub fn height(self) -> i32 {
+        match self {
+            Dimension::Overworld => 384,
+            Dimension::Nether | Dimension::End => 256,
+        }
+    }
+
+    /// How many blocks in the overworld one block in this dimension spans horizontally.
+    pub fn coordinate_scale(self) -> f64 {
+        match self {
+            Dimension::Nether => 8.0,
+            Dimension::Overworld | Dimension::End => 1.0,
+        }
+    }
+
+    /// Whether the sky lights up the dimension. The Nether and the End are only lit by blocks.
+    pub fn has_skylight(self) -> bool {
+        self == Dimension::Overworld
+    }
+
+    /// The y coordinates of the chunk sections, from bottom to top.
+    pub fn sections(self) -> Range<i8> {
+        let min = self.min_y() >> 4;
+        min as i8..(min + (self.height() >> 4)) as i8
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        for dimension in Dimension::ALL {
+            assert_eq!(Dimension::from_name(dimension.name()), Some(dimension));
+            assert_eq!(
+                Dimension::from_name(dimension.identifier()),
+                Some(dimension)
+            );
+        }
+        assert_eq!(Dimension::from_name("nether"), None);
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(Dimension::Overworld.sections(), -4..20);
+        assert_eq!(Dimension::Nether.sections(), 0..16);
+        assert_eq!(Dimension::End.sections().len(), 16);
+    }
+
+    #[test]
+    fn test_coordinate_scale() {
+        let x =
+            800.0 * Dimension::Overworld.coordinate_scale() / Dimension::Nether.coordinate_scale();
+        assert_eq!(x, 100.0);
+        assert_eq!(Dimension::End.coordinate_scale(), 1.0);
+    }
+}
@@ -0,0 +1,73 @@
+use bevy_ecs::prelude::Component;
+use ferrumc_macros::get_registry_entry;
+use simd_json::base::ValueAsScalar;
+use simd_json::prelude::{ValueAsObject, ValueObjectAccess};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use typename::TypeName;
+
+/// Entity type names by id.
+static KIND_NAMES: LazyLock<HashMap<i32, String>> = LazyLock::new(|| {
+    ferrumc_registry::lookup("minecraft:entity_type/entries")
+        .and_then(|entries| {
+            entries.as_object().map(|obj| {
+                obj.iter()
+                    .filter_map(|(name, value)| {
+                        Some((value.get("protocol_id")?.as_i32()?, name.to_string()))
+                    })
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+});
+
+/// What an entity is, as its id in the `minecraft:entity_type` registry.
+#[derive(TypeName, Debug, Component, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityKind(pub i32);
+
+impl EntityKind {
+    pub const PLAYER: Self =
+        Self(get_registry_entry!("minecraft:entity_type.entries.minecraft:player") as i32);
+    pub const ITEM: Self =
+        Self(get_registry_entry!("minecraft:entity_type.entries.minecraft:item") as i32);
+    pub const FALLING_BLOCK: Self =
+        Self(get_registry_entry!("minecraft:entity_type.entries.minecraft:falling_block") as i32);
+
+    /// Looks up an entity type by name, with or without the `minecraft:` prefix.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        ferrumc_registry::lookup(&format!(
+            "minecraft:entity_type/entries/minecraft:{name}/protocol_id"
+        ))
+        .and_then(|id| id.as_i32())
+        .map(Self)
+    }
+
+    /// The name of this entity type, e.g. `minecraft:zombie`.
+    pub fn name(self) -> Option<&'static str> {
+        KIND_NAMES.get(&self.0).map(String::as_str)
+    }
+
+    /// The names of every entity type, in no particular order.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        KIND_NAMES.values().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(EntityKind::from_name("item"), Some(EntityKind::ITEM));
+        assert_eq!(
+            EntityKind::from_name("minecraft:falling_block"),
+            Some(EntityKind::FALLING_BLOCK)
+        );
+        assert_eq!(EntityKind::PLAYER.name(), Some("minecraft:player"));
+        let zombie = EntityKind::from_name("zombie").unwrap();
+        assert_eq!(zombie.name(), Some("minecraft:zombie"));
+        assert_eq!(EntityKind::from_name("not_an_entity"), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Entities that aren't players: dropped items, falling blocks and mobs.
+//!
+//! An entity is anything spawned with an [`EntityBundle`]. The server sends it to the players
+//! close enough to see it, keeps them updated when it moves and removes it from them when it's
+//! despawned or out of range, so gameplay code only has to spawn the bundle and change its
+//! components.
+
+pub mod kind;
+pub mod physics;
+pub mod tracker;
+pub mod velocity;
+
+use crate::dimension::Dimension;
+use crate::entities::kind::EntityKind;
+use crate::entities::physics::Physics;
+use crate::entities::velocity::Velocity;
+use crate::identity::entity_identity::EntityIdentity;
+use crate::transform::grounded::OnGround;
+use crate::transform::position::Position;
+use crate::transform::rotation::Rotation;
+use bevy_ecs::prelude::{Bundle, Component};
+use typename::TypeName;
+
+/// The extra value sent when an entity spawns. What it means depends on the kind of entity, for
+/// falling blocks it's the block state.
+#[derive(TypeName, Debug, Default, Component, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnData(pub i32);
+
+/// The components every entity has.
+#[derive(Bundle)]
+pub struct EntityBundle {
+    pub identity: EntityIdentity,
+    pub kind: EntityKind,
+    pub position: Position,
+    pub rotation: Rotation,
+    pub velocity: Velocity,
+    pub physics: Physics,
+    pub on_ground: OnGround,
+    pub dimension: Dimension,
+    pub spawn_data: SpawnData,
+}
+
+impl EntityBundle {
+    /// A new entity of `kind` standing still at `position`, with the physics of its kind.
+    pub fn new(kind: EntityKind, position: Position, dimension: Dimension) -> Self {
+        Self {
+            identity: EntityIdentity::new(),
+            kind,
+            position,
+            rotation: Rotation::default(),
+            velocity: Velocity::default(),
+            physics: Physics::for_kind(kind),
+            on_ground: OnGround(false),
+            dimension,
+            spawn_data: SpawnData::default(),
+        }
+    }
+
+    pub fn with_velocity(mut self, velocity: Velocity) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_spawn_data(mut self, data: i32) -> Self {
+        self.spawn_data = SpawnData(data);
+        self
+    }
+}
@@ -0,0 +1,104 @@
+use crate::collisions::bounds::CollisionBounds;
+use crate::entities::kind::EntityKind;
+use crate::entities::velocity::Velocity;
+use bevy_ecs::prelude::Component;
+use typename::TypeName;
+
+/// How an entity falls and slows down each tick.
+#[derive(TypeName, Debug, Component, Clone, Copy, PartialEq)]
+pub struct Physics {
+    /// Taken off the vertical velocity every tick, before the entity moves.
+    pub gravity: f64,
+    /// The vertical velocity is multiplied by this after the entity moves.
+    pub vertical_drag: f64,
+    /// The horizontal velocity is multiplied by this after the entity moves.
+    pub horizontal_drag: f64,
+    /// The horizontal velocity is also multiplied by this when the entity is on the ground.
+    pub friction: f64,
+    /// The box the entity collides with blocks with, around its feet.
+    pub bounds: CollisionBounds,
+}
+
+impl Physics {
+    pub const ITEM: Self = Self {
+        gravity: 0.04,
+        vertical_drag: 0.98,
+        horizontal_drag: 0.98,
+        friction: 0.6,
+        bounds: CollisionBounds::centered(0.25, 0.25),
+    };
+    pub const FALLING_BLOCK: Self = Self {
+        gravity: 0.04,
+        vertical_drag: 0.98,
+        horizontal_drag: 0.98,
+        friction: 0.7,
+        bounds: CollisionBounds::centered(0.98, 0.98),
+    };
+    pub const LIVING: Self = Self {
+        gravity: 0.08,
+        vertical_drag: 0.98,
+        horizontal_drag: 0.91,
+        friction: 0.6,
+        bounds: CollisionBounds::centered(0.6, 1.8),
+    };
+
+    /// The physics vanilla gives entities of `kind`. Everything that isn't an item or a
+    /// falling block moves like a mob.
+    pub fn for_kind(kind: EntityKind) -> Self {
+        match kind {
+            EntityKind::ITEM => Self::ITEM,
+            EntityKind::FALLING_BLOCK => Self::FALLING_BLOCK,
+            _ => Self::LIVING,
+        }
+    }
+
+    /// Pulls the entity down, before it moves.
+    pub fn fall(&self, velocity: &mut Velocity) {
+        velocity.y -= self.gravity;
+    }
+
+    /// Slows the entity down after it moved. Speeds too small to see are rounded to zero so
+    /// resting entities stop being sent to clients.
+    pub fn slow_down(&self, velocity: &mut Velocity, on_ground: bool) {
+        let horizontal = if on_ground {
+            self.horizontal_drag * self.friction
+        } else {
+            self.horizontal_drag
+        };
+        velocity.x *= horizontal;
+        velocity.y *= self.vertical_drag;
+        velocity.z *= horizontal;
+        for speed in [&mut velocity.x, &mut velocity.y, &mut velocity.z] {
+            if speed.abs() < 0.003 {
+                *speed = 0.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_velocity() {
+        let physics = Physics::FALLING_BLOCK;
+        let mut velocity = Velocity::default();
+        for _ in 0..500 {
+            physics.fall(&mut velocity);
+            physics.slow_down(&mut velocity, false);
+        }
+        // Vanilla falling blocks top out just under 2 blocks per tick
+        assert!((velocity.y + 1.96).abs() < 0.01, "{}", velocity.y);
+    }
+
+    #[test]
+    fn test_friction_stops_sliding() {
+        let physics = Physics::ITEM;
+        let mut velocity = Velocity::new(0.2, 0.0, 0.0);
+        for _ in 0..20 {
+            physics.slow_down(&mut velocity, true);
+        }
+        assert_eq!(velocity.x, 0.0);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::transform::position::Position;
+use bevy_ecs::prelude::{Component, Entity};
+use std::collections::HashSet;
+use typename::TypeName;
+
+/// The players and entities a player has been sent, and so gets updates about. The entity
+/// tracker keeps it up to date as things move in and out of the player's tracking range.
+#[derive(TypeName, Debug, Default, Component)]
+pub struct EntityTracker {
+    pub visible: HashSet<Entity>,
+}
+
+impl EntityTracker {
+    pub fn can_see(&self, entity: Entity) -> bool {
+        self.visible.contains(&entity)
+    }
+}
+
+/// The chunk a position is in.
+pub fn chunk_of(position: &Position) -> (i32, i32) {
+    (
+        (position.x.floor() as i32) >> 4,
+        (position.z.floor() as i32) >> 4,
+    )
+}
+
+/// Whether two chunks are at most `radius` chunks apart on both axes, which is the square of
+/// chunks a client has loaded around it.
+pub fn in_range(a: (i32, i32), b: (i32, i32), radius: i32) -> bool {
+    (a.0 - b.0).abs() <= radius && (a.1 - b.1).abs() <= radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_of() {
+        assert_eq!(chunk_of(&Position::new(0.0, 64.0, 15.9)), (0, 0));
+        assert_eq!(chunk_of(&Position::new(-0.5, 64.0, 16.0)), (-1, 1));
+        assert_eq!(chunk_of(&Position::new(-16.0, 64.0, -16.1)), (-1, -2));
+    }
+
+    #[test]
+    fn test_in_range() {
+        assert!(in_range((0, 0), (8, -8), 8));
+        assert!(!in_range((0, 0), (9, 0), 8));
+        assert!(!in_range((-4, 3), (-4, -6), 8));
+    }
+}
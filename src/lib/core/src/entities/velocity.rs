@@ -0,0 +1,44 @@
+use bevy_ecs::prelude::Component;
+use typename::TypeName;
+
+/// The fastest an entity can be sent as moving, in blocks per tick. Clients clamp it too.
+const MAX_NETWORK_SPEED: f64 = 3.9;
+
+/// How fast an entity is moving, in blocks per tick.
+#[derive(TypeName, Debug, Default, Component, Clone, Copy, PartialEq)]
+pub struct Velocity {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Velocity {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.x == 0.0 && self.y == 0.0 && self.z == 0.0
+    }
+
+    /// The velocity in the protocol's unit of 1/8000 of a block per tick.
+    pub fn to_network(&self) -> (i16, i16, i16) {
+        let encode =
+            |speed: f64| (speed.clamp(-MAX_NETWORK_SPEED, MAX_NETWORK_SPEED) * 8000.0) as i16;
+        (encode(self.x), encode(self.y), encode(self.z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_network() {
+        assert_eq!(Velocity::new(0.5, -1.0, 0.0).to_network(), (4000, -8000, 0));
+        assert_eq!(
+            Velocity::new(10.0, -10.0, 0.0).to_network(),
+            (31200, -31200, 0)
+        );
+    }
+}
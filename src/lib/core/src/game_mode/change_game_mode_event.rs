@@ -0,0 +1,9 @@
+use crate::game_mode::GameMode;
+use bevy_ecs::prelude::{Entity, Event};
+
+// Fired to switch a player to another game mode
+#[derive(Event)]
+pub struct ChangeGameModeEvent {
+    pub player: Entity,
+    pub game_mode: GameMode,
+}
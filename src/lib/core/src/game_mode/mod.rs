@@ -0,0 +1,104 @@
+use crate::abilities::Abilities;
+use bevy_ecs::prelude::Component;
+use std::fmt::{Display, Formatter};
+use typename::TypeName;
+
+pub mod change_game_mode_event;
+
+/// How a player plays the game.
+#[derive(TypeName, Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    /// Flies through everything and can only look around.
+    Spectator,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Survival,
+        GameMode::Creative,
+        GameMode::Adventure,
+        GameMode::Spectator,
+    ];
+
+    /// The name of the game mode, as used in commands and the config.
+    pub const fn name(self) -> &'static str {
+        match self {
+            GameMode::Survival => "survival",
+            GameMode::Creative => "creative",
+            GameMode::Adventure => "adventure",
+            GameMode::Spectator => "spectator",
+        }
+    }
+
+    /// The ID of the game mode in packets and vanilla player data.
+    pub const fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// What players in this game mode are allowed to do.
+    pub fn abilities(self) -> Abilities {
+        match self {
+            GameMode::Survival => Abilities {
+                may_build: true,
+                ..Default::default()
+            },
+            GameMode::Creative => Abilities {
+                invulnerable: true,
+                may_fly: true,
+                instant_build: true,
+                may_build: true,
+                ..Default::default()
+            },
+            GameMode::Adventure => Abilities::default(),
+            GameMode::Spectator => Abilities {
+                invulnerable: true,
+                flying: true,
+                may_fly: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_and_names() {
+        for mode in GameMode::ALL {
+            assert_eq!(GameMode::from_id(mode.id()), Some(mode));
+            assert_eq!(GameMode::from_name(mode.name()), Some(mode));
+        }
+        assert_eq!(GameMode::Spectator.id(), 3);
+        assert_eq!(GameMode::from_id(4), None);
+        assert_eq!(GameMode::from_name("hardcore"), None);
+    }
+
+    #[test]
+    fn test_abilities() {
+        assert!(!GameMode::Survival.abilities().may_fly);
+        assert!(GameMode::Creative.abilities().instant_build);
+        assert!(!GameMode::Adventure.abilities().may_build);
+        let spectator = GameMode::Spectator.abilities();
+        assert!(spectator.flying && !spectator.may_build);
+    }
+}
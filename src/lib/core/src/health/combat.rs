@@ -0,0 +1,80 @@
+//! Melee attacks between players.
+
+use crate::entities::velocity::Velocity;
+use bevy_ecs::prelude::Component;
+use typename::TypeName;
+
+/// The damage of a hit with a fist.
+pub const FIST_DAMAGE: f32 = 1.0;
+/// How long it takes to recover a full strength attack with a fist, from its attack speed of 4.
+pub const FIST_COOLDOWN_TICKS: u32 = 5;
+/// How hard a hit knocks its target back.
+pub const KNOCKBACK_STRENGTH: f64 = 0.4;
+/// How far away players can hit things from. Vanilla allows 3 blocks in survival, with some
+/// leeway for lag.
+pub const ATTACK_REACH: f64 = 6.0;
+
+/// Ticks since a player last swung, which decides how strong their next hit is.
+#[derive(TypeName, Debug, Default, Clone, Copy, Component)]
+pub struct AttackCooldown {
+    pub ticks: u32,
+}
+
+impl AttackCooldown {
+    /// How much of a full strength hit the next attack does, from 0.2 right after a swing to 1
+    /// once the cooldown is over, like vanilla.
+    pub fn strength(&self) -> f32 {
+        let progress = ((self.ticks as f32 + 0.5) / FIST_COOLDOWN_TICKS as f32).clamp(0.0, 1.0);
+        0.2 + progress * progress * 0.8
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+    }
+}
+
+/// The velocity of something moving at `velocity` after a hit from an attacker looking at
+/// `yaw` knocks it back, like vanilla's `LivingEntity::knockback`. Things on the ground are
+/// also knocked up a little.
+pub fn knockback(velocity: Velocity, yaw: f32, strength: f64, on_ground: bool) -> Velocity {
+    let yaw = f64::from(yaw).to_radians();
+    // The direction the attacker is looking at
+    let (x, z) = (-yaw.sin(), yaw.cos());
+    let y = if on_ground {
+        (velocity.y / 2.0 + strength).min(0.4)
+    } else {
+        velocity.y
+    };
+    Velocity::new(
+        velocity.x / 2.0 + x * strength,
+        y,
+        velocity.z / 2.0 + z * strength,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strength() {
+        let mut cooldown = AttackCooldown::default();
+        assert!(cooldown.strength() < 0.25);
+        cooldown.ticks = FIST_COOLDOWN_TICKS;
+        assert_eq!(cooldown.strength(), 1.0);
+    }
+
+    #[test]
+    fn test_knockback() {
+        // Facing south pushes towards positive z
+        let pushed = knockback(Velocity::default(), 0.0, KNOCKBACK_STRENGTH, true);
+        assert!(pushed.x.abs() < 1e-9);
+        assert!((pushed.z - KNOCKBACK_STRENGTH).abs() < 1e-9);
+        assert_eq!(pushed.y, 0.4);
+
+        // Facing west pushes towards negative x, and doesn't lift things in the air
+        let pushed = knockback(Velocity::new(0.0, -0.5, 0.0), 90.0, 0.4, false);
+        assert!((pushed.x + 0.4).abs() < 1e-9);
+        assert_eq!(pushed.y, -0.5);
+    }
+}
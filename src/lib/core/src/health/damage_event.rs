@@ -0,0 +1,78 @@
+use bevy_ecs::prelude::{Entity, Event};
+use ferrumc_text::{ComponentBuilder, TextComponent};
+
+// Fired to hurt a player or entity. Things that can't be hurt ignore it
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub entity: Entity,
+    pub amount: f32,
+    pub source: DamageSource,
+}
+
+impl DamageEvent {
+    pub fn new(entity: Entity, amount: f32, source: DamageSource) -> Self {
+        Self {
+            entity,
+            amount,
+            source,
+        }
+    }
+}
+
+/// What caused some damage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSource {
+    /// Landing after falling too far.
+    Fall,
+    /// Being under the bottom of the world.
+    Void,
+    /// Being hit by a player.
+    Attack(Entity),
+    /// Running out of air under water.
+    Drowning,
+}
+
+impl DamageSource {
+    /// The `minecraft:damage_type` registry entry of this source, which decides how clients show
+    /// the damage.
+    pub fn damage_type(&self) -> &'static str {
+        match self {
+            Self::Fall => "fall",
+            Self::Void => "out_of_world",
+            Self::Attack(_) => "player_attack",
+            Self::Drowning => "drown",
+        }
+    }
+
+    pub fn attacker(&self) -> Option<Entity> {
+        match self {
+            Self::Attack(attacker) => Some(*attacker),
+            _ => None,
+        }
+    }
+
+    /// Whether players that can't be hurt, like those in creative mode, still take this damage.
+    pub fn bypasses_invulnerability(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    /// The message shown when this kills `victim`. `attacker` is the name of the attacker, if
+    /// they're still around.
+    pub fn death_message(&self, victim: &str, attacker: Option<&str>) -> TextComponent {
+        let victim = TextComponent::from(victim.to_string());
+        match (self, attacker) {
+            (Self::Fall, _) => {
+                ComponentBuilder::translate("death.fell.accident.generic", vec![victim])
+            }
+            (Self::Void, _) => ComponentBuilder::translate("death.attack.outOfWorld", vec![victim]),
+            (Self::Attack(_), Some(attacker)) => ComponentBuilder::translate(
+                "death.attack.player",
+                vec![victim, TextComponent::from(attacker.to_string())],
+            ),
+            (Self::Attack(_), None) => {
+                ComponentBuilder::translate("death.attack.generic", vec![victim])
+            }
+            (Self::Drowning, _) => ComponentBuilder::translate("death.attack.drown", vec![victim]),
+        }
+    }
+}
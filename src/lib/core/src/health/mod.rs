@@ -0,0 +1,232 @@
+//! Health, hunger and breath of players, and the damage that takes their health away.
+
+pub mod combat;
+pub mod damage_event;
+
+use bevy_ecs::prelude::{Bundle, Component};
+use typename::TypeName;
+
+/// How long something can't be hurt again after taking damage, like vanilla's 10 ticks.
+pub const HURT_COOLDOWN_TICKS: u32 = 10;
+/// How many ticks of air a player has when they go under water.
+pub const MAX_AIR: i32 = 300;
+/// The most hunger points a player can have.
+pub const MAX_FOOD: i32 = 20;
+/// The hunger points a player needs to slowly regain health.
+pub const REGENERATION_FOOD: i32 = 18;
+/// How often health is regained while fed enough.
+pub const REGENERATION_TICKS: u32 = 80;
+/// The exhaustion that costs a point of saturation or hunger.
+const EXHAUSTION_LIMIT: f32 = 4.0;
+/// How far something can fall before it gets hurt.
+const SAFE_FALL_DISTANCE: f64 = 3.0;
+
+/// Everything a player needs to take damage, heal and drown.
+#[derive(Bundle, Default)]
+pub struct HealthBundle {
+    pub health: Health,
+    pub food: Food,
+    pub breath: Breath,
+    pub fall_distance: FallDistance,
+    pub attack_cooldown: combat::AttackCooldown,
+}
+
+#[derive(TypeName, Debug, Clone, Copy, PartialEq, Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    /// Ticks left before this can be hurt again. It isn't sent to clients, so it's changed
+    /// without triggering change detection.
+    pub hurt_cooldown: u32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new(20.0)
+    }
+}
+
+impl Health {
+    /// Full health out of `max`.
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            hurt_cooldown: 0,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current >= self.max
+    }
+
+    /// Takes up to `amount` health away and starts the hurt cooldown. Returns the health that
+    /// was actually lost.
+    pub fn hurt(&mut self, amount: f32) -> f32 {
+        let lost = amount.clamp(0.0, self.current.max(0.0));
+        self.current -= lost;
+        self.hurt_cooldown = HURT_COOLDOWN_TICKS;
+        lost
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+/// How hungry a player is. Saturation is used up before hunger points, and exhaustion builds up
+/// from healing and taking damage until it costs one of them.
+#[derive(TypeName, Debug, Clone, Copy, PartialEq, Component)]
+pub struct Food {
+    pub level: i32,
+    pub saturation: f32,
+    pub exhaustion: f32,
+    /// Ticks since health was last regained. Like the hurt cooldown it isn't sent to clients.
+    pub regeneration_timer: u32,
+}
+
+impl Default for Food {
+    fn default() -> Self {
+        Self {
+            level: MAX_FOOD,
+            saturation: 5.0,
+            exhaustion: 0.0,
+            regeneration_timer: 0,
+        }
+    }
+}
+
+impl Food {
+    pub fn exhaust(&mut self, amount: f32) {
+        self.exhaustion += amount;
+        while self.exhaustion >= EXHAUSTION_LIMIT {
+            self.exhaustion -= EXHAUSTION_LIMIT;
+            if self.saturation > 0.0 {
+                self.saturation = (self.saturation - 1.0).max(0.0);
+            } else {
+                self.level = (self.level - 1).max(0);
+            }
+        }
+    }
+
+    /// Whether a player is fed enough to regain health.
+    pub fn can_regenerate(&self) -> bool {
+        self.level >= REGENERATION_FOOD
+    }
+}
+
+/// The air a player has left under water, in ticks.
+#[derive(TypeName, Debug, Clone, Copy, PartialEq, Component)]
+pub struct Breath {
+    pub air: i32,
+}
+
+impl Default for Breath {
+    fn default() -> Self {
+        Self { air: MAX_AIR }
+    }
+}
+
+impl Breath {
+    /// Uses up air while `underwater`, or catches breath otherwise. Returns whether the player
+    /// ran out of air and drowns this tick, which happens once a second once the air is gone.
+    pub fn tick(&mut self, underwater: bool) -> bool {
+        if !underwater {
+            self.air = (self.air + 4).min(MAX_AIR);
+            return false;
+        }
+        self.air -= 1;
+        if self.air <= -20 {
+            self.air = 0;
+            return true;
+        }
+        false
+    }
+}
+
+/// How far something has fallen since it last stood on the ground.
+#[derive(TypeName, Debug, Default, Clone, Copy, PartialEq, Component)]
+pub struct FallDistance {
+    pub distance: f64,
+    /// The height it was at when it last moved, `None` until it moves for the first time.
+    pub last_y: Option<f64>,
+}
+
+impl FallDistance {
+    /// Adds a move to height `y`. Returns the fall damage once it lands, if it fell far enough.
+    /// Landing in water breaks the fall.
+    pub fn update(&mut self, y: f64, on_ground: bool, in_water: bool) -> Option<f32> {
+        if let Some(last_y) = self.last_y.replace(y) {
+            self.distance += (last_y - y).max(0.0);
+        }
+        if in_water {
+            self.distance = 0.0;
+        }
+        if !on_ground {
+            return None;
+        }
+        let damage = (std::mem::take(&mut self.distance) - SAFE_FALL_DISTANCE).ceil();
+        (damage > 0.0).then_some(damage as f32)
+    }
+
+    /// Forgets the fall, for when a player is moved by the server.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hurt_and_heal() {
+        let mut health = Health::default();
+        assert_eq!(health.hurt(5.0), 5.0);
+        assert_eq!(health.current, 15.0);
+        assert_eq!(health.hurt_cooldown, HURT_COOLDOWN_TICKS);
+        assert_eq!(health.hurt(100.0), 15.0);
+        assert!(health.is_dead());
+        health.heal(50.0);
+        assert!(health.is_full());
+        assert_eq!(health.current, 20.0);
+    }
+
+    #[test]
+    fn test_exhaustion_uses_saturation_first() {
+        let mut food = Food::default();
+        food.exhaust(4.0 * 5.0);
+        assert_eq!(food.level, MAX_FOOD);
+        assert_eq!(food.saturation, 0.0);
+        food.exhaust(6.0);
+        assert_eq!(food.level, MAX_FOOD - 1);
+        assert_eq!(food.exhaustion, 2.0);
+    }
+
+    #[test]
+    fn test_drowning() {
+        let mut breath = Breath::default();
+        let ticks = (0..MAX_AIR + 20).take_while(|_| !breath.tick(true)).count();
+        assert_eq!(ticks as i32, MAX_AIR + 19);
+        assert_eq!(breath.air, 0);
+        assert!(!breath.tick(false));
+        assert_eq!(breath.air, 4);
+    }
+
+    #[test]
+    fn test_fall_damage() {
+        let mut fall = FallDistance::default();
+        assert_eq!(fall.update(100.0, true, false), None);
+        assert_eq!(fall.update(95.0, false, false), None);
+        assert_eq!(fall.update(90.0, true, false), Some(7.0));
+        assert_eq!(fall.distance, 0.0);
+
+        fall.update(80.0, false, false);
+        assert_eq!(fall.update(70.0, true, true), None);
+        assert_eq!(fall.update(68.0, true, false), None);
+    }
+}
@@ -0,0 +1,62 @@
+use bevy_ecs::prelude::Component;
+use std::sync::atomic::{AtomicI32, Ordering};
+use typename::TypeName;
+
+/// The next network ID handed out to an entity.
+static NEXT_ENTITY_ID: AtomicI32 = AtomicI32::new(1);
+
+/// Allocates a network entity ID. IDs are never reused while the server is running.
+pub fn next_entity_id() -> i32 {
+    NEXT_ENTITY_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The identity of an entity that isn't a player, like a dropped item.
+///
+/// Players and other entities get their entity IDs from [`next_entity_id`], so they never
+/// collide.
+#[derive(TypeName, Debug, Component, Clone)]
+pub struct EntityIdentity {
+    pub uuid: uuid::Uuid,
+    pub entity_id: i32,
+}
+
+impl EntityIdentity {
+    /// Creates an identity with a random UUID and a freshly allocated entity ID.
+    pub fn new() -> Self {
+        Self {
+            uuid: uuid::Uuid::new_v4(),
+            entity_id: next_entity_id(),
+        }
+    }
+}
+
+impl Default for EntityIdentity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::player_identity::PlayerIdentity;
+
+    #[test]
+    fn test_ids_are_unique() {
+        let first = EntityIdentity::new();
+        let second = EntityIdentity::new();
+        assert!(second.entity_id > first.entity_id);
+        assert_ne!(first.uuid, second.uuid);
+    }
+
+    #[test]
+    fn test_players_share_ids() {
+        // Two UUIDs with the same low bits used to give players the same ID
+        let first = PlayerIdentity::new("first".to_string(), 1);
+        let second = PlayerIdentity::new("second".to_string(), (1 << 64) | 1);
+        let entity = EntityIdentity::new();
+        assert_ne!(first.entity_id, second.entity_id);
+        assert_ne!(first.entity_id, entity.entity_id);
+        assert_ne!(second.entity_id, entity.entity_id);
+    }
+}
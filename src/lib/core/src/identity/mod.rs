@@ -1 +1,2 @@
+pub mod entity_identity;
 pub mod player_identity;
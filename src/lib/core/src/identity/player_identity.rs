@@ -1,3 +1,4 @@
+use crate::identity::entity_identity::next_entity_id;
 use bevy_ecs::prelude::Component;
 use typename::TypeName;
 
@@ -5,15 +6,17 @@ use typename::TypeName;
 pub struct PlayerIdentity {
     pub username: String,
     pub uuid: uuid::Uuid,
-    pub short_uuid: i32,
+    /// The network ID of the player's entity, allocated from [`next_entity_id`] when they log in.
+    pub entity_id: i32,
 }
 
 impl PlayerIdentity {
+    /// Creates an identity with a freshly allocated entity ID.
     pub fn new(username: String, uuid: u128) -> Self {
         Self {
             username,
             uuid: uuid::Uuid::from_u128(uuid),
-            short_uuid: uuid as i32,
+            entity_id: next_entity_id(),
         }
     }
 }
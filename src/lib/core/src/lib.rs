@@ -1,10 +1,17 @@
 pub mod errors;
 
 // Core structs/types. Usually used in ECS Components.
+pub mod abilities;
 pub mod chunks;
 pub mod collisions;
 pub mod conn;
+pub mod digging;
+pub mod dimension;
+pub mod entities;
+pub mod game_mode;
+pub mod health;
 pub mod identity;
+pub mod movement;
 pub mod mq;
 pub mod state;
 pub mod transform;
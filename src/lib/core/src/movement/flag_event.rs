@@ -0,0 +1,9 @@
+use super::Violation;
+use bevy_ecs::prelude::{Entity, Event};
+
+// Fired when a player's move fails a check. Rejected moves have already been undone by then
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MovementFlagEvent {
+    pub player: Entity,
+    pub violation: Violation,
+}
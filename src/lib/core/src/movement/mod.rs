@@ -0,0 +1,499 @@
+//! Server side checks of how players move.
+//!
+//! Clients move their players themselves and only tell the server where they ended up, so each
+//! move is checked by a [`MovementValidator`] against what the player could have done: how far they
+//! can go in their current [`MovementState`], whether they went through blocks, whether they rose
+//! higher than they can jump, and whether the ground they claim to stand on is there. Moves that
+//! fail are rejected and the player is sent back to where they last were allowed to be.
+//!
+//! The checks are lenient on purpose. Potion effects, enchantments and anything that pushes
+//! players around aren't modelled, so each check leaves room for them.
+
+pub mod flag_event;
+
+use crate::collisions::blocks::{self as collisions, Axis, EPSILON};
+use crate::collisions::bounds::CollisionBounds;
+use bevy_ecs::prelude::Component;
+use typename::TypeName;
+
+/// How far players can walk each tick. This is a bit more than vanilla's 0.22 blocks to allow for
+/// jumping.
+pub const WALK_SPEED: f64 = 0.3;
+pub const SNEAK_SPEED: f64 = 0.15;
+/// Sprint jumping on ice is the fastest way to get around on foot.
+pub const SPRINT_SPEED: f64 = 0.5;
+pub const SWIM_SPEED: f64 = 0.4;
+pub const FLY_SPEED: f64 = 1.5;
+/// Gliding with an elytra boosted by fireworks.
+pub const GLIDE_SPEED: f64 = 3.5;
+/// How far players can get ahead of their speed, like when they're knocked back or the moves of
+/// a few ticks arrive together.
+const BURST_DISTANCE: f64 = 1.5;
+/// How far players can move up or down each tick. Falling tops out at 3.92 blocks a tick.
+const MAX_VERTICAL_SPEED: f64 = 4.0;
+/// How far players can move up or down at once, since the moves of two ticks can arrive together.
+const MAX_VERTICAL_BURST: f64 = 2.0 * MAX_VERTICAL_SPEED;
+/// How far above the ground they last stood on players can get without flying. A jump reaches
+/// about 1.25 blocks.
+const JUMP_HEIGHT: f64 = 1.5;
+/// How far below their feet players can be standing on something.
+const GROUND_DISTANCE: f64 = 0.1;
+/// How much further than blocks allow players can move, for rounding errors.
+const TOLERANCE: f64 = 1e-5;
+
+/// What the checks need to know about a block.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BlockInfo {
+    /// The boxes players collide with, relative to the block's lowest corner.
+    pub shape: &'static [CollisionBounds],
+    /// Whether players can stand on the block.
+    pub solid: bool,
+    pub fluid: bool,
+    pub climbable: bool,
+}
+
+/// The blocks around a player.
+pub trait BlockView {
+    /// The block at a position, or `None` if its chunk isn't loaded. Moves through unloaded
+    /// blocks are given the benefit of the doubt.
+    fn block_info(&mut self, x: i32, y: i32, z: i32) -> Option<BlockInfo>;
+}
+
+impl<F: FnMut(i32, i32, i32) -> Option<BlockInfo>> BlockView for F {
+    fn block_info(&mut self, x: i32, y: i32, z: i32) -> Option<BlockInfo> {
+        self(x, y, z)
+    }
+}
+
+/// What a player is doing that changes how fast they can move, from their player commands.
+#[derive(TypeName, Debug, Default, Clone, Copy, Component)]
+pub struct MovementState {
+    pub sprinting: bool,
+    pub sneaking: bool,
+    /// Gliding with an elytra, which stops once the player lands.
+    pub gliding: bool,
+}
+
+impl MovementState {
+    /// Starts gliding, which takes an elytra and being in the air. Returns whether the player is
+    /// gliding.
+    pub fn start_gliding(&mut self, has_elytra: bool, on_ground: bool) -> bool {
+        self.gliding = has_elytra && !on_ground;
+        self.gliding
+    }
+
+    /// How far a player can move each tick.
+    pub fn speed(&self, may_fly: bool, in_fluid: bool) -> f64 {
+        if self.gliding {
+            GLIDE_SPEED
+        } else if may_fly {
+            FLY_SPEED
+        } else if in_fluid {
+            SWIM_SPEED
+        } else if self.sneaking {
+            SNEAK_SPEED
+        } else if self.sprinting {
+            SPRINT_SPEED
+        } else {
+            WALK_SPEED
+        }
+    }
+}
+
+/// What was wrong with a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// The player moved further than they could have.
+    TooFast,
+    /// The player went through blocks.
+    NoClip,
+    /// The player rose higher than they can jump without being able to fly.
+    Flying,
+    /// The player claimed to be on the ground in mid-air, which stops them from taking fall
+    /// damage. The move itself is fine, they just aren't on the ground.
+    GroundSpoof,
+    /// The player sent coordinates that aren't finite numbers, which no client sends.
+    InvalidPosition,
+}
+
+impl Violation {
+    /// Whether moves with this violation are undone.
+    pub fn rejects_move(&self) -> bool {
+        !matches!(self, Self::GroundSpoof)
+    }
+
+    /// Whether the player is kicked for this violation, like vanilla does.
+    pub fn kicks(&self) -> bool {
+        matches!(self, Self::InvalidPosition)
+    }
+}
+
+/// The outcome of checking a move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckedMove {
+    /// Whether the player is on the ground, whatever their client said.
+    pub on_ground: bool,
+    pub violation: Option<Violation>,
+}
+
+/// Checks the moves of a player against the last one that was allowed.
+#[derive(TypeName, Debug, Component)]
+pub struct MovementValidator {
+    /// Where the player was last allowed to be.
+    last_valid: (f64, f64, f64),
+    /// How far the player can still move before they're going too fast. Each tick adds the
+    /// player's speed to it, up to a burst on top of it, however many moves they send.
+    budget: f64,
+    /// How far the player can still move up or down.
+    vertical_budget: f64,
+    /// The ticks since the budgets were last topped up.
+    ticks: u32,
+    /// The height the player last stood on the ground at.
+    ground_y: f64,
+    /// The highest the player got since they left the ground.
+    peak_y: f64,
+    /// How far above the ground the player can get. Players that fell can bounce back up as high
+    /// as they fell from, so it's the jump height plus the last fall.
+    max_rise: f64,
+    /// A teleport the client hasn't confirmed yet. Moves it sent before it are ignored.
+    pending_teleport: Option<i32>,
+    next_teleport_id: i32,
+}
+
+impl MovementValidator {
+    pub fn new(position: (f64, f64, f64)) -> Self {
+        Self {
+            last_valid: position,
+            budget: BURST_DISTANCE,
+            vertical_budget: MAX_VERTICAL_SPEED,
+            ticks: 0,
+            ground_y: position.1,
+            peak_y: position.1,
+            max_rise: JUMP_HEIGHT,
+            pending_teleport: None,
+            next_teleport_id: 1,
+        }
+    }
+
+    /// Lets the player move another tick's worth. Called once every server tick.
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.saturating_add(1);
+    }
+
+    /// Where the player was last allowed to be, and where rejected moves send them back to.
+    pub fn last_valid(&self) -> (f64, f64, f64) {
+        self.last_valid
+    }
+
+    /// Starts moving the player to `to` on the server's behalf. Returns the ID to send with the
+    /// teleport, and moves are ignored until the client confirms it.
+    pub fn teleport(&mut self, to: (f64, f64, f64)) -> i32 {
+        *self = Self {
+            next_teleport_id: self.next_teleport_id,
+            ..Self::new(to)
+        };
+        self.reject()
+    }
+
+    /// Sends the player back to where they were last allowed to be, returning the teleport ID.
+    /// Where they last stood stays the same, so they can't climb by being sent back mid-air.
+    pub fn reject(&mut self) -> i32 {
+        let id = self.next_teleport_id;
+        self.next_teleport_id = self.next_teleport_id.wrapping_add(1);
+        self.pending_teleport = Some(id);
+        id
+    }
+
+    /// Handles the client confirming a teleport. Others than the last one are ignored.
+    pub fn confirm_teleport(&mut self, id: i32) {
+        if self.pending_teleport == Some(id) {
+            self.pending_teleport = None;
+        }
+    }
+
+    /// Whether the client still has to confirm a teleport, until which its moves are ignored.
+    pub fn is_teleporting(&self) -> bool {
+        self.pending_teleport.is_some()
+    }
+
+    /// Checks a move to `to`, which the client says ended `on_ground`. Moves that aren't rejected
+    /// become the last valid position.
+    pub fn check(
+        &mut self,
+        to: (f64, f64, f64),
+        on_ground: bool,
+        state: &mut MovementState,
+        may_fly: bool,
+        blocks: &mut impl BlockView,
+    ) -> CheckedMove {
+        // NaN fails every comparison, so it would get through all of the checks below
+        if ![to.0, to.1, to.2]
+            .iter()
+            .all(|coordinate| coordinate.is_finite())
+        {
+            return Self::rejected(Violation::InvalidPosition);
+        }
+        let from = self.last_valid;
+        let body = CollisionBounds::PLAYER;
+        let in_fluid = touches(blocks, &body, to, |block| block.fluid);
+        let climbing = blocks
+            .block_info(
+                to.0.floor() as i32,
+                to.1.floor() as i32,
+                to.2.floor() as i32,
+            )
+            .is_some_and(|block| block.climbable);
+
+        let speed = state.speed(may_fly, in_fluid);
+        let ticks = f64::from(std::mem::take(&mut self.ticks));
+        self.budget = (self.budget + speed * ticks).min(speed + BURST_DISTANCE);
+        self.vertical_budget =
+            (self.vertical_budget + MAX_VERTICAL_SPEED * ticks).min(MAX_VERTICAL_BURST);
+        let horizontal = (to.0 - from.0).hypot(to.2 - from.2);
+        let vertical = (to.1 - from.1).abs();
+        if horizontal > self.budget || vertical > self.vertical_budget {
+            return Self::rejected(Violation::TooFast);
+        }
+        let stuck = {
+            let mut shapes = |x, y, z| blocks.block_info(x, y, z).map(|block| block.shape);
+            collisions::collides(&mut shapes, &body, from)
+        };
+        if !stuck && path_blocked(blocks, &body, from, to) {
+            return Self::rejected(Violation::NoClip);
+        }
+        let free = may_fly || state.gliding || in_fluid || climbing;
+        if !free && to.1 > self.ground_y + self.max_rise {
+            return Self::rejected(Violation::Flying);
+        }
+
+        // Anything under the player holds them up, as do blocks they're already inside of
+        let feet = CollisionBounds {
+            y_offset_start: -GROUND_DISTANCE,
+            y_offset_end: EPSILON,
+            ..body
+        };
+        let supported = touches(blocks, &feet, to, |block| block.solid);
+        self.budget -= horizontal;
+        self.vertical_budget -= vertical;
+        self.last_valid = to;
+        if supported && on_ground {
+            state.gliding = false;
+            self.max_rise = JUMP_HEIGHT + (self.peak_y - to.1).max(0.0);
+            self.ground_y = to.1;
+            self.peak_y = to.1;
+        } else if free {
+            if in_fluid {
+                state.gliding = false;
+            }
+            self.max_rise = JUMP_HEIGHT;
+            self.ground_y = to.1;
+            self.peak_y = to.1;
+        } else {
+            self.peak_y = self.peak_y.max(to.1);
+        }
+        CheckedMove {
+            on_ground: on_ground && supported,
+            violation: (on_ground && !supported).then_some(Violation::GroundSpoof),
+        }
+    }
+
+    fn rejected(violation: Violation) -> CheckedMove {
+        CheckedMove {
+            on_ground: false,
+            violation: Some(violation),
+        }
+    }
+}
+
+/// Whether a box at `position` overlaps any block matching `filter`, or a block that isn't loaded.
+fn touches(
+    blocks: &mut impl BlockView,
+    bounds: &CollisionBounds,
+    position: (f64, f64, f64),
+    filter: impl Fn(&BlockInfo) -> bool,
+) -> bool {
+    collisions::blocks_in(bounds, position)
+        .filter(|&(x, y, z)| {
+            bounds.collides(
+                position,
+                &CollisionBounds::FULL_BLOCK,
+                (f64::from(x), f64::from(y), f64::from(z)),
+            )
+        })
+        .any(|(x, y, z)| {
+            blocks
+                .block_info(x, y, z)
+                .is_none_or(|block| filter(&block))
+        })
+}
+
+/// Whether a box runs into a block on its way from `from` to `to`. Clients move along one axis
+/// at a time, vertically first unless that's blocked, so the move is fine if either order is
+/// clear.
+fn path_blocked(
+    blocks: &mut impl BlockView,
+    bounds: &CollisionBounds,
+    from: (f64, f64, f64),
+    to: (f64, f64, f64),
+) -> bool {
+    let mut shapes = |x, y, z| blocks.block_info(x, y, z).map(|block| block.shape);
+    let motion = (to.0 - from.0, to.1 - from.1, to.2 - from.2);
+    [[Axis::Y, Axis::X, Axis::Z], [Axis::X, Axis::Z, Axis::Y]]
+        .into_iter()
+        .all(|order| {
+            let moved = collisions::sweep_in_order(&mut shapes, bounds, from, motion, order);
+            (moved.0 - motion.0).abs() > TOLERANCE
+                || (moved.1 - motion.1).abs() > TOLERANCE
+                || (moved.2 - motion.2).abs() > TOLERANCE
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: BlockInfo = BlockInfo {
+        shape: &[CollisionBounds::FULL_BLOCK],
+        solid: true,
+        fluid: false,
+        climbable: false,
+    };
+
+    /// Flat ground with its top at y = 0 and a wall along x = 2.
+    fn world(x: i32, y: i32, _z: i32) -> Option<BlockInfo> {
+        Some(if y < 0 || x == 2 {
+            STONE
+        } else {
+            BlockInfo::default()
+        })
+    }
+
+    /// Walks to `to` in the next tick.
+    fn walk(validator: &mut MovementValidator, to: (f64, f64, f64)) -> CheckedMove {
+        validator.tick();
+        validator.check(to, true, &mut MovementState::default(), false, &mut world)
+    }
+
+    #[test]
+    fn test_speed() {
+        let mut validator = MovementValidator::new((0.5, 0.0, 0.5));
+        for step in 1..=20 {
+            let to = (0.5, 0.0, 0.5 + f64::from(step) * 0.25);
+            assert_eq!(walk(&mut validator, to).violation, None);
+        }
+        let result = walk(&mut validator, (0.5, 0.0, 10.0));
+        assert_eq!(result.violation, Some(Violation::TooFast));
+        assert_eq!(validator.last_valid(), (0.5, 0.0, 5.5));
+
+        let mut state = MovementState {
+            sprinting: true,
+            ..Default::default()
+        };
+        let to = (0.5, 0.0, 5.95);
+        validator.tick();
+        let result = validator.check(to, true, &mut state, false, &mut world);
+        assert_eq!(result.violation, None);
+    }
+
+    #[test]
+    fn test_moves_within_a_tick() {
+        // Sending more moves doesn't get players any further in a tick
+        let mut validator = MovementValidator::new((0.5, 0.0, 0.5));
+        validator.tick();
+        let mut state = MovementState::default();
+        let mut results = (1..=20).map(|step| {
+            let to = (0.5, 0.0, 0.5 + f64::from(step) * 0.25);
+            validator.check(to, true, &mut state, false, &mut world)
+        });
+        assert!(results.any(|result| result.violation == Some(Violation::TooFast)));
+        assert!(validator.last_valid().2 <= 0.5 + WALK_SPEED + BURST_DISTANCE);
+
+        // Or further down
+        let mut validator = MovementValidator::new((0.5, 100.0, 0.5));
+        validator.tick();
+        let mut results = (1..=10).map(|step| {
+            let to = (0.5, 100.0 - f64::from(step) * 3.0, 0.5);
+            validator.check(to, false, &mut state, false, &mut world)
+        });
+        assert!(results.any(|result| result.violation == Some(Violation::TooFast)));
+        assert!(validator.last_valid().1 >= 100.0 - MAX_VERTICAL_BURST);
+    }
+
+    #[test]
+    fn test_gliding() {
+        let mut validator = MovementValidator::new((0.5, 10.0, 0.5));
+        let mut state = MovementState::default();
+        // Without an elytra players keep their walking speed
+        assert!(!state.start_gliding(false, false));
+        validator.tick();
+        let result = validator.check((0.5, 10.0, 3.0), false, &mut state, false, &mut world);
+        assert_eq!(result.violation, Some(Violation::TooFast));
+
+        assert!(!state.start_gliding(true, true));
+        assert!(state.start_gliding(true, false));
+        validator.tick();
+        let result = validator.check((0.5, 10.0, 3.0), false, &mut state, false, &mut world);
+        assert_eq!(result.violation, None);
+    }
+
+    #[test]
+    fn test_walls() {
+        let mut validator = MovementValidator::new((1.5, 0.0, 0.5));
+        assert_eq!(walk(&mut validator, (1.7, 0.0, 0.5)).violation, None);
+        let result = walk(&mut validator, (1.9, 0.0, 0.5));
+        assert_eq!(result.violation, Some(Violation::NoClip));
+    }
+
+    #[test]
+    fn test_flying_and_ground() {
+        let mut validator = MovementValidator::new((0.5, 0.0, 0.5));
+        let mut state = MovementState::default();
+        for y in [0.42, 0.75, 1.0, 1.17] {
+            let result = validator.check((0.5, y, 0.5), false, &mut state, false, &mut world);
+            assert_eq!(result.violation, None);
+        }
+        let result = validator.check((0.5, 1.6, 0.5), false, &mut state, false, &mut world);
+        assert_eq!(result.violation, Some(Violation::Flying));
+        let result = validator.check((0.5, 1.6, 0.5), false, &mut state, true, &mut world);
+        assert_eq!(result.violation, None);
+
+        let result = validator.check((0.5, 1.0, 0.5), true, &mut state, false, &mut world);
+        assert!(!result.on_ground);
+        assert_eq!(result.violation, Some(Violation::GroundSpoof));
+        assert!(!Violation::GroundSpoof.rejects_move());
+        assert!(walk(&mut validator, (0.5, 0.0, 0.5)).on_ground);
+    }
+
+    #[test]
+    fn test_invalid_position() {
+        let mut validator = MovementValidator::new((0.5, 0.0, 0.5));
+        for to in [
+            (f64::NAN, 0.0, 0.5),
+            (0.5, f64::NAN, 0.5),
+            (0.5, 0.0, f64::INFINITY),
+            (f64::NEG_INFINITY, 0.0, 0.5),
+        ] {
+            let result = walk(&mut validator, to);
+            assert_eq!(result.violation, Some(Violation::InvalidPosition));
+            assert_eq!(validator.last_valid(), (0.5, 0.0, 0.5));
+        }
+        assert!(Violation::InvalidPosition.kicks());
+        assert!(!Violation::TooFast.kicks());
+        // The rejected moves don't use up the budget
+        assert_eq!(walk(&mut validator, (0.5, 0.0, 1.4)).violation, None);
+    }
+
+    #[test]
+    fn test_teleports() {
+        let mut validator = MovementValidator::new((0.5, 0.0, 0.5));
+        let id = validator.teleport((0.5, 50.0, 0.5));
+        assert!(validator.is_teleporting());
+        validator.confirm_teleport(id + 1);
+        assert!(validator.is_teleporting());
+        validator.confirm_teleport(id);
+        assert!(!validator.is_teleporting());
+        assert_eq!(validator.last_valid(), (0.5, 50.0, 0.5));
+        assert_ne!(validator.reject(), id);
+        assert!(validator.is_teleporting());
+    }
+}
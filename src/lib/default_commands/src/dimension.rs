@@ -0,0 +1,39 @@
+use bevy_ecs::prelude::*;
+use ferrumc_commands::Sender;
+use ferrumc_core::dimension::change_dimension_event::ChangeDimensionEvent;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_macros::command;
+use ferrumc_text::TextComponent;
+
+#[command("dimension", permission = "ferrumc.command.dimension")]
+fn dimension_command(
+    #[arg] dimension: Dimension,
+    #[sender] sender: Sender,
+    query: Query<&Dimension>,
+    mut events: EventWriter<ChangeDimensionEvent>,
+) {
+    let Sender::Player(entity) = sender else {
+        sender.send_message(
+            TextComponent::from("Only players can change dimension"),
+            false,
+        );
+        return;
+    };
+
+    if query.get(entity).is_ok_and(|current| *current == dimension) {
+        sender.send_message(
+            TextComponent::from(format!("You are already in {}", dimension)),
+            false,
+        );
+        return;
+    }
+
+    events.write(ChangeDimensionEvent {
+        player: entity,
+        dimension,
+    });
+    sender.send_message(
+        TextComponent::from(format!("Moving you to {}", dimension)),
+        false,
+    );
+}
@@ -0,0 +1,54 @@
+use bevy_ecs::prelude::*;
+use ferrumc_commands::arg::player::PlayerTarget;
+use ferrumc_commands::Sender;
+use ferrumc_core::game_mode::change_game_mode_event::ChangeGameModeEvent;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::command;
+use ferrumc_text::TextComponent;
+
+#[command("gamemode", permission = "ferrumc.command.gamemode")]
+fn gamemode_command(
+    #[arg] game_mode: GameMode,
+    #[arg] target: Option<PlayerTarget>,
+    #[sender] sender: Sender,
+    players: Query<(Entity, &PlayerIdentity, &GameMode)>,
+    mut events: EventWriter<ChangeGameModeEvent>,
+) {
+    let target = target.unwrap_or(PlayerTarget::Sender);
+    let Some(player) = target.resolve(
+        sender,
+        players
+            .iter()
+            .map(|(entity, identity, _)| (entity, identity)),
+    ) else {
+        let message = match target {
+            PlayerTarget::Sender => "Only players can change their own game mode".to_string(),
+            PlayerTarget::Name(name) => format!("{name} isn't online"),
+        };
+        sender.send_message(TextComponent::from(message), false);
+        return;
+    };
+    let Ok((_, identity, &current)) = players.get(player) else {
+        return;
+    };
+
+    let own = sender == Sender::Player(player);
+    if current == game_mode {
+        let message = if own {
+            format!("You are already in {game_mode} mode")
+        } else {
+            format!("{} is already in {game_mode} mode", identity.username)
+        };
+        sender.send_message(TextComponent::from(message), false);
+        return;
+    }
+
+    events.write(ChangeGameModeEvent { player, game_mode });
+    let message = if own {
+        format!("Set own game mode to {game_mode}")
+    } else {
+        format!("Set {}'s game mode to {game_mode}", identity.username)
+    };
+    sender.send_message(TextComponent::from(message), false);
+}
@@ -1,5 +1,9 @@
+pub mod dimension;
 pub mod echo;
+pub mod gamemode;
 pub mod nested;
+pub mod op;
+pub mod summon;
 
 /// Static library initialisation shenanigans.
 pub fn init() {}
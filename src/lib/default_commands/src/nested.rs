@@ -8,6 +8,7 @@ use ferrumc_text::TextComponent;
 fn nested_command(#[sender] sender: Sender, query: Query<&PlayerIdentity>) {
     let username = match sender {
         Sender::Server => "Server".to_string(),
+        Sender::Rcon(_) => "Rcon".to_string(),
         Sender::Player(entity) => query
             .get(entity)
             .expect("sender does not exist")
@@ -25,6 +26,7 @@ fn nested_command(#[sender] sender: Sender, query: Query<&PlayerIdentity>) {
 fn nested_nested_command(#[sender] sender: Sender, query: Query<&PlayerIdentity>) {
     let username = match sender {
         Sender::Server => "Server".to_string(),
+        Sender::Rcon(_) => "Rcon".to_string(),
         Sender::Player(entity) => query
             .get(entity)
             .expect("sender does not exist")
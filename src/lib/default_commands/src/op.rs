@@ -0,0 +1,82 @@
+use bevy_ecs::prelude::*;
+use ferrumc_commands::arg::player::PlayerTarget;
+use ferrumc_commands::arg::primitive::int::Integer;
+use ferrumc_commands::Sender;
+use ferrumc_config::permissions::{self, MAX_OP_LEVEL};
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::command;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::packets::outgoing::commands::CommandsPacket;
+use ferrumc_text::TextComponent;
+use tracing::error;
+
+type Players<'w, 's> = Query<'w, 's, (Entity, &'static PlayerIdentity, &'static StreamWriter)>;
+
+#[command("op", permission = "ferrumc.command.op")]
+fn op_command(
+    #[arg] target: PlayerTarget,
+    #[arg] level: Option<Integer<1, 4>>,
+    #[sender] sender: Sender,
+    players: Players,
+) {
+    let level = level.map_or(MAX_OP_LEVEL, |level| *level as u8);
+    set_op_level(sender, &target, level, &players);
+}
+
+#[command("deop", permission = "ferrumc.command.deop")]
+fn deop_command(#[arg] target: PlayerTarget, #[sender] sender: Sender, players: Players) {
+    set_op_level(sender, &target, 0, &players);
+}
+
+/// Sets the op level of the `target` player and resends their commands, since the ones they
+/// may use have changed.
+fn set_op_level(sender: Sender, target: &PlayerTarget, level: u8, players: &Players) {
+    let online = players
+        .iter()
+        .map(|(entity, identity, _)| (entity, identity));
+    let Some(Ok((_, identity, conn))) = target
+        .resolve(sender, online)
+        .map(|player| players.get(player))
+    else {
+        let message = match target {
+            PlayerTarget::Sender => "Only players can be operators".to_string(),
+            PlayerTarget::Name(name) => format!("{name} isn't online"),
+        };
+        sender.send_message(TextComponent::from(message), false);
+        return;
+    };
+
+    // Nobody can hand out more than they have themselves
+    if let Sender::Player(entity) = sender {
+        let own_level = players
+            .get(entity)
+            .map_or(0, |(_, own, _)| permissions::op_level(own.uuid.as_u128()));
+        if level > own_level {
+            sender.send_message(
+                TextComponent::from(format!("You can't give out op level {level}")),
+                false,
+            );
+            return;
+        }
+    }
+
+    let uuid = identity.uuid.as_u128();
+    if let Err(e) = permissions::set_op_level(uuid, level) {
+        error!("Failed to save permissions: {e}");
+        sender.send_message(TextComponent::from("Failed to save permissions"), false);
+        return;
+    }
+    if let Err(e) = conn.send_packet(CommandsPacket::for_player(uuid)) {
+        error!(
+            "Failed to resend commands to {}: {:?}",
+            identity.username, e
+        );
+    }
+
+    let message = if level == 0 {
+        format!("Made {} no longer an operator", identity.username)
+    } else {
+        format!("Made {} a level {level} operator", identity.username)
+    };
+    sender.send_message(TextComponent::from(message), false);
+}
@@ -0,0 +1,51 @@
+use bevy_ecs::prelude::*;
+use ferrumc_commands::Sender;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::entities::kind::EntityKind;
+use ferrumc_core::entities::EntityBundle;
+use ferrumc_core::health::Health;
+use ferrumc_core::transform::position::Position;
+use ferrumc_macros::command;
+use ferrumc_text::TextComponent;
+
+#[command("summon", permission = "ferrumc.command.summon")]
+fn summon_command(
+    #[arg] kind: EntityKind,
+    #[sender] sender: Sender,
+    query: Query<(&Position, &Dimension)>,
+    mut commands: Commands,
+) {
+    let Sender::Player(entity) = sender else {
+        sender.send_message(
+            TextComponent::from("Only players can summon entities"),
+            false,
+        );
+        return;
+    };
+    let name = kind.name().unwrap_or("entity");
+    // These need more than a type to exist, like the item or block they are
+    if [
+        EntityKind::PLAYER,
+        EntityKind::ITEM,
+        EntityKind::FALLING_BLOCK,
+    ]
+    .contains(&kind)
+    {
+        sender.send_message(
+            TextComponent::from(format!("{name} can't be summoned")),
+            false,
+        );
+        return;
+    }
+    let Ok((position, &dimension)) = query.get(entity) else {
+        return;
+    };
+
+    let position = Position::new(position.x, position.y, position.z);
+    // Anything summoned can be hit, and dies like a mob with 20 health
+    commands.spawn((
+        EntityBundle::new(kind, position, dimension),
+        Health::default(),
+    ));
+    sender.send_message(TextComponent::from(format!("Summoned {name}")), false);
+}
@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    parse_macro_input, FnArg, ItemFn, LitStr, Pat, Result as SynResult, Type,
+    parse_macro_input, FnArg, Ident, ItemFn, LitStr, Pat, Result as SynResult, Token, Type,
 };
 
 #[derive(Clone, Debug)]
@@ -14,12 +14,28 @@ struct Arg {
 
 struct CommandAttr {
     name: String,
+    permission: Option<String>,
 }
 
 impl Parse for CommandAttr {
     fn parse(input: ParseStream) -> SynResult<Self> {
         let name = input.parse::<LitStr>()?.value();
-        Ok(CommandAttr { name })
+        let mut permission = None;
+
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            let value = input.parse::<LitStr>()?;
+            if key != "permission" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "unknown command option, expected `permission`",
+                ));
+            }
+            permission = Some(value.value());
+        }
+
+        Ok(CommandAttr { name, permission })
     }
 }
 
@@ -120,7 +136,7 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
             if let Type::Path(path) = *refr.clone().elem {
                 println!("path reference: {:?}", path.path.segments.clone());
                 let is_bevy = path.path.segments.iter().any(|seg| {
-                    println!("{}", &seg.ident.to_string());
+                    println!("{}", seg.ident);
                     &seg.ident.to_string() == "bevy_ecs"
                 });
                 println!("is bevy? {is_bevy}");
@@ -140,11 +156,17 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 
     let system_name = format_ident!("__{}_handler", fn_name);
+    let runner_name = format_ident!("__{}_runner", fn_name);
     let system_args = bevy_args
         .clone()
         .iter()
-        .map(|(pat, ty)| {
-            quote! { #pat: #ty, }
+        .map(|(pat, ty)| match pat.as_ref() {
+            // The parameters are only moved into the command, so they don't need to be mutable
+            syn::Pat::Ident(pat_ident) => {
+                let ident = &pat_ident.ident;
+                quote! { #ident: #ty, }
+            }
+            _ => quote! { #pat: #ty, },
         })
         .collect::<Vec<proc_macro2::TokenStream>>();
     let system_arg_pats = bevy_args
@@ -153,9 +175,9 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
         .map(|(pat, _)| match pat.as_ref() {
             syn::Pat::Ident(pat_ident) => {
                 let ident = &pat_ident.ident;
-                quote!(#ident)
+                quote!(#ident,)
             }
-            _ => quote!(#pat),
+            _ => quote!(#pat,),
         })
         .collect::<Vec<proc_macro2::TokenStream>>();
 
@@ -174,7 +196,7 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
                             .extra(*err)
                             .color(ferrumc_text::NamedColor::Red)
                             .build(), false);
-                        return;
+                        return true;
                     }
                 },
             }
@@ -189,13 +211,17 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let ctor_fn_name = format_ident!("__{}_register", fn_name);
     let command_name = command_attr.name;
+    let permission = match command_attr.permission {
+        Some(permission) => quote! { Some(#permission) },
+        None => quote! { None },
+    };
 
     let command_args = args
         .iter()
         .map(|arg| {
             let name = arg.name.clone();
             let required = arg.required;
-            let ty = format_ident!("{}", &arg.ty);
+            let ty = syn::parse_str::<Type>(&arg.ty).expect("invalid arg type");
 
             quote! {
                 ferrumc_commands::arg::CommandArgumentNode {
@@ -228,26 +254,37 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
         #[doc(hidden)]
         #input_fn
 
+        // Handles the next dispatch of this command, if there is one. The system params are moved
+        // into the command, so it can only run once per system run.
         #[allow(non_snake_case)]
         #[allow(unused_variables)] // if there is no sender arg
         #[doc(hidden)]
-        fn #system_name(mut __events: bevy_ecs::prelude::EventMutator<ferrumc_commands::events::ResolvedCommandDispatchEvent>, #(#system_args)*) {
+        fn #system_name(mut __events: bevy_ecs::prelude::EventMutator<ferrumc_commands::events::ResolvedCommandDispatchEvent>, #(#system_args)*) -> bool {
             for ferrumc_commands::events::ResolvedCommandDispatchEvent { command: __command, ctx, sender } in __events.read() {
                 if __command.name == #command_name {
                     #call
-                    return // this is due to ownership issues
+                    return true;
                 }
             }
+            false
+        }
+
+        // Runs the handler until every dispatch of this command in this tick is handled.
+        #[allow(non_snake_case)]
+        #[doc(hidden)]
+        fn #runner_name(world: &mut bevy_ecs::prelude::World) {
+            while let Ok(true) = world.run_system_cached(#system_name) {}
         }
 
         #[ctor::ctor]
         #[doc(hidden)]
         fn #ctor_fn_name() {
-            ferrumc_commands::infrastructure::add_system(#system_name);
+            ferrumc_commands::infrastructure::add_system(#runner_name);
 
             ferrumc_commands::infrastructure::register_command(std::sync::Arc::new(ferrumc_commands::Command {
                 name: #command_name,
                 args: vec![#(#command_args)*],
+                permission: #permission,
             }));
         }
     })
@@ -1,5 +1,3 @@
-#![feature(proc_macro_quote)]
-
 use block::matches;
 use proc_macro::TokenStream;
 
@@ -69,6 +67,9 @@ pub fn lookup_packet(input: TokenStream) -> TokenStream {
 /// The optional sender argument is marked with `#[sender]` attribute and command arguments are marked with
 /// the `#[arg]` attribute. Any other argument is treated as a bevy system arg.
 ///
+/// Commands can require a permission node with `permission = "..."`. Players without it don't see
+/// the command and can't run it.
+///
 /// Usage example:
 ///
 /// ```ignore
@@ -76,6 +77,11 @@ pub fn lookup_packet(input: TokenStream) -> TokenStream {
 /// fn command(#[sender] sender: Sender) {
 ///     sender.send_message(TextComponent::from("Hello, world!"), false);
 /// }
+///
+/// #[command("kick", permission = "ferrumc.command.kick")]
+/// fn kick_command(#[arg] target: PlayerTarget, #[sender] sender: Sender) {
+///     // ...
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -0,0 +1,1708 @@
+//! Item data components, the extra data an item stack can carry like a custom name or
+//! enchantments.
+//!
+//! The components the server cares about are modelled, the rest are kept as the bytes they were
+//! sent as. Component types are numbered by the `minecraft:data_component_type` registry.
+
+mod layout;
+
+use crate::errors::InventoryError;
+use ferrumc_nbt::{
+    FromNbt, NBTError, NBTSerializable, NBTSerializeOptions, NbtTape, NbtTapeElement, NetworkNbt,
+};
+use ferrumc_net_codec::decode::errors::NetDecodeError;
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
+use ferrumc_net_codec::encode::errors::NetEncodeError;
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use once_cell::sync::Lazy;
+use simd_json::base::ValueAsScalar;
+use simd_json::prelude::ValueAsObject;
+use simd_json::prelude::ValueObjectAccess;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tracing::debug;
+
+const TAG_END: u8 = 0;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+
+/// The vanilla enchantments in the order of the `minecraft:enchantment` registry, which isn't
+/// among the registries the server has data for. Their index is their network id.
+const ENCHANTMENTS: [&str; 42] = [
+    "aqua_affinity",
+    "bane_of_arthropods",
+    "binding_curse",
+    "blast_protection",
+    "breach",
+    "channeling",
+    "density",
+    "depth_strider",
+    "efficiency",
+    "feather_falling",
+    "fire_aspect",
+    "fire_protection",
+    "flame",
+    "fortune",
+    "frost_walker",
+    "impaling",
+    "infinity",
+    "knockback",
+    "looting",
+    "loyalty",
+    "luck_of_the_sea",
+    "lure",
+    "mending",
+    "multishot",
+    "piercing",
+    "power",
+    "projectile_protection",
+    "protection",
+    "punch",
+    "quick_charge",
+    "respiration",
+    "riptide",
+    "sharpness",
+    "silk_touch",
+    "smite",
+    "soul_speed",
+    "sweeping_edge",
+    "swift_sneak",
+    "thorns",
+    "unbreaking",
+    "vanishing_curse",
+    "wind_burst",
+];
+
+/// Component type names by id.
+static COMPONENT_NAMES: Lazy<HashMap<i32, String>> =
+    Lazy::new(|| registry_names("minecraft:data_component_type"));
+static POTION_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| registry_names("minecraft:potion"));
+static EFFECT_NAMES: Lazy<HashMap<i32, String>> =
+    Lazy::new(|| registry_names("minecraft:mob_effect"));
+
+/// The most hidden effects a status effect may have behind it.
+const MAX_HIDDEN_EFFECTS: usize = 64;
+
+/// The entry names of a registry by id.
+fn registry_names(registry: &str) -> HashMap<i32, String> {
+    ferrumc_registry::lookup(&format!("{registry}/entries"))
+        .and_then(|entries| {
+            entries.as_object().map(|obj| {
+                obj.iter()
+                    .filter_map(|(name, value)| {
+                        Some((value.get("protocol_id")?.as_i32()?, name.to_string()))
+                    })
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// The id of a registry entry by name. Names may leave out the `minecraft:` namespace, and may
+/// contain slashes, which registry lookups can't.
+fn registry_id(names: &HashMap<i32, String>, name: &str) -> Option<VarInt> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    names
+        .iter()
+        .find(|(_, known)| known.strip_prefix("minecraft:") == Some(name))
+        .map(|(id, _)| VarInt::new(*id))
+}
+
+/// The id of a component type, e.g. `minecraft:custom_name`.
+pub fn component_type_id(name: &str) -> Option<VarInt> {
+    registry_id(&COMPONENT_NAMES, name)
+}
+
+/// The name of the component type with the given id.
+pub fn component_type_name(id: VarInt) -> Option<&'static str> {
+    COMPONENT_NAMES.get(&id.0).map(String::as_str)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    const ALL: [Rarity; 4] = [Self::Common, Self::Uncommon, Self::Rare, Self::Epic];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Common => "common",
+            Self::Uncommon => "uncommon",
+            Self::Rare => "rare",
+            Self::Epic => "epic",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rarity| rarity.name() == name)
+    }
+
+    fn from_id(id: VarInt) -> Result<Self, NetDecodeError> {
+        usize::try_from(id.0)
+            .ok()
+            .and_then(|id| Self::ALL.get(id).copied())
+            .ok_or(NetDecodeError::InvalidEnumVariant)
+    }
+}
+
+/// An enchantment and its level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Enchantment {
+    /// The index of the enchantment in the `minecraft:enchantment` registry.
+    pub id: VarInt,
+    pub level: i32,
+}
+
+impl Enchantment {
+    /// An enchantment by name, e.g. `minecraft:sharpness`.
+    pub fn from_name(name: &str, level: i32) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let id = ENCHANTMENTS.iter().position(|known| *known == name)?;
+        Some(Self {
+            id: VarInt::new(id as i32),
+            level,
+        })
+    }
+
+    /// The name of the enchantment, e.g. `minecraft:sharpness`.
+    pub fn name(&self) -> Option<String> {
+        let name = ENCHANTMENTS.get(usize::try_from(self.id.0).ok()?)?;
+        Some(format!("minecraft:{name}"))
+    }
+}
+
+/// How strong a status effect is and how long it lasts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EffectDetails {
+    pub amplifier: i32,
+    /// In ticks, or -1 for an effect that doesn't run out.
+    pub duration: i32,
+    /// Whether the effect comes from a beacon or conduit, which shows fewer particles.
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+    /// A weaker but longer effect of the same type that takes over when this one runs out.
+    pub hidden_effect: Option<Box<EffectDetails>>,
+}
+
+impl EffectDetails {
+    fn read<R: Read>(
+        reader: &mut R,
+        opts: &NetDecodeOpts,
+        depth: usize,
+    ) -> Result<Self, NetDecodeError> {
+        let amplifier = VarInt::decode(reader, opts)?.0;
+        let duration = VarInt::decode(reader, opts)?.0;
+        let ambient = bool::decode(reader, opts)?;
+        let show_particles = bool::decode(reader, opts)?;
+        let show_icon = bool::decode(reader, opts)?;
+        let hidden_effect = if bool::decode(reader, opts)? {
+            if depth >= MAX_HIDDEN_EFFECTS {
+                return Err(layout::invalid("hidden effects nested too deeply"));
+            }
+            Some(Box::new(Self::read(reader, opts, depth + 1)?))
+        } else {
+            None
+        };
+        Ok(Self {
+            amplifier,
+            duration,
+            ambient,
+            show_particles,
+            show_icon,
+            hidden_effect,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        VarInt::new(self.amplifier).encode(writer, opts)?;
+        VarInt::new(self.duration).encode(writer, opts)?;
+        self.ambient.encode(writer, opts)?;
+        self.show_particles.encode(writer, opts)?;
+        self.show_icon.encode(writer, opts)?;
+        write_optional(
+            writer,
+            opts,
+            self.hidden_effect.as_deref(),
+            |writer, hidden| hidden.write(writer, opts),
+        )
+    }
+
+    /// Reads the details from the fields of a compound, where they're all optional.
+    fn from_nbt(tapes: &NbtTape, element: &NbtTapeElement) -> Option<Self> {
+        // The amplifier is an unsigned byte
+        let amplifier = field::<i8>(tapes, element, "amplifier")
+            .map(|amplifier| i32::from(amplifier as u8))
+            .or_else(|| field(tapes, element, "amplifier"))
+            .unwrap_or(0);
+        let show_particles = field(tapes, element, "show_particles").unwrap_or(true);
+        let hidden_effect = match element.get("hidden_effect") {
+            Some(hidden) => Some(Box::new(Self::from_nbt(tapes, hidden)?)),
+            None => None,
+        };
+        Some(Self {
+            amplifier,
+            duration: field(tapes, element, "duration").unwrap_or(0),
+            ambient: field(tapes, element, "ambient").unwrap_or(false),
+            show_particles,
+            show_icon: field(tapes, element, "show_icon").unwrap_or(show_particles),
+            hidden_effect,
+        })
+    }
+
+    /// Writes the details as fields of the compound that is being written.
+    fn write_nbt_fields(&self, buf: &mut Vec<u8>) {
+        (self.amplifier as i8).serialize(buf, &NBTSerializeOptions::WithHeader("amplifier"));
+        self.duration
+            .serialize(buf, &NBTSerializeOptions::WithHeader("duration"));
+        self.ambient
+            .serialize(buf, &NBTSerializeOptions::WithHeader("ambient"));
+        self.show_particles
+            .serialize(buf, &NBTSerializeOptions::WithHeader("show_particles"));
+        self.show_icon
+            .serialize(buf, &NBTSerializeOptions::WithHeader("show_icon"));
+        if let Some(hidden) = &self.hidden_effect {
+            buf.push(TAG_COMPOUND);
+            "hidden_effect".serialize(buf, &NBTSerializeOptions::None);
+            hidden.write_nbt_fields(buf);
+            buf.push(TAG_END);
+        }
+    }
+}
+
+/// A status effect, like the ones a potion gives.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Effect {
+    /// The index of the effect in the `minecraft:mob_effect` registry.
+    pub id: VarInt,
+    pub details: EffectDetails,
+}
+
+impl Effect {
+    fn read<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            id: VarInt::decode(reader, opts)?,
+            details: EffectDetails::read(reader, opts, 0)?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        self.id.encode(writer, opts)?;
+        self.details.write(writer, opts)
+    }
+
+    fn write_nbt_payload(&self, buf: &mut Vec<u8>) {
+        if let Some(name) = EFFECT_NAMES.get(&self.id.0) {
+            name.serialize(buf, &NBTSerializeOptions::WithHeader("id"));
+        }
+        self.details.write_nbt_fields(buf);
+        buf.push(TAG_END);
+    }
+}
+
+impl<'a> FromNbt<'a> for Effect {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> ferrumc_nbt::Result<Self> {
+        let id =
+            field::<&str>(tapes, element, "id").and_then(|name| registry_id(&EFFECT_NAMES, name));
+        Ok(Self {
+            id: id.ok_or(NBTError::InvalidNBTData)?,
+            details: EffectDetails::from_nbt(tapes, element).ok_or(NBTError::InvalidNBTData)?,
+        })
+    }
+}
+
+/// What a potion, tipped arrow or area effect cloud does.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PotionContents {
+    /// The index of the potion in the `minecraft:potion` registry.
+    pub potion: Option<VarInt>,
+    /// The colour of the liquid as RGB, instead of the one mixed from the effects.
+    pub custom_color: Option<i32>,
+    /// Effects on top of the potion's own.
+    pub custom_effects: Vec<Effect>,
+    /// Used for the item's name instead of the potion's, e.g. `swiftness` for a potion called
+    /// "Potion of Swiftness".
+    pub custom_name: Option<String>,
+}
+
+impl PotionContents {
+    fn read<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            potion: read_optional(reader, opts, |reader| VarInt::decode(reader, opts))?,
+            custom_color: read_optional(reader, opts, |reader| i32::decode(reader, opts))?,
+            custom_effects: read_list(reader, opts, |reader| Effect::read(reader, opts))?,
+            custom_name: read_optional(reader, opts, |reader| String::decode(reader, opts))?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        write_optional(writer, opts, self.potion.as_ref(), |writer, potion| {
+            potion.encode(writer, opts)
+        })?;
+        write_optional(writer, opts, self.custom_color.as_ref(), |writer, color| {
+            color.encode(writer, opts)
+        })?;
+        write_list(writer, opts, &self.custom_effects, |writer, effect| {
+            effect.write(writer, opts)
+        })?;
+        write_optional(writer, opts, self.custom_name.as_ref(), |writer, name| {
+            name.encode(writer, opts)
+        })
+    }
+
+    /// Vanilla saves a potion without anything custom as only the potion's name.
+    fn from_nbt(tapes: &NbtTape, element: &NbtTapeElement) -> Option<Self> {
+        if let Ok(potion) = <&str>::from_nbt(tapes, element) {
+            return Some(Self {
+                potion: Some(registry_id(&POTION_NAMES, potion)?),
+                ..Default::default()
+            });
+        }
+        let potion = match field::<&str>(tapes, element, "potion") {
+            Some(potion) => Some(registry_id(&POTION_NAMES, potion)?),
+            None => None,
+        };
+        Some(Self {
+            potion,
+            custom_color: field(tapes, element, "custom_color"),
+            custom_effects: element
+                .get("custom_effects")
+                .and_then(|effects| list_from_nbt(tapes, effects))
+                .unwrap_or_default(),
+            custom_name: field(tapes, element, "custom_name"),
+        })
+    }
+
+    fn write_nbt(&self, name: &str, buf: &mut Vec<u8>) {
+        buf.push(TAG_COMPOUND);
+        name.serialize(buf, &NBTSerializeOptions::None);
+        if let Some(potion) = self.potion.and_then(|potion| POTION_NAMES.get(&potion.0)) {
+            potion.serialize(buf, &NBTSerializeOptions::WithHeader("potion"));
+        }
+        if let Some(color) = self.custom_color {
+            color.serialize(buf, &NBTSerializeOptions::WithHeader("custom_color"));
+        }
+        if !self.custom_effects.is_empty() {
+            buf.push(TAG_LIST);
+            "custom_effects".serialize(buf, &NBTSerializeOptions::None);
+            buf.push(TAG_COMPOUND);
+            (self.custom_effects.len() as i32).serialize(buf, &NBTSerializeOptions::None);
+            for effect in &self.custom_effects {
+                effect.write_nbt_payload(buf);
+            }
+        }
+        if let Some(custom_name) = &self.custom_name {
+            custom_name.serialize(buf, &NBTSerializeOptions::WithHeader("custom_name"));
+        }
+        buf.push(TAG_END);
+    }
+}
+
+/// Text players wrote, with the version the chat filter let through if the server filters it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Filterable<T> {
+    pub raw: T,
+    pub filtered: Option<T>,
+}
+
+impl<T> Filterable<T> {
+    fn read<R: Read>(
+        reader: &mut R,
+        opts: &NetDecodeOpts,
+        read: impl Fn(&mut R) -> Result<T, NetDecodeError>,
+    ) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            raw: read(reader)?,
+            filtered: read_optional(reader, opts, &read)?,
+        })
+    }
+
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+        write: impl Fn(&mut W, &T) -> Result<(), NetEncodeError>,
+    ) -> Result<(), NetEncodeError> {
+        write(writer, &self.raw)?;
+        write_optional(writer, opts, self.filtered.as_ref(), write)
+    }
+
+    /// Writes the text as the payload of a compound with the raw and filtered versions.
+    fn write_nbt_payload(&self, buf: &mut Vec<u8>, write_named: impl Fn(&T, &str, &mut Vec<u8>)) {
+        write_named(&self.raw, "raw", buf);
+        if let Some(filtered) = &self.filtered {
+            write_named(filtered, "filtered", buf);
+        }
+        buf.push(TAG_END);
+    }
+}
+
+/// Vanilla accepts the text on its own when there's no filtered version.
+impl<'a, T: FromNbt<'a>> FromNbt<'a> for Filterable<T> {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> ferrumc_nbt::Result<Self> {
+        let Some(raw) = element.get("raw") else {
+            return Ok(Self {
+                raw: T::from_nbt(tapes, element)?,
+                filtered: None,
+            });
+        };
+        Ok(Self {
+            raw: T::from_nbt(tapes, raw)?,
+            filtered: element
+                .get("filtered")
+                .map(|filtered| T::from_nbt(tapes, filtered))
+                .transpose()?,
+        })
+    }
+}
+
+fn write_named_string(value: &String, name: &str, buf: &mut Vec<u8>) {
+    value.serialize(buf, &NBTSerializeOptions::WithHeader(name));
+}
+
+fn write_named_text(value: &NetworkNbt, name: &str, buf: &mut Vec<u8>) {
+    value.write_named(name, buf);
+}
+
+/// A signed book.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WrittenBookContent {
+    pub title: Filterable<String>,
+    pub author: String,
+    /// How many times the book has been copied, up to 3 for a tattered book.
+    pub generation: i32,
+    /// The pages as text components.
+    pub pages: Vec<Filterable<NetworkNbt>>,
+    /// Whether selectors and scores in the pages have already been filled in.
+    pub resolved: bool,
+}
+
+impl WrittenBookContent {
+    fn read<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            title: Filterable::read(reader, opts, |reader| String::decode(reader, opts))?,
+            author: String::decode(reader, opts)?,
+            generation: VarInt::decode(reader, opts)?.0,
+            pages: read_list(reader, opts, |reader| {
+                Filterable::read(reader, opts, |reader| NetworkNbt::read(reader))
+            })?,
+            resolved: bool::decode(reader, opts)?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        self.title
+            .write(writer, opts, |writer, title| title.encode(writer, opts))?;
+        self.author.encode(writer, opts)?;
+        VarInt::new(self.generation).encode(writer, opts)?;
+        write_list(writer, opts, &self.pages, |writer, page| {
+            page.write(writer, opts, |writer, text| text.encode(writer, opts))
+        })?;
+        self.resolved.encode(writer, opts)
+    }
+
+    fn from_nbt(tapes: &NbtTape, element: &NbtTapeElement) -> Option<Self> {
+        Some(Self {
+            title: Filterable::from_nbt(tapes, element.get("title")?).ok()?,
+            author: field(tapes, element, "author")?,
+            generation: field(tapes, element, "generation").unwrap_or(0),
+            pages: element
+                .get("pages")
+                .and_then(|pages| list_from_nbt(tapes, pages))
+                .unwrap_or_default(),
+            resolved: field(tapes, element, "resolved").unwrap_or(false),
+        })
+    }
+
+    fn write_nbt(&self, name: &str, buf: &mut Vec<u8>) {
+        buf.push(TAG_COMPOUND);
+        name.serialize(buf, &NBTSerializeOptions::None);
+        buf.push(TAG_COMPOUND);
+        "title".serialize(buf, &NBTSerializeOptions::None);
+        self.title.write_nbt_payload(buf, write_named_string);
+        self.author
+            .serialize(buf, &NBTSerializeOptions::WithHeader("author"));
+        self.generation
+            .serialize(buf, &NBTSerializeOptions::WithHeader("generation"));
+        write_filterable_list("pages", &self.pages, buf, write_named_text);
+        self.resolved
+            .serialize(buf, &NBTSerializeOptions::WithHeader("resolved"));
+        buf.push(TAG_END);
+    }
+}
+
+/// Writes pages as a list of compounds with their raw and filtered versions.
+fn write_filterable_list<T>(
+    name: &str,
+    pages: &[Filterable<T>],
+    buf: &mut Vec<u8>,
+    write_named: impl Fn(&T, &str, &mut Vec<u8>) + Copy,
+) {
+    buf.push(TAG_LIST);
+    name.serialize(buf, &NBTSerializeOptions::None);
+    buf.push(if pages.is_empty() {
+        TAG_END
+    } else {
+        TAG_COMPOUND
+    });
+    (pages.len() as i32).serialize(buf, &NBTSerializeOptions::None);
+    for page in pages {
+        page.write_nbt_payload(buf, write_named);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FireworkShape {
+    SmallBall,
+    LargeBall,
+    Star,
+    Creeper,
+    Burst,
+}
+
+impl FireworkShape {
+    const ALL: [FireworkShape; 5] = [
+        Self::SmallBall,
+        Self::LargeBall,
+        Self::Star,
+        Self::Creeper,
+        Self::Burst,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SmallBall => "small_ball",
+            Self::LargeBall => "large_ball",
+            Self::Star => "star",
+            Self::Creeper => "creeper",
+            Self::Burst => "burst",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.name() == name)
+    }
+
+    fn from_id(id: VarInt) -> Result<Self, NetDecodeError> {
+        usize::try_from(id.0)
+            .ok()
+            .and_then(|id| Self::ALL.get(id).copied())
+            .ok_or(NetDecodeError::InvalidEnumVariant)
+    }
+}
+
+/// The explosion of a firework star, or one of the explosions of a firework rocket.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FireworkExplosion {
+    pub shape: FireworkShape,
+    /// The colours of the particles as RGB.
+    pub colors: Vec<i32>,
+    /// The colours the particles fade to as RGB.
+    pub fade_colors: Vec<i32>,
+    pub has_trail: bool,
+    pub has_twinkle: bool,
+}
+
+impl FireworkExplosion {
+    fn read<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            shape: FireworkShape::from_id(VarInt::decode(reader, opts)?)?,
+            colors: read_list(reader, opts, |reader| i32::decode(reader, opts))?,
+            fade_colors: read_list(reader, opts, |reader| i32::decode(reader, opts))?,
+            has_trail: bool::decode(reader, opts)?,
+            has_twinkle: bool::decode(reader, opts)?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        VarInt::new(self.shape as i32).encode(writer, opts)?;
+        write_list(writer, opts, &self.colors, |writer, color| {
+            color.encode(writer, opts)
+        })?;
+        write_list(writer, opts, &self.fade_colors, |writer, color| {
+            color.encode(writer, opts)
+        })?;
+        self.has_trail.encode(writer, opts)?;
+        self.has_twinkle.encode(writer, opts)
+    }
+
+    fn write_nbt_payload(&self, buf: &mut Vec<u8>) {
+        self.shape
+            .name()
+            .serialize(buf, &NBTSerializeOptions::WithHeader("shape"));
+        self.colors
+            .serialize(buf, &NBTSerializeOptions::WithHeader("colors"));
+        self.fade_colors
+            .serialize(buf, &NBTSerializeOptions::WithHeader("fade_colors"));
+        self.has_trail
+            .serialize(buf, &NBTSerializeOptions::WithHeader("has_trail"));
+        self.has_twinkle
+            .serialize(buf, &NBTSerializeOptions::WithHeader("has_twinkle"));
+        buf.push(TAG_END);
+    }
+}
+
+impl<'a> FromNbt<'a> for FireworkExplosion {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> ferrumc_nbt::Result<Self> {
+        let shape = field::<&str>(tapes, element, "shape").and_then(FireworkShape::from_name);
+        // Colours are int arrays, which `NbtTape::unpack_list` would reinterpret as anything
+        let colors = |name| match element.get(name) {
+            Some(NbtTapeElement::IntArray(colors)) => colors.clone(),
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            shape: shape.ok_or(NBTError::InvalidNBTData)?,
+            colors: colors("colors"),
+            fade_colors: colors("fade_colors"),
+            has_trail: field(tapes, element, "has_trail").unwrap_or(false),
+            has_twinkle: field(tapes, element, "has_twinkle").unwrap_or(false),
+        })
+    }
+}
+
+/// A firework rocket.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fireworks {
+    /// How long the rocket flies, as the amount of gunpowder it was crafted with.
+    pub flight_duration: i32,
+    pub explosions: Vec<FireworkExplosion>,
+}
+
+impl Fireworks {
+    fn read<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        Ok(Self {
+            flight_duration: VarInt::decode(reader, opts)?.0,
+            explosions: read_list(reader, opts, |reader| FireworkExplosion::read(reader, opts))?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        VarInt::new(self.flight_duration).encode(writer, opts)?;
+        write_list(writer, opts, &self.explosions, |writer, explosion| {
+            explosion.write(writer, opts)
+        })
+    }
+
+    fn from_nbt(tapes: &NbtTape, element: &NbtTapeElement) -> Option<Self> {
+        // The flight duration is an unsigned byte
+        let flight_duration = field::<i8>(tapes, element, "flight_duration")
+            .map(|duration| i32::from(duration as u8))
+            .unwrap_or(0);
+        Some(Self {
+            flight_duration,
+            explosions: element
+                .get("explosions")
+                .and_then(|explosions| list_from_nbt(tapes, explosions))
+                .unwrap_or_default(),
+        })
+    }
+
+    fn write_nbt(&self, name: &str, buf: &mut Vec<u8>) {
+        buf.push(TAG_COMPOUND);
+        name.serialize(buf, &NBTSerializeOptions::None);
+        (self.flight_duration as i8)
+            .serialize(buf, &NBTSerializeOptions::WithHeader("flight_duration"));
+        buf.push(TAG_LIST);
+        "explosions".serialize(buf, &NBTSerializeOptions::None);
+        buf.push(if self.explosions.is_empty() {
+            TAG_END
+        } else {
+            TAG_COMPOUND
+        });
+        (self.explosions.len() as i32).serialize(buf, &NBTSerializeOptions::None);
+        for explosion in &self.explosions {
+            explosion.write_nbt_payload(buf);
+        }
+        buf.push(TAG_END);
+    }
+}
+
+/// One data component of an item stack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Component {
+    /// Data the game doesn't use itself, for plugins and commands.
+    CustomData(NetworkNbt),
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    /// How worn out the item is, counting up to its max damage.
+    Damage(i32),
+    Unbreakable,
+    /// The name an item was given in an anvil, as a text component.
+    CustomName(NetworkNbt),
+    /// The default name of the item, as a text component.
+    ItemName(NetworkNbt),
+    /// The model the item is shown with.
+    ItemModel(String),
+    /// Lines of text under the name, as text components.
+    Lore(Vec<NetworkNbt>),
+    Rarity(Rarity),
+    Enchantments(Vec<Enchantment>),
+    /// The extra experience repairing the item in an anvil costs.
+    RepairCost(i32),
+    /// Whether the item glints whether or not it's enchanted.
+    EnchantmentGlintOverride(bool),
+    /// The enchantments an enchanted book applies.
+    StoredEnchantments(Vec<Enchantment>),
+    /// The colour of dyed leather armor, as RGB.
+    DyedColor(i32),
+    PotionContents(PotionContents),
+    /// The pages of a book and quill.
+    WritableBookContent(Vec<Filterable<String>>),
+    WrittenBookContent(WrittenBookContent),
+    FireworkExplosion(FireworkExplosion),
+    Fireworks(Fireworks),
+    /// A component the server doesn't model, kept as the bytes it was sent as so the item can be
+    /// sent back unchanged. It's left out when the item is saved.
+    Raw {
+        id: VarInt,
+        data: Vec<u8>,
+    },
+    /// A component of a saved item the server doesn't model, kept as its NBT so the item is saved
+    /// back unchanged. Its network data can't be worked out from the NBT, so it isn't sent to
+    /// clients.
+    Nbt {
+        id: VarInt,
+        nbt: NetworkNbt,
+    },
+}
+
+impl Component {
+    /// The name of the component type, e.g. `minecraft:custom_name`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CustomData(_) => "minecraft:custom_data",
+            Self::MaxStackSize(_) => "minecraft:max_stack_size",
+            Self::MaxDamage(_) => "minecraft:max_damage",
+            Self::Damage(_) => "minecraft:damage",
+            Self::Unbreakable => "minecraft:unbreakable",
+            Self::CustomName(_) => "minecraft:custom_name",
+            Self::ItemName(_) => "minecraft:item_name",
+            Self::ItemModel(_) => "minecraft:item_model",
+            Self::Lore(_) => "minecraft:lore",
+            Self::Rarity(_) => "minecraft:rarity",
+            Self::Enchantments(_) => "minecraft:enchantments",
+            Self::RepairCost(_) => "minecraft:repair_cost",
+            Self::EnchantmentGlintOverride(_) => "minecraft:enchantment_glint_override",
+            Self::StoredEnchantments(_) => "minecraft:stored_enchantments",
+            Self::DyedColor(_) => "minecraft:dyed_color",
+            Self::PotionContents(_) => "minecraft:potion_contents",
+            Self::WritableBookContent(_) => "minecraft:writable_book_content",
+            Self::WrittenBookContent(_) => "minecraft:written_book_content",
+            Self::FireworkExplosion(_) => "minecraft:firework_explosion",
+            Self::Fireworks(_) => "minecraft:fireworks",
+            Self::Raw { id, .. } | Self::Nbt { id, .. } => {
+                component_type_name(*id).unwrap_or("minecraft:unknown")
+            }
+        }
+    }
+
+    /// The id of the component type.
+    pub fn type_id(&self) -> Option<VarInt> {
+        match self {
+            Self::Raw { id, .. } | Self::Nbt { id, .. } => Some(*id),
+            _ => component_type_id(self.name()),
+        }
+    }
+
+    /// Whether the component can be sent to clients, see [`Component::Nbt`].
+    pub fn is_networked(&self) -> bool {
+        !matches!(self, Self::Nbt { .. })
+    }
+
+    /// Reads a component from its entry in a vanilla `components` compound. Components the server
+    /// doesn't model are kept as [`Component::Nbt`]. `None` if the component type isn't in the
+    /// registry, or the data of a modelled one doesn't look like it should.
+    pub fn from_nbt(name: &str, tapes: &NbtTape, element: &NbtTapeElement) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let int = || i32::from_nbt(tapes, element).ok();
+        let nbt = || NetworkNbt::from_nbt(tapes, element).ok();
+        Some(match name {
+            "custom_data" => Self::CustomData(nbt()?),
+            "max_stack_size" => Self::MaxStackSize(int()?),
+            "max_damage" => Self::MaxDamage(int()?),
+            "damage" => Self::Damage(int()?),
+            "unbreakable" => Self::Unbreakable,
+            "custom_name" => Self::CustomName(nbt()?),
+            "item_name" => Self::ItemName(nbt()?),
+            "item_model" => Self::ItemModel(String::from_nbt(tapes, element).ok()?),
+            "lore" => Self::Lore(list_from_nbt(tapes, element)?),
+            "rarity" => Self::Rarity(Rarity::from_name(<&str>::from_nbt(tapes, element).ok()?)?),
+            "enchantments" => Self::Enchantments(enchantments_from_nbt(tapes, element)?),
+            "repair_cost" => Self::RepairCost(int()?),
+            "enchantment_glint_override" => {
+                Self::EnchantmentGlintOverride(bool::from_nbt(tapes, element).ok()?)
+            }
+            "stored_enchantments" => {
+                Self::StoredEnchantments(enchantments_from_nbt(tapes, element)?)
+            }
+            // Before 1.21.5 the colour was in a compound with whether to show it
+            "dyed_color" => {
+                Self::DyedColor(int().or_else(|| i32::from_nbt(tapes, element.get("rgb")?).ok())?)
+            }
+            "potion_contents" => Self::PotionContents(PotionContents::from_nbt(tapes, element)?),
+            "writable_book_content" => Self::WritableBookContent(
+                element
+                    .get("pages")
+                    .and_then(|pages| list_from_nbt(tapes, pages))
+                    .unwrap_or_default(),
+            ),
+            "written_book_content" => {
+                Self::WrittenBookContent(WrittenBookContent::from_nbt(tapes, element)?)
+            }
+            "firework_explosion" => {
+                Self::FireworkExplosion(FireworkExplosion::from_nbt(tapes, element).ok()?)
+            }
+            "fireworks" => Self::Fireworks(Fireworks::from_nbt(tapes, element)?),
+            _ => Self::Nbt {
+                id: component_type_id(name)?,
+                nbt: nbt()?,
+            },
+        })
+    }
+
+    /// Writes the component as an entry of a vanilla `components` compound. [`Component::Raw`]
+    /// is left out: vanilla can't read its network data, and would drop the whole item over a key
+    /// it doesn't know.
+    pub fn write_nbt(&self, buf: &mut Vec<u8>) {
+        let header = NBTSerializeOptions::WithHeader(self.name());
+        match self {
+            Self::CustomData(nbt) | Self::CustomName(nbt) | Self::ItemName(nbt) => {
+                nbt.write_named(self.name(), buf)
+            }
+            Self::MaxStackSize(value)
+            | Self::MaxDamage(value)
+            | Self::Damage(value)
+            | Self::RepairCost(value)
+            | Self::DyedColor(value) => value.serialize(buf, &header),
+            Self::Unbreakable => {
+                buf.push(TAG_COMPOUND);
+                self.name().serialize(buf, &NBTSerializeOptions::None);
+                buf.push(TAG_END);
+            }
+            Self::ItemModel(model) => model.serialize(buf, &header),
+            Self::Lore(lines) => {
+                buf.push(TAG_LIST);
+                self.name().serialize(buf, &NBTSerializeOptions::None);
+                write_text_list(lines, buf);
+            }
+            Self::Rarity(rarity) => rarity.name().serialize(buf, &header),
+            Self::Enchantments(enchantments) | Self::StoredEnchantments(enchantments) => {
+                buf.push(TAG_COMPOUND);
+                self.name().serialize(buf, &NBTSerializeOptions::None);
+                for enchantment in enchantments {
+                    if let Some(name) = enchantment.name() {
+                        enchantment
+                            .level
+                            .serialize(buf, &NBTSerializeOptions::WithHeader(&name));
+                    }
+                }
+                buf.push(TAG_END);
+            }
+            Self::EnchantmentGlintOverride(glint) => glint.serialize(buf, &header),
+            Self::PotionContents(contents) => contents.write_nbt(self.name(), buf),
+            Self::WritableBookContent(pages) => {
+                buf.push(TAG_COMPOUND);
+                self.name().serialize(buf, &NBTSerializeOptions::None);
+                write_filterable_list("pages", pages, buf, write_named_string);
+                buf.push(TAG_END);
+            }
+            Self::WrittenBookContent(content) => content.write_nbt(self.name(), buf),
+            Self::FireworkExplosion(explosion) => {
+                buf.push(TAG_COMPOUND);
+                self.name().serialize(buf, &NBTSerializeOptions::None);
+                explosion.write_nbt_payload(buf);
+            }
+            Self::Fireworks(fireworks) => fireworks.write_nbt(self.name(), buf),
+            Self::Nbt { nbt, .. } => nbt.write_named(self.name(), buf),
+            Self::Raw { .. } => {}
+        }
+    }
+
+    fn unknown(id: VarInt) -> NetDecodeError {
+        NetDecodeError::ExternalError(Box::new(InventoryError::UnsupportedComponent(id.0)))
+    }
+}
+
+/// Enchantments by name with their levels, which were kept under `levels` before 1.21.5.
+fn enchantments_from_nbt(tapes: &NbtTape, element: &NbtTapeElement) -> Option<Vec<Enchantment>> {
+    let levels = element.get("levels").unwrap_or(element);
+    Some(
+        levels
+            .as_compound()?
+            .iter()
+            .filter_map(|(name, level)| {
+                Enchantment::from_name(name, i32::from_nbt(tapes, level).ok()?)
+            })
+            .collect(),
+    )
+}
+
+/// Writes text components as the payload of a list. Lists only hold one type of tag, so
+/// plain strings are turned into compounds when they're mixed with other components.
+fn write_text_list(lines: &[NetworkNbt], buf: &mut Vec<u8>) {
+    let tag = match lines.first() {
+        Some(first) if lines.iter().all(|line| line.tag() == first.tag()) => first.tag(),
+        Some(_) => TAG_COMPOUND,
+        None => TAG_END,
+    };
+    buf.push(tag);
+    (lines.len() as i32).serialize(buf, &NBTSerializeOptions::None);
+    for line in lines {
+        match line.as_string() {
+            Some(text) if tag == TAG_COMPOUND => {
+                text.serialize(buf, &NBTSerializeOptions::WithHeader("text"));
+                buf.push(TAG_END);
+            }
+            _ => line.write_payload(buf),
+        }
+    }
+}
+
+/// A field of a compound, if it's there and has the right type.
+fn field<'a, T: FromNbt<'a>>(
+    tapes: &NbtTape<'a>,
+    element: &NbtTapeElement<'a>,
+    name: &str,
+) -> Option<T> {
+    T::from_nbt(tapes, element.get(name)?).ok()
+}
+
+/// The elements of a list that have the right type. `NbtTape::unpack_list` panics on the first
+/// that doesn't, and reinterprets arrays as whatever it's asked for.
+fn list_from_nbt<'a, T: FromNbt<'a>>(
+    tapes: &NbtTape<'a>,
+    element: &NbtTapeElement<'a>,
+) -> Option<Vec<T>> {
+    struct Lenient<T>(Option<T>);
+
+    impl<'a, T: FromNbt<'a>> FromNbt<'a> for Lenient<T> {
+        fn from_nbt(
+            tapes: &NbtTape<'a>,
+            element: &NbtTapeElement<'a>,
+        ) -> ferrumc_nbt::Result<Self> {
+            Ok(Self(T::from_nbt(tapes, element).ok()))
+        }
+    }
+
+    if !matches!(element, NbtTapeElement::List { .. }) {
+        return None;
+    }
+    let elements = tapes.unpack_list::<Lenient<T>>(element)?;
+    Some(
+        elements
+            .into_iter()
+            .filter_map(|element| element.0)
+            .collect(),
+    )
+}
+
+fn read_list<R: Read, T>(
+    reader: &mut R,
+    opts: &NetDecodeOpts,
+    read: impl Fn(&mut R) -> Result<T, NetDecodeError>,
+) -> Result<Vec<T>, NetDecodeError> {
+    let len = VarInt::decode(reader, opts)?.0;
+    (0..len).map(|_| read(reader)).collect()
+}
+
+fn read_optional<R: Read, T>(
+    reader: &mut R,
+    opts: &NetDecodeOpts,
+    read: impl Fn(&mut R) -> Result<T, NetDecodeError>,
+) -> Result<Option<T>, NetDecodeError> {
+    if bool::decode(reader, opts)? {
+        read(reader).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn write_list<W: Write, T>(
+    writer: &mut W,
+    opts: &NetEncodeOpts,
+    values: &[T],
+    write: impl Fn(&mut W, &T) -> Result<(), NetEncodeError>,
+) -> Result<(), NetEncodeError> {
+    VarInt::new(values.len() as i32).encode(writer, opts)?;
+    values.iter().try_for_each(|value| write(writer, value))
+}
+
+fn write_optional<W: Write, T>(
+    writer: &mut W,
+    opts: &NetEncodeOpts,
+    value: Option<&T>,
+    write: impl Fn(&mut W, &T) -> Result<(), NetEncodeError>,
+) -> Result<(), NetEncodeError> {
+    value.is_some().encode(writer, opts)?;
+    value.map_or(Ok(()), |value| write(writer, value))
+}
+
+fn decode_enchantments<R: Read>(
+    reader: &mut R,
+    opts: &NetDecodeOpts,
+) -> Result<Vec<Enchantment>, NetDecodeError> {
+    let len = VarInt::decode(reader, opts)?.0;
+    (0..len)
+        .map(|_| {
+            Ok(Enchantment {
+                id: VarInt::decode(reader, opts)?,
+                level: VarInt::decode(reader, opts)?.0,
+            })
+        })
+        .collect()
+}
+
+impl Component {
+    /// Reads the data of a component of the type `id`.
+    fn read<R: Read>(
+        id: VarInt,
+        reader: &mut R,
+        opts: &NetDecodeOpts,
+    ) -> Result<Self, NetDecodeError> {
+        let name = component_type_name(id).ok_or_else(|| Self::unknown(id))?;
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let var_int = |reader: &mut R| VarInt::decode(reader, opts).map(|value| value.0);
+        Ok(match name {
+            "custom_data" => Self::CustomData(NetworkNbt::read(reader)?),
+            "max_stack_size" => Self::MaxStackSize(var_int(reader)?),
+            "max_damage" => Self::MaxDamage(var_int(reader)?),
+            "damage" => Self::Damage(var_int(reader)?),
+            "unbreakable" => Self::Unbreakable,
+            "custom_name" => Self::CustomName(NetworkNbt::read(reader)?),
+            "item_name" => Self::ItemName(NetworkNbt::read(reader)?),
+            "item_model" => Self::ItemModel(String::decode(reader, opts)?),
+            "lore" => {
+                let len = var_int(reader)?;
+                Self::Lore(
+                    (0..len)
+                        .map(|_| NetworkNbt::read(reader))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "rarity" => Self::Rarity(Rarity::from_id(VarInt::decode(reader, opts)?)?),
+            "enchantments" => Self::Enchantments(decode_enchantments(reader, opts)?),
+            "repair_cost" => Self::RepairCost(var_int(reader)?),
+            "enchantment_glint_override" => {
+                Self::EnchantmentGlintOverride(bool::decode(reader, opts)?)
+            }
+            "stored_enchantments" => Self::StoredEnchantments(decode_enchantments(reader, opts)?),
+            "dyed_color" => Self::DyedColor(i32::decode(reader, opts)?),
+            "potion_contents" => Self::PotionContents(PotionContents::read(reader, opts)?),
+            "writable_book_content" => {
+                Self::WritableBookContent(read_list(reader, opts, |reader| {
+                    Filterable::read(reader, opts, |reader| String::decode(reader, opts))
+                })?)
+            }
+            "written_book_content" => {
+                Self::WrittenBookContent(WrittenBookContent::read(reader, opts)?)
+            }
+            "firework_explosion" => Self::FireworkExplosion(FireworkExplosion::read(reader, opts)?),
+            "fireworks" => Self::Fireworks(Fireworks::read(reader, opts)?),
+            _ => {
+                let layout = layout::layout(name).ok_or_else(|| Self::unknown(id))?;
+                Self::Raw {
+                    id,
+                    data: layout::read(layout, reader)?,
+                }
+            }
+        })
+    }
+}
+
+impl NetDecode for Component {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        let id = VarInt::decode(reader, opts)?;
+        Self::read(id, reader, opts)
+    }
+
+    async fn decode_async<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        opts: &NetDecodeOpts,
+    ) -> Result<Self, NetDecodeError> {
+        let id = VarInt::decode_async(reader, opts).await?;
+        // The layout says how much data there is, which is then read like any other
+        let layout = layout::for_type(id).ok_or_else(|| Self::unknown(id))?;
+        let data = layout::read_async(layout, reader).await?;
+        Self::read(id, &mut data.as_slice(), opts)
+    }
+}
+
+impl NetEncode for Component {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        let id = self.type_id().ok_or_else(|| {
+            NetEncodeError::ExternalError(Box::new(InventoryError::UnknownComponent(
+                self.name().to_string(),
+            )))
+        })?;
+        id.encode(writer, opts)?;
+        match self {
+            Self::CustomData(nbt) | Self::CustomName(nbt) | Self::ItemName(nbt) => {
+                nbt.encode(writer, opts)
+            }
+            Self::MaxStackSize(value)
+            | Self::MaxDamage(value)
+            | Self::Damage(value)
+            | Self::RepairCost(value) => VarInt::new(*value).encode(writer, opts),
+            Self::Unbreakable => Ok(()),
+            Self::ItemModel(model) => model.encode(writer, opts),
+            Self::Lore(lines) => {
+                VarInt::new(lines.len() as i32).encode(writer, opts)?;
+                for line in lines {
+                    line.encode(writer, opts)?;
+                }
+                Ok(())
+            }
+            Self::Rarity(rarity) => VarInt::new(*rarity as i32).encode(writer, opts),
+            Self::Enchantments(enchantments) | Self::StoredEnchantments(enchantments) => {
+                VarInt::new(enchantments.len() as i32).encode(writer, opts)?;
+                for enchantment in enchantments {
+                    enchantment.id.encode(writer, opts)?;
+                    VarInt::new(enchantment.level).encode(writer, opts)?;
+                }
+                Ok(())
+            }
+            Self::EnchantmentGlintOverride(glint) => glint.encode(writer, opts),
+            Self::DyedColor(color) => color.encode(writer, opts),
+            Self::PotionContents(contents) => contents.write(writer, opts),
+            Self::WritableBookContent(pages) => write_list(writer, opts, pages, |writer, page| {
+                page.write(writer, opts, |writer, text| text.encode(writer, opts))
+            }),
+            Self::WrittenBookContent(content) => content.write(writer, opts),
+            Self::FireworkExplosion(explosion) => explosion.write(writer, opts),
+            Self::Fireworks(fireworks) => fireworks.write(writer, opts),
+            Self::Raw { data, .. } => Ok(writer.write_all(data)?),
+            Self::Nbt { id, .. } => Err(NetEncodeError::ExternalError(Box::new(
+                InventoryError::UnsupportedComponent(id.0),
+            ))),
+        }
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> Result<(), NetEncodeError> {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+/// The components an item stack has on top of, or removed from, its item's defaults. Vanilla
+/// saves it as the `components` compound of an item, with removed components as `!` followed by
+/// their name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentPatch {
+    pub added: Vec<Component>,
+    /// The ids of the removed component types.
+    pub removed: Vec<VarInt>,
+}
+
+impl ComponentPatch {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// The patch as a root compound, for storing it on its own.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes, &NBTSerializeOptions::WithHeader(""));
+        bytes
+    }
+
+    /// Reads a patch stored with [`ComponentPatch::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> ferrumc_nbt::Result<Self> {
+        let mut tape = NbtTape::new(bytes);
+        tape.parse();
+        let root = tape
+            .root
+            .as_ref()
+            .map(|(_, root)| root)
+            .ok_or(NBTError::NoRootTag)?;
+        Self::from_nbt(&tape, root)
+    }
+}
+
+impl<'a> FromNbt<'a> for ComponentPatch {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> ferrumc_nbt::Result<Self> {
+        let compound = element.as_compound().ok_or(NBTError::TypeMismatch {
+            expected: "Compound (from ComponentPatch)",
+            found: element.nbt_type(),
+        })?;
+        let mut patch = Self::default();
+        for (name, value) in compound {
+            if let Some(removed) = name.strip_prefix('!') {
+                patch.removed.extend(component_type_id(removed));
+            } else if let Some(component) = Component::from_nbt(name, tapes, value) {
+                patch.added.push(component);
+            } else {
+                debug!("Skipping unsupported item component {}", name);
+            }
+        }
+        Ok(patch)
+    }
+}
+
+impl NBTSerializable for ComponentPatch {
+    fn serialize(&self, buf: &mut Vec<u8>, options: &NBTSerializeOptions<'_>) {
+        match options {
+            NBTSerializeOptions::None => {}
+            NBTSerializeOptions::WithHeader(name) => {
+                buf.push(TAG_COMPOUND);
+                name.serialize(buf, &NBTSerializeOptions::None);
+            }
+            NBTSerializeOptions::Network | NBTSerializeOptions::Flatten => buf.push(TAG_COMPOUND),
+        }
+        for component in &self.added {
+            component.write_nbt(buf);
+        }
+        for name in self
+            .removed
+            .iter()
+            .filter_map(|id| component_type_name(*id))
+        {
+            buf.push(TAG_COMPOUND);
+            format!("!{name}").serialize(buf, &NBTSerializeOptions::None);
+            buf.push(TAG_END);
+        }
+        buf.push(TAG_END);
+    }
+
+    fn id() -> u8 {
+        TAG_COMPOUND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAG_STRING: u8 = 8;
+
+    fn encoded(component: &Component) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        component.encode(&mut buffer, &NetEncodeOpts::None).unwrap();
+        buffer
+    }
+
+    fn sharpness(level: i32) -> Enchantment {
+        Enchantment::from_name("minecraft:sharpness", level).unwrap()
+    }
+
+    fn components() -> Vec<Component> {
+        vec![
+            Component::CustomName(NetworkNbt::string("Excalibur")),
+            Component::Lore(vec![
+                NetworkNbt::string("Pulled from a stone"),
+                NetworkNbt::string("Very sharp"),
+            ]),
+            Component::Damage(12),
+            Component::Unbreakable,
+            Component::Rarity(Rarity::Epic),
+            Component::Enchantments(vec![sharpness(5)]),
+            Component::RepairCost(3),
+            Component::EnchantmentGlintOverride(false),
+            Component::DyedColor(0xA06540),
+            Component::PotionContents(PotionContents {
+                potion: registry_id(&POTION_NAMES, "minecraft:swiftness"),
+                custom_color: Some(0x33EBFF),
+                custom_effects: vec![Effect {
+                    id: registry_id(&EFFECT_NAMES, "minecraft:jump_boost").unwrap(),
+                    details: EffectDetails {
+                        amplifier: 1,
+                        duration: 200,
+                        ambient: false,
+                        show_particles: true,
+                        show_icon: true,
+                        hidden_effect: Some(Box::new(EffectDetails {
+                            amplifier: 0,
+                            duration: 1200,
+                            ambient: false,
+                            show_particles: false,
+                            show_icon: false,
+                            hidden_effect: None,
+                        })),
+                    },
+                }],
+                custom_name: None,
+            }),
+            Component::WritableBookContent(vec![Filterable {
+                raw: "Dear diary".to_string(),
+                filtered: Some("**** diary".to_string()),
+            }]),
+            Component::WrittenBookContent(WrittenBookContent {
+                title: Filterable {
+                    raw: "Memoirs".to_string(),
+                    filtered: None,
+                },
+                author: "Steve".to_string(),
+                generation: 1,
+                pages: vec![Filterable {
+                    raw: NetworkNbt::string("It was a dark and stormy night"),
+                    filtered: None,
+                }],
+                resolved: true,
+            }),
+            Component::FireworkExplosion(explosion()),
+            Component::Fireworks(Fireworks {
+                flight_duration: 3,
+                explosions: vec![explosion(), explosion()],
+            }),
+        ]
+    }
+
+    fn explosion() -> FireworkExplosion {
+        FireworkExplosion {
+            shape: FireworkShape::Creeper,
+            colors: vec![0x11FF00, 0xFFFFFF],
+            fade_colors: vec![0x000000],
+            has_trail: true,
+            has_twinkle: false,
+        }
+    }
+
+    fn var_int(buf: &mut Vec<u8>, value: i32) {
+        VarInt::new(value)
+            .encode(buf, &NetEncodeOpts::None)
+            .unwrap();
+    }
+
+    fn string(buf: &mut Vec<u8>, value: &str) {
+        value.encode(buf, &NetEncodeOpts::None).unwrap();
+    }
+
+    /// Components that aren't modelled, as vanilla sends them.
+    fn raw_components() -> Vec<Vec<u8>> {
+        let id = |name| component_type_id(name).unwrap().0;
+
+        // A pickaxe's tool rules
+        let mut tool = Vec::new();
+        var_int(&mut tool, id("minecraft:tool"));
+        var_int(&mut tool, 1);
+        var_int(&mut tool, 0);
+        string(&mut tool, "minecraft:mineable/pickaxe");
+        tool.push(1);
+        tool.extend(8.0f32.to_be_bytes());
+        tool.push(1);
+        tool.push(1);
+        tool.extend(1.0f32.to_be_bytes());
+        var_int(&mut tool, 1);
+        tool.push(0);
+
+        // A drink that gives an effect with another one hidden behind it, then clears them
+        let mut consumable = Vec::new();
+        var_int(&mut consumable, id("minecraft:consumable"));
+        consumable.extend(1.6f32.to_be_bytes());
+        var_int(&mut consumable, 3);
+        var_int(&mut consumable, 0);
+        string(&mut consumable, "minecraft:entity.generic.drink");
+        consumable.push(0);
+        consumable.push(1);
+        var_int(&mut consumable, 2);
+        var_int(&mut consumable, 0);
+        var_int(&mut consumable, 1);
+        consumable.extend([1, 0, 0x80, 0x01, 0, 1, 1, 1, 0, 100, 0, 0, 0, 0]);
+        consumable.extend(1.0f32.to_be_bytes());
+        var_int(&mut consumable, 2);
+
+        // A bundle holding a potion and an empty slot
+        let mut bundle = Vec::new();
+        var_int(&mut bundle, id("minecraft:bundle_contents"));
+        var_int(&mut bundle, 2);
+        var_int(&mut bundle, 1);
+        var_int(&mut bundle, 5);
+        var_int(&mut bundle, 1);
+        var_int(&mut bundle, 1);
+        bundle.extend(encoded(&components()[9]));
+        var_int(&mut bundle, id("minecraft:tool"));
+        var_int(&mut bundle, 0);
+
+        vec![tool, consumable, bundle]
+    }
+
+    #[test]
+    fn test_component_type_ids() {
+        assert_eq!(
+            component_type_id("minecraft:custom_name"),
+            Some(VarInt::new(5))
+        );
+        assert_eq!(
+            component_type_name(VarInt::new(10)),
+            Some("minecraft:enchantments")
+        );
+        for component in components() {
+            assert!(component.type_id().is_some(), "{}", component.name());
+        }
+        assert_eq!(sharpness(5).id, VarInt::new(32));
+        assert_eq!(sharpness(5).name().as_deref(), Some("minecraft:sharpness"));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encoded(&Component::Damage(300)), vec![3, 0xAC, 0x02]);
+        assert_eq!(encoded(&Component::Unbreakable), vec![4]);
+        assert_eq!(
+            encoded(&Component::CustomName(NetworkNbt::string("a"))),
+            vec![5, TAG_STRING, 0, 1, b'a']
+        );
+        assert_eq!(
+            encoded(&Component::Enchantments(vec![sharpness(5)])),
+            vec![10, 1, 32, 5]
+        );
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        for component in components() {
+            let bytes = encoded(&component);
+            let mut reader = bytes.as_slice();
+            let decoded = Component::decode(&mut reader, &NetDecodeOpts::None).unwrap();
+            assert_eq!(decoded, component);
+            assert!(reader.is_empty());
+        }
+        // Data that ends early can't be read
+        let tool = component_type_id("minecraft:tool").unwrap();
+        assert!(Component::decode(&mut &[tool.0 as u8][..], &NetDecodeOpts::None).is_err());
+    }
+
+    #[test]
+    fn test_every_component_has_layout() {
+        assert_eq!(COMPONENT_NAMES.len(), 96);
+        for (id, name) in COMPONENT_NAMES.iter() {
+            assert!(layout::for_type(VarInt::new(*id)).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_decode_raw() {
+        for bytes in raw_components() {
+            let mut reader = bytes.as_slice();
+            let decoded = Component::decode(&mut reader, &NetDecodeOpts::None).unwrap();
+            assert!(matches!(decoded, Component::Raw { .. }), "{decoded:?}");
+            assert!(reader.is_empty(), "{}", decoded.name());
+            assert_eq!(encoded(&decoded), bytes);
+
+            let truncated = &bytes[..bytes.len() - 1];
+            assert!(Component::decode(&mut &truncated[..], &NetDecodeOpts::None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_hostile() {
+        // A list claiming more entries than vanilla would ever send
+        let mut bytes = Vec::new();
+        var_int(
+            &mut bytes,
+            component_type_id("minecraft:container").unwrap().0,
+        );
+        var_int(&mut bytes, i32::MAX);
+        assert!(Component::decode(&mut bytes.as_slice(), &NetDecodeOpts::None).is_err());
+
+        // Bundles nested in bundles are read without recursing
+        let bundle = component_type_id("minecraft:bundle_contents").unwrap().0;
+        let nested = |depth, siblings: u8| {
+            let mut bytes = Vec::new();
+            for _ in 0..depth {
+                var_int(&mut bytes, bundle);
+                bytes.extend([1 + siblings, 1, 5, 1, 0]);
+            }
+            var_int(&mut bytes, bundle);
+            bytes.push(0);
+            bytes.extend(std::iter::repeat_n(0, depth * siblings as usize));
+            bytes
+        };
+        let bytes = nested(2000, 0);
+        let decoded = Component::decode(&mut bytes.as_slice(), &NetDecodeOpts::None).unwrap();
+        assert_eq!(encoded(&decoded), bytes);
+        // Unless every level leaves something to read once the deeper ones are done
+        let bytes = nested(2000, 1);
+        assert!(Component::decode(&mut bytes.as_slice(), &NetDecodeOpts::None).is_err());
+        assert!(Component::decode(&mut nested(100, 1).as_slice(), &NetDecodeOpts::None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_decode_async() {
+        for component in components() {
+            let bytes = encoded(&component);
+            let decoded = Component::decode_async(&mut bytes.as_slice(), &NetDecodeOpts::None)
+                .await
+                .unwrap();
+            assert_eq!(decoded, component);
+        }
+        for bytes in raw_components() {
+            let decoded = Component::decode_async(&mut bytes.as_slice(), &NetDecodeOpts::None)
+                .await
+                .unwrap();
+            assert_eq!(
+                decoded,
+                Component::decode(&mut bytes.as_slice(), &NetDecodeOpts::None).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_nbt_roundtrip() {
+        let patch = ComponentPatch {
+            added: components(),
+            removed: vec![component_type_id("minecraft:food").unwrap()],
+        };
+        assert_eq!(
+            ComponentPatch::from_bytes(&patch.to_bytes()).unwrap(),
+            patch
+        );
+
+        // Network data can't be saved in a form vanilla reads
+        let raw = raw_components()
+            .iter()
+            .map(|bytes| Component::decode(&mut bytes.as_slice(), &NetDecodeOpts::None).unwrap())
+            .collect();
+        let patch = ComponentPatch {
+            added: raw,
+            removed: Vec::new(),
+        };
+        assert!(
+            ComponentPatch::from_bytes(&patch.to_bytes())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_unmodelled_nbt_kept() {
+        // {"minecraft:trim": {pattern: "minecraft:coast", material: "minecraft:iron"}}
+        let mut bytes = vec![TAG_COMPOUND, 0, 0, TAG_COMPOUND];
+        "minecraft:trim".serialize(&mut bytes, &NBTSerializeOptions::None);
+        "minecraft:coast".serialize(&mut bytes, &NBTSerializeOptions::WithHeader("pattern"));
+        "minecraft:iron".serialize(&mut bytes, &NBTSerializeOptions::WithHeader("material"));
+        bytes.extend([TAG_END, TAG_END]);
+
+        let patch = ComponentPatch::from_bytes(&bytes).unwrap();
+        let [trim] = patch.added.as_slice() else {
+            panic!("{patch:?}");
+        };
+        assert!(matches!(trim, Component::Nbt { .. }));
+        assert_eq!(trim.name(), "minecraft:trim");
+        assert!(!trim.is_networked());
+        assert_eq!(patch.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_vanilla_potion_and_fireworks() {
+        let mut bytes = vec![TAG_COMPOUND, 0, 0];
+        "minecraft:swiftness".serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("minecraft:potion_contents"),
+        );
+        bytes.push(TAG_COMPOUND);
+        "minecraft:fireworks".serialize(&mut bytes, &NBTSerializeOptions::None);
+        2i8.serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("flight_duration"),
+        );
+        bytes.push(TAG_LIST);
+        "explosions".serialize(&mut bytes, &NBTSerializeOptions::None);
+        bytes.push(TAG_COMPOUND);
+        1i32.serialize(&mut bytes, &NBTSerializeOptions::None);
+        "star".serialize(&mut bytes, &NBTSerializeOptions::WithHeader("shape"));
+        vec![0xFF0000i32].serialize(&mut bytes, &NBTSerializeOptions::WithHeader("colors"));
+        true.serialize(&mut bytes, &NBTSerializeOptions::WithHeader("has_trail"));
+        bytes.push(TAG_END);
+        bytes.push(TAG_END);
+        bytes.push(TAG_END);
+
+        let patch = ComponentPatch::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            patch.added,
+            vec![
+                Component::PotionContents(PotionContents {
+                    potion: registry_id(&POTION_NAMES, "swiftness"),
+                    ..Default::default()
+                }),
+                Component::Fireworks(Fireworks {
+                    flight_duration: 2,
+                    explosions: vec![FireworkExplosion {
+                        shape: FireworkShape::Star,
+                        colors: vec![0xFF0000],
+                        fade_colors: Vec::new(),
+                        has_trail: true,
+                        has_twinkle: false,
+                    }],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vanilla_nbt() {
+        // How 1.21.4 saves components, with a tooltip flag next to the enchantments
+        let mut bytes = vec![TAG_COMPOUND, 0, 0];
+        "Excalibur".serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("minecraft:custom_name"),
+        );
+        bytes.push(TAG_COMPOUND);
+        "minecraft:enchantments".serialize(&mut bytes, &NBTSerializeOptions::None);
+        bytes.push(TAG_COMPOUND);
+        "levels".serialize(&mut bytes, &NBTSerializeOptions::None);
+        5i32.serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("minecraft:sharpness"),
+        );
+        bytes.push(TAG_END);
+        true.serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("show_in_tooltip"),
+        );
+        bytes.push(TAG_END);
+        1i32.serialize(
+            &mut bytes,
+            &NBTSerializeOptions::WithHeader("minecraft:map_id"),
+        );
+        bytes.push(TAG_END);
+
+        let patch = ComponentPatch::from_bytes(&bytes).unwrap();
+        let mut map_id = Vec::new();
+        1i32.serialize(&mut map_id, &NBTSerializeOptions::Network);
+        assert_eq!(
+            patch.added,
+            vec![
+                Component::CustomName(NetworkNbt::string("Excalibur")),
+                Component::Enchantments(vec![sharpness(5)]),
+                // Components the server doesn't model are kept as they are
+                Component::Nbt {
+                    id: component_type_id("minecraft:map_id").unwrap(),
+                    nbt: NetworkNbt(map_id),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mixed_lore() {
+        let mut compound = vec![TAG_COMPOUND];
+        "b".serialize(&mut compound, &NBTSerializeOptions::WithHeader("text"));
+        compound.push(TAG_END);
+        let patch = ComponentPatch {
+            added: vec![Component::Lore(vec![
+                NetworkNbt::string("a"),
+                NetworkNbt(compound.clone()),
+            ])],
+            removed: Vec::new(),
+        };
+        let mut a = vec![TAG_COMPOUND];
+        "a".serialize(&mut a, &NBTSerializeOptions::WithHeader("text"));
+        a.push(TAG_END);
+        assert_eq!(
+            ComponentPatch::from_bytes(&patch.to_bytes()).unwrap().added,
+            vec![Component::Lore(vec![NetworkNbt(a), NetworkNbt(compound)])]
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! How the data of every item component type is laid out on the network.
+//!
+//! Component data isn't prefixed with its length, so reading past a component needs its layout,
+//! even when the server doesn't model it. The layouts follow the stream codecs of vanilla 1.21.8.
+
+use ferrumc_nbt::NetworkNbt;
+use ferrumc_net_codec::decode::errors::NetDecodeError;
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use ferrumc_net_codec::net_types::var_int;
+use std::io::{self, Read};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The most values waiting to be read at once. Only items nested in each other very deeply get
+/// near it.
+const MAX_PENDING: usize = 1024;
+/// The longest string vanilla reads, in bytes.
+const MAX_STRING_LEN: usize = 32767 * 3;
+
+pub(super) enum Layout {
+    /// No data at all.
+    Empty,
+    VarInt,
+    /// A value of a fixed number of bytes, like an int or a float.
+    Fixed(usize),
+    /// A string or an identifier.
+    String,
+    /// An NBT value, which is also how text components are sent.
+    Nbt,
+    /// An item stack, which may be empty.
+    Slot,
+    /// A component type id followed by the component's data.
+    Component,
+    /// A bool followed by the value if it's `true`.
+    Optional(&'static Layout),
+    /// A VarInt length followed by that many values.
+    List(&'static Layout),
+    /// Values one after another.
+    Seq(&'static [Layout]),
+    /// A registry id plus one, or 0 followed by the entry itself.
+    Holder(&'static Layout),
+    /// A VarInt that is 0 for a tag name, or the number of registry ids that follow plus one.
+    IdSet,
+    /// A bool followed by the first layout if it's `true`, or the second if it's `false`.
+    Either(&'static Layout, &'static Layout),
+    /// A VarInt picking which of the layouts follows.
+    Dispatch(&'static [Layout]),
+}
+
+use Layout::*;
+
+const INT: Layout = Fixed(4);
+const LONG: Layout = Fixed(8);
+const FLOAT: Layout = Fixed(4);
+const DOUBLE: Layout = Fixed(8);
+const BOOL: Layout = Fixed(1);
+const UUID: Layout = Fixed(16);
+const IDENTIFIER: Layout = String;
+const TEXT: Layout = Nbt;
+
+const SOUND_EVENT: Layout = Holder(&Seq(&[IDENTIFIER, Optional(&FLOAT)]));
+/// Amplifier, duration, whether it's ambient, shows particles and shows an icon, and the hidden
+/// effect, which has the same layout.
+static EFFECT_DETAILS: Layout = Seq(&[VarInt, VarInt, BOOL, BOOL, BOOL, Optional(&EFFECT_DETAILS)]);
+const EFFECT: Layout = Seq(&[
+    VarInt,
+    VarInt,
+    VarInt,
+    BOOL,
+    BOOL,
+    BOOL,
+    Optional(&EFFECT_DETAILS),
+]);
+/// Apply effects, remove effects, clear all effects, teleport randomly and play sound.
+const CONSUME_EFFECT: Layout = Dispatch(&[
+    Seq(&[List(&EFFECT), FLOAT]),
+    IdSet,
+    Empty,
+    FLOAT,
+    SOUND_EVENT,
+]);
+const ENCHANTMENTS: Layout = List(&Seq(&[VarInt, VarInt]));
+const BLOCK_PREDICATE: Layout = Seq(&[
+    Optional(&IdSet),
+    // Properties, with either an exact value or a range
+    Optional(&List(&Seq(&[
+        String,
+        Either(&String, &Seq(&[Optional(&String), Optional(&String)])),
+    ]))),
+    Optional(&Nbt),
+    // Exact components, then partial component predicates
+    List(&Component),
+    List(&Seq(&[VarInt, Nbt])),
+]);
+const TRIM_MATERIAL: Layout = Seq(&[String, List(&Seq(&[IDENTIFIER, String])), TEXT]);
+const TRIM_PATTERN: Layout = Seq(&[IDENTIFIER, TEXT, BOOL]);
+const FIREWORK_EXPLOSION: Layout = Seq(&[VarInt, List(&INT), List(&INT), BOOL, BOOL]);
+
+/// The layout of the component type called `name`, without its namespace.
+pub(super) fn layout(name: &str) -> Option<&'static Layout> {
+    Some(match name {
+        "unbreakable" | "creative_slot_lock" | "glider" => &Empty,
+        "custom_data"
+        | "intangible_projectile"
+        | "map_decorations"
+        | "debug_stick_state"
+        | "entity_data"
+        | "bucket_entity_data"
+        | "block_entity_data"
+        | "recipes"
+        | "lock"
+        | "container_loot" => &Nbt,
+        "custom_name" | "item_name" => &TEXT,
+        "max_stack_size"
+        | "max_damage"
+        | "damage"
+        | "rarity"
+        | "repair_cost"
+        | "enchantable"
+        | "map_id"
+        | "map_post_processing"
+        | "ominous_bottle_amplifier"
+        | "base_color" => &VarInt,
+        "item_model"
+        | "damage_resistant"
+        | "tooltip_style"
+        | "note_block_sound"
+        | "provides_banner_patterns" => &IDENTIFIER,
+        "enchantment_glint_override" => &BOOL,
+        "dyed_color" | "map_color" => &INT,
+        "potion_duration_scale" => &FLOAT,
+        "lore" => &List(&TEXT),
+        "enchantments" | "stored_enchantments" => &ENCHANTMENTS,
+        "can_place_on" | "can_break" => &List(&BLOCK_PREDICATE),
+        "attribute_modifiers" => &List(&Seq(&[
+            VarInt,
+            IDENTIFIER,
+            DOUBLE,
+            VarInt,
+            VarInt,
+            // Shown as usual, hidden, or replaced by a text component
+            Dispatch(&[Empty, Empty, TEXT]),
+        ])),
+        "custom_model_data" => &Seq(&[List(&FLOAT), List(&BOOL), List(&String), List(&INT)]),
+        "tooltip_display" => &Seq(&[BOOL, List(&VarInt)]),
+        "food" => &Seq(&[VarInt, FLOAT, BOOL]),
+        "consumable" => &Seq(&[FLOAT, VarInt, SOUND_EVENT, BOOL, List(&CONSUME_EFFECT)]),
+        "use_remainder" => &Slot,
+        "use_cooldown" => &Seq(&[FLOAT, Optional(&IDENTIFIER)]),
+        "tool" => &Seq(&[
+            List(&Seq(&[IdSet, Optional(&FLOAT), Optional(&BOOL)])),
+            FLOAT,
+            VarInt,
+            BOOL,
+        ]),
+        "weapon" => &Seq(&[VarInt, FLOAT]),
+        "equippable" => &Seq(&[
+            VarInt,
+            SOUND_EVENT,
+            Optional(&IDENTIFIER),
+            Optional(&IDENTIFIER),
+            Optional(&IdSet),
+            BOOL,
+            BOOL,
+            BOOL,
+            BOOL,
+            BOOL,
+            SOUND_EVENT,
+        ]),
+        "repairable" => &IdSet,
+        "death_protection" => &List(&CONSUME_EFFECT),
+        "blocks_attacks" => &Seq(&[
+            FLOAT,
+            FLOAT,
+            List(&Seq(&[FLOAT, Optional(&IdSet), FLOAT, FLOAT])),
+            Seq(&[FLOAT, FLOAT, FLOAT]),
+            Optional(&IDENTIFIER),
+            Optional(&SOUND_EVENT),
+            Optional(&SOUND_EVENT),
+        ]),
+        "charged_projectiles" | "bundle_contents" | "container" => &List(&Slot),
+        "potion_contents" => &Seq(&[
+            Optional(&VarInt),
+            Optional(&INT),
+            List(&EFFECT),
+            Optional(&String),
+        ]),
+        "suspicious_stew_effects" => &List(&Seq(&[VarInt, VarInt])),
+        "writable_book_content" => &List(&Seq(&[String, Optional(&String)])),
+        "written_book_content" => &Seq(&[
+            String,
+            Optional(&String),
+            String,
+            VarInt,
+            List(&Seq(&[TEXT, Optional(&TEXT)])),
+            BOOL,
+        ]),
+        "trim" => &Seq(&[Holder(&TRIM_MATERIAL), Holder(&TRIM_PATTERN)]),
+        "instrument" => &Either(
+            &Holder(&Seq(&[SOUND_EVENT, FLOAT, FLOAT, TEXT])),
+            &IDENTIFIER,
+        ),
+        "provides_trim_material" => &Either(&Holder(&TRIM_MATERIAL), &IDENTIFIER),
+        "jukebox_playable" => &Either(
+            &Holder(&Seq(&[SOUND_EVENT, TEXT, FLOAT, VarInt])),
+            &IDENTIFIER,
+        ),
+        "lodestone_tracker" => &Seq(&[Optional(&Seq(&[IDENTIFIER, LONG])), BOOL]),
+        "firework_explosion" => &FIREWORK_EXPLOSION,
+        "fireworks" => &Seq(&[VarInt, List(&FIREWORK_EXPLOSION)]),
+        "profile" => &Seq(&[
+            Optional(&String),
+            Optional(&UUID),
+            List(&Seq(&[String, String, Optional(&String)])),
+        ]),
+        "banner_patterns" => &List(&Seq(&[Holder(&Seq(&[IDENTIFIER, String])), VarInt])),
+        "pot_decorations" => &List(&VarInt),
+        "block_state" => &List(&Seq(&[String, String])),
+        "bees" => &List(&Seq(&[Nbt, VarInt, VarInt])),
+        "break_sound" => &SOUND_EVENT,
+        "painting/variant" => &Holder(&Seq(&[
+            VarInt,
+            VarInt,
+            IDENTIFIER,
+            Optional(&TEXT),
+            Optional(&TEXT),
+        ])),
+        "chicken/variant" => &Either(&VarInt, &IDENTIFIER),
+        _ if name.ends_with("/variant")
+            || name.ends_with("/sound_variant")
+            || name.ends_with("/collar")
+            || name.ends_with("/color")
+            || name.ends_with("/size")
+            || name.starts_with("tropical_fish/") =>
+        {
+            &VarInt
+        }
+        _ => return None,
+    })
+}
+
+/// The layout of the component type with the given id.
+pub(super) fn for_type(id: var_int::VarInt) -> Option<&'static Layout> {
+    let name = super::component_type_name(id)?;
+    layout(name.strip_prefix("minecraft:").unwrap_or(name))
+}
+
+/// What is left to read.
+enum Pending {
+    Value(&'static Layout),
+    /// `count` more values of the same layout.
+    Repeat(&'static Layout, i32),
+}
+
+pub(super) fn invalid(reason: &str) -> NetDecodeError {
+    NetDecodeError::IoError(io::Error::new(io::ErrorKind::InvalidData, reason))
+}
+
+fn push(stack: &mut Vec<Pending>, pending: Pending) -> Result<(), NetDecodeError> {
+    if stack.len() >= MAX_PENDING {
+        return Err(invalid("item components nested too deeply"));
+    }
+    stack.push(pending);
+    Ok(())
+}
+
+fn repeat(
+    stack: &mut Vec<Pending>,
+    layout: &'static Layout,
+    count: i32,
+) -> Result<(), NetDecodeError> {
+    match count {
+        0 => Ok(()),
+        1.. => push(stack, Pending::Repeat(layout, count)),
+        _ => Err(invalid("negative length")),
+    }
+}
+
+/// Takes the next value to read off the stack.
+fn next(stack: &mut Vec<Pending>) -> Result<Option<&'static Layout>, NetDecodeError> {
+    Ok(match stack.pop() {
+        None => None,
+        Some(Pending::Value(layout)) => Some(layout),
+        Some(Pending::Repeat(layout, count)) => {
+            repeat(stack, layout, count - 1)?;
+            Some(layout)
+        }
+    })
+}
+
+/// Queues up what a value that starts with `value` continues with, for the layouts that
+/// continue depending on what was read first.
+fn continue_with(
+    stack: &mut Vec<Pending>,
+    layout: &'static Layout,
+    value: i32,
+) -> Result<(), NetDecodeError> {
+    match layout {
+        Optional(inner) | Either(inner, _) if value != 0 => push(stack, Pending::Value(inner)),
+        Either(_, inner) => push(stack, Pending::Value(inner)),
+        Optional(_) => Ok(()),
+        List(inner) => repeat(stack, inner, value),
+        Holder(inner) if value == 0 => push(stack, Pending::Value(inner)),
+        Holder(_) => Ok(()),
+        IdSet if value == 0 => push(stack, Pending::Value(&String)),
+        IdSet => repeat(stack, &VarInt, value - 1),
+        Dispatch(variants) => {
+            let variant = usize::try_from(value)
+                .ok()
+                .and_then(|value| variants.get(value))
+                .ok_or(NetDecodeError::InvalidEnumVariant)?;
+            push(stack, Pending::Value(variant))
+        }
+        Component => {
+            let id = var_int::VarInt::new(value);
+            let inner = for_type(id).ok_or_else(|| super::Component::unknown(id))?;
+            push(stack, Pending::Value(inner))
+        }
+        // Slots start with their count, and the rest is read from here on
+        _ => Ok(()),
+    }
+}
+
+/// Reads a value laid out as `layout`, returning its bytes.
+pub(super) fn read<R: Read>(
+    layout: &'static Layout,
+    reader: &mut R,
+) -> Result<Vec<u8>, NetDecodeError> {
+    let mut out = Vec::new();
+    let mut stack = vec![Pending::Value(layout)];
+    while let Some(layout) = next(&mut stack)? {
+        match layout {
+            Empty => {}
+            Fixed(len) => read_bytes(reader, *len, &mut out)?,
+            String => {
+                let len = read_var_int(reader, &mut out)?;
+                read_bytes(reader, string_len(len)?, &mut out)?;
+            }
+            Nbt => out.extend(NetworkNbt::read(reader)?.0),
+            Seq(layouts) => {
+                for layout in layouts.iter().rev() {
+                    push(&mut stack, Pending::Value(layout))?;
+                }
+            }
+            Slot => {
+                let count = read_var_int(reader, &mut out)?;
+                if count > 0 {
+                    read_var_int(reader, &mut out)?;
+                    let added = read_var_int(reader, &mut out)?;
+                    let removed = read_var_int(reader, &mut out)?;
+                    repeat(&mut stack, &VarInt, removed)?;
+                    repeat(&mut stack, &Component, added)?;
+                }
+            }
+            Optional(_) | Either(..) => {
+                let value = read_bool(reader, &mut out)?;
+                continue_with(&mut stack, layout, value)?;
+            }
+            VarInt | List(_) | Holder(_) | IdSet | Dispatch(_) | Component => {
+                let value = read_var_int(reader, &mut out)?;
+                continue_with(&mut stack, layout, value)?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Reads a value laid out as `layout`, returning its bytes.
+pub(super) async fn read_async<R: AsyncRead + Unpin>(
+    layout: &'static Layout,
+    reader: &mut R,
+) -> Result<Vec<u8>, NetDecodeError> {
+    let mut out = Vec::new();
+    let mut stack = vec![Pending::Value(layout)];
+    while let Some(layout) = next(&mut stack)? {
+        match layout {
+            Empty => {}
+            Fixed(len) => read_bytes_async(reader, *len, &mut out).await?,
+            String => {
+                let len = read_var_int_async(reader, &mut out).await?;
+                read_bytes_async(reader, string_len(len)?, &mut out).await?;
+            }
+            Nbt => out.extend(NetworkNbt::read_async(reader).await?.0),
+            Seq(layouts) => {
+                for layout in layouts.iter().rev() {
+                    push(&mut stack, Pending::Value(layout))?;
+                }
+            }
+            Slot => {
+                let count = read_var_int_async(reader, &mut out).await?;
+                if count > 0 {
+                    read_var_int_async(reader, &mut out).await?;
+                    let added = read_var_int_async(reader, &mut out).await?;
+                    let removed = read_var_int_async(reader, &mut out).await?;
+                    repeat(&mut stack, &VarInt, removed)?;
+                    repeat(&mut stack, &Component, added)?;
+                }
+            }
+            Optional(_) | Either(..) => {
+                let value = read_bool_async(reader, &mut out).await?;
+                continue_with(&mut stack, layout, value)?;
+            }
+            VarInt | List(_) | Holder(_) | IdSet | Dispatch(_) | Component => {
+                let value = read_var_int_async(reader, &mut out).await?;
+                continue_with(&mut stack, layout, value)?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn string_len(len: i32) -> Result<usize, NetDecodeError> {
+    usize::try_from(len)
+        .ok()
+        .filter(|&len| len <= MAX_STRING_LEN)
+        .ok_or_else(|| invalid("string too long"))
+}
+
+fn read_bytes<R: Read>(
+    reader: &mut R,
+    len: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), NetDecodeError> {
+    let start = out.len();
+    out.resize(start + len, 0);
+    reader.read_exact(&mut out[start..])?;
+    Ok(())
+}
+
+async fn read_bytes_async<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    len: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), NetDecodeError> {
+    let start = out.len();
+    out.resize(start + len, 0);
+    reader.read_exact(&mut out[start..]).await?;
+    Ok(())
+}
+
+fn read_var_int<R: Read>(reader: &mut R, out: &mut Vec<u8>) -> Result<i32, NetDecodeError> {
+    let value = var_int::VarInt::decode(reader, &NetDecodeOpts::None)?;
+    value
+        .encode(out, &NetEncodeOpts::None)
+        .map_err(|e| invalid(&e.to_string()))?;
+    Ok(value.0)
+}
+
+async fn read_var_int_async<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    out: &mut Vec<u8>,
+) -> Result<i32, NetDecodeError> {
+    let value = var_int::VarInt::decode_async(reader, &NetDecodeOpts::None).await?;
+    value
+        .encode(out, &NetEncodeOpts::None)
+        .map_err(|e| invalid(&e.to_string()))?;
+    Ok(value.0)
+}
+
+fn read_bool<R: Read>(reader: &mut R, out: &mut Vec<u8>) -> Result<i32, NetDecodeError> {
+    read_bytes(reader, 1, out)?;
+    Ok(i32::from(out[out.len() - 1] != 0))
+}
+
+async fn read_bool_async<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    out: &mut Vec<u8>,
+) -> Result<i32, NetDecodeError> {
+    read_bytes_async(reader, 1, out).await?;
+    Ok(i32::from(out[out.len() - 1] != 0))
+}
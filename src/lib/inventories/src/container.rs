@@ -0,0 +1,94 @@
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use simd_json::base::ValueAsScalar;
+
+/// The kinds of block that hold items and open a window when used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerKind {
+    Chest,
+    Barrel,
+    ShulkerBox,
+    Dispenser,
+    Dropper,
+    Hopper,
+}
+
+impl ContainerKind {
+    /// The container kind of a block entity type, e.g. `minecraft:chest`.
+    pub fn from_block_entity(id: &str) -> Option<Self> {
+        match id.strip_prefix("minecraft:").unwrap_or(id) {
+            "chest" | "trapped_chest" => Some(Self::Chest),
+            "barrel" => Some(Self::Barrel),
+            "shulker_box" => Some(Self::ShulkerBox),
+            "dispenser" => Some(Self::Dispenser),
+            "dropper" => Some(Self::Dropper),
+            "hopper" => Some(Self::Hopper),
+            _ => None,
+        }
+    }
+
+    /// How many slots the container has.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Chest | Self::Barrel | Self::ShulkerBox => 27,
+            Self::Dispenser | Self::Dropper => 9,
+            Self::Hopper => 5,
+        }
+    }
+
+    /// The menu the client shows for the container.
+    pub fn menu(&self) -> &'static str {
+        match self {
+            Self::Chest | Self::Barrel => "minecraft:generic_9x3",
+            Self::ShulkerBox => "minecraft:shulker_box",
+            Self::Dispenser | Self::Dropper => "minecraft:generic_3x3",
+            Self::Hopper => "minecraft:hopper",
+        }
+    }
+
+    /// The id of the menu in the registry, which is what gets sent to clients.
+    pub fn menu_id(&self) -> Option<VarInt> {
+        menu_id(self.menu())
+    }
+
+    /// The translation key of the window title.
+    pub fn title_key(&self) -> &'static str {
+        match self {
+            Self::Chest => "container.chest",
+            Self::Barrel => "container.barrel",
+            Self::ShulkerBox => "container.shulkerBox",
+            Self::Dispenser => "container.dispenser",
+            Self::Dropper => "container.dropper",
+            Self::Hopper => "container.hopper",
+        }
+    }
+}
+
+/// The registry id of a menu, e.g. `minecraft:crafting`.
+pub fn menu_id(menu: &str) -> Option<VarInt> {
+    ferrumc_registry::lookup(&format!("minecraft:menu/entries/{menu}/protocol_id"))
+        .and_then(|id| id.as_i32())
+        .map(VarInt::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_id() {
+        assert_eq!(ContainerKind::Chest.menu_id(), Some(VarInt::new(2)));
+        assert_eq!(ContainerKind::Dropper.menu_id(), Some(VarInt::new(6)));
+        assert_eq!(ContainerKind::Hopper.menu_id(), Some(VarInt::new(16)));
+        assert_eq!(ContainerKind::ShulkerBox.menu_id(), Some(VarInt::new(20)));
+        assert_eq!(menu_id("minecraft:crafting"), Some(VarInt::new(12)));
+    }
+
+    #[test]
+    fn test_from_block_entity() {
+        assert_eq!(
+            ContainerKind::from_block_entity("minecraft:trapped_chest"),
+            Some(ContainerKind::Chest)
+        );
+        assert_eq!(ContainerKind::from_block_entity("minecraft:sign"), None);
+    }
+}
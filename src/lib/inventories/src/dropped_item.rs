@@ -0,0 +1,19 @@
+use crate::slot::InventorySlot;
+use bevy_ecs::prelude::Component;
+use std::time::Instant;
+
+/// An item lying on the ground as an entity.
+#[derive(Component, Debug, Clone)]
+pub struct DroppedItem {
+    pub item: InventorySlot,
+    pub spawned_at: Instant,
+}
+
+impl DroppedItem {
+    pub fn new(item: InventorySlot) -> Self {
+        Self {
+            item,
+            spawned_at: Instant::now(),
+        }
+    }
+}
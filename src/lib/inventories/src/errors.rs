@@ -10,4 +10,10 @@ pub enum InventoryError {
     InvalidSlotIndex(usize),
     #[error("Outside maximum slot range of {0}: {1}")]
     OutsideMaxSlotRange(usize, usize),
+    #[error("Invalid window slot: {0}")]
+    InvalidWindowSlot(i16),
+    #[error("Unsupported item component type: {0}")]
+    UnsupportedComponent(i32),
+    #[error("Item component {0} is missing from the registry")]
+    UnknownComponent(String),
 }
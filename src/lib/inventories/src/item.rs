@@ -3,9 +3,11 @@ use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
 use ferrumc_net_codec::encode::errors::NetEncodeError;
 use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
 use ferrumc_net_codec::net_types::var_int::VarInt;
+use once_cell::sync::Lazy;
 use simd_json::base::ValueAsScalar;
 use simd_json::prelude::ValueAsObject;
 use simd_json::prelude::ValueObjectAccess;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::io::{Read, Write};
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -33,22 +35,26 @@ impl ItemID {
             .map(|id| Self(VarInt::new(id)))
     }
 
-    /// Converts the `ItemID` to a name, e.g. "minecraft:stone" or "stone".
-    /// This is extremely expensive, as it iterates over the entire items registry.
+    /// Converts the `ItemID` to a name, e.g. "minecraft:stone".
     pub fn to_name(&self) -> Option<String> {
-        ferrumc_registry::lookup("minecraft:item/entries").map(|entries| {
-            entries.as_object().and_then(|obj| {
-                obj.iter().find_map(|(key, value)| {
-                    if value.get("protocol_id")?.as_i32() == Some(self.0.0) {
-                        Some(key.to_string())
-                    } else {
-                        None
-                    }
-                })
-            })
-        })?
+        ITEM_NAMES.get(&self.0.0).cloned()
     }
 }
+
+/// Item names by item id, so names don't need a search through the registry.
+static ITEM_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| {
+    ferrumc_registry::lookup("minecraft:item/entries")
+        .and_then(|entries| {
+            entries.as_object().map(|obj| {
+                obj.iter()
+                    .filter_map(|(name, value)| {
+                        Some((value.get("protocol_id")?.as_i32()?, name.to_string()))
+                    })
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+});
 impl ItemID {
     pub fn new(id: i32) -> Self {
         Self(VarInt::from(id))
@@ -59,6 +65,123 @@ impl ItemID {
     }
 }
 
+/// Items that stack to less than 64, by item id.
+static MAX_STACK_SIZES: Lazy<HashMap<i32, i32>> = Lazy::new(|| {
+    let Some(entries) = ferrumc_registry::lookup("minecraft:item/entries") else {
+        return HashMap::new();
+    };
+    let Some(entries) = entries.as_object() else {
+        return HashMap::new();
+    };
+    entries
+        .iter()
+        .filter_map(|(name, value)| {
+            let id = value.get("protocol_id")?.as_i32()?;
+            let name = name.strip_prefix("minecraft:").unwrap_or(name);
+            let size = max_stack_size_for(name);
+            (size != 64).then_some((id, size))
+        })
+        .collect()
+});
+
+/// Items that only stack to 16.
+const STACKS_TO_16: &[&str] = &[
+    "ender_pearl",
+    "snowball",
+    "egg",
+    "blue_egg",
+    "brown_egg",
+    "bucket",
+    "honey_bottle",
+    "armor_stand",
+    "written_book",
+];
+
+/// Endings of item names that don't stack.
+const UNSTACKABLE_SUFFIXES: &[&str] = &[
+    "_sword",
+    "_shovel",
+    "_pickaxe",
+    "_axe",
+    "_hoe",
+    "_helmet",
+    "_chestplate",
+    "_leggings",
+    "_boots",
+    "_horse_armor",
+    "_bucket",
+    "_boat",
+    "_raft",
+    "minecart",
+    "_bed",
+    "shulker_box",
+    "_bundle",
+    "potion",
+    "_stew",
+    "_soup",
+    "_on_a_stick",
+];
+
+/// Items that don't stack and aren't covered by the endings above.
+const UNSTACKABLE: &[&str] = &[
+    "bundle",
+    "bow",
+    "crossbow",
+    "trident",
+    "mace",
+    "shears",
+    "flint_and_steel",
+    "fishing_rod",
+    "brush",
+    "shield",
+    "elytra",
+    "wolf_armor",
+    "saddle",
+    "cake",
+    "totem_of_undying",
+    "writable_book",
+    "enchanted_book",
+    "knowledge_book",
+    "debug_stick",
+    "spyglass",
+    "goat_horn",
+    "ominous_bottle",
+];
+
+fn max_stack_size_for(name: &str) -> i32 {
+    if STACKS_TO_16.contains(&name) || name.ends_with("_sign") || name.ends_with("_banner") {
+        16
+    } else if UNSTACKABLE.contains(&name)
+        || name.starts_with("music_disc_")
+        || UNSTACKABLE_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+    {
+        1
+    } else {
+        64
+    }
+}
+
+impl ItemID {
+    /// How many of the item fit in one stack.
+    pub fn max_stack_size(&self) -> i32 {
+        MAX_STACK_SIZES.get(&self.0.0).copied().unwrap_or(64)
+    }
+
+    /// What's left behind in the crafting grid when the item is used up in a recipe, like the
+    /// bucket of a milk bucket.
+    pub fn crafting_remainder(&self) -> Option<ItemID> {
+        let name = self.to_name()?;
+        let remainder = match name.strip_prefix("minecraft:").unwrap_or(&name) {
+            "water_bucket" | "lava_bucket" | "milk_bucket" | "powder_snow_bucket" => "bucket",
+            "honey_bottle" | "dragon_breath" => "glass_bottle",
+            _ => return None,
+        };
+        Self::from_name(remainder)
+    }
+}
+
 impl NetDecode for ItemID {
     fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
         let id = VarInt::decode(reader, opts)?;
@@ -115,6 +238,32 @@ mod tests {
         assert_eq!(item_id, decoded);
     }
 
+    #[test]
+    fn test_max_stack_size() {
+        let size = |name| ItemID::from_name(name).unwrap().max_stack_size();
+        assert_eq!(size("stone"), 64);
+        assert_eq!(size("ender_pearl"), 16);
+        assert_eq!(size("oak_sign"), 16);
+        assert_eq!(size("diamond_pickaxe"), 1);
+        assert_eq!(size("water_bucket"), 1);
+        assert_eq!(size("bucket"), 16);
+        assert_eq!(size("mushroom_stew"), 1);
+    }
+
+    #[test]
+    fn test_crafting_remainder() {
+        let item = |name| ItemID::from_name(name).unwrap();
+        assert_eq!(
+            item("milk_bucket").crafting_remainder(),
+            Some(item("bucket"))
+        );
+        assert_eq!(
+            item("honey_bottle").crafting_remainder(),
+            Some(item("glass_bottle"))
+        );
+        assert_eq!(item("wheat").crafting_remainder(), None);
+    }
+
     #[test]
     fn test_invalid_item_name() {
         let item_name = ItemID::from_name("minecraft:nonexistent_item");
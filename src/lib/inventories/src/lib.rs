@@ -1,9 +1,13 @@
+pub mod components;
+pub mod container;
 pub mod defined_slots;
+pub mod dropped_item;
 pub mod errors;
 pub mod hotbar;
 pub mod inventory;
 pub mod item;
 pub mod slot;
+pub mod window;
 
 use crate::slot::InventorySlot;
 use bevy_ecs::prelude::Entity;
@@ -1,3 +1,4 @@
+use crate::components::{Component, ComponentPatch};
 use crate::item::ItemID;
 use ferrumc_net_codec::decode::errors::NetDecodeError;
 use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
@@ -6,19 +7,84 @@ use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use std::fmt::Display;
 use std::io::{Read, Write};
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
-#[derive(Debug, Clone, Hash, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct InventorySlot {
     pub count: VarInt,
     pub item_id: Option<ItemID>,
     pub components_to_add_count: Option<VarInt>,
     pub components_to_remove_count: Option<VarInt>,
-    pub components_to_add: Option<Vec<VarInt>>,
+    pub components_to_add: Option<Vec<Component>>,
+    /// The ids of the component types the item doesn't have, despite its defaults.
     pub components_to_remove: Option<Vec<VarInt>>,
     // https://minecraft.wiki/w/Java_Edition_protocol/Slot_data
 }
 
+impl InventorySlot {
+    /// A stack of `count` items without any components.
+    pub fn new(item_id: ItemID, count: i32) -> Self {
+        Self {
+            count: VarInt::new(count),
+            item_id: Some(item_id),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the slot holds nothing, either because it has no item or none of it.
+    pub fn is_empty(&self) -> bool {
+        self.item_id.is_none() || self.count.0 <= 0
+    }
+
+    /// Whether the items in both slots are the same and can go in one stack.
+    pub fn stacks_with(&self, other: &InventorySlot) -> bool {
+        self.item_id == other.item_id && self.component_patch() == other.component_patch()
+    }
+
+    /// How many of the item fit in one stack.
+    pub fn max_stack_size(&self) -> i32 {
+        self.components()
+            .iter()
+            .find_map(|component| match component {
+                Component::MaxStackSize(size) => Some(*size),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.item_id.map_or(64, |item_id| item_id.max_stack_size()))
+    }
+
+    /// The same items, `count` of them.
+    pub fn with_count(&self, count: i32) -> Self {
+        Self {
+            count: VarInt::new(count),
+            ..self.clone()
+        }
+    }
+
+    /// The components added to the item.
+    pub fn components(&self) -> &[Component] {
+        self.components_to_add.as_deref().unwrap_or_default()
+    }
+
+    /// How the item's components differ from its defaults.
+    pub fn component_patch(&self) -> ComponentPatch {
+        ComponentPatch {
+            added: self.components().to_vec(),
+            removed: self.components_to_remove.clone().unwrap_or_default(),
+        }
+    }
+
+    /// The same items with the components of `patch`.
+    pub fn with_component_patch(self, patch: ComponentPatch) -> Self {
+        Self {
+            components_to_add_count: Some(VarInt::new(patch.added.len() as i32)),
+            components_to_remove_count: Some(VarInt::new(patch.removed.len() as i32)),
+            components_to_add: Some(patch.added),
+            components_to_remove: Some(patch.removed),
+            ..self
+        }
+    }
+}
+
 impl Display for InventorySlot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -35,88 +101,209 @@ impl Display for InventorySlot {
 impl NetDecode for InventorySlot {
     fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
         let count = VarInt::decode(reader, opts)?;
-        if count.0 == 0 {
-            Ok(Self {
+        if count.0 <= 0 {
+            return Ok(Self {
                 count,
-                item_id: None,
-                components_to_add_count: None,
-                components_to_remove_count: None,
-                components_to_add: None,
-                components_to_remove: None,
-            })
-        } else {
-            let item_id = VarInt::decode(reader, opts)?;
-            let components_to_add_count = VarInt::decode(reader, opts)?;
-            let components_to_remove_count = VarInt::decode(reader, opts)?;
-            let components_to_add = {
-                let mut components = Vec::with_capacity(components_to_add_count.0 as usize);
-                for _ in 0..components_to_add_count.0 {
-                    components.push(VarInt::decode(reader, opts)?);
-                }
-                Some(components)
-            };
-            let components_to_remove = {
-                let mut components = Vec::with_capacity(components_to_remove_count.0 as usize);
-                for _ in 0..components_to_remove_count.0 {
-                    components.push(VarInt::decode(reader, opts)?);
-                }
-                Some(components)
-            };
-            Ok(Self {
-                count,
-                item_id: Some(ItemID(item_id)),
-                components_to_add_count: Some(components_to_add_count),
-                components_to_remove_count: Some(components_to_remove_count),
-                components_to_add,
-                components_to_remove,
-            })
+                ..Default::default()
+            });
         }
+        let item_id = ItemID::decode(reader, opts)?;
+        let components_to_add_count = VarInt::decode(reader, opts)?;
+        let components_to_remove_count = VarInt::decode(reader, opts)?;
+        let components_to_add = (0..components_to_add_count.0)
+            .map(|_| Component::decode(reader, opts))
+            .collect::<Result<_, _>>()?;
+        let components_to_remove = (0..components_to_remove_count.0)
+            .map(|_| VarInt::decode(reader, opts))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            count,
+            item_id: Some(item_id),
+            components_to_add_count: Some(components_to_add_count),
+            components_to_remove_count: Some(components_to_remove_count),
+            components_to_add: Some(components_to_add),
+            components_to_remove: Some(components_to_remove),
+        })
     }
 
     async fn decode_async<R: AsyncRead + Unpin>(
-        _reader: &mut R,
-        _opts: &NetDecodeOpts,
+        reader: &mut R,
+        opts: &NetDecodeOpts,
     ) -> Result<Self, NetDecodeError> {
-        todo!()
+        let count = VarInt::decode_async(reader, opts).await?;
+        if count.0 <= 0 {
+            return Ok(Self {
+                count,
+                ..Default::default()
+            });
+        }
+        let item_id = ItemID::decode_async(reader, opts).await?;
+        let components_to_add_count = VarInt::decode_async(reader, opts).await?;
+        let components_to_remove_count = VarInt::decode_async(reader, opts).await?;
+        let mut components_to_add = Vec::new();
+        for _ in 0..components_to_add_count.0 {
+            components_to_add.push(Component::decode_async(reader, opts).await?);
+        }
+        let mut components_to_remove = Vec::new();
+        for _ in 0..components_to_remove_count.0 {
+            components_to_remove.push(VarInt::decode_async(reader, opts).await?);
+        }
+        Ok(Self {
+            count,
+            item_id: Some(item_id),
+            components_to_add_count: Some(components_to_add_count),
+            components_to_remove_count: Some(components_to_remove_count),
+            components_to_add: Some(components_to_add),
+            components_to_remove: Some(components_to_remove),
+        })
     }
 }
 
 impl NetEncode for InventorySlot {
     fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        let Some(item_id) = self.item_id.filter(|_| !self.is_empty()) else {
+            // Empty slots are only their count
+            return VarInt::new(0).encode(writer, opts);
+        };
         self.count.encode(writer, opts)?;
-        if let Some(item_id) = &self.item_id {
-            item_id.encode(writer, opts)?;
-        } else {
-            VarInt::new(0).encode(writer, opts)?; // Encode 0 for empty slot
-        }
-        if let Some(components_to_add_count) = &self.components_to_add_count {
-            components_to_add_count.encode(writer, opts)?;
-            if let Some(components) = &self.components_to_add {
-                for component in components {
-                    component.encode(writer, opts)?;
-                }
-            }
-        } else {
-            VarInt::new(0).encode(writer, opts)?; // Encode 0 for no components to add
+        item_id.encode(writer, opts)?;
+        // The counts come from the lists, so the two can't disagree. Components only kept for
+        // saving can't be sent.
+        let to_add = self
+            .components()
+            .iter()
+            .filter(|component| component.is_networked())
+            .collect::<Vec<_>>();
+        let to_remove = self.components_to_remove.as_deref().unwrap_or_default();
+        VarInt::new(to_add.len() as i32).encode(writer, opts)?;
+        VarInt::new(to_remove.len() as i32).encode(writer, opts)?;
+        for component in to_add {
+            component.encode(writer, opts)?;
         }
-        if let Some(components_to_remove_count) = &self.components_to_remove_count {
-            components_to_remove_count.encode(writer, opts)?;
-            if let Some(components) = &self.components_to_remove {
-                for component in components {
-                    component.encode(writer, opts)?;
-                }
-            }
-        } else {
-            VarInt::new(0).encode(writer, opts)?; // Encode 0 for no components to remove
+        for component in to_remove {
+            component.encode(writer, opts)?;
         }
         Ok(())
     }
 
     async fn encode_async<W: AsyncWrite + Unpin>(
         &self,
-        _writer: &mut W,
-        _opts: &NetEncodeOpts,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
     ) -> Result<(), NetEncodeError> {
-        todo!()
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Enchantment, component_type_id};
+    use ferrumc_nbt::NetworkNbt;
+
+    #[test]
+    fn test_encode_empty_slot() {
+        let mut buf = Vec::new();
+        InventorySlot::default()
+            .encode(&mut buf, &NetEncodeOpts::None)
+            .unwrap();
+        assert_eq!(buf, vec![0]);
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let slot = InventorySlot {
+            components_to_add_count: Some(VarInt::new(0)),
+            components_to_remove_count: Some(VarInt::new(0)),
+            components_to_add: Some(Vec::new()),
+            components_to_remove: Some(Vec::new()),
+            ..InventorySlot::new(ItemID::new(1), 12)
+        };
+        let mut buf = Vec::new();
+        slot.encode(&mut buf, &NetEncodeOpts::None).unwrap();
+        let decoded = InventorySlot::decode(&mut buf.as_slice(), &NetDecodeOpts::None).unwrap();
+        assert_eq!(decoded, slot);
+    }
+
+    #[test]
+    fn test_encode_skips_nbt_components() {
+        let trim = Component::Nbt {
+            id: component_type_id("minecraft:trim").unwrap(),
+            nbt: NetworkNbt::string("kept for saving"),
+        };
+        let slot = InventorySlot::new(ItemID::new(1), 1).with_component_patch(ComponentPatch {
+            added: vec![trim, Component::Damage(3)],
+            removed: Vec::new(),
+        });
+        let mut buf = Vec::new();
+        slot.encode(&mut buf, &NetEncodeOpts::None).unwrap();
+        let decoded = InventorySlot::decode(&mut buf.as_slice(), &NetDecodeOpts::None).unwrap();
+        assert_eq!(decoded.components(), [Component::Damage(3)]);
+    }
+
+    #[test]
+    fn test_stacks_with() {
+        let stone = InventorySlot::new(ItemID::new(1), 3);
+        assert!(stone.stacks_with(&stone.with_count(60)));
+        assert!(!stone.stacks_with(&InventorySlot::new(ItemID::new(2), 3)));
+        let with_components = InventorySlot {
+            components_to_add: Some(vec![Component::RepairCost(4)]),
+            ..stone.clone()
+        };
+        assert!(!stone.stacks_with(&with_components));
+        assert!(stone.stacks_with(&InventorySlot {
+            components_to_add: Some(Vec::new()),
+            ..stone.clone()
+        }));
+    }
+
+    fn named_sword() -> InventorySlot {
+        let sword = ItemID::from_name("diamond_sword").unwrap();
+        InventorySlot::new(sword, 1).with_component_patch(ComponentPatch {
+            added: vec![
+                Component::CustomName(NetworkNbt::string("Excalibur")),
+                Component::Enchantments(vec![Enchantment::from_name("sharpness", 5).unwrap()]),
+                Component::Damage(7),
+            ],
+            removed: vec![component_type_id("minecraft:tool").unwrap()],
+        })
+    }
+
+    #[test]
+    fn test_component_roundtrip() {
+        let slot = named_sword();
+        let mut buf = Vec::new();
+        slot.encode(&mut buf, &NetEncodeOpts::None).unwrap();
+        let mut reader = buf.as_slice();
+        let decoded = InventorySlot::decode(&mut reader, &NetDecodeOpts::None).unwrap();
+        assert_eq!(decoded, slot);
+        assert!(reader.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_async_roundtrip() {
+        let slot = named_sword();
+        let mut buf = Vec::new();
+        slot.encode_async(&mut buf, &NetEncodeOpts::None)
+            .await
+            .unwrap();
+        let decoded = InventorySlot::decode_async(&mut buf.as_slice(), &NetDecodeOpts::None)
+            .await
+            .unwrap();
+        assert_eq!(decoded, slot);
+    }
+
+    #[test]
+    fn test_max_stack_size_component() {
+        let stone = InventorySlot::new(ItemID::new(1), 1);
+        assert_eq!(stone.max_stack_size(), 64);
+        let patch = ComponentPatch {
+            added: vec![Component::MaxStackSize(16)],
+            removed: Vec::new(),
+        };
+        assert_eq!(stone.with_component_patch(patch).max_stack_size(), 16);
     }
 }
@@ -0,0 +1,1108 @@
+//! Windows on a player's inventory, and what clicking in them does.
+//!
+//! Most windows' slots are their own, a container's or a crafting table's, followed by the
+//! player's main inventory and hotbar, in the order the client numbers them. The player's own
+//! inventory window is numbered like [`Inventory`] is. Clicks are run on the server following
+//! vanilla's rules, so what the client guessed they did can be checked against what they actually
+//! did.
+
+use crate::errors::InventoryError;
+use crate::inventory::Inventory;
+use crate::item::ItemID;
+use crate::slot::InventorySlot;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::ops::Range;
+
+/// The slot clicked when clicking outside the window.
+pub const OUTSIDE: i16 = -999;
+/// How many slots a crafting table window has of its own, the result and a 3x3 grid.
+pub const CRAFTING_SLOTS: usize = 10;
+/// The first of the player's slots shown below a container.
+const PLAYER_SLOTS_START: usize = 9;
+/// How many of the player's slots are shown below a container, the main inventory and hotbar.
+const PLAYER_SLOTS: usize = 36;
+const HOTBAR_START: usize = 36;
+const OFFHAND: usize = 45;
+/// The swap button for the offhand, the others being the hotbar slots.
+const OFFHAND_BUTTON: i8 = 40;
+
+/// What crafting grids make, which is up to the recipe registry.
+pub trait Recipes {
+    /// The result of crafting with `grid`, a crafting grid `width` slots wide.
+    fn craft(&self, grid: &[Option<InventorySlot>], width: usize) -> Option<InventorySlot>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickMode {
+    /// Picking up or putting down items with the left or right button.
+    Pickup,
+    /// Shift-clicking items over to the other side of the window.
+    QuickMove,
+    /// Swapping with a hotbar slot or the offhand.
+    Swap,
+    /// Middle-clicking for a full stack, only in creative.
+    Clone,
+    /// Dropping items out of a slot.
+    Throw,
+    /// Spreading the carried items over several slots.
+    Drag,
+    /// Double-clicking to gather matching items.
+    PickupAll,
+}
+
+impl ClickMode {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::Pickup),
+            1 => Some(Self::QuickMove),
+            2 => Some(Self::Swap),
+            3 => Some(Self::Clone),
+            4 => Some(Self::Throw),
+            5 => Some(Self::Drag),
+            6 => Some(Self::PickupAll),
+            _ => None,
+        }
+    }
+}
+
+/// A drag over several slots, which takes a click to start, one per slot and one to end.
+#[derive(Debug, Clone, Default)]
+pub struct Drag {
+    /// 0 splits the items evenly, 1 places one in each slot. `None` if there is no drag.
+    kind: Option<i8>,
+    slots: Vec<usize>,
+}
+
+impl Drag {
+    pub fn is_dragging(&self) -> bool {
+        self.kind.is_some()
+    }
+
+    fn reset(&mut self) {
+        self.kind = None;
+        self.slots.clear();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// A container's slots, then the player's.
+    Container,
+    /// A crafting table's result and grid, then the player's slots.
+    Crafting,
+    /// The player's own inventory, with its 2x2 crafting grid.
+    Inventory,
+}
+
+/// Where a window's crafting result and grid are.
+struct Grid {
+    result: usize,
+    slots: Range<usize>,
+    width: usize,
+}
+
+pub struct Window<'a> {
+    layout: Layout,
+    /// The window's own slots, shown before the player's.
+    slots: &'a mut [Option<InventorySlot>],
+    inventory: &'a mut Inventory,
+    carried: &'a mut Option<InventorySlot>,
+    drag: &'a mut Drag,
+    recipes: Option<&'a dyn Recipes>,
+    /// What the current click dropped out of the window.
+    dropped: Vec<InventorySlot>,
+}
+
+impl<'a> Window<'a> {
+    /// A window on a container's slots.
+    pub fn new(
+        container: &'a mut [Option<InventorySlot>],
+        inventory: &'a mut Inventory,
+        carried: &'a mut Option<InventorySlot>,
+        drag: &'a mut Drag,
+    ) -> Self {
+        Self {
+            layout: Layout::Container,
+            slots: container,
+            inventory,
+            carried,
+            drag,
+            recipes: None,
+            dropped: Vec::new(),
+        }
+    }
+
+    /// A crafting table window, `slots` being its result and grid, [`CRAFTING_SLOTS`] long.
+    pub fn crafting(
+        slots: &'a mut [Option<InventorySlot>],
+        inventory: &'a mut Inventory,
+        carried: &'a mut Option<InventorySlot>,
+        drag: &'a mut Drag,
+        recipes: &'a dyn Recipes,
+    ) -> Self {
+        Self {
+            layout: Layout::Crafting,
+            recipes: Some(recipes),
+            ..Self::new(slots, inventory, carried, drag)
+        }
+    }
+
+    /// The window on the player's own inventory, which is open whenever no other window is.
+    pub fn inventory(
+        inventory: &'a mut Inventory,
+        carried: &'a mut Option<InventorySlot>,
+        drag: &'a mut Drag,
+        recipes: &'a dyn Recipes,
+    ) -> Self {
+        Self {
+            layout: Layout::Inventory,
+            recipes: Some(recipes),
+            ..Self::new(&mut [], inventory, carried, drag)
+        }
+    }
+
+    /// How many slots the window has.
+    pub fn size(&self) -> usize {
+        match self.layout {
+            Layout::Inventory => self.inventory.slots.len(),
+            _ => self.slots.len() + PLAYER_SLOTS,
+        }
+    }
+
+    /// Every slot of the window in order, with empty slots as empty stacks.
+    pub fn contents(&self) -> Vec<InventorySlot> {
+        match self.layout {
+            Layout::Inventory => self
+                .inventory
+                .slots
+                .iter()
+                .map(|slot| slot.clone().unwrap_or_default())
+                .collect(),
+            _ => contents(self.slots, self.inventory),
+        }
+    }
+
+    /// The items held by the cursor.
+    pub fn carried(&self) -> InventorySlot {
+        self.carried.clone().unwrap_or_default()
+    }
+
+    /// Puts the carried items back in the player's inventory, like when the window is closed,
+    /// and returns what didn't fit.
+    pub fn return_carried(&mut self) -> Option<InventorySlot> {
+        self.drag.reset();
+        let carried = self.carried.take()?;
+        self.move_into(carried, &self.return_slots())
+    }
+
+    /// Puts the carried items and whatever is in the crafting grid back in the player's
+    /// inventory, as the window closes, and returns what didn't fit.
+    pub fn close(&mut self) -> Vec<InventorySlot> {
+        let mut rest = Vec::from_iter(self.return_carried());
+        if let Some(grid) = self.grid() {
+            let slots = self.return_slots();
+            for index in grid.slots {
+                if let Some(item) = self.take(index) {
+                    rest.extend(self.move_into(item, &slots));
+                }
+            }
+            self.put(grid.result, None);
+        }
+        rest
+    }
+
+    /// The window index of the player's first main inventory slot.
+    fn player_start(&self) -> usize {
+        match self.layout {
+            Layout::Inventory => PLAYER_SLOTS_START,
+            _ => self.slots.len(),
+        }
+    }
+
+    fn hotbar_start(&self) -> usize {
+        self.player_start() + HOTBAR_START - PLAYER_SLOTS_START
+    }
+
+    fn player_end(&self) -> usize {
+        self.player_start() + PLAYER_SLOTS
+    }
+
+    /// The player's slots items go back into, the hotbar first.
+    fn return_slots(&self) -> Vec<usize> {
+        (self.hotbar_start()..self.player_end())
+            .chain(self.player_start()..self.hotbar_start())
+            .collect()
+    }
+
+    fn grid(&self) -> Option<Grid> {
+        let (slots, width) = match self.layout {
+            Layout::Container => return None,
+            Layout::Crafting => (1..CRAFTING_SLOTS, 3),
+            Layout::Inventory => (1..5, 2),
+        };
+        Some(Grid {
+            result: 0,
+            slots,
+            width,
+        })
+    }
+
+    fn is_result(&self, index: usize) -> bool {
+        self.grid().is_some_and(|grid| grid.result == index)
+    }
+
+    fn slot(&self, index: usize) -> Option<&Option<InventorySlot>> {
+        if self.layout == Layout::Inventory {
+            return self.inventory.slots.get(index);
+        }
+        match index.checked_sub(self.slots.len()) {
+            None => self.slots.get(index),
+            Some(player) if player < PLAYER_SLOTS => {
+                self.inventory.slots.get(PLAYER_SLOTS_START + player)
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn slot_mut(&mut self, index: usize) -> Option<&mut Option<InventorySlot>> {
+        if self.layout == Layout::Inventory {
+            return self.inventory.slots.get_mut(index);
+        }
+        match index.checked_sub(self.slots.len()) {
+            None => self.slots.get_mut(index),
+            Some(player) if player < PLAYER_SLOTS => {
+                self.inventory.slots.get_mut(PLAYER_SLOTS_START + player)
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn take(&mut self, index: usize) -> Option<InventorySlot> {
+        self.slot_mut(index).and_then(Option::take)
+    }
+
+    fn put(&mut self, index: usize, item: Option<InventorySlot>) {
+        if let Some(slot) = self.slot_mut(index) {
+            *slot = item.filter(|item| !item.is_empty());
+        }
+    }
+
+    fn set_carried(&mut self, item: Option<InventorySlot>) {
+        *self.carried = item.filter(|item| !item.is_empty());
+    }
+
+    fn index(&self, slot: i16) -> Result<usize, InventoryError> {
+        usize::try_from(slot)
+            .ok()
+            .filter(|&index| index < self.size())
+            .ok_or(InventoryError::InvalidWindowSlot(slot))
+    }
+
+    /// Runs a click on `slot`, returning the items it dropped out of the window.
+    pub fn click(
+        &mut self,
+        slot: i16,
+        button: i8,
+        mode: ClickMode,
+    ) -> Result<Vec<InventorySlot>, InventoryError> {
+        if mode != ClickMode::Drag && self.drag.is_dragging() {
+            // Any other click cancels the drag and does nothing else
+            self.drag.reset();
+            return Ok(Vec::new());
+        }
+        match mode {
+            ClickMode::Pickup if button == 0 || button == 1 => {
+                if slot == OUTSIDE {
+                    if let Some(carried) = self.carried.take() {
+                        let count = if button == 0 { carried.count.0 } else { 1 };
+                        self.dropped.push(carried.with_count(count));
+                        self.set_carried(Some(carried.with_count(carried.count.0 - count)));
+                    }
+                } else if slot >= 0 {
+                    let index = self.index(slot)?;
+                    if self.is_result(index) {
+                        self.pickup_result(index);
+                    } else {
+                        self.pickup(index, button == 1);
+                    }
+                }
+            }
+            ClickMode::QuickMove if slot >= 0 => self.quick_move(self.index(slot)?),
+            ClickMode::Swap => {
+                let target = match button {
+                    0..=8 => HOTBAR_START + button as usize,
+                    OFFHAND_BUTTON => OFFHAND,
+                    _ => return Ok(Vec::new()),
+                };
+                let index = self.index(slot)?;
+                if target >= self.inventory.slots.len() {
+                    return Err(InventoryError::InvalidSlotIndex(target));
+                }
+                if self.is_result(index) {
+                    // Crafts straight into the slot, as long as it's empty
+                    if self.inventory.slots[target].is_none() {
+                        self.inventory.slots[target] = self.take(index);
+                        self.consume_grid();
+                    }
+                } else {
+                    // Taking the window's slot first keeps this right when it's the target itself
+                    let item = self.take(index);
+                    let other = self.inventory.slots[target].take();
+                    self.put(index, other);
+                    self.inventory.slots[target] = item;
+                }
+            }
+            ClickMode::Throw if slot >= 0 && self.carried.is_none() => {
+                let index = self.index(slot)?;
+                if self.is_result(index) {
+                    self.throw_result(index, button != 0);
+                } else if let Some(item) = self.take(index) {
+                    let count = if button == 0 { 1 } else { item.count.0 };
+                    self.dropped.push(item.with_count(count));
+                    self.put(index, Some(item.with_count(item.count.0 - count)));
+                }
+            }
+            ClickMode::Drag => self.drag_click(slot, button)?,
+            ClickMode::PickupAll if slot >= 0 => self.pickup_all(self.index(slot)?, button),
+            // Cloning needs creative mode, and every other combination does nothing
+            _ => {}
+        }
+        self.update_result();
+        Ok(std::mem::take(&mut self.dropped))
+    }
+
+    fn pickup(&mut self, index: usize, right: bool) {
+        let (slot, carried) = match (self.take(index), self.carried.take()) {
+            (None, None) => (None, None),
+            (Some(item), None) if right => {
+                // Right-clicking takes the bigger half
+                let taken = (item.count.0 + 1) / 2;
+                (
+                    Some(item.with_count(item.count.0 - taken)),
+                    Some(item.with_count(taken)),
+                )
+            }
+            (Some(item), None) => (None, Some(item)),
+            (None, Some(carried)) => {
+                let placed = if right {
+                    1
+                } else {
+                    carried.count.0.min(carried.max_stack_size())
+                };
+                (
+                    Some(carried.with_count(placed)),
+                    Some(carried.with_count(carried.count.0 - placed)),
+                )
+            }
+            (Some(item), Some(carried)) if item.stacks_with(&carried) => {
+                let room = (item.max_stack_size() - item.count.0).max(0);
+                let placed = if right { room.min(1) } else { room }.min(carried.count.0);
+                (
+                    Some(item.with_count(item.count.0 + placed)),
+                    Some(carried.with_count(carried.count.0 - placed)),
+                )
+            }
+            (Some(item), Some(carried)) => (Some(carried), Some(item)),
+        };
+        self.put(index, slot);
+        self.set_carried(carried);
+    }
+
+    /// Takes the crafting result into the carried items, if all of it fits there.
+    fn pickup_result(&mut self, index: usize) {
+        let Some(Some(result)) = self.slot(index).cloned() else {
+            return;
+        };
+        let carried = match self.carried.take() {
+            None => result,
+            Some(carried)
+                if carried.stacks_with(&result)
+                    && carried.count.0 + result.count.0 <= carried.max_stack_size() =>
+            {
+                carried.with_count(carried.count.0 + result.count.0)
+            }
+            carried => {
+                *self.carried = carried;
+                return;
+            }
+        };
+        self.set_carried(Some(carried));
+        self.consume_grid();
+    }
+
+    /// Drops the crafting result, crafting for as long as it stays the same with `all`.
+    fn throw_result(&mut self, index: usize, all: bool) {
+        while let Some(result) = self.take(index) {
+            self.consume_grid();
+            let same = matches!(self.slot(index), Some(Some(next)) if next.stacks_with(&result));
+            self.dropped.push(result);
+            if !all || !same {
+                break;
+            }
+        }
+    }
+
+    /// Uses up one of each item in the crafting grid for the result that was just taken, leaving
+    /// behind their remainders, and works out the next result.
+    fn consume_grid(&mut self) {
+        let Some(grid) = self.grid() else {
+            return;
+        };
+        for index in grid.slots {
+            let Some(item) = self.take(index) else {
+                continue;
+            };
+            let remainder = item
+                .item_id
+                .and_then(|id| id.crafting_remainder())
+                .map(|id| InventorySlot::new(id, 1));
+            self.put(index, Some(item.with_count(item.count.0 - 1)));
+            let Some(remainder) = remainder else {
+                continue;
+            };
+            if matches!(self.slot(index), Some(None)) {
+                self.put(index, Some(remainder));
+            } else if let Some(rest) = self.move_into(remainder, &self.return_slots()) {
+                self.dropped.push(rest);
+            }
+        }
+        self.update_result();
+    }
+
+    /// Puts what the crafting grid makes in the result slot.
+    fn update_result(&mut self) {
+        let (Some(grid), Some(recipes)) = (self.grid(), self.recipes) else {
+            return;
+        };
+        let items = grid
+            .slots
+            .map(|index| self.slot(index).cloned().flatten())
+            .collect::<Vec<_>>();
+        self.put(grid.result, recipes.craft(&items, grid.width));
+    }
+
+    fn quick_move(&mut self, index: usize) {
+        if self.is_result(index) {
+            self.quick_move_result(index);
+            return;
+        }
+        let Some(item) = self.take(index) else {
+            return;
+        };
+        let (start, hotbar, end) = (self.player_start(), self.hotbar_start(), self.player_end());
+        let other_section = if index < hotbar {
+            (hotbar..end).collect::<Vec<_>>()
+        } else {
+            (start..hotbar).collect()
+        };
+        let targets = match self.layout {
+            // Into the player's inventory, filling the hotbar first
+            Layout::Container if index < start => vec![(start..end).rev().collect()],
+            Layout::Container => vec![(0..start).collect()],
+            Layout::Crafting if index < start => vec![(start..end).collect()],
+            Layout::Crafting => vec![(1..start).collect(), other_section],
+            // Out of the crafting grid, armor and offhand
+            Layout::Inventory if index < start || index >= end => vec![(start..end).collect()],
+            Layout::Inventory => {
+                let equipment = equipment_slot(&item)
+                    .filter(|&slot| matches!(self.slot(slot), Some(None)))
+                    .into_iter()
+                    .collect();
+                vec![equipment, other_section]
+            }
+        };
+        let mut rest = Some(item);
+        for slots in targets {
+            let Some(item) = rest else {
+                break;
+            };
+            rest = self.move_into(item, &slots);
+        }
+        self.put(index, rest);
+    }
+
+    /// Crafts into the player's inventory, for as long as the result stays the same and fits.
+    fn quick_move_result(&mut self, index: usize) {
+        let slots = (self.player_start()..self.player_end())
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(Some(result)) = self.slot(index).cloned() {
+            if self.room_for(&result, &slots) < result.count.0 {
+                break;
+            }
+            self.take(index);
+            self.move_into(result.clone(), &slots);
+            self.consume_grid();
+            if !matches!(self.slot(index), Some(Some(next)) if next.stacks_with(&result)) {
+                break;
+            }
+        }
+    }
+
+    /// How many of `item` fit in `slots`.
+    fn room_for(&self, item: &InventorySlot, slots: &[usize]) -> i32 {
+        slots
+            .iter()
+            .map(|&index| match self.slot(index) {
+                Some(None) => item.max_stack_size(),
+                Some(Some(slot)) if slot.stacks_with(item) => {
+                    (slot.max_stack_size() - slot.count.0).max(0)
+                }
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Moves as much of `item` as fits into `slots`, topping up stacks of it before using empty
+    /// slots, and returns what's left.
+    fn move_into(&mut self, mut item: InventorySlot, slots: &[usize]) -> Option<InventorySlot> {
+        for &index in slots {
+            if item.count.0 <= 0 {
+                break;
+            }
+            let Some(Some(slot)) = self.slot_mut(index) else {
+                continue;
+            };
+            if slot.stacks_with(&item) {
+                let moved = (slot.max_stack_size() - slot.count.0)
+                    .max(0)
+                    .min(item.count.0);
+                slot.count = VarInt::new(slot.count.0 + moved);
+                item.count = VarInt::new(item.count.0 - moved);
+            }
+        }
+        for &index in slots {
+            if item.count.0 <= 0 {
+                break;
+            }
+            if let Some(slot @ None) = self.slot_mut(index) {
+                let moved = item.count.0.min(item.max_stack_size());
+                *slot = Some(item.with_count(moved));
+                item.count = VarInt::new(item.count.0 - moved);
+            }
+        }
+        Some(item).filter(|item| !item.is_empty())
+    }
+
+    fn drag_click(&mut self, slot: i16, button: i8) -> Result<(), InventoryError> {
+        let (stage, kind) = (button & 3, (button >> 2) & 3);
+        match stage {
+            // Start, only for splitting evenly or one per slot, cloning needs creative mode
+            0 => {
+                self.drag.reset();
+                if self.carried.is_some() && kind <= 1 {
+                    self.drag.kind = Some(kind);
+                }
+            }
+            // Add a slot
+            1 => {
+                let index = self.index(slot)?;
+                let (Some(carried), true) = (self.carried.as_ref(), self.drag.is_dragging()) else {
+                    return Ok(());
+                };
+                let fits = match self.slot(index) {
+                    _ if self.is_result(index) => false,
+                    Some(None) => true,
+                    Some(Some(item)) => item.stacks_with(carried),
+                    None => false,
+                };
+                if fits
+                    && carried.count.0 as usize > self.drag.slots.len()
+                    && !self.drag.slots.contains(&index)
+                {
+                    self.drag.slots.push(index);
+                }
+            }
+            // End
+            2 => {
+                let kind = self.drag.kind;
+                let slots = std::mem::take(&mut self.drag.slots);
+                self.drag.reset();
+                let Some(kind) = kind else {
+                    return Ok(());
+                };
+                if let [index] = slots[..] {
+                    // Dragging over a single slot is just a click on it
+                    self.pickup(index, kind == 1);
+                } else if let Some(carried) = self.carried.take() {
+                    let each = if kind == 0 {
+                        carried.count.0 / slots.len().max(1) as i32
+                    } else {
+                        1
+                    };
+                    let mut left = carried.count.0;
+                    for index in slots {
+                        let existing = match self.slot(index) {
+                            Some(None) => 0,
+                            Some(Some(item)) if item.stacks_with(&carried) => item.count.0,
+                            _ => continue,
+                        };
+                        let placed = each
+                            .min(carried.max_stack_size() - existing)
+                            .min(left)
+                            .max(0);
+                        self.put(index, Some(carried.with_count(existing + placed)));
+                        left -= placed;
+                    }
+                    self.set_carried(Some(carried.with_count(left)));
+                }
+            }
+            _ => self.drag.reset(),
+        }
+        Ok(())
+    }
+
+    fn pickup_all(&mut self, index: usize, button: i8) {
+        // Only when the clicked slot was emptied by the first click of the double-click
+        if !matches!(self.slot(index), Some(None)) {
+            return;
+        }
+        let Some(mut carried) = self.carried.take() else {
+            return;
+        };
+        let max = carried.max_stack_size();
+        let mut slots = (0..self.size())
+            .filter(|&slot| !self.is_result(slot))
+            .collect::<Vec<_>>();
+        if button != 0 {
+            slots.reverse();
+        }
+        // Full stacks are only taken from once there are no partial ones left
+        for take_full in [false, true] {
+            for &slot in &slots {
+                if carried.count.0 >= max {
+                    break;
+                }
+                let Some(Some(item)) = self.slot_mut(slot) else {
+                    continue;
+                };
+                if !item.stacks_with(&carried) || (!take_full && item.count.0 >= max) {
+                    continue;
+                }
+                let taken = item.count.0.min(max - carried.count.0);
+                let rest = item.with_count(item.count.0 - taken);
+                carried.count = VarInt::new(carried.count.0 + taken);
+                self.put(slot, Some(rest));
+            }
+        }
+        self.set_carried(Some(carried));
+    }
+
+    /// Fills the crafting grid from the player's inventory for a recipe, `pattern` being the
+    /// items each of its slots takes, row by row in rows `width` long. Whatever was in the grid
+    /// goes back in the inventory first. Fills it once, or as many times as there are items for
+    /// with `all`, and returns whether there were enough for once.
+    pub fn place_recipe(
+        &mut self,
+        pattern: &[Option<Vec<ItemID>>],
+        width: usize,
+        all: bool,
+    ) -> bool {
+        let Some(grid) = self.grid() else {
+            return false;
+        };
+        let height = grid.slots.len() / grid.width;
+        if width == 0 || width > grid.width || pattern.len().div_ceil(width) > height {
+            return false;
+        }
+        let slots = self.return_slots();
+        for index in grid.slots.clone() {
+            let Some(item) = self.take(index) else {
+                continue;
+            };
+            if let Some(rest) = self.move_into(item, &slots) {
+                self.put(index, Some(rest));
+                return false;
+            }
+        }
+
+        let cells = pattern
+            .iter()
+            .enumerate()
+            .filter_map(|(i, items)| {
+                Some((
+                    grid.slots.start + i / width * grid.width + i % width,
+                    items.as_ref()?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let mut placed = 0;
+        loop {
+            let saved = (self.slots.to_vec(), self.inventory.slots.clone());
+            if !cells
+                .iter()
+                .all(|&(cell, items)| self.place_ingredient(cell, items))
+            {
+                self.slots.clone_from_slice(&saved.0);
+                self.inventory.slots = saved.1;
+                break;
+            }
+            placed += 1;
+            if !all {
+                break;
+            }
+        }
+        self.update_result();
+        placed > 0
+    }
+
+    /// Moves one of `items` from the player's inventory into the grid slot `cell`.
+    fn place_ingredient(&mut self, cell: usize, items: &[ItemID]) -> bool {
+        let existing = self.slot(cell).cloned().flatten();
+        let source = (self.player_start()..self.player_end()).find(|&index| {
+            matches!(self.slot(index), Some(Some(item))
+            if item.item_id.is_some_and(|id| items.contains(&id))
+                && existing.as_ref().is_none_or(|existing| {
+                    existing.stacks_with(item) && existing.count.0 < existing.max_stack_size()
+                }))
+        });
+        let Some(item) = source.and_then(|source| {
+            let item = self.take(source)?;
+            self.put(source, Some(item.with_count(item.count.0 - 1)));
+            Some(item)
+        }) else {
+            return false;
+        };
+        let count = existing.map_or(0, |existing| existing.count.0);
+        self.put(cell, Some(item.with_count(count + 1)));
+        true
+    }
+}
+
+/// The slot of the player's inventory an item is worn in, if it isn't held.
+fn equipment_slot(item: &InventorySlot) -> Option<usize> {
+    let name = item.item_id?.to_name()?;
+    match name.strip_prefix("minecraft:").unwrap_or(&name) {
+        name if name.ends_with("_helmet") => Some(5),
+        "elytra" => Some(6),
+        name if name.ends_with("_chestplate") => Some(6),
+        name if name.ends_with("_leggings") => Some(7),
+        name if name.ends_with("_boots") => Some(8),
+        "shield" => Some(OFFHAND),
+        _ => None,
+    }
+}
+
+/// The slots of a window on `container`, with empty slots as empty stacks.
+pub fn contents(container: &[Option<InventorySlot>], inventory: &Inventory) -> Vec<InventorySlot> {
+    container
+        .iter()
+        .chain(
+            inventory
+                .slots
+                .iter()
+                .skip(PLAYER_SLOTS_START)
+                .take(PLAYER_SLOTS),
+        )
+        .map(|slot| slot.clone().unwrap_or_default())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::ItemID;
+
+    const CHEST: usize = 27;
+
+    fn stone(count: i32) -> InventorySlot {
+        InventorySlot::new(ItemID::new(1), count)
+    }
+
+    fn dirt(count: i32) -> InventorySlot {
+        InventorySlot::new(ItemID::from_name("dirt").unwrap(), count)
+    }
+
+    struct Setup {
+        container: Vec<Option<InventorySlot>>,
+        inventory: Inventory,
+        carried: Option<InventorySlot>,
+        drag: Drag,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            Self {
+                container: vec![None; CHEST],
+                inventory: Inventory::new(46),
+                carried: None,
+                drag: Drag::default(),
+            }
+        }
+
+        fn click(&mut self, slot: i16, button: i8, mode: ClickMode) -> Vec<InventorySlot> {
+            Window::new(
+                &mut self.container,
+                &mut self.inventory,
+                &mut self.carried,
+                &mut self.drag,
+            )
+            .click(slot, button, mode)
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_pickup() {
+        let mut setup = Setup::new();
+        setup.container[0] = Some(stone(11));
+        // Right-clicking takes half, rounding up
+        setup.click(0, 1, ClickMode::Pickup);
+        assert_eq!(setup.carried, Some(stone(6)));
+        assert_eq!(setup.container[0], Some(stone(5)));
+        // Right-clicking an empty slot puts one down
+        setup.click(1, 1, ClickMode::Pickup);
+        assert_eq!(setup.container[1], Some(stone(1)));
+        // Left-clicking a matching stack puts everything down
+        setup.click(0, 0, ClickMode::Pickup);
+        assert_eq!(setup.container[0], Some(stone(10)));
+        assert_eq!(setup.carried, None);
+        // Picking up and clicking a different item swaps them
+        setup.container[2] = Some(dirt(3));
+        setup.click(0, 0, ClickMode::Pickup);
+        setup.click(2, 0, ClickMode::Pickup);
+        assert_eq!(setup.container[2], Some(stone(10)));
+        assert_eq!(setup.carried, Some(dirt(3)));
+        // Clicking outside drops everything
+        let dropped = setup.click(OUTSIDE, 0, ClickMode::Pickup);
+        assert_eq!(dropped, vec![dirt(3)]);
+        assert_eq!(setup.carried, None);
+    }
+
+    #[test]
+    fn test_pickup_full_stack() {
+        let mut setup = Setup::new();
+        setup.container[0] = Some(stone(60));
+        setup.carried = Some(stone(10));
+        setup.click(0, 0, ClickMode::Pickup);
+        assert_eq!(setup.container[0], Some(stone(64)));
+        assert_eq!(setup.carried, Some(stone(6)));
+    }
+
+    #[test]
+    fn test_quick_move() {
+        let mut setup = Setup::new();
+        setup.container[0] = Some(stone(40));
+        setup.inventory.slots[10] = Some(stone(50));
+        // Tops up the existing stack, then fills the last hotbar slot
+        setup.click(0, 0, ClickMode::QuickMove);
+        assert_eq!(setup.container[0], None);
+        assert_eq!(setup.inventory.slots[10], Some(stone(64)));
+        assert_eq!(setup.inventory.slots[44], Some(stone(26)));
+        // And back into the first free container slot
+        setup.click((CHEST + 35) as i16, 0, ClickMode::QuickMove);
+        assert_eq!(setup.container[0], Some(stone(26)));
+        assert_eq!(setup.inventory.slots[44], None);
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut setup = Setup::new();
+        setup.container[4] = Some(stone(5));
+        setup.inventory.slots[38] = Some(dirt(2));
+        setup.click(4, 2, ClickMode::Swap);
+        assert_eq!(setup.container[4], Some(dirt(2)));
+        assert_eq!(setup.inventory.slots[38], Some(stone(5)));
+        setup.click(4, OFFHAND_BUTTON, ClickMode::Swap);
+        assert_eq!(setup.container[4], None);
+        assert_eq!(setup.inventory.slots[OFFHAND], Some(dirt(2)));
+    }
+
+    #[test]
+    fn test_drag() {
+        let mut setup = Setup::new();
+        setup.carried = Some(stone(10));
+        setup.container[2] = Some(stone(1));
+        setup.click(OUTSIDE, 0, ClickMode::Drag);
+        for slot in [0, 1, 2] {
+            setup.click(slot, 1, ClickMode::Drag);
+        }
+        setup.click(OUTSIDE, 2, ClickMode::Drag);
+        assert_eq!(setup.container[0], Some(stone(3)));
+        assert_eq!(setup.container[1], Some(stone(3)));
+        assert_eq!(setup.container[2], Some(stone(4)));
+        assert_eq!(setup.carried, Some(stone(1)));
+
+        // One per slot with the right button
+        setup.click(OUTSIDE, 4, ClickMode::Drag);
+        setup.click(5, 5, ClickMode::Drag);
+        setup.click(6, 5, ClickMode::Drag);
+        setup.click(OUTSIDE, 6, ClickMode::Drag);
+        // There was only one item, so only the first slot got added
+        assert_eq!(setup.container[5], Some(stone(1)));
+        assert_eq!(setup.container[6], None);
+        assert_eq!(setup.carried, None);
+    }
+
+    #[test]
+    fn test_pickup_all() {
+        let mut setup = Setup::new();
+        setup.container[0] = Some(stone(64));
+        setup.container[1] = Some(stone(20));
+        setup.inventory.slots[20] = Some(stone(30));
+        setup.container[2] = Some(dirt(5));
+        setup.carried = Some(stone(10));
+        setup.click(3, 0, ClickMode::PickupAll);
+        // Partial stacks go first, then the full one
+        assert_eq!(setup.carried, Some(stone(64)));
+        assert_eq!(setup.container[1], None);
+        assert_eq!(setup.inventory.slots[20], None);
+        assert_eq!(setup.container[0], Some(stone(60)));
+        assert_eq!(setup.container[2], Some(dirt(5)));
+    }
+
+    #[test]
+    fn test_throw() {
+        let mut setup = Setup::new();
+        setup.container[0] = Some(stone(5));
+        assert_eq!(setup.click(0, 0, ClickMode::Throw), vec![stone(1)]);
+        assert_eq!(setup.click(0, 1, ClickMode::Throw), vec![stone(4)]);
+        assert_eq!(setup.container[0], None);
+    }
+
+    #[test]
+    fn test_return_carried() {
+        let mut setup = Setup::new();
+        setup.inventory.slots[20] = Some(stone(60));
+        for slot in 36..45 {
+            setup.inventory.slots[slot] = Some(dirt(1));
+        }
+        setup.carried = Some(stone(10));
+        let rest = Window::new(
+            &mut setup.container,
+            &mut setup.inventory,
+            &mut setup.carried,
+            &mut setup.drag,
+        )
+        .return_carried();
+        assert_eq!(rest, None);
+        assert_eq!(setup.carried, None);
+        assert_eq!(setup.inventory.slots[20], Some(stone(64)));
+        assert_eq!(setup.inventory.slots[9], Some(stone(6)));
+    }
+
+    #[test]
+    fn test_invalid_slot() {
+        let mut setup = Setup::new();
+        let mut window = Window::new(
+            &mut setup.container,
+            &mut setup.inventory,
+            &mut setup.carried,
+            &mut setup.drag,
+        );
+        assert!(matches!(
+            window.click(63, 0, ClickMode::Pickup),
+            Err(InventoryError::InvalidWindowSlot(63))
+        ));
+    }
+
+    /// Any one stone makes four dirt.
+    struct StoneToDirt;
+
+    impl Recipes for StoneToDirt {
+        fn craft(&self, grid: &[Option<InventorySlot>], _width: usize) -> Option<InventorySlot> {
+            let mut items = grid.iter().flatten();
+            match (items.next(), items.next()) {
+                (Some(item), None) if item.item_id == stone(1).item_id => Some(dirt(4)),
+                _ => None,
+            }
+        }
+    }
+
+    impl Setup {
+        fn click_inventory(
+            &mut self,
+            slot: i16,
+            button: i8,
+            mode: ClickMode,
+        ) -> Vec<InventorySlot> {
+            Window::inventory(
+                &mut self.inventory,
+                &mut self.carried,
+                &mut self.drag,
+                &StoneToDirt,
+            )
+            .click(slot, button, mode)
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_crafting_pickup() {
+        let mut setup = Setup::new();
+        setup.carried = Some(stone(3));
+        setup.click_inventory(2, 0, ClickMode::Pickup);
+        assert_eq!(setup.inventory.slots[0], Some(dirt(4)));
+        // Taking the result uses up one stone and leaves the next result
+        setup.click_inventory(0, 0, ClickMode::Pickup);
+        assert_eq!(setup.carried, Some(dirt(4)));
+        assert_eq!(setup.inventory.slots[2], Some(stone(2)));
+        assert_eq!(setup.inventory.slots[0], Some(dirt(4)));
+        // Unless the carried items can't take all of it
+        setup.carried = Some(dirt(62));
+        setup.click_inventory(0, 0, ClickMode::Pickup);
+        assert_eq!(setup.carried, Some(dirt(62)));
+        assert_eq!(setup.inventory.slots[2], Some(stone(2)));
+        // Nothing can be put in the result slot
+        setup.click_inventory(0, 1, ClickMode::Drag);
+        assert_eq!(setup.inventory.slots[0], Some(dirt(4)));
+    }
+
+    #[test]
+    fn test_crafting_quick_move() {
+        let mut setup = Setup::new();
+        setup.inventory.slots[3] = Some(stone(3));
+        setup.inventory.slots[44] = Some(dirt(60));
+        setup.click_inventory(3, 0, ClickMode::Pickup);
+        setup.click_inventory(1, 0, ClickMode::Pickup);
+        setup.click_inventory(0, 0, ClickMode::QuickMove);
+        assert_eq!(setup.inventory.slots[1], None);
+        assert_eq!(setup.inventory.slots[0], None);
+        assert_eq!(setup.inventory.slots[44], Some(dirt(64)));
+        assert_eq!(setup.inventory.slots[43], Some(dirt(8)));
+    }
+
+    #[test]
+    fn test_inventory_quick_move() {
+        let mut setup = Setup::new();
+        let helmet = InventorySlot::new(ItemID::from_name("iron_helmet").unwrap(), 1);
+        setup.inventory.slots[20] = Some(helmet.clone());
+        setup.inventory.slots[21] = Some(stone(5));
+        setup.click_inventory(20, 0, ClickMode::QuickMove);
+        assert_eq!(setup.inventory.slots[5], Some(helmet));
+        // The main inventory goes to the hotbar and back
+        setup.click_inventory(21, 0, ClickMode::QuickMove);
+        assert_eq!(setup.inventory.slots[36], Some(stone(5)));
+        setup.click_inventory(36, 0, ClickMode::QuickMove);
+        assert_eq!(setup.inventory.slots[9], Some(stone(5)));
+    }
+
+    #[test]
+    fn test_crafting_table() {
+        let mut setup = Setup::new();
+        let mut grid = vec![None; CRAFTING_SLOTS];
+        setup.inventory.slots[20] = Some(stone(5));
+        setup.inventory.slots[21] = Some(dirt(1));
+        let mut window = Window::crafting(
+            &mut grid,
+            &mut setup.inventory,
+            &mut setup.carried,
+            &mut setup.drag,
+            &StoneToDirt,
+        );
+        let stone_only = vec![Some(vec![stone(1).item_id.unwrap()])];
+        assert!(window.place_recipe(&stone_only, 1, false));
+        // Placing again puts the grid back first
+        assert!(window.place_recipe(&stone_only, 1, true));
+        // There's only one dirt for two slots
+        let two_dirt = vec![Some(vec![dirt(1).item_id.unwrap()]); 2];
+        assert!(!window.place_recipe(&two_dirt, 2, false));
+        assert_eq!(window.contents()[CRAFTING_SLOTS + 12], dirt(1));
+        assert!(window.place_recipe(&stone_only, 1, true));
+        assert_eq!(window.contents()[0], dirt(4));
+        assert!(window.close().is_empty());
+        assert_eq!(grid, vec![None; CRAFTING_SLOTS]);
+        // Back in the hotbar, where returned items go first
+        assert_eq!(setup.inventory.slots[36], Some(stone(5)));
+    }
+}
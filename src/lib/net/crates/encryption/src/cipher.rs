@@ -0,0 +1,106 @@
+use crate::errors::NetEncryptionError;
+use aes::Aes128;
+use cfb8::cipher::inout::InOutBuf;
+use cfb8::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+
+/// Length of the shared secret negotiated during login, in bytes.
+pub const SHARED_SECRET_LENGTH: usize = 16;
+
+/// Encrypts outgoing bytes with AES-128 in CFB8 mode.
+///
+/// The cipher is a stream cipher, so the state carries over between calls; bytes must be
+/// encrypted in the exact order they are written to the socket.
+pub struct PacketEncryptor {
+    cipher: cfb8::Encryptor<Aes128>,
+}
+
+/// Decrypts incoming bytes with AES-128 in CFB8 mode.
+///
+/// Like [`PacketEncryptor`], this is stateful and must see every byte read from the socket in order.
+pub struct PacketDecryptor {
+    cipher: cfb8::Decryptor<Aes128>,
+}
+
+impl PacketEncryptor {
+    /// Creates a new encryptor. Minecraft uses the shared secret as both the key and the IV.
+    pub fn new(shared_secret: &[u8]) -> Result<Self, NetEncryptionError> {
+        let cipher = cfb8::Encryptor::<Aes128>::new_from_slices(shared_secret, shared_secret)
+            .map_err(|_| NetEncryptionError::InvalidSecretLength(shared_secret.len()))?;
+        Ok(Self { cipher })
+    }
+
+    /// Encrypts the given bytes in place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.cipher.encrypt_blocks_inout_mut(blocks);
+    }
+}
+
+impl PacketDecryptor {
+    /// Creates a new decryptor. Minecraft uses the shared secret as both the key and the IV.
+    pub fn new(shared_secret: &[u8]) -> Result<Self, NetEncryptionError> {
+        let cipher = cfb8::Decryptor::<Aes128>::new_from_slices(shared_secret, shared_secret)
+            .map_err(|_| NetEncryptionError::InvalidSecretLength(shared_secret.len()))?;
+        Ok(Self { cipher })
+    }
+
+    /// Decrypts the given bytes in place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.cipher.decrypt_blocks_inout_mut(blocks);
+    }
+}
+
+/// Creates a matching encryptor/decryptor pair from a shared secret.
+pub fn create_ciphers(
+    shared_secret: &[u8],
+) -> Result<(PacketEncryptor, PacketDecryptor), NetEncryptionError> {
+    if shared_secret.len() != SHARED_SECRET_LENGTH {
+        return Err(NetEncryptionError::InvalidSecretLength(shared_secret.len()));
+    }
+    Ok((
+        PacketEncryptor::new(shared_secret)?,
+        PacketDecryptor::new(shared_secret)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: [u8; 16] = *b"0123456789abcdef";
+
+    #[test]
+    fn test_roundtrip() {
+        let (mut encryptor, mut decryptor) = create_ciphers(&SECRET).unwrap();
+        let original = b"Hello, FerrumC! This is longer than a single AES block.".to_vec();
+        let mut data = original.clone();
+        encryptor.encrypt(&mut data);
+        assert_ne!(data, original);
+        decryptor.decrypt(&mut data);
+        assert_eq!(data, original);
+    }
+
+    #[test]
+    fn test_stream_state_carries_over() {
+        let original = (0..=255u8).collect::<Vec<_>>();
+
+        let mut whole = original.clone();
+        PacketEncryptor::new(&SECRET).unwrap().encrypt(&mut whole);
+
+        let mut pieces = original.clone();
+        let mut encryptor = PacketEncryptor::new(&SECRET).unwrap();
+        for chunk in pieces.chunks_mut(7) {
+            encryptor.encrypt(chunk);
+        }
+        assert_eq!(whole, pieces);
+    }
+
+    #[test]
+    fn test_invalid_secret_length() {
+        assert!(matches!(
+            create_ciphers(&[0; 8]),
+            Err(NetEncryptionError::InvalidSecretLength(8))
+        ));
+    }
+}
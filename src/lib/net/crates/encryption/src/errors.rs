@@ -2,6 +2,14 @@ use thiserror::Error;
 
 #[derive(Debug, Clone, Error)]
 pub enum NetEncryptionError {
-    #[error("Something failed lol")]
-    SomeError,
+    #[error("Failed to generate the server RSA key pair: {0}")]
+    KeyGeneration(String),
+    #[error("Failed to encode the server public key: {0}")]
+    PublicKeyEncoding(String),
+    #[error("Failed to decrypt data sent by the client: {0}")]
+    Decryption(String),
+    #[error("Invalid shared secret length: expected 16 bytes, got {0}")]
+    InvalidSecretLength(usize),
+    #[error("The verify token sent by the client does not match")]
+    VerifyTokenMismatch,
 }
@@ -0,0 +1,37 @@
+use num_bigint::BigInt;
+use sha1::{Digest, Sha1};
+
+/// Computes the server id hash used by the Mojang session server.
+///
+/// This is a SHA-1 digest of the server id, the shared secret and the DER encoded public key,
+/// formatted as a signed (two's complement) big-endian number in hexadecimal. That means the
+/// result can be negative and has no leading zeros, which is unlike every other hex digest.
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key_der: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(server_id.as_bytes());
+    hasher.update(shared_secret);
+    hasher.update(public_key_der);
+    BigInt::from_signed_bytes_be(&hasher.finalize()).to_str_radix(16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well known values from the protocol documentation.
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            server_hash("Notch", &[], &[]),
+            "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+        );
+        assert_eq!(
+            server_hash("jeb_", &[], &[]),
+            "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+        );
+        assert_eq!(
+            server_hash("simon", &[], &[]),
+            "88e16a1019277b15d58faf0541e11910eb756f6"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use crate::errors::NetEncryptionError;
+use rsa::pkcs8::EncodePublicKey;
+use rsa::rand_core::OsRng;
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey};
+use std::sync::OnceLock;
+
+/// The key size used by the vanilla server.
+const KEY_BITS: usize = 1024;
+
+static SERVER_KEYS: OnceLock<ServerKeyPair> = OnceLock::new();
+
+/// The RSA key pair the server uses to receive the shared secret from clients.
+pub struct ServerKeyPair {
+    private_key: RsaPrivateKey,
+    public_key_der: Vec<u8>,
+}
+
+impl ServerKeyPair {
+    /// Generates a fresh 1024-bit key pair.
+    pub fn generate() -> Result<Self, NetEncryptionError> {
+        let private_key = RsaPrivateKey::new(&mut OsRng, KEY_BITS)
+            .map_err(|e| NetEncryptionError::KeyGeneration(e.to_string()))?;
+        let public_key_der = private_key
+            .to_public_key()
+            .to_public_key_der()
+            .map_err(|e| NetEncryptionError::PublicKeyEncoding(e.to_string()))?
+            .into_vec();
+        Ok(Self {
+            private_key,
+            public_key_der,
+        })
+    }
+
+    /// The public key, encoded as ASN.1 DER (`SubjectPublicKeyInfo`), as sent in the encryption request.
+    pub fn public_key_der(&self) -> &[u8] {
+        &self.public_key_der
+    }
+
+    /// Decrypts data the client encrypted with our public key (PKCS#1 v1.5 padding).
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, NetEncryptionError> {
+        self.private_key
+            .decrypt(Pkcs1v15Encrypt, data)
+            .map_err(|e| NetEncryptionError::Decryption(e.to_string()))
+    }
+}
+
+/// Returns the server key pair, generating it on first use.
+///
+/// Vanilla generates a single key pair at startup and uses it for every login, so we do the same.
+pub fn get_server_keys() -> &'static ServerKeyPair {
+    SERVER_KEYS.get_or_init(|| ServerKeyPair::generate().expect("Failed to generate server keys"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::DecodePublicKey;
+    use rsa::RsaPublicKey;
+
+    #[test]
+    fn test_decrypt_client_payload() {
+        let keys = get_server_keys();
+        let public_key = RsaPublicKey::from_public_key_der(keys.public_key_der()).unwrap();
+        let secret = b"0123456789abcdef";
+        let encrypted = public_key
+            .encrypt(&mut OsRng, Pkcs1v15Encrypt, secret)
+            .unwrap();
+        assert_eq!(keys.decrypt(&encrypted).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_decrypt_garbage_fails() {
+        assert!(get_server_keys().decrypt(&[1, 2, 3]).is_err());
+    }
+}
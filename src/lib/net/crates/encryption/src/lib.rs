@@ -1,16 +1,13 @@
-pub mod errors;
-
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+//! # FerrumC network encryption
+//!
+//! Implements the pieces needed for online-mode logins:
+//! - [`keys`]: The server's RSA key pair used during the encryption request/response exchange.
+//! - [`cipher`]: The AES-128/CFB8 stream cipher applied to every byte after login.
+//! - [`reader`]: An [`tokio::io::AsyncRead`] wrapper that transparently decrypts incoming bytes.
+//! - [`hash`]: The (non-standard) server id hash sent to the Mojang session server.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod cipher;
+pub mod errors;
+pub mod hash;
+pub mod keys;
+pub mod reader;
@@ -0,0 +1,82 @@
+use crate::cipher::PacketDecryptor;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Wraps a reader and decrypts everything read from it once encryption has been enabled.
+///
+/// Before [`EncryptedReader::enable_encryption`] is called, bytes are passed through untouched,
+/// which lets the same reader be used for the whole lifetime of a connection.
+pub struct EncryptedReader<R> {
+    inner: R,
+    decryptor: Option<PacketDecryptor>,
+}
+
+impl<R> EncryptedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            decryptor: None,
+        }
+    }
+
+    /// Decrypts every byte read from now on.
+    pub fn enable_encryption(&mut self, decryptor: PacketDecryptor) {
+        self.decryptor = Some(decryptor);
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.decryptor.is_some()
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for EncryptedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let already_filled = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        if let Some(decryptor) = &mut this.decryptor {
+            decryptor.decrypt(&mut buf.filled_mut()[already_filled..]);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::create_ciphers;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_passthrough_then_decrypt() {
+        let (mut encryptor, decryptor) = create_ciphers(b"0123456789abcdef").unwrap();
+        let mut encrypted = b"secret".to_vec();
+        encryptor.encrypt(&mut encrypted);
+
+        let mut stream = b"plain".to_vec();
+        stream.extend_from_slice(&encrypted);
+
+        let mut reader = EncryptedReader::new(stream.as_slice());
+        let mut plain = [0u8; 5];
+        reader.read_exact(&mut plain).await.unwrap();
+        assert_eq!(&plain, b"plain");
+
+        reader.enable_encryption(decryptor);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"secret");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::conn_init::NetDecodeOpts;
+use crate::connection::StreamWriter;
+use crate::errors::{NetError, PacketError};
+use crate::packets::incoming::encryption_response::EncryptionResponsePacket;
+use crate::packets::incoming::packet_skeleton::PacketSkeleton;
+use crate::packets::outgoing::encryption_request::EncryptionRequestPacket;
+use crate::ConnState::Login;
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_macros::lookup_packet;
+use ferrumc_net_codec::decode::NetDecode;
+use ferrumc_net_encryption::cipher::create_ciphers;
+use ferrumc_net_encryption::errors::NetEncryptionError;
+use ferrumc_net_encryption::hash::server_hash;
+use ferrumc_net_encryption::keys::get_server_keys;
+use ferrumc_net_encryption::reader::EncryptedReader;
+use serde_derive::Deserialize;
+use tokio::net::tcp::OwnedReadHalf;
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+/// A player profile as returned by the session server's `hasJoined` endpoint.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GameProfile {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub properties: Vec<GameProfileProperty>,
+}
+
+/// A signed profile property, usually just the player's skin `textures`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GameProfileProperty {
+    pub name: String,
+    pub value: String,
+    pub signature: Option<String>,
+}
+
+/// Performs the encryption handshake and authenticates the player with the session server.
+///
+/// 1. Sends an encryption request containing the server public key and a random verify token.
+/// 2. Reads the encryption response, decrypts the shared secret and checks the verify token.
+/// 3. Enables AES/CFB8 on both halves of the connection.
+/// 4. Asks the session server whether `username` has joined using the computed server hash.
+///
+/// Every packet after this function returns is encrypted.
+pub(super) async fn authenticate(
+    conn_read: &mut EncryptedReader<OwnedReadHalf>,
+    conn_write: &StreamWriter,
+    username: &str,
+) -> Result<GameProfile, NetError> {
+    let keys = get_server_keys();
+    let verify_token: [u8; 4] = rand::random();
+
+    conn_write.send_packet(EncryptionRequestPacket::new(
+        keys.public_key_der(),
+        &verify_token,
+        true,
+    ))?;
+
+    let mut skel = PacketSkeleton::new(conn_read, false, Login).await?;
+    let expected_id = lookup_packet!("login", "serverbound", "key");
+    if skel.id != expected_id {
+        return Err(NetError::Packet(PacketError::UnexpectedPacket {
+            expected: expected_id,
+            received: skel.id,
+            state: Login,
+        }));
+    }
+
+    let response = EncryptionResponsePacket::decode(&mut skel.data, &NetDecodeOpts::None)?;
+
+    if keys.decrypt(&response.verify_token.data)? != verify_token {
+        return Err(NetEncryptionError::VerifyTokenMismatch.into());
+    }
+    let shared_secret = keys.decrypt(&response.shared_secret.data)?;
+
+    let (encryptor, decryptor) = create_ciphers(&shared_secret)?;
+    conn_write.enable_encryption(encryptor);
+    conn_read.enable_encryption(decryptor);
+    trace!("Encryption enabled for {}", username);
+
+    let hash = server_hash("", &shared_secret, keys.public_key_der());
+    let session_server = get_global_config().session_server.clone();
+    let profile = has_joined(session_server, username.to_string(), hash).await?;
+
+    match profile {
+        Some(profile) => {
+            debug!("Authenticated {} ({})", profile.name, profile.id);
+            Ok(profile)
+        }
+        None => Err(NetError::AuthenticationFailed(username.to_string())),
+    }
+}
+
+/// Queries `<session_server>/session/minecraft/hasJoined`.
+///
+/// Returns `None` if the session server doesn't know about this login (HTTP 204), which means
+/// the client didn't authenticate or is using a cracked account.
+async fn has_joined(
+    session_server: String,
+    username: String,
+    server_hash: String,
+) -> Result<Option<GameProfile>, NetError> {
+    let url = format!(
+        "{}/session/minecraft/hasJoined",
+        session_server.trim_end_matches('/')
+    );
+
+    // ureq is blocking, keep it off the network thread.
+    tokio::task::spawn_blocking(move || {
+        let mut response = ureq::get(&url)
+            .query("username", &username)
+            .query("serverId", &server_hash)
+            .call()
+            .map_err(|e| NetError::SessionServer(e.to_string()))?;
+
+        if response.status() != 200 {
+            return Ok(None);
+        }
+
+        response
+            .body_mut()
+            .read_json::<GameProfile>()
+            .map(Some)
+            .map_err(|e| NetError::SessionServer(e.to_string()))
+    })
+    .await
+    .map_err(|e| NetError::SessionServer(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// Serves a single request with the given status and body, and returns the request line.
+    fn mock_session_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request_line
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_has_joined() {
+        let (url, server) = mock_session_server(
+            "200 OK",
+            r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","properties":[{"name":"textures","value":"e30=","signature":"c2ln"}]}"#,
+        );
+        let profile = has_joined(url, "Notch".to_string(), "-1a2b".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            profile.id,
+            Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap()
+        );
+        assert_eq!(profile.name, "Notch");
+        assert_eq!(profile.properties[0].name, "textures");
+        assert_eq!(profile.properties[0].signature.as_deref(), Some("c2ln"));
+
+        let request_line = server.join().unwrap();
+        assert!(request_line.starts_with("GET /session/minecraft/hasJoined?"));
+        assert!(request_line.contains("username=Notch"));
+        assert!(request_line.contains("serverId=-1a2b"));
+    }
+
+    #[tokio::test]
+    async fn test_has_joined_not_authenticated() {
+        let (url, server) = mock_session_server("204 No Content", "");
+        let profile = has_joined(url, "Notch".to_string(), "-1a2b".to_string())
+            .await
+            .unwrap();
+        assert!(profile.is_none());
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_has_joined_malformed_response() {
+        let (url, server) = mock_session_server("200 OK", r#"{"id":"#);
+        let result = has_joined(url, "Notch".to_string(), "-1a2b".to_string()).await;
+        assert!(matches!(result, Err(NetError::SessionServer(_))));
+        server.join().unwrap();
+    }
+}
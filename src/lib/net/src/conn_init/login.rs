@@ -1,26 +1,32 @@
 use crate::compression::compress_packet;
+use crate::conn_init::authentication::authenticate;
 use crate::conn_init::VarInt;
 use crate::conn_init::{LoginResult, NetDecodeOpts};
 use crate::connection::StreamWriter;
 use crate::errors::{NetError, PacketError};
 use crate::packets::incoming::packet_skeleton::PacketSkeleton;
+use crate::packets::outgoing::login_disconnect::LoginDisconnectPacket;
+use crate::packets::outgoing::login_success::LoginSuccessProperties;
 use crate::packets::outgoing::{commands::CommandsPacket, registry_data::REGISTRY_PACKETS};
 use crate::ConnState::*;
 use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_macros::lookup_packet;
 use ferrumc_net_codec::decode::NetDecode;
 use ferrumc_net_codec::encode::NetEncodeOpts;
 use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_encryption::reader::EncryptedReader;
 use ferrumc_state::GlobalState;
 use tokio::net::tcp::OwnedReadHalf;
 use tracing::{error, trace};
-use uuid::Uuid;
 
 /// Handles the **login sequence** for a newly connecting client.
 ///
 /// This function follows the Minecraft login/configuration handshake:
-/// 1. Reads the initial login packet and authenticates the username/UUID.
+/// 1. Reads the initial login packet and, in online mode, enables encryption and
+///    authenticates the username/UUID with the session server.
 /// 2. Optionally enables network compression.
 /// 3. Sends required handshake completion packets:
 ///    - Login success
@@ -36,7 +42,7 @@ use uuid::Uuid;
 /// # Errors
 /// Returns `NetError` for protocol violations, unexpected packets, or I/O errors.
 pub(super) async fn login(
-    conn_read: &mut OwnedReadHalf,
+    conn_read: &mut EncryptedReader<OwnedReadHalf>,
     conn_write: &StreamWriter,
     state: GlobalState,
 ) -> Result<(bool, LoginResult), NetError> {
@@ -61,6 +67,24 @@ pub(super) async fn login(
         &NetDecodeOpts::None,
     )?;
 
+    // =============================================================================================
+    // 1.5 Online mode: encrypt the connection and verify the player with the session server
+    let (uuid, username, profile_properties) = if get_global_config().online_mode {
+        let profile = match authenticate(conn_read, conn_write, &login_start.username).await {
+            Ok(profile) => profile,
+            Err(err) => {
+                let disconnect = LoginDisconnectPacket::new("Failed to verify username!");
+                if let Err(send_err) = conn_write.send_packet(disconnect) {
+                    error!("Failed to send login disconnect packet {:?}", send_err);
+                }
+                return Err(err);
+            }
+        };
+        (profile.id.as_u128(), profile.name, profile.properties)
+    } else {
+        (login_start.uuid, login_start.username, Vec::new())
+    };
+
     // =============================================================================================
     // 2 Negotiate compression if configured
     if get_global_config().network_compression_threshold > 0 {
@@ -79,19 +103,24 @@ pub(super) async fn login(
     // =============================================================================================
     // 3 Send Login Success (UUID and username acknowledgement)
     let login_success = crate::packets::outgoing::login_success::LoginSuccessPacket {
-        uuid: login_start.uuid,
-        username: &login_start.username,
-        properties: LengthPrefixedVec::default(),
+        uuid,
+        username: &username,
+        properties: LengthPrefixedVec::new(
+            profile_properties
+                .iter()
+                .map(|property| LoginSuccessProperties {
+                    name: &property.name,
+                    value: &property.value,
+                    signature: PrefixedOptional::new(property.signature.as_deref()),
+                })
+                .collect(),
+        ),
     };
 
     conn_write.send_packet(login_success)?;
 
-    // Build PlayerIdentity for server-side tracking
-    let player_identity = PlayerIdentity {
-        uuid: Uuid::from_u128(login_start.uuid),
-        username: login_start.username.clone(),
-        short_uuid: login_start.uuid as i32,
-    };
+    // Build PlayerIdentity for server-side tracking, with an entity ID no other entity has
+    let player_identity = PlayerIdentity::new(username.clone(), uuid);
 
     // =============================================================================================
     // 4 Wait for client Login Acknowledged packet
@@ -194,18 +223,49 @@ pub(super) async fn login(
 
     // =============================================================================================
     // 11 Send login_play packet to switch to Play state
-    let login_play =
-        crate::packets::outgoing::login_play::LoginPlayPacket::new(player_identity.short_uuid);
+    // Returning players spawn where they left off, in the dimension they left from
+    let saved_data = match state.world.load_player_data(player_identity.uuid.as_u128()) {
+        Ok(data) => data,
+        Err(e) => {
+            error!(
+                "Failed to load player data for {}: {}",
+                player_identity.username, e
+            );
+            None
+        }
+    };
+    let dimension = saved_data
+        .as_ref()
+        .and_then(|data| Dimension::from_name(&data.dimension))
+        .unwrap_or_default();
+    let login_play = crate::packets::outgoing::login_play::LoginPlayPacket::new(
+        player_identity.entity_id,
+        dimension,
+        ferrumc_world::player_data::game_mode(saved_data.as_ref()),
+    );
     conn_write.send_packet(login_play)?;
 
     // =============================================================================================
     // 12 Send initial player position sync (requires teleport confirmation)
     let teleport_id_i32: i32 = (rand::random::<u32>() & 0x3FFF_FFFF) as i32;
-    let sync_player_pos =
-        crate::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket {
-            teleport_id: VarInt::new(teleport_id_i32),
-            ..Default::default()
-        };
+    let sync_player_pos = match &saved_data {
+        Some(data) => {
+            crate::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket::new(
+                data.position,
+                (0.0, 0.0, 0.0),
+                data.rotation.0,
+                data.rotation.1,
+                0,
+                VarInt::new(teleport_id_i32),
+            )
+        }
+        None => {
+            crate::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket {
+                teleport_id: VarInt::new(teleport_id_i32),
+                ..Default::default()
+            }
+        }
+    };
     conn_write.send_packet(sync_player_pos)?;
 
     // =============================================================================================
@@ -268,7 +328,17 @@ pub(super) async fn login(
 
     // =============================================================================================
     // 16 Send center chunk packet (player spawn location)
-    let center_chunk = crate::packets::outgoing::set_center_chunk::SetCenterChunk::new(0, 0);
+    let (center_x, center_z) = saved_data
+        .as_ref()
+        .map(|data| {
+            (
+                (data.position.0.floor() as i32) >> 4,
+                (data.position.2.floor() as i32) >> 4,
+            )
+        })
+        .unwrap_or((0, 0));
+    let center_chunk =
+        crate::packets::outgoing::set_center_chunk::SetCenterChunk::new(center_x, center_z);
     conn_write.send_packet(center_chunk)?;
 
     // =============================================================================================
@@ -277,12 +347,22 @@ pub(super) async fn login(
 
     let mut batch = state.thread_pool.batch();
 
-    for x in -radius..=radius {
-        for z in -radius..=radius {
+    for x in center_x - radius..=center_x + radius {
+        for z in center_z - radius..=center_z + radius {
             batch.execute({
                 let state = state.clone();
                 move || -> Result<Vec<u8>, NetError> {
-                    let chunk = state.world.load_chunk(x, z, "overworld")?;
+                    // Only the spawn area is generated ahead of time
+                    let chunk = if state.world.chunk_exists(x, z, dimension.name())? {
+                        state.world.load_chunk(x, z, dimension.name())?
+                    } else {
+                        std::sync::Arc::new(
+                            state
+                                .terrain_generator
+                                .generate_chunk(x, z, dimension)
+                                .map_err(|e| NetError::Misc(e.to_string()))?,
+                        )
+                    };
                     let chunk_data =
                         crate::packets::outgoing::chunk_and_light_data::ChunkAndLightData::from_chunk(
                             &chunk,
@@ -312,12 +392,9 @@ pub(super) async fn login(
     }
 
     // =============================================================================================
-    conn_write.send_packet(CommandsPacket::new())?;
-
-    trace!(
-        "sending command graph {:#?}",
-        ferrumc_commands::infrastructure::get_graph()
-    );
+    let commands = CommandsPacket::for_player(player_identity.uuid.as_u128());
+    trace!("sending command graph {:#?}", commands.graph);
+    conn_write.send_packet(commands)?;
 
     // =============================================================================================
     // ✅ Login sequence complete
@@ -326,6 +403,7 @@ pub(super) async fn login(
         LoginResult {
             player_identity: Some(player_identity),
             compression: compressed,
+            player_data: saved_data,
         },
     ))
 }
@@ -1,3 +1,4 @@
+mod authentication;
 mod login;
 mod status;
 
@@ -11,8 +12,10 @@ use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_macros::lookup_packet;
 use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
 use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_net_encryption::reader::EncryptedReader;
 use ferrumc_state::GlobalState;
 use ferrumc_text::{ComponentBuilder, NamedColor, TextComponent};
+use ferrumc_world::player_data::PlayerData;
 use std::sync::atomic::Ordering;
 use tokio::net::tcp::OwnedReadHalf;
 use tracing::{error, trace};
@@ -21,15 +24,20 @@ use tracing::{error, trace};
 ///
 /// - `player_identity`: Populated when login is successful and a player is identified.
 /// - `compression`: Indicates whether network compression should be enabled for this connection.
+/// - `player_data`: The saved state of a returning player, loaded once during login.
 pub(crate) struct LoginResult {
     pub player_identity: Option<PlayerIdentity>,
     pub compression: bool,
+    pub player_data: Option<PlayerData>,
 }
 
 /// Protocol version supported by this server implementation (Minecraft 1.21.8).
 /// Used for rejecting clients with mismatched versions during handshake.
 pub const PROTOCOL_VERSION_1_21_8: i32 = 772;
 
+/// Name of the Minecraft version matching [`PROTOCOL_VERSION_1_21_8`], as shown to clients.
+pub const MINECRAFT_VERSION: &str = "1.21.8";
+
 /// Handles the initial handshake sequence from a connecting client.
 ///
 /// This function performs:
@@ -57,7 +65,7 @@ pub const PROTOCOL_VERSION_1_21_8: i32 = 772;
 /// - Protocol version mismatches and cannot be gracefully handled.
 /// - An invalid or unsupported handshake state is encountered.
 pub async fn handle_handshake(
-    mut conn_read: &mut OwnedReadHalf,
+    mut conn_read: &mut EncryptedReader<OwnedReadHalf>,
     conn_write: &StreamWriter,
     state: GlobalState,
 ) -> Result<(bool, LoginResult), NetError> {
@@ -125,7 +133,7 @@ pub async fn handle_handshake(
 /// Always returns `Err(NetError::MismatchedProtocolVersion)` to signal the mismatch.
 async fn handle_version_mismatch(
     hs_packet: Handshake,
-    conn_read: &mut OwnedReadHalf,
+    conn_read: &mut EncryptedReader<OwnedReadHalf>,
     conn_write: &StreamWriter,
     state: GlobalState,
 ) -> Result<(bool, LoginResult), NetError> {
@@ -10,6 +10,7 @@ use ferrumc_config::favicon::get_favicon_base64;
 use ferrumc_config::server_config::get_global_config;
 use ferrumc_macros::lookup_packet;
 use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
+use ferrumc_net_encryption::reader::EncryptedReader;
 use ferrumc_state::GlobalState;
 use rand::prelude::IndexedRandom;
 use tokio::net::tcp::OwnedReadHalf;
@@ -34,7 +35,7 @@ use tokio::net::tcp::OwnedReadHalf;
 /// - `true`: Indicates that the connection should be closed after responding.
 /// - `LoginResult`: Contains no player identity or compression because this is a stateless query.
 pub(super) async fn status(
-    mut conn_read: &mut OwnedReadHalf,
+    mut conn_read: &mut EncryptedReader<OwnedReadHalf>,
     conn_write: &StreamWriter,
     state: GlobalState,
 ) -> Result<(bool, LoginResult), NetError> {
@@ -97,6 +98,7 @@ pub(super) async fn status(
         LoginResult {
             player_identity: None,
             compression: false,
+            player_data: None,
         },
     ))
 }
@@ -158,7 +160,7 @@ fn get_server_status(state: &GlobalState) -> String {
 
     // Protocol info
     let version = structs::Version {
-        name: "1.21.8",
+        name: crate::conn_init::MINECRAFT_VERSION,
         protocol: crate::conn_init::PROTOCOL_VERSION_1_21_8 as u16,
     };
 
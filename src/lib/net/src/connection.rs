@@ -12,7 +12,10 @@ use crossbeam_channel::Sender;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_net_codec::encode::NetEncode;
 use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_encryption::cipher::PacketEncryptor;
+use ferrumc_net_encryption::reader::EncryptedReader;
 use ferrumc_state::ServerState;
+use ferrumc_world::player_data::PlayerData;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -35,6 +38,7 @@ const MAX_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 /// - Buffers outgoing packets via a Tokio `mpsc` channel.
 /// - Runs a background task that writes packets to the underlying socket.
 /// - Supports toggling compression dynamically.
+/// - Encrypts outgoing bytes once encryption has been enabled during login.
 /// - Gracefully handles disconnection when dropped.
 #[derive(TypeName, Component)]
 pub struct StreamWriter {
@@ -43,6 +47,7 @@ pub struct StreamWriter {
     pub compress: Arc<AtomicBool>,
     pub state: Arc<ServerState>,
     pub entity: Arc<Mutex<Option<Entity>>>,
+    encryptor: Mutex<Option<PacketEncryptor>>,
 }
 
 impl Drop for StreamWriter {
@@ -104,9 +109,31 @@ impl StreamWriter {
             compress,
             state,
             entity,
+            encryptor: Mutex::new(None),
         }
     }
 
+    /// Encrypts every byte sent from now on.
+    ///
+    /// Anything already queued has been queued in plain text, which is what the client expects,
+    /// since it only enables decryption after sending the encryption response.
+    pub fn enable_encryption(&self, encryptor: PacketEncryptor) {
+        *self.encryptor.lock().unwrap() = Some(encryptor);
+    }
+
+    /// Queues bytes for the writer task, encrypting them first if needed.
+    ///
+    /// The lock is held until the bytes are in the channel so the cipher stream stays in the same
+    /// order as the bytes on the wire.
+    fn queue_bytes(&self, mut bytes: Vec<u8>) -> Result<(), NetError> {
+        let mut encryptor = self.encryptor.lock().unwrap();
+        if let Some(encryptor) = encryptor.as_mut() {
+            encryptor.encrypt(&mut bytes);
+        }
+        self.sender.send(bytes).map_err(std::io::Error::other)?;
+        Ok(())
+    }
+
     /// Sets the entity ID for this stream writer.
     /// This should be called after the entity is created in the ECS.
     pub fn set_entity(&self, entity: Entity) {
@@ -150,8 +177,7 @@ impl StreamWriter {
             )))
         })?;
 
-        self.sender.send(raw_bytes).map_err(std::io::Error::other)?;
-        Ok(())
+        self.queue_bytes(raw_bytes)
     }
 
     /// Sends pre-encoded raw bytes to the client without additional processing.
@@ -162,8 +188,7 @@ impl StreamWriter {
             return Err(NetError::ConnectionDropped);
         }
 
-        self.sender.send(raw_bytes).map_err(std::io::Error::other)?;
-        Ok(())
+        self.queue_bytes(raw_bytes)
     }
 }
 
@@ -172,6 +197,8 @@ impl StreamWriter {
 pub struct NewConnection {
     pub stream: StreamWriter,
     pub player_identity: PlayerIdentity,
+    /// The saved state of a returning player, `None` for new players.
+    pub player_data: Option<PlayerData>,
     pub entity_return: oneshot::Sender<Entity>,
     pub disconnect_handle: oneshot::Sender<()>,
 }
@@ -203,7 +230,8 @@ pub async fn handle_connection(
     packet_sender: Arc<PacketSender>,
     new_join_sender: Arc<Sender<NewConnection>>,
 ) -> Result<(), NetError> {
-    let (mut tcp_reader, tcp_writer) = tcp_stream.into_split();
+    let (tcp_reader, tcp_writer) = tcp_stream.into_split();
+    let mut tcp_reader = EncryptedReader::new(tcp_reader);
 
     let running = Arc::new(AtomicBool::new(true));
 
@@ -279,6 +307,7 @@ pub async fn handle_connection(
         .send(NewConnection {
             stream,
             player_identity: login_result.player_identity.unwrap_or_default(),
+            player_data: login_result.player_data,
             entity_return,
             disconnect_handle: disconnect_return,
         })
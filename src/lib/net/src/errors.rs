@@ -55,6 +55,15 @@ pub enum NetError {
     #[error("Compression error: {0}")]
     CompressionError(#[from] CompressionError),
 
+    #[error("Failed to authenticate {0} with the session server")]
+    AuthenticationFailed(String),
+
+    #[error("Session server error: {0}")]
+    SessionServer(String),
+
+    #[error("Invalid RCON packet: {0}")]
+    InvalidRconPacket(String),
+
     #[error("Misc error: {0}")]
     Misc(String),
 }
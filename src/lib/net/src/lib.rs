@@ -10,6 +10,8 @@ mod conn_init;
 pub mod connection;
 pub mod errors;
 pub mod packets;
+pub mod query;
+pub mod rcon;
 pub mod server;
 
 setup_packet_handling!("\\src\\packets\\incoming");
@@ -0,0 +1,38 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetDecode, Debug)]
+#[packet(packet_id = "container_click", state = "play")]
+pub struct ClickContainer {
+    pub window_id: VarInt,
+    pub state_id: VarInt,
+    pub slot: i16,
+    pub button: i8,
+    pub mode: VarInt,
+    /// The slots the client thinks the click changed, and what it thinks is in them now.
+    pub changed_slots: LengthPrefixedVec<ChangedSlot>,
+    pub carried_item: PrefixedOptional<HashedItem>,
+}
+
+#[derive(NetDecode, Debug)]
+pub struct ChangedSlot {
+    pub slot: i16,
+    pub item: PrefixedOptional<HashedItem>,
+}
+
+/// An item as the client sends it back, with hashes of its components instead of their data.
+#[derive(NetDecode, Debug)]
+pub struct HashedItem {
+    pub item_id: VarInt,
+    pub count: VarInt,
+    pub components_to_add: LengthPrefixedVec<HashedComponent>,
+    pub components_to_remove: LengthPrefixedVec<VarInt>,
+}
+
+#[derive(NetDecode, Debug)]
+pub struct HashedComponent {
+    pub component_type: VarInt,
+    pub hash: i32,
+}
@@ -0,0 +1,17 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetDecode)]
+#[packet(packet_id = "client_command", state = "play")]
+pub struct ClientCommandPacket {
+    pub action: ClientCommandAction,
+}
+
+#[derive(Debug, NetDecode)]
+#[net(type_cast = "VarInt", type_cast_handler = "value.0 as u8")]
+#[repr(u8)]
+pub enum ClientCommandAction {
+    /// Sent from the death screen, and once after login when immediate respawn is on.
+    PerformRespawn = 0,
+    RequestStats = 1,
+}
@@ -0,0 +1,8 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetDecode, Debug)]
+#[packet(packet_id = "container_close", state = "play")]
+pub struct CloseContainer {
+    pub window_id: VarInt,
+}
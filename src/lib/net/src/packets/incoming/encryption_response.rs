@@ -0,0 +1,11 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+
+/// Sent by the client in reply to the encryption request.
+/// Both fields are encrypted with the server's public key.
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = "key", state = "login")]
+pub struct EncryptionResponsePacket {
+    pub shared_secret: LengthPrefixedVec<u8>,
+    pub verify_token: LengthPrefixedVec<u8>,
+}
@@ -0,0 +1,103 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::decode::errors::NetDecodeError;
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Read;
+use tokio::io::AsyncRead;
+
+/// Sent when a player right clicks or attacks an entity.
+#[derive(NetDecode)]
+#[packet(packet_id = "interact", state = "play")]
+pub struct InteractPacket {
+    pub entity_id: VarInt,
+    pub action: InteractAction,
+    pub sneaking: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteractAction {
+    Interact {
+        hand: VarInt,
+    },
+    Attack,
+    /// Interacting with a specific spot on the entity, relative to its position.
+    InteractAt {
+        x: f32,
+        y: f32,
+        z: f32,
+        hand: VarInt,
+    },
+}
+
+impl NetDecode for InteractAction {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> Result<Self, NetDecodeError> {
+        match VarInt::decode(reader, opts)?.0 {
+            0 => Ok(Self::Interact {
+                hand: VarInt::decode(reader, opts)?,
+            }),
+            1 => Ok(Self::Attack),
+            2 => Ok(Self::InteractAt {
+                x: f32::decode(reader, opts)?,
+                y: f32::decode(reader, opts)?,
+                z: f32::decode(reader, opts)?,
+                hand: VarInt::decode(reader, opts)?,
+            }),
+            _ => Err(NetDecodeError::InvalidEnumVariant),
+        }
+    }
+
+    async fn decode_async<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        opts: &NetDecodeOpts,
+    ) -> Result<Self, NetDecodeError> {
+        match VarInt::decode_async(reader, opts).await?.0 {
+            0 => Ok(Self::Interact {
+                hand: VarInt::decode_async(reader, opts).await?,
+            }),
+            1 => Ok(Self::Attack),
+            2 => Ok(Self::InteractAt {
+                x: f32::decode_async(reader, opts).await?,
+                y: f32::decode_async(reader, opts).await?,
+                z: f32::decode_async(reader, opts).await?,
+                hand: VarInt::decode_async(reader, opts).await?,
+            }),
+            _ => Err(NetDecodeError::InvalidEnumVariant),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_decode_attack() {
+        // Entity 5, attack, not sneaking
+        let mut reader = Cursor::new(vec![5, 1, 0]);
+        let packet = InteractPacket::decode(&mut reader, &NetDecodeOpts::None).unwrap();
+        assert_eq!(packet.entity_id.0, 5);
+        assert_eq!(packet.action, InteractAction::Attack);
+        assert!(!packet.sneaking);
+    }
+
+    #[test]
+    fn test_decode_interact_at() {
+        let mut bytes = vec![7, 2];
+        for value in [0.5f32, 1.0, -0.5] {
+            bytes.extend(value.to_be_bytes());
+        }
+        bytes.extend([1, 1]);
+        let packet = InteractPacket::decode(&mut Cursor::new(bytes), &NetDecodeOpts::None).unwrap();
+        assert_eq!(
+            packet.action,
+            InteractAction::InteractAt {
+                x: 0.5,
+                y: 1.0,
+                z: -0.5,
+                hand: VarInt::new(1),
+            }
+        );
+        assert!(packet.sneaking);
+    }
+}
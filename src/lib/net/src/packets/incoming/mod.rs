@@ -1,5 +1,6 @@
 pub mod ack_finish_configuration;
 pub mod client_information;
+pub mod encryption_response;
 pub mod handshake;
 pub mod login_acknowledged;
 pub mod login_start;
@@ -23,6 +24,9 @@ pub mod command_suggestion_request;
 
 pub mod swing_arm;
 
+pub mod client_command;
+pub mod interact;
+
 pub mod chunk_batch_ack;
 
 pub mod player_action;
@@ -35,3 +39,7 @@ pub mod player_loaded;
 pub mod set_creative_mode_slot;
 
 pub mod set_held_item;
+
+pub mod click_container;
+pub mod close_container;
+pub mod place_recipe;
@@ -0,0 +1,12 @@
+use ferrumc_macros::{packet, NetDecode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+/// Clicking a recipe in the recipe book, to fill the crafting grid with it.
+#[derive(NetDecode, Debug)]
+#[packet(packet_id = "place_recipe", state = "play")]
+pub struct PlaceRecipe {
+    pub window_id: VarInt,
+    pub recipe_id: VarInt,
+    /// Shift-clicked, to fill the grid with as many as possible.
+    pub make_all: bool,
+}
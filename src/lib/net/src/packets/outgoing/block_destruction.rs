@@ -0,0 +1,29 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+/// Shows the crack animation of a block being broken by another player.
+#[derive(NetEncode)]
+#[packet(packet_id = "block_destruction", state = "play")]
+pub struct BlockDestructionPacket {
+    /// The entity breaking the block, each entity can only break one block at a time.
+    pub entity_id: VarInt,
+    pub location: NetworkPosition,
+    /// 0 to 9, any other value removes the animation.
+    pub stage: i8,
+}
+
+impl BlockDestructionPacket {
+    pub fn new(entity_id: i32, location: NetworkPosition, stage: i8) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            location,
+            stage,
+        }
+    }
+
+    /// Removes the animation, when the block is broken or digging is cancelled.
+    pub fn clear(entity_id: i32, location: NetworkPosition) -> Self {
+        Self::new(entity_id, location, -1)
+    }
+}
@@ -0,0 +1,24 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_world::block_entities::BlockEntity;
+
+#[derive(NetEncode)]
+#[packet(packet_id = "block_entity_data", state = "play")]
+pub struct BlockEntityData {
+    pub location: NetworkPosition,
+    pub entity_type: VarInt,
+    /// The block entity's NBT, in the network format.
+    pub nbt: Vec<u8>,
+}
+
+impl BlockEntityData {
+    /// The update for a block entity, or `None` if its type isn't in the registry.
+    pub fn new(location: NetworkPosition, block_entity: &BlockEntity) -> Option<Self> {
+        Some(Self {
+            location,
+            entity_type: block_entity.network_id()?,
+            nbt: block_entity.nbt.clone(),
+        })
+    }
+}
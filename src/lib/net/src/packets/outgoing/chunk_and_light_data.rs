@@ -1,28 +1,35 @@
 use crate::errors::NetError;
+use crate::packets::outgoing::registry_data::{biome_count, biome_id};
 use byteorder::{BigEndian, WriteBytesExt};
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::bitset::BitSet;
 use ferrumc_net_codec::net_types::byte_array::ByteArray;
 use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
 use ferrumc_net_codec::net_types::var_int::VarInt;
-use ferrumc_world::chunk_format::{Chunk, PaletteType};
-use std::io::Cursor;
+use ferrumc_world::chunk_format::{BiomeStates, Chunk, PaletteType};
+use ferrumc_world::heightmaps::HeightmapKind;
+use ferrumc_world::lighting::LIGHT_ARRAY_SIZE;
+use std::io::{Cursor, Write};
 use std::ops::Not;
 use tracing::warn;
 
 const SECTIONS: usize = 24; // Number of sections, adjust for your Y range (-64 to 319)
 
+/// The most bits a biome palette can be sent with. Past this the registry ids are sent directly.
+const MAX_INDIRECT_BIOME_BITS: u8 = 3;
+
 #[derive(NetEncode)]
 pub struct BlockEntity {
+    /// The x coordinate in the chunk in the high 4 bits, and the z coordinate in the low 4.
     pub xz: u8,
-    pub y: u16,
+    pub y: i16,
     pub entity_type: VarInt,
     pub nbt: Vec<u8>,
 }
 
 #[derive(NetEncode)]
 pub struct NetHeightmap {
-    // Define the structure of your heightmaps here
+    /// The heightmap type, see [`HeightmapKind::network_id`].
     pub id: VarInt,
     pub data: LengthPrefixedVec<i64>,
 }
@@ -36,6 +43,15 @@ pub struct ChunkAndLightData {
     pub heightmaps: LengthPrefixedVec<NetHeightmap>,
     pub data: ByteArray,
     pub block_entities: LengthPrefixedVec<BlockEntity>,
+    pub light: LightData,
+}
+
+/// The light of a chunk column, sent with the chunk and again whenever it changes.
+///
+/// Bit 0 of the masks is the section below the world and the last bit the section above it,
+/// which are never sent.
+#[derive(NetEncode)]
+pub struct LightData {
     pub sky_light_mask: BitSet,
     pub block_light_mask: BitSet,
     pub empty_sky_light_mask: BitSet,
@@ -44,13 +60,61 @@ pub struct ChunkAndLightData {
     pub block_light_arrays: LengthPrefixedVec<ByteArray>,
 }
 
+impl LightData {
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        let mut sky_light_mask = BitSet::new(SECTIONS + 2);
+        let mut block_light_mask = BitSet::new(SECTIONS + 2);
+        let mut sky_light_arrays = Vec::new();
+        let mut block_light_arrays = Vec::new();
+
+        for (i, section) in chunk.sections.iter().enumerate() {
+            // Sections without light data are sent as empty rather than guessed
+            if section.sky_light.len() == LIGHT_ARRAY_SIZE {
+                sky_light_mask.set(i + 1, true);
+                sky_light_arrays.push(ByteArray::new(section.sky_light.clone()));
+            } else if !section.sky_light.is_empty() {
+                warn!(
+                    "Sky light data for section at {}, {} is not 2048 bytes long",
+                    chunk.x, chunk.z
+                );
+            }
+            if section.block_light.len() == LIGHT_ARRAY_SIZE {
+                block_light_mask.set(i + 1, true);
+                block_light_arrays.push(ByteArray::new(section.block_light.clone()));
+            } else if !section.block_light.is_empty() {
+                warn!(
+                    "Block light data for section at {}, {} is not 2048 bytes long",
+                    chunk.x, chunk.z
+                );
+            }
+        }
+
+        // Leave the light of the sections outside the world as it is on the client
+        let mut empty_sky_light_mask = sky_light_mask.clone().not();
+        let mut empty_block_light_mask = block_light_mask.clone().not();
+        for bit in [0, SECTIONS + 1] {
+            empty_sky_light_mask.set(bit, false);
+            empty_block_light_mask.set(bit, false);
+        }
+
+        LightData {
+            sky_light_mask,
+            block_light_mask,
+            empty_sky_light_mask,
+            empty_block_light_mask,
+            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
+            block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+        }
+    }
+}
+
 impl ChunkAndLightData {
     pub fn empty(chunk_x: i32, chunk_z: i32) -> Self {
         let sky_light_arrays = (0..SECTIONS)
-            .map(|_| ByteArray::new(vec![0; 2048]))
+            .map(|_| ByteArray::new(vec![0; LIGHT_ARRAY_SIZE]))
             .collect();
         let block_light_arrays = (0..SECTIONS)
-            .map(|_| ByteArray::new(vec![0; 2048]))
+            .map(|_| ByteArray::new(vec![0; LIGHT_ARRAY_SIZE]))
             .collect();
         let mut empty_sky_light_mask = BitSet::new(SECTIONS + 2);
         empty_sky_light_mask.set_all(false);
@@ -62,41 +126,20 @@ impl ChunkAndLightData {
             heightmaps: LengthPrefixedVec::default(),
             data: ByteArray::new(vec![0; SECTIONS * 10]),
             block_entities: LengthPrefixedVec::new(Vec::new()),
-            sky_light_mask: BitSet::new(SECTIONS),
-            block_light_mask: BitSet::new(SECTIONS),
-            empty_sky_light_mask,
-            empty_block_light_mask,
-            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
-            block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+            light: LightData {
+                sky_light_mask: BitSet::new(SECTIONS),
+                block_light_mask: BitSet::new(SECTIONS),
+                empty_sky_light_mask,
+                empty_block_light_mask,
+                sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
+                block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+            },
         }
     }
 
     pub fn from_chunk(chunk: &Chunk) -> Result<Self, NetError> {
         let mut raw_data = Cursor::new(Vec::new());
-        let mut sky_light_data = Vec::new();
-        let mut block_light_data = Vec::new();
         for section in &chunk.sections {
-            let section_sky_light_data = if section.sky_light.len() != 2048 {
-                warn!(
-                    "Sky light data for section at {}, {} is not 2048 bytes long",
-                    chunk.x, chunk.z
-                );
-                vec![255; 2048]
-            } else {
-                section.sky_light.clone()
-            };
-            sky_light_data.push(section_sky_light_data);
-            let section_block_light_data = if section.block_light.len() != 2048 {
-                warn!(
-                    "Block light data for section at {}, {} is not 2048 bytes long",
-                    chunk.x, chunk.z
-                );
-                vec![255; 2048]
-            } else {
-                section.block_light.clone()
-            };
-            block_light_data.push(section_block_light_data);
-
             raw_data.write_u16::<BigEndian>(section.block_states.non_air_blocks)?;
 
             match &section.block_states.block_data {
@@ -122,70 +165,185 @@ impl ChunkAndLightData {
                         raw_data.write_i64::<BigEndian>(*data_entry)?;
                     }
                 }
-                PaletteType::Direct { .. } => {
-                    todo!("Direct palette type")
+                PaletteType::Direct {
+                    bits_per_block,
+                    data,
+                } => {
+                    // The global block state ids, without a palette
+                    raw_data.write_u8(*bits_per_block)?;
+                    for data_entry in data {
+                        raw_data.write_i64::<BigEndian>(*data_entry)?;
+                    }
                 }
             }
 
-            // Empty biome data for now
-            raw_data.write_u8(0)?;
-            // Forest biome id
-            raw_data.write_u8(21)?;
-        }
-        let mut sky_light_mask = BitSet::new(SECTIONS + 2);
-        let mut block_light_mask = BitSet::new(SECTIONS + 2);
-
-        // Populate masks based on light data
-        for (i, section) in chunk.sections.iter().enumerate() {
-            if !section.sky_light.is_empty() && section.sky_light.len() == 2048 {
-                sky_light_mask.set(i, true);
-            }
-            if !section.block_light.is_empty() && section.block_light.len() == 2048 {
-                block_light_mask.set(i, true);
-            }
+            write_biomes(&section.biome_states, &mut raw_data)?;
         }
-
-        // Invert masks to create empty masks
-        let empty_sky_light_mask = sky_light_mask.clone().not();
-        let empty_block_light_mask = block_light_mask.clone().not();
-
-        // Align light arrays with masks
-        let sky_light_arrays = chunk
-            .sections
+        let heightmaps = HeightmapKind::CLIENT
             .iter()
-            .filter(|section| !section.sky_light.is_empty())
-            .map(|section| ByteArray::new(section.sky_light.clone()))
+            .map(|&kind| NetHeightmap {
+                id: VarInt::new(kind.network_id()),
+                data: LengthPrefixedVec::new(chunk.heightmaps.get(kind).clone()),
+            })
             .collect();
 
-        let block_light_arrays = chunk
-            .sections
+        // Block entities of types the client doesn't know would stop it reading the chunk
+        let block_entities = chunk
+            .block_entities
             .iter()
-            .filter(|section| !section.block_light.is_empty())
-            .map(|section| ByteArray::new(section.block_light.clone()))
+            .filter_map(|(pos, block_entity)| {
+                let Some(entity_type) = block_entity.network_id() else {
+                    warn!("Unknown block entity type {}", block_entity.id);
+                    return None;
+                };
+                Some(BlockEntity {
+                    xz: pos.x << 4 | pos.z,
+                    y: pos.y,
+                    entity_type,
+                    nbt: block_entity.nbt.clone(),
+                })
+            })
             .collect();
-        let heightmaps = vec![
-            NetHeightmap {
-                id: VarInt::new(1), // Placeholder for heightmap ID
-                data: LengthPrefixedVec::new(chunk.heightmaps.world_surface.clone()),
-            },
-            NetHeightmap {
-                id: VarInt::new(4), // Placeholder for heightmap ID
-                data: LengthPrefixedVec::new(chunk.heightmaps.motion_blocking.clone()),
-            },
-        ];
 
         Ok(ChunkAndLightData {
             chunk_x: chunk.x,
             chunk_z: chunk.z,
             heightmaps: LengthPrefixedVec::new(heightmaps),
             data: ByteArray::new(raw_data.into_inner()),
-            block_entities: LengthPrefixedVec::new(Vec::new()),
-            sky_light_mask,
-            block_light_mask,
-            empty_sky_light_mask,
-            empty_block_light_mask,
-            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
-            block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+            block_entities: LengthPrefixedVec::new(block_entities),
+            light: LightData::from_chunk(chunk),
+        })
+    }
+}
+
+/// Writes the biomes of a section as a paletted container of biome registry ids.
+fn write_biomes(biomes: &BiomeStates, writer: &mut impl Write) -> Result<(), NetError> {
+    let plains = biome_id("minecraft:plains").unwrap_or_default();
+    let ids = biomes
+        .palette
+        .iter()
+        .map(|biome| {
+            biome_id(biome).unwrap_or_else(|| {
+                warn!("Unknown biome {}, sending plains instead", biome);
+                plains
+            })
         })
+        .collect::<Vec<_>>();
+
+    if ids.len() <= 1 || biomes.bits_per_biome == 0 {
+        writer.write_u8(0)?;
+        ids.first().copied().unwrap_or(plains).write(writer)?;
+    } else if biomes.bits_per_biome <= MAX_INDIRECT_BIOME_BITS {
+        writer.write_u8(biomes.bits_per_biome)?;
+        VarInt::new(ids.len() as i32).write(writer)?;
+        for id in &ids {
+            id.write(writer)?;
+        }
+        for long in &biomes.data {
+            writer.write_i64::<BigEndian>(*long)?;
+        }
+    } else {
+        // Too many biomes for a palette, so send the registry id of every cell
+        let bits = (usize::BITS - (biome_count() - 1).leading_zeros()) as usize;
+        writer.write_u8(bits as u8)?;
+        let cells = biomes
+            .indices()
+            .into_iter()
+            .map(|index| ids.get(index as usize).unwrap_or(&plains).0 as u64)
+            .collect::<Vec<_>>();
+        for cells in cells.chunks(64 / bits) {
+            let long = cells
+                .iter()
+                .enumerate()
+                .fold(0u64, |long, (index, &id)| long | (id << (index * bits)));
+            writer.write_i64::<BigEndian>(long as i64)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_world::chunk_format::BlockStates;
+
+    fn encode(biomes: &BiomeStates) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        write_biomes(biomes, &mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_write_biomes() {
+        // A single biome is just its id
+        assert_eq!(encode(&BiomeStates::single("minecraft:plains")), [0, 40]);
+
+        let mut biomes = BiomeStates::single("minecraft:plains");
+        biomes.set(0, 0, 0, "minecraft:desert");
+        let encoded = encode(&biomes);
+        assert_eq!(encoded[..4], [1, 2, 14, 40]);
+        // One long of 64 cells at 1 bit each
+        assert_eq!(encoded.len(), 4 + 8);
+
+        let names = [
+            "forest", "taiga", "swamp", "jungle", "savanna", "beach", "ocean",
+        ];
+        for (i, name) in (1..).zip(names) {
+            biomes.set(4 * (i % 4), 4 * (i / 4), 0, name);
+        }
+        let encoded = encode(&biomes);
+        // 9 biomes don't fit a palette, so the ids are sent with 7 bits each, 9 to a long
+        assert_eq!(encoded[0], 7);
+        assert_eq!(encoded.len(), 1 + 8 * 64usize.div_ceil(9));
+    }
+
+    #[test]
+    fn test_direct_section() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        let ids = (0..4096).map(|i| i % 1000).collect::<Vec<_>>();
+        chunk.sections[0].block_states = BlockStates::from_block_ids(&ids);
+
+        let packet = ChunkAndLightData::from_chunk(&chunk).unwrap();
+        let data = packet.data.0;
+        // Non-air count, then 15 bits per block with no palette, 4 to a long
+        assert_eq!(u16::from_be_bytes([data[0], data[1]]), 4096 - 5);
+        assert_eq!(data[2], 15);
+        let first = i64::from_be_bytes(data[3..11].try_into().unwrap());
+        assert_eq!(first, 1 << 15 | 2 << 30 | 3 << 45);
+        assert_eq!(data[3 + 8 * 1024], 0);
+    }
+    #[test]
+    fn test_heightmap_ids() {
+        let chunk = Chunk::new(0, 0, "overworld".to_string());
+        let packet = ChunkAndLightData::from_chunk(&chunk).unwrap();
+        let ids = packet
+            .heightmaps
+            .data
+            .iter()
+            .map(|heightmap| heightmap.id.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [1, 4, 5]);
+        // 256 columns of 9 bits, 7 to a long
+        assert!(packet
+            .heightmaps
+            .data
+            .iter()
+            .all(|heightmap| heightmap.data.data.len() == 37));
+    }
+    #[test]
+    fn test_block_entities() {
+        let mut chunk = Chunk::new(1, 1, "overworld".to_string());
+        chunk.set_block_entity(
+            19,
+            -10,
+            21,
+            ferrumc_world::block_entities::BlockEntity::empty("minecraft:barrel"),
+        );
+        let packet = ChunkAndLightData::from_chunk(&chunk).unwrap();
+        let block_entity = &packet.block_entities.data[0];
+        assert_eq!(block_entity.xz, 3 << 4 | 5);
+        assert_eq!(block_entity.y, -10);
+        assert_eq!(block_entity.entity_type, VarInt::new(27));
+        assert_eq!(block_entity.nbt, [10, 0]);
     }
 }
@@ -20,6 +20,11 @@ impl CommandsPacket {
     pub fn new() -> Self {
         Self::new_with(ferrumc_commands::infrastructure::get_graph())
     }
+
+    /// The commands the player with the given `uuid` has permission to use.
+    pub fn for_player(uuid: u128) -> Self {
+        Self::new_with(ferrumc_commands::infrastructure::get_player_graph(uuid))
+    }
 }
 
 impl Default for CommandsPacket {
@@ -0,0 +1,9 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+/// Closes the client's window, e.g. when the container it was open on is broken.
+#[derive(NetEncode)]
+#[packet(packet_id = "container_close", state = "play")]
+pub struct ContainerClose {
+    pub window_id: VarInt,
+}
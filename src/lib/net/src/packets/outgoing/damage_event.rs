@@ -0,0 +1,38 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+/// Tells clients an entity was hurt, which plays its hurt animation and sound.
+#[derive(NetEncode)]
+#[packet(packet_id = "damage_event", state = "play")]
+pub struct DamageEventPacket {
+    pub entity_id: VarInt,
+    /// The index of the damage type in the `minecraft:damage_type` registry.
+    pub source_type_id: VarInt,
+    /// The entity ID of whatever caused the damage plus 1, or 0 if nothing did.
+    pub source_cause_id: VarInt,
+    /// The entity ID of whatever dealt the damage, like an arrow, plus 1, or 0 if nothing did.
+    pub source_direct_id: VarInt,
+    pub source_position: PrefixedOptional<SourcePosition>,
+}
+
+#[derive(NetEncode)]
+pub struct SourcePosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl DamageEventPacket {
+    /// Damage from `damage_type`, dealt directly by the entity with the ID `attacker`, if any.
+    pub fn new(entity_id: i32, damage_type: VarInt, attacker: Option<i32>) -> Self {
+        let source = VarInt::new(attacker.map_or(0, |id| id + 1));
+        Self {
+            entity_id: VarInt::new(entity_id),
+            source_type_id: damage_type,
+            source_cause_id: source,
+            source_direct_id: source,
+            source_position: PrefixedOptional::None,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::byte_array::ByteArray;
+
+/// Asks the client to start encryption (and, in online mode, authenticate with the session server).
+#[derive(NetEncode)]
+#[packet(packet_id = "hello", state = "login")]
+pub struct EncryptionRequestPacket {
+    /// Always empty on modern versions.
+    pub server_id: String,
+    pub public_key: ByteArray,
+    pub verify_token: ByteArray,
+    pub should_authenticate: bool,
+}
+
+impl EncryptionRequestPacket {
+    pub fn new(public_key: &[u8], verify_token: &[u8], should_authenticate: bool) -> Self {
+        Self {
+            server_id: String::new(),
+            public_key: ByteArray::new(public_key.to_vec()),
+            verify_token: ByteArray::new(verify_token.to_vec()),
+            should_authenticate,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use ferrumc_macros::{packet, NetEncode};
+
+/// Triggers an effect on an entity on clients, like the death animation.
+#[derive(NetEncode)]
+#[packet(packet_id = "entity_event", state = "play")]
+pub struct EntityEventPacket {
+    pub entity_id: i32,
+    pub status: i8,
+}
+
+impl EntityEventPacket {
+    /// Plays the death animation of a living entity.
+    pub const DEATH: i8 = 3;
+
+    pub fn new(entity_id: i32, status: i8) -> Self {
+        Self { entity_id, status }
+    }
+}
@@ -56,6 +56,7 @@ pub struct EntityMetadata {
 pub mod constructors {
     use super::*;
     use crate::packets::outgoing::entity_metadata::extra_data_types::EntityPose;
+    use ferrumc_inventories::slot::InventorySlot;
 
     impl EntityMetadata {
         fn new(index_type: EntityMetadataIndexType, value: EntityMetadataValue) -> Self {
@@ -89,6 +90,14 @@ pub mod constructors {
                 EntityMetadataValue::Entity6(EntityPose::Standing),
             )
         }
+
+        /// The item a dropped item entity shows
+        pub fn item(slot: InventorySlot) -> Self {
+            Self::new(
+                EntityMetadataIndexType::Slot,
+                EntityMetadataValue::Item8(slot),
+            )
+        }
     }
 }
 
@@ -101,6 +110,7 @@ mod index_type {
     #[derive(Debug, Clone, Copy)]
     pub enum EntityMetadataIndexType {
         Byte, // (0) Used for bit masks and small numbers
+        Slot, // (7) Used for item stacks
         Pose, // (21) Used for entity pose
     }
 
@@ -109,6 +119,7 @@ mod index_type {
             use EntityMetadataIndexType::*;
             let val = match self {
                 Byte => 0,
+                Slot => 7,
                 Pose => 21,
             };
 
@@ -138,6 +149,7 @@ mod index_type {
 mod value {
     use super::*;
     use crate::packets::outgoing::entity_metadata::extra_data_types::EntityPose;
+    use ferrumc_inventories::slot::InventorySlot;
     /// Possible metadata values that can be sent
     ///
     /// Couldn't be arsed coming up with the names.
@@ -150,6 +162,7 @@ mod value {
     pub enum EntityMetadataValue {
         Entity0(EntityStateMask),
         Entity6(EntityPose),
+        Item8(InventorySlot),
     }
 
     impl EntityMetadataValue {
@@ -158,6 +171,7 @@ mod value {
             match self {
                 Entity0(_) => 0,
                 Entity6(_) => 6,
+                Item8(_) => 8,
             }
         }
     }
@@ -1,3 +1,5 @@
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_core::transform::position::Position;
 use ferrumc_core::transform::rotation::Rotation;
@@ -21,14 +23,14 @@ pub struct TeleportEntityPacket {
 
 impl TeleportEntityPacket {
     pub fn new(
-        entity_id: &PlayerIdentity,
+        identity: &PlayerIdentity,
         position: &Position,
         angle: &Rotation,
         on_ground: bool,
     ) -> Self {
         // Todo: Add velocity parameters if needed
         Self {
-            entity_id: VarInt::new(entity_id.short_uuid),
+            entity_id: VarInt::new(identity.entity_id),
             x: position.x,
             y: position.y,
             z: position.z,
@@ -40,4 +42,26 @@ impl TeleportEntityPacket {
             on_ground,
         }
     }
+
+    /// Sets where an entity that isn't a player is and how fast it's moving.
+    pub fn entity(
+        identity: &EntityIdentity,
+        position: &Position,
+        angle: &Rotation,
+        velocity: &Velocity,
+        on_ground: bool,
+    ) -> Self {
+        Self {
+            entity_id: VarInt::new(identity.entity_id),
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            vel_x: velocity.x,
+            vel_y: velocity.y,
+            vel_z: velocity.z,
+            yaw: angle.yaw,
+            pitch: angle.pitch,
+            on_ground,
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use ferrumc_core::game_mode::GameMode;
 use ferrumc_macros::{packet, NetEncode};
 
 #[derive(NetEncode)]
@@ -15,4 +16,8 @@ impl GameEventPacket {
     pub fn start_waiting_for_level_chunks() -> Self {
         Self::new(13, 0f32)
     }
+
+    pub fn change_game_mode(game_mode: GameMode) -> Self {
+        Self::new(3, f32::from(game_mode.id()))
+    }
 }
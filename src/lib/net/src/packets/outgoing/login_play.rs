@@ -1,7 +1,17 @@
+use crate::packets::outgoing::registry_data::dimension_type_id;
 use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::var_int::VarInt;
 
+/// Every dimension the client can be sent to.
+const DIMENSION_NAMES: &[&str] = &[
+    Dimension::Overworld.identifier(),
+    Dimension::Nether.identifier(),
+    Dimension::End.identifier(),
+];
+
 #[derive(NetEncode)]
 #[packet(packet_id = "login", state = "play")]
 pub struct LoginPlayPacket<'a> {
@@ -31,22 +41,23 @@ pub struct LoginPlayPacket<'a> {
 }
 
 impl LoginPlayPacket<'_> {
-    pub fn new(conn_id: i32) -> Self {
+    /// Joins the game in `dimension`, playing in `game_mode`.
+    pub fn new(conn_id: i32, dimension: Dimension, game_mode: GameMode) -> Self {
         Self {
             entity_id: conn_id,
             is_hardcore: false,
-            dimension_length: VarInt::from(1),
-            dimension_names: &["minecraft:overworld"],
+            dimension_length: VarInt::from(DIMENSION_NAMES.len() as i32),
+            dimension_names: DIMENSION_NAMES,
             max_players: VarInt::from(get_global_config().max_players as i32),
             view_distance: VarInt::from(get_global_config().chunk_render_distance as i32),
             simulation_distance: VarInt::from(get_global_config().chunk_render_distance as i32),
             reduced_debug_info: false,
             enable_respawn_screen: true,
             do_limited_crafting: false,
-            dimension_type: VarInt::new(0),
-            dimension_name: "minecraft:overworld",
+            dimension_type: dimension_type_id(dimension),
+            dimension_name: dimension.identifier(),
             seed_hash: 0,
-            gamemode: 1,
+            gamemode: game_mode.id(),
             previous_gamemode: -1,
             is_debug: false,
             is_flat: false,
@@ -3,6 +3,7 @@ pub mod chunk_batch_finish;
 pub mod chunk_batch_start;
 pub mod client_bound_known_packs;
 pub mod disconnect;
+pub mod encryption_request;
 pub mod finish_configuration;
 pub mod game_event;
 pub mod keep_alive;
@@ -10,7 +11,9 @@ pub mod login_disconnect;
 pub mod login_play;
 pub mod login_success;
 pub mod ping_response;
+pub mod player_abilities;
 pub mod registry_data;
+pub mod respawn;
 pub mod set_center_chunk;
 pub mod set_default_spawn_position;
 pub mod set_render_distance;
@@ -27,6 +30,7 @@ pub mod player_info_update;
 
 // --------- Movement ----------
 pub mod entity_position_sync;
+pub mod set_entity_motion;
 pub mod set_head_rotation;
 pub mod update_entity_position;
 pub mod update_entity_position_and_rotation;
@@ -34,14 +38,29 @@ pub mod update_entity_rotation;
 // -----------------------------
 
 pub mod block_change_ack;
+pub mod block_destruction;
 
+pub mod block_entity_data;
 pub mod block_update;
+pub mod update_light;
 
 pub mod command_suggestions;
 pub mod commands;
 
 pub(crate) mod set_compression;
 
+pub mod container_close;
 pub mod set_container_content;
 pub mod set_container_slot;
+
+pub mod open_screen;
+pub mod place_ghost_recipe;
+pub mod recipe_book_add;
+pub mod set_held_slot;
 pub mod set_player_inventory_slot;
+pub mod update_recipes;
+
+pub mod damage_event;
+pub mod entity_event;
+pub mod player_combat_kill;
+pub mod set_health;
@@ -0,0 +1,11 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_recipes::display::RecipeDisplay;
+
+/// Shows a recipe faded out in the crafting grid, when the player doesn't have the items for it.
+#[derive(NetEncode)]
+#[packet(packet_id = "place_ghost_recipe", state = "play")]
+pub struct PlaceGhostRecipe {
+    pub window_id: VarInt,
+    pub recipe: RecipeDisplay,
+}
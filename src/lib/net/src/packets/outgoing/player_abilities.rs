@@ -0,0 +1,22 @@
+use ferrumc_core::abilities::Abilities;
+use ferrumc_macros::{packet, NetEncode};
+
+/// Tells the client what its player is allowed to do, like flying.
+#[derive(NetEncode)]
+#[packet(packet_id = "player_abilities", state = "play")]
+pub struct PlayerAbilitiesPacket {
+    pub flags: u8,
+    pub flying_speed: f32,
+    /// Changes the field of view, like speed effects do.
+    pub fov_modifier: f32,
+}
+
+impl PlayerAbilitiesPacket {
+    pub fn new(abilities: &Abilities) -> Self {
+        Self {
+            flags: abilities.flags(),
+            flying_speed: 0.05,
+            fov_modifier: 0.1,
+        }
+    }
+}
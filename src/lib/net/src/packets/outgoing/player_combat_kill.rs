@@ -0,0 +1,20 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_text::TextComponent;
+
+/// Shows the death screen with the message of how the player died.
+#[derive(NetEncode)]
+#[packet(packet_id = "player_combat_kill", state = "play")]
+pub struct PlayerCombatKillPacket {
+    pub player_id: VarInt,
+    pub message: TextComponent,
+}
+
+impl PlayerCombatKillPacket {
+    pub fn new(player_id: i32, message: TextComponent) -> Self {
+        Self {
+            player_id: VarInt::new(player_id),
+            message,
+        }
+    }
+}
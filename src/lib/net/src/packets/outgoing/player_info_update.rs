@@ -1,4 +1,5 @@
 use bevy_ecs::prelude::{Component, Entity, Query};
+use ferrumc_core::game_mode::GameMode;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
@@ -27,8 +28,11 @@ impl PlayerInfoUpdatePacket {
     }
 
     /// The packet to be sent to all already connected players when a new player joins the server
-    pub fn new_player_join_packet(identity: PlayerIdentity) -> Self {
-        let player = PlayerWithActions::add_player(identity.short_uuid, identity.username);
+    pub fn new_player_join_packet(identity: PlayerIdentity, game_mode: GameMode) -> Self {
+        let mut player = PlayerWithActions::add_player(identity.uuid.as_u128(), identity.username);
+        player.actions.push(PlayerAction::UpdateGameMode {
+            game_mode: VarInt::new(i32::from(game_mode.id())),
+        });
 
         Self::with_players(vec![player])
     }
@@ -51,7 +55,7 @@ impl PlayerInfoUpdatePacket {
         let players = players
             .into_iter()
             .map(|player| {
-                let uuid = player.short_uuid;
+                let uuid = player.uuid.as_u128();
                 let name = player.username.clone();
 
                 (uuid, name)
@@ -67,7 +71,7 @@ impl PlayerInfoUpdatePacket {
 
 #[derive(NetEncode, Debug, Component)]
 pub struct PlayerWithActions {
-    pub uuid: i32,
+    pub uuid: u128,
     pub actions: Vec<PlayerAction>,
 }
 
@@ -77,12 +81,22 @@ impl PlayerWithActions {
         for action in &self.actions {
             mask |= match action {
                 PlayerAction::AddPlayer { .. } => 0x01,
+                PlayerAction::UpdateGameMode { .. } => 0x04,
             }
         }
         mask
     }
 
-    pub fn add_player(uuid: i32, name: impl Into<String>) -> Self {
+    pub fn update_game_mode(uuid: u128, game_mode: GameMode) -> Self {
+        Self {
+            uuid,
+            actions: vec![PlayerAction::UpdateGameMode {
+                game_mode: VarInt::new(i32::from(game_mode.id())),
+            }],
+        }
+    }
+
+    pub fn add_player(uuid: u128, name: impl Into<String>) -> Self {
         Self {
             uuid,
             actions: vec![PlayerAction::AddPlayer {
@@ -99,6 +113,8 @@ pub enum PlayerAction {
         name: String,
         properties: LengthPrefixedVec<PlayerProperty>,
     },
+    /// Actions have to be in the order of their bits in the mask.
+    UpdateGameMode { game_mode: VarInt },
 }
 
 #[derive(NetEncode, Debug)]
@@ -0,0 +1,48 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_recipes::display::{ItemSet, RecipeDisplay};
+use ferrumc_recipes::registry::RecipeRegistry;
+
+#[derive(NetEncode)]
+#[packet(packet_id = "recipe_book_add", state = "play")]
+pub struct RecipeBookAdd {
+    pub entries: LengthPrefixedVec<RecipeBookEntry>,
+    /// Whether these replace every recipe the client had.
+    pub replace: bool,
+}
+
+#[derive(NetEncode)]
+pub struct RecipeBookEntry {
+    /// The id the client uses to ask for the recipe to be placed.
+    pub id: VarInt,
+    pub display: RecipeDisplay,
+    /// The group id plus one, or 0 for none.
+    pub group: VarInt,
+    pub category: VarInt,
+    pub requirements: PrefixedOptional<LengthPrefixedVec<ItemSet>>,
+    /// 1 shows a notification, 2 highlights the recipe as new.
+    pub flags: u8,
+}
+
+impl RecipeBookAdd {
+    /// Every recipe, unlocked without any fuss, replacing what the client had.
+    pub fn all(recipes: &RecipeRegistry) -> Self {
+        let entries = recipes
+            .iter()
+            .map(|(id, recipe)| RecipeBookEntry {
+                id: VarInt::new(id as i32),
+                display: recipe.display(),
+                group: VarInt::new(recipes.group_id(recipe).map_or(0, |group| group + 1)),
+                category: VarInt::new(recipe.category as i32),
+                requirements: PrefixedOptional::Some(LengthPrefixedVec::new(recipe.requirements())),
+                flags: 0,
+            })
+            .collect();
+        Self {
+            entries: LengthPrefixedVec::new(entries),
+            replace: true,
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use bitcode::{Decode, Encode};
+use ferrumc_core::dimension::Dimension;
 use ferrumc_macros::{build_registry_packets, packet, NetEncode};
 use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
 use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
+use ferrumc_net_codec::net_types::var_int::VarInt;
 use lazy_static::lazy_static;
+use std::collections::HashMap;
 
 #[derive(NetEncode)]
 #[packet(packet_id = "registry_data", state = "configuration")]
@@ -51,6 +54,70 @@ fn process_reg_packets() -> Vec<RegistryDataPacket> {
         .collect()
 }
 
+/// The network id of the dimension type of `dimension`, which is its index in the
+/// `minecraft:dimension_type` registry sent during configuration.
+pub fn dimension_type_id(dimension: Dimension) -> VarInt {
+    let index = REGISTRY_PACKETS
+        .iter()
+        .find(|packet| packet.registry_id == "minecraft:dimension_type")
+        .and_then(|packet| {
+            packet
+                .entries
+                .data
+                .iter()
+                .position(|entry| entry.id == dimension.name())
+        })
+        .expect("dimension type is missing from the registry");
+    VarInt::new(index as i32)
+}
+
+lazy_static! {
+    static ref BIOME_IDS: HashMap<String, i32> = REGISTRY_PACKETS
+        .iter()
+        .find(|packet| packet.registry_id == "minecraft:worldgen/biome")
+        .map(|packet| {
+            packet
+                .entries
+                .data
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| (entry.id.clone(), index as i32))
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+/// The network id of a biome, like `minecraft:plains`, which is its index in the
+/// `minecraft:worldgen/biome` registry sent during configuration.
+pub fn biome_id(biome: &str) -> Option<VarInt> {
+    let name = biome.strip_prefix("minecraft:").unwrap_or(biome);
+    BIOME_IDS.get(name).copied().map(VarInt::new)
+}
+
+/// The network id of a damage type, like `minecraft:fall`, which is its index in the
+/// `minecraft:damage_type` registry sent during configuration.
+pub fn damage_type_id(damage_type: &str) -> Option<VarInt> {
+    let name = damage_type
+        .strip_prefix("minecraft:")
+        .unwrap_or(damage_type);
+    REGISTRY_PACKETS
+        .iter()
+        .find(|packet| packet.registry_id == "minecraft:damage_type")
+        .and_then(|packet| {
+            packet
+                .entries
+                .data
+                .iter()
+                .position(|entry| entry.id == name)
+        })
+        .map(|index| VarInt::new(index as i32))
+}
+
+/// The number of biomes in the registry.
+pub fn biome_count() -> usize {
+    BIOME_IDS.len()
+}
+
 #[derive(NetEncode, Encode, Decode)]
 pub struct RegistryEntry {
     pub id: String,
@@ -59,12 +126,37 @@ pub struct RegistryEntry {
 
 #[cfg(test)]
 mod tests {
-    use crate::packets::outgoing::registry_data::RegistryEntry;
+    use crate::packets::outgoing::registry_data::{
+        biome_count, biome_id, damage_type_id, dimension_type_id, RegistryEntry,
+    };
+    use ferrumc_core::dimension::Dimension;
     use ferrumc_net_codec::net_types::prefixed_optional::PrefixedOptional;
     use indexmap::IndexMap;
     use serde_json::Value;
     use std::io::Write;
 
+    #[test]
+    fn test_dimension_type_id() {
+        assert_eq!(dimension_type_id(Dimension::Overworld).0, 0);
+        assert_eq!(dimension_type_id(Dimension::End).0, 2);
+        assert_eq!(dimension_type_id(Dimension::Nether).0, 3);
+    }
+
+    #[test]
+    fn test_biome_id() {
+        assert_eq!(biome_count(), 65);
+        assert_eq!(biome_id("minecraft:badlands").map(|id| id.0), Some(0));
+        assert_eq!(biome_id("plains").map(|id| id.0), Some(40));
+        assert!(biome_id("minecraft:not_a_biome").is_none());
+    }
+
+    #[test]
+    fn test_damage_type_id() {
+        assert_eq!(damage_type_id("minecraft:drown").map(|id| id.0), Some(6));
+        assert_eq!(damage_type_id("fall").map(|id| id.0), Some(10));
+        assert!(damage_type_id("not_a_damage_type").is_none());
+    }
+
     #[test]
     #[ignore]
     fn generate_nbt() {
@@ -16,10 +16,20 @@ impl RemoveEntitiesPacket {
     {
         let entity_ids: Vec<VarInt> = entity_ids
             .into_iter()
-            .map(|entity| VarInt::new(entity.short_uuid))
+            .map(|entity| VarInt::new(entity.entity_id))
             .collect();
         Self {
             entity_ids: LengthPrefixedVec::new(entity_ids),
         }
     }
+
+    /// Removes entities by their network IDs.
+    pub fn from_ids<T>(entity_ids: T) -> Self
+    where
+        T: IntoIterator<Item = i32>,
+    {
+        Self {
+            entity_ids: LengthPrefixedVec::new(entity_ids.into_iter().map(VarInt::new).collect()),
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use crate::packets::outgoing::registry_data::dimension_type_id;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetEncode)]
+#[packet(packet_id = "respawn", state = "play")]
+pub struct RespawnPacket<'a> {
+    pub dimension_type: VarInt,
+    pub dimension_name: &'a str,
+    pub seed_hash: i64,
+    pub gamemode: u8,
+    pub previous_gamemode: i8,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    pub has_death_location: bool,
+    pub death_dimension_name: Option<&'a str>,
+    pub death_location: Option<u8>, // same as in the login packet, this needs to be a Position
+    pub portal_cooldown: VarInt,
+    pub sea_level: VarInt,
+    /// Bit 0 keeps the attributes, bit 1 keeps the entity metadata.
+    pub data_kept: u8,
+}
+
+impl RespawnPacket<'_> {
+    /// Moves the player into `dimension`, keeping their attributes and metadata. Clients reset
+    /// their abilities to the ones of `game_mode`.
+    pub fn new(dimension: Dimension, game_mode: GameMode) -> Self {
+        Self {
+            dimension_type: dimension_type_id(dimension),
+            dimension_name: dimension.identifier(),
+            seed_hash: 0,
+            gamemode: game_mode.id(),
+            previous_gamemode: -1,
+            is_debug: false,
+            is_flat: false,
+            has_death_location: false,
+            death_dimension_name: None,
+            death_location: None,
+            portal_cooldown: VarInt::from(0),
+            sea_level: VarInt::from(63),
+            data_kept: 0b11,
+        }
+    }
+    /// Respawns a player that died, into `dimension`. Nothing is kept, so clients reset the
+    /// player's attributes and metadata.
+    pub fn after_death(dimension: Dimension, game_mode: GameMode) -> Self {
+        Self {
+            data_kept: 0,
+            ..Self::new(dimension, game_mode)
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetEncode, Clone)]
+#[packet(packet_id = "set_entity_motion", state = "play")]
+pub struct SetEntityMotionPacket {
+    pub entity_id: VarInt,
+    pub velocity_x: i16,
+    pub velocity_y: i16,
+    pub velocity_z: i16,
+}
+
+impl SetEntityMotionPacket {
+    pub fn new(identity: &EntityIdentity, velocity: &Velocity) -> Self {
+        Self::with_id(identity.entity_id, velocity)
+    }
+
+    /// Sets the velocity of a player. Their own client is the one that moves them.
+    pub fn player(identity: &PlayerIdentity, velocity: &Velocity) -> Self {
+        Self::with_id(identity.entity_id, velocity)
+    }
+
+    fn with_id(entity_id: i32, velocity: &Velocity) -> Self {
+        let (velocity_x, velocity_y, velocity_z) = velocity.to_network();
+        Self {
+            entity_id: VarInt::new(entity_id),
+            velocity_x,
+            velocity_y,
+            velocity_z,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use ferrumc_core::health::{Food, Health};
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+/// Updates the health and hunger bars. Health of 0 or less shows the death screen.
+#[derive(NetEncode)]
+#[packet(packet_id = "set_health", state = "play")]
+pub struct SetHealthPacket {
+    pub health: f32,
+    pub food: VarInt,
+    pub saturation: f32,
+}
+
+impl SetHealthPacket {
+    pub fn new(health: &Health, food: &Food) -> Self {
+        Self {
+            health: health.current,
+            food: VarInt::new(food.level),
+            saturation: food.saturation,
+        }
+    }
+}
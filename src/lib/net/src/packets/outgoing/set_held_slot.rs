@@ -0,0 +1,17 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+
+#[derive(NetEncode)]
+#[packet(packet_id = "set_held_slot", state = "play")]
+pub struct SetHeldSlot {
+    /// The selected hotbar slot, 0-8.
+    pub slot: VarInt,
+}
+
+impl SetHeldSlot {
+    pub fn new(slot: u8) -> Self {
+        Self {
+            slot: VarInt::new(slot as i32),
+        }
+    }
+}
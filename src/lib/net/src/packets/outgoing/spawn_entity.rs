@@ -1,10 +1,10 @@
-use crate::errors::NetError;
-
-use bevy_ecs::prelude::{Entity, Query};
+use ferrumc_core::entities::kind::EntityKind;
+use ferrumc_core::entities::velocity::Velocity;
+use ferrumc_core::identity::entity_identity::EntityIdentity;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_core::transform::position::Position;
 use ferrumc_core::transform::rotation::Rotation;
-use ferrumc_macros::{get_registry_entry, packet, NetEncode};
+use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::angle::NetAngle;
 use ferrumc_net_codec::net_types::var_int::VarInt;
 
@@ -26,22 +26,12 @@ pub struct SpawnEntityPacket {
     velocity_z: i16,
 }
 
-const PLAYER_ID: u64 = get_registry_entry!("minecraft:entity_type.entries.minecraft:player");
-
 impl SpawnEntityPacket {
-    pub fn player(
-        entity_id: Entity,
-        query: Query<(&PlayerIdentity, &Position, &Rotation)>,
-    ) -> Result<Self, NetError> {
-        let (player_identity, position, rotation) = query
-            .get(entity_id)
-            .unwrap_or_else(|_| panic!("Failed to get player identity, position, and rotation for entity ID: {entity_id:?}"
-            ));
-
-        Ok(Self {
-            entity_id: VarInt::new(player_identity.short_uuid),
-            entity_uuid: player_identity.uuid.as_u128(),
-            r#type: VarInt::new(PLAYER_ID as i32),
+    pub fn player(identity: &PlayerIdentity, position: &Position, rotation: &Rotation) -> Self {
+        Self {
+            entity_id: VarInt::new(identity.entity_id),
+            entity_uuid: identity.uuid.as_u128(),
+            r#type: VarInt::new(EntityKind::PLAYER.0),
             x: position.x,
             y: position.y,
             z: position.z,
@@ -52,6 +42,34 @@ impl SpawnEntityPacket {
             velocity_x: 0,
             velocity_y: 0,
             velocity_z: 0,
-        })
+        }
+    }
+
+    /// Spawns an entity that isn't a player. What `data` means depends on `kind`, see
+    /// [`ferrumc_core::entities::SpawnData`].
+    pub fn entity(
+        identity: &EntityIdentity,
+        kind: EntityKind,
+        position: &Position,
+        rotation: &Rotation,
+        velocity: &Velocity,
+        data: i32,
+    ) -> Self {
+        let (velocity_x, velocity_y, velocity_z) = velocity.to_network();
+        Self {
+            entity_id: VarInt::new(identity.entity_id),
+            entity_uuid: identity.uuid.as_u128(),
+            r#type: VarInt::new(kind.0),
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            pitch: NetAngle::from_degrees(rotation.pitch as f64),
+            yaw: NetAngle::from_degrees(rotation.yaw as f64),
+            head_yaw: NetAngle::from_degrees(rotation.yaw as f64),
+            data: VarInt::new(data),
+            velocity_x,
+            velocity_y,
+            velocity_z,
+        }
     }
 }
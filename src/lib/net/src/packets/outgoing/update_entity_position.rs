@@ -1,3 +1,4 @@
+use ferrumc_core::identity::entity_identity::EntityIdentity;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::var_int::VarInt;
@@ -14,12 +15,27 @@ pub struct UpdateEntityPositionPacket {
 
 impl UpdateEntityPositionPacket {
     pub fn new(
-        entity_id: &PlayerIdentity,
+        identity: &PlayerIdentity,
         delta_positions: (i16, i16, i16),
         on_ground: bool,
     ) -> Self {
         Self {
-            entity_id: VarInt::new(entity_id.short_uuid),
+            entity_id: VarInt::new(identity.entity_id),
+            delta_x: delta_positions.0,
+            delta_y: delta_positions.1,
+            delta_z: delta_positions.2,
+            on_ground,
+        }
+    }
+
+    /// Moves an entity that isn't a player by `delta_positions`, in 1/4096 of a block.
+    pub fn entity(
+        identity: &EntityIdentity,
+        delta_positions: (i16, i16, i16),
+        on_ground: bool,
+    ) -> Self {
+        Self {
+            entity_id: VarInt::new(identity.entity_id),
             delta_x: delta_positions.0,
             delta_y: delta_positions.1,
             delta_z: delta_positions.2,
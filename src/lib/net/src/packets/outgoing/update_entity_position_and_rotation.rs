@@ -18,13 +18,13 @@ pub struct UpdateEntityPositionAndRotationPacket {
 
 impl UpdateEntityPositionAndRotationPacket {
     pub fn new(
-        entity_id: &PlayerIdentity,
+        identity: &PlayerIdentity,
         delta_positions: (i16, i16, i16),
         new_rot: &Rotation,
         on_ground: bool,
     ) -> Self {
         Self {
-            entity_id: VarInt::new(entity_id.short_uuid),
+            entity_id: VarInt::new(identity.entity_id),
             delta_x: delta_positions.0,
             delta_y: delta_positions.1,
             delta_z: delta_positions.2,
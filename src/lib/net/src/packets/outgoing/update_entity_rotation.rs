@@ -13,9 +13,9 @@ pub struct UpdateEntityRotationPacket {
     pub on_ground: bool,
 }
 impl UpdateEntityRotationPacket {
-    pub fn new(entity_id: &PlayerIdentity, new_rot: &Rotation, on_ground: bool) -> Self {
+    pub fn new(identity: &PlayerIdentity, new_rot: &Rotation, on_ground: bool) -> Self {
         Self {
-            entity_id: VarInt::new(entity_id.short_uuid),
+            entity_id: VarInt::new(identity.entity_id),
             yaw: NetAngle::from_degrees(new_rot.yaw as f64),
             pitch: NetAngle::from_degrees(new_rot.pitch as f64),
             on_ground,
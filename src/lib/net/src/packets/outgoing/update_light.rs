@@ -0,0 +1,23 @@
+use crate::packets::outgoing::chunk_and_light_data::LightData;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_world::chunk_format::Chunk;
+
+/// Resends the light of a chunk the client already has, after a block change relit it.
+#[derive(NetEncode)]
+#[packet(packet_id = "light_update", state = "play")]
+pub struct UpdateLightPacket {
+    pub chunk_x: VarInt,
+    pub chunk_z: VarInt,
+    pub light: LightData,
+}
+
+impl UpdateLightPacket {
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        Self {
+            chunk_x: VarInt::new(chunk.x),
+            chunk_z: VarInt::new(chunk.z),
+            light: LightData::from_chunk(chunk),
+        }
+    }
+}
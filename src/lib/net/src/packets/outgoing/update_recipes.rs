@@ -0,0 +1,59 @@
+use ferrumc_inventories::item::ItemID;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_recipes::display::{ItemSet, SlotDisplay};
+use ferrumc_recipes::recipe::CookingMethod;
+use ferrumc_recipes::registry::RecipeRegistry;
+
+/// Tells the client which items can go in the input slots of furnaces and smithing tables, and
+/// what the stonecutter makes. Everything else about recipes is sent to the recipe book.
+#[derive(NetEncode)]
+#[packet(packet_id = "update_recipes", state = "play")]
+pub struct UpdateRecipes {
+    pub property_sets: LengthPrefixedVec<PropertySet>,
+    pub stonecutter_recipes: LengthPrefixedVec<StonecutterRecipe>,
+}
+
+#[derive(NetEncode)]
+pub struct PropertySet {
+    pub id: String,
+    pub items: LengthPrefixedVec<ItemID>,
+}
+
+#[derive(NetEncode)]
+pub struct StonecutterRecipe {
+    pub ingredient: ItemSet,
+    pub result: SlotDisplay,
+}
+
+impl UpdateRecipes {
+    pub fn new(recipes: &RecipeRegistry) -> Self {
+        let cooking = [
+            ("minecraft:furnace_input", CookingMethod::Smelting),
+            ("minecraft:blast_furnace_input", CookingMethod::Blasting),
+            ("minecraft:smoker_input", CookingMethod::Smoking),
+            ("minecraft:campfire_input", CookingMethod::Campfire),
+        ]
+        .map(|(id, method)| (id, recipes.cooking_inputs(method)));
+        // Smithing isn't supported, so nothing goes in a smithing table
+        let smithing = [
+            "minecraft:smithing_template",
+            "minecraft:smithing_base",
+            "minecraft:smithing_addition",
+        ]
+        .map(|id| (id, Vec::new()));
+        Self {
+            property_sets: LengthPrefixedVec::new(
+                cooking
+                    .into_iter()
+                    .chain(smithing)
+                    .map(|(id, items)| PropertySet {
+                        id: id.to_string(),
+                        items: LengthPrefixedVec::new(items),
+                    })
+                    .collect(),
+            ),
+            stonecutter_recipes: LengthPrefixedVec::new(Vec::new()),
+        }
+    }
+}
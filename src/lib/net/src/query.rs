@@ -0,0 +1,357 @@
+//! A responder for the UDP query protocol (GameSpy4), used by server lists and monitoring.
+//!
+//! Clients first request a challenge token with a handshake, then send it back with a basic or
+//! full stat request. Tokens are derived from the client's IP with a key that rotates every
+//! [`CHALLENGE_ROTATION`], so a client has to repeat the handshake every now and then. The
+//! previous key is still accepted to avoid rejecting requests made right before a rotation.
+
+use crate::conn_init::MINECRAFT_VERSION;
+use crate::errors::NetError;
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_state::GlobalState;
+use rand::prelude::IndexedRandom;
+use std::hash::{BuildHasher, RandomState};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::{debug, error, info};
+
+/// Every query packet starts with these bytes.
+const MAGIC: [u8; 2] = [0xFE, 0xFD];
+const TYPE_HANDSHAKE: u8 = 9;
+const TYPE_STAT: u8 = 0;
+
+/// How often the challenge token key is rotated.
+pub const CHALLENGE_ROTATION: Duration = Duration::from_secs(30);
+
+/// The padding preceding the key/value section of a full stat response.
+const FULL_STAT_KV_PADDING: &[u8] = b"splitnum\0\x80\0";
+/// The padding preceding the player section of a full stat response.
+const FULL_STAT_PLAYER_PADDING: &[u8] = b"\x01player_\0\0";
+
+/// Hands out challenge tokens bound to the client's IP address.
+pub struct ChallengeTokens {
+    current: RandomState,
+    previous: RandomState,
+}
+
+impl Default for ChallengeTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChallengeTokens {
+    pub fn new() -> Self {
+        Self {
+            current: RandomState::new(),
+            previous: RandomState::new(),
+        }
+    }
+
+    fn token_with(key: &RandomState, ip: IpAddr) -> i32 {
+        // Tokens are sent as decimal strings and parsed as signed ints by some clients
+        (key.hash_one(ip) & 0x7FFF_FFFF) as i32
+    }
+
+    /// The current token for `ip`.
+    pub fn token_for(&self, ip: IpAddr) -> i32 {
+        Self::token_with(&self.current, ip)
+    }
+
+    /// Whether `token` was handed out to `ip` with the current or previous key.
+    pub fn is_valid(&self, ip: IpAddr, token: i32) -> bool {
+        Self::token_with(&self.current, ip) == token
+            || Self::token_with(&self.previous, ip) == token
+    }
+
+    /// Replaces the key, invalidating tokens handed out before the previous rotation.
+    pub fn rotate(&mut self) {
+        self.previous = std::mem::replace(&mut self.current, RandomState::new());
+    }
+}
+
+/// The server information reported in stat responses.
+pub struct QueryInfo {
+    pub motd: String,
+    pub map: String,
+    pub players: Vec<String>,
+    pub max_players: u32,
+    pub host_ip: String,
+    pub host_port: u16,
+    pub plugins: String,
+}
+
+impl QueryInfo {
+    /// Collects the current server information.
+    pub fn collect(state: &GlobalState, plugins: &[String]) -> Self {
+        let config = get_global_config();
+        let motd = config
+            .motd
+            .choose(&mut rand::rng())
+            .cloned()
+            .unwrap_or_default();
+        let players = state
+            .players
+            .player_list
+            .iter()
+            .map(|player| player.value().1.clone())
+            .collect();
+        let server = format!("FerrumC {}", env!("CARGO_PKG_VERSION"));
+        let plugins = if plugins.is_empty() {
+            server
+        } else {
+            format!("{server}: {}", plugins.join("; "))
+        };
+
+        Self {
+            motd,
+            map: config.world.clone(),
+            players,
+            max_players: config.max_players,
+            host_ip: config.host.clone(),
+            host_port: config.port,
+            plugins,
+        }
+    }
+}
+
+/// Builds the response to a query packet from `ip`, or `None` if it should be ignored.
+///
+/// `info` is only called for valid stat requests.
+pub fn handle_query(
+    packet: &[u8],
+    ip: IpAddr,
+    tokens: &ChallengeTokens,
+    info: impl FnOnce() -> QueryInfo,
+) -> Option<Vec<u8>> {
+    let rest = packet.strip_prefix(&MAGIC)?;
+    let (&kind, rest) = rest.split_first()?;
+    let session_id = i32::from_be_bytes(rest.get(..4)?.try_into().ok()?);
+    let rest = &rest[4..];
+
+    let mut out = vec![kind];
+    out.extend_from_slice(&session_id.to_be_bytes());
+
+    match kind {
+        TYPE_HANDSHAKE => {
+            write_string(&mut out, &tokens.token_for(ip).to_string());
+        }
+        TYPE_STAT => {
+            let token = i32::from_be_bytes(rest.get(..4)?.try_into().ok()?);
+            if !tokens.is_valid(ip, token) {
+                return None;
+            }
+            // Full stat requests are padded with 4 extra bytes
+            if rest.len() >= 8 {
+                write_full_stat(&mut out, &info());
+            } else {
+                write_basic_stat(&mut out, &info());
+            }
+        }
+        _ => return None,
+    }
+
+    Some(out)
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(value.as_bytes());
+    out.push(0);
+}
+
+fn write_basic_stat(out: &mut Vec<u8>, info: &QueryInfo) {
+    write_string(out, &info.motd);
+    write_string(out, "SMP");
+    write_string(out, &info.map);
+    write_string(out, &info.players.len().to_string());
+    write_string(out, &info.max_players.to_string());
+    // The port is the only little endian value in the protocol
+    out.extend_from_slice(&info.host_port.to_le_bytes());
+    write_string(out, &info.host_ip);
+}
+
+fn write_full_stat(out: &mut Vec<u8>, info: &QueryInfo) {
+    out.extend_from_slice(FULL_STAT_KV_PADDING);
+    let pairs = [
+        ("hostname", info.motd.clone()),
+        ("gametype", "SMP".to_string()),
+        ("game_id", "MINECRAFT".to_string()),
+        ("version", MINECRAFT_VERSION.to_string()),
+        ("plugins", info.plugins.clone()),
+        ("map", info.map.clone()),
+        ("numplayers", info.players.len().to_string()),
+        ("maxplayers", info.max_players.to_string()),
+        ("hostport", info.host_port.to_string()),
+        ("hostip", info.host_ip.clone()),
+    ];
+    for (key, value) in pairs {
+        write_string(out, key);
+        write_string(out, &value);
+    }
+    out.push(0);
+
+    out.extend_from_slice(FULL_STAT_PLAYER_PADDING);
+    for player in &info.players {
+        write_string(out, player);
+    }
+    out.push(0);
+}
+
+/// Runs the query responder until the server shuts down. Does nothing if queries are disabled.
+///
+/// `plugins` are the names of the loaded plugins, reported in full stat responses.
+pub async fn run_query_listener(state: GlobalState, plugins: Vec<String>) -> Result<(), NetError> {
+    let config = get_global_config();
+    if !config.query.enabled {
+        return Ok(());
+    }
+
+    let addy = format!("{}:{}", config.host, config.query.port);
+    let socket = match UdpSocket::bind(&addy).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            error!("Failed to bind query listener to {}", addy);
+            return Err(e.into());
+        }
+    };
+    info!("Query listening on {}", addy);
+
+    let mut tokens = ChallengeTokens::new();
+    let mut rotation = tokio::time::interval(CHALLENGE_ROTATION);
+    // Requests are tiny, anything bigger is ignored anyway
+    let mut buf = [0u8; 64];
+
+    while !state.shut_down.load(Ordering::Relaxed) {
+        tokio::select! {
+            _ = rotation.tick() => tokens.rotate(),
+            received = socket.recv_from(&mut buf) => {
+                let (len, addr): (usize, SocketAddr) = match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        debug!("Failed to receive query packet: {}", e);
+                        continue;
+                    }
+                };
+                let response = handle_query(&buf[..len], addr.ip(), &tokens, || {
+                    QueryInfo::collect(&state, &plugins)
+                });
+                if let Some(response) = response {
+                    if let Err(e) = socket.send_to(&response, addr).await {
+                        debug!("Failed to send query response to {}: {}", addr, e);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    fn info() -> QueryInfo {
+        QueryInfo {
+            motd: "A FerrumC server".to_string(),
+            map: "world".to_string(),
+            players: vec!["Notch".to_string(), "jeb_".to_string()],
+            max_players: 20,
+            host_ip: "127.0.0.1".to_string(),
+            host_port: 25565,
+            plugins: "FerrumC".to_string(),
+        }
+    }
+
+    fn request(kind: u8, token: Option<i32>, full: bool) -> Vec<u8> {
+        let mut packet = vec![0xFE, 0xFD, kind, 0, 0, 0, 1];
+        if let Some(token) = token {
+            packet.extend_from_slice(&token.to_be_bytes());
+        }
+        if full {
+            packet.extend_from_slice(&[0; 4]);
+        }
+        packet
+    }
+
+    fn handshake(tokens: &ChallengeTokens) -> i32 {
+        let response = handle_query(&request(TYPE_HANDSHAKE, None, false), IP, tokens, info)
+            .expect("handshake should be answered");
+        assert_eq!(&response[..5], &[TYPE_HANDSHAKE, 0, 0, 0, 1]);
+        std::str::from_utf8(&response[5..response.len() - 1])
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_basic_stat() {
+        let tokens = ChallengeTokens::new();
+        let token = handshake(&tokens);
+
+        let response = handle_query(&request(TYPE_STAT, Some(token), false), IP, &tokens, info)
+            .expect("basic stat should be answered");
+        let mut expected = vec![TYPE_STAT, 0, 0, 0, 1];
+        expected.extend_from_slice(b"A FerrumC server\0SMP\0world\x002\x0020\0");
+        expected.extend_from_slice(&25565u16.to_le_bytes());
+        expected.extend_from_slice(b"127.0.0.1\0");
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_full_stat() {
+        let tokens = ChallengeTokens::new();
+        let token = handshake(&tokens);
+
+        let response = handle_query(&request(TYPE_STAT, Some(token), true), IP, &tokens, info)
+            .expect("full stat should be answered");
+        let body = &response[5..];
+        assert!(body.starts_with(FULL_STAT_KV_PADDING));
+        let players_start = body
+            .windows(FULL_STAT_PLAYER_PADDING.len())
+            .position(|w| w == FULL_STAT_PLAYER_PADDING)
+            .unwrap();
+        assert_eq!(
+            &body[players_start + FULL_STAT_PLAYER_PADDING.len()..],
+            b"Notch\0jeb_\0\0"
+        );
+        let kv = &body[FULL_STAT_KV_PADDING.len()..players_start];
+        assert!(kv.windows(12).any(|w| w == b"numplayers\x002"));
+        assert!(kv.ends_with(b"127.0.0.1\0\0"));
+    }
+
+    #[test]
+    fn test_challenge_tokens() {
+        let mut tokens = ChallengeTokens::new();
+        let token = handshake(&tokens);
+        let other_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        assert!(!tokens.is_valid(other_ip, token));
+        assert!(handle_query(
+            &request(TYPE_STAT, Some(token), false),
+            other_ip,
+            &tokens,
+            info
+        )
+        .is_none());
+
+        // The previous key stays valid for one rotation
+        tokens.rotate();
+        assert!(tokens.is_valid(IP, token));
+        tokens.rotate();
+        assert!(!tokens.is_valid(IP, token));
+        assert!(handle_query(&request(TYPE_STAT, Some(token), false), IP, &tokens, info).is_none());
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        let tokens = ChallengeTokens::new();
+        assert!(handle_query(&[], IP, &tokens, info).is_none());
+        assert!(handle_query(&[0xFE, 0xFD, TYPE_STAT], IP, &tokens, info).is_none());
+        assert!(handle_query(&request(7, None, false), IP, &tokens, info).is_none());
+    }
+}
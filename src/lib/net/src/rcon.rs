@@ -0,0 +1,318 @@
+//! A listener for the Source RCON protocol, used for remote administration.
+//!
+//! Clients authenticate with the password from the `[rcon]` config section and can then run
+//! commands. Commands are handed to the game loop as [`RconCommand`]s, which dispatches them
+//! with [`ferrumc_commands::Sender::Rcon`] and answers with the captured output.
+
+use crate::errors::NetError;
+use bevy_ecs::prelude::Resource;
+use crossbeam_channel::{Receiver, Sender};
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_state::GlobalState;
+use std::sync::atomic::Ordering;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, warn};
+
+/// A response to a command request, possibly split across several packets.
+pub const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+/// A command request from an authenticated client.
+pub const SERVERDATA_EXECCOMMAND: i32 = 2;
+/// The response to an authentication request. The id is `-1` if it failed.
+pub const SERVERDATA_AUTH_RESPONSE: i32 = 2;
+/// An authentication request carrying the password.
+pub const SERVERDATA_AUTH: i32 = 3;
+
+/// The smallest valid packet: id, type and two null terminators.
+const MIN_PACKET_LENGTH: i32 = 10;
+/// Requests are small, so anything bigger than this is treated as garbage.
+const MAX_PACKET_LENGTH: i32 = 4096;
+/// Responses longer than this are split into several packets.
+const MAX_RESPONSE_FRAGMENT: usize = 4096;
+
+/// A single RCON packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RconPacket {
+    pub id: i32,
+    pub kind: i32,
+    pub body: String,
+}
+
+impl RconPacket {
+    pub fn new(id: i32, kind: i32, body: impl Into<String>) -> Self {
+        Self {
+            id,
+            kind,
+            body: body.into(),
+        }
+    }
+
+    /// Reads a packet, returning `None` if the connection was closed cleanly in between packets.
+    pub async fn read<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<Self>, NetError> {
+        let length = match reader.read_i32_le().await {
+            Ok(length) => length,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if !(MIN_PACKET_LENGTH..=MAX_PACKET_LENGTH).contains(&length) {
+            return Err(NetError::InvalidRconPacket(format!(
+                "invalid length {length}"
+            )));
+        }
+
+        let id = reader.read_i32_le().await?;
+        let kind = reader.read_i32_le().await?;
+        let mut body = vec![0u8; length as usize - 8];
+        reader.read_exact(&mut body).await?;
+
+        // The body and the packet are both null terminated
+        let Some(end) = body.iter().position(|&b| b == 0) else {
+            return Err(NetError::InvalidRconPacket(
+                "missing null terminator".to_string(),
+            ));
+        };
+        body.truncate(end);
+
+        Ok(Some(Self {
+            id,
+            kind,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }))
+    }
+
+    /// Writes this packet.
+    pub async fn write<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<(), NetError> {
+        let body = self.body.as_bytes();
+        let mut buf = Vec::with_capacity(body.len() + 14);
+        buf.extend_from_slice(&(body.len() as i32 + MIN_PACKET_LENGTH).to_le_bytes());
+        buf.extend_from_slice(&self.id.to_le_bytes());
+        buf.extend_from_slice(&self.kind.to_le_bytes());
+        buf.extend_from_slice(body);
+        buf.extend_from_slice(&[0, 0]);
+        writer.write_all(&buf).await?;
+        Ok(())
+    }
+}
+
+/// A command received over RCON, waiting for the game loop to run it.
+pub struct RconCommand {
+    pub command: String,
+    /// Receives the output of the command.
+    pub response: oneshot::Sender<String>,
+}
+
+/// Commands received over RCON.
+#[derive(Resource)]
+pub struct RconCommandReceiver(pub Receiver<RconCommand>);
+
+/// Runs the RCON listener until the server shuts down. Does nothing if RCON is disabled.
+pub async fn run_rcon_listener(
+    state: GlobalState,
+    commands: Sender<RconCommand>,
+) -> Result<(), NetError> {
+    let config = get_global_config();
+    if !config.rcon.enabled {
+        return Ok(());
+    }
+    if config.rcon.password.is_empty() {
+        warn!("RCON is enabled but no password is set, not starting the RCON listener");
+        return Ok(());
+    }
+
+    let addy = format!("{}:{}", config.host, config.rcon.port);
+    let listener = match TcpListener::bind(&addy).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to bind RCON listener to {}", addy);
+            return Err(e.into());
+        }
+    };
+    info!("RCON listening on {}", addy);
+
+    while !state.shut_down.load(Ordering::Relaxed) {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                debug!("Got RCON connection from {}", peer);
+                let commands = commands.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_rcon_connection(stream, commands).await {
+                        if !matches!(e, NetError::ConnectionDropped) {
+                            warn!("RCON connection from {} failed: {}", peer, e);
+                        }
+                    }
+                });
+            }
+            Err(e) => error!("Failed to accept RCON connection: {:?}", e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_rcon_connection(
+    mut stream: TcpStream,
+    commands: Sender<RconCommand>,
+) -> Result<(), NetError> {
+    let password = &get_global_config().rcon.password;
+    handle_rcon_session(&mut stream, password, commands).await
+}
+
+/// Compares the `given` password in constant time, so it can't be guessed byte by byte from
+/// how long the answer takes.
+fn passwords_match(given: &str, password: &str) -> bool {
+    let (given, password) = (given.as_bytes(), password.as_bytes());
+    let diff = given
+        .iter()
+        .zip(password)
+        .fold(given.len() ^ password.len(), |diff, (a, b)| {
+            diff | usize::from(a ^ b)
+        });
+    diff == 0
+}
+
+/// Answers requests on a single connection until it is closed.
+async fn handle_rcon_session<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    password: &str,
+    commands: Sender<RconCommand>,
+) -> Result<(), NetError> {
+    let mut authenticated = false;
+
+    while let Some(packet) = RconPacket::read(stream).await? {
+        match packet.kind {
+            SERVERDATA_AUTH => {
+                authenticated = passwords_match(&packet.body, password);
+                let id = if authenticated { packet.id } else { -1 };
+                RconPacket::new(id, SERVERDATA_AUTH_RESPONSE, "")
+                    .write(stream)
+                    .await?;
+            }
+            SERVERDATA_EXECCOMMAND if authenticated => {
+                let (response, output) = oneshot::channel();
+                let command = packet.body.strip_prefix('/').unwrap_or(&packet.body);
+                commands
+                    .send(RconCommand {
+                        command: command.to_string(),
+                        response,
+                    })
+                    .map_err(|_| NetError::ConnectionDropped)?;
+                let output = output.await.unwrap_or_default();
+                write_response(stream, packet.id, &output).await?;
+            }
+            SERVERDATA_EXECCOMMAND => {
+                RconPacket::new(-1, SERVERDATA_AUTH_RESPONSE, "")
+                    .write(stream)
+                    .await?;
+            }
+            kind => {
+                write_response(stream, packet.id, &format!("Unknown request {kind:x}")).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_response<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    id: i32,
+    output: &str,
+) -> Result<(), NetError> {
+    if output.is_empty() {
+        return RconPacket::new(id, SERVERDATA_RESPONSE_VALUE, "")
+            .write(writer)
+            .await;
+    }
+
+    let mut rest = output;
+    while !rest.is_empty() {
+        let mut split = rest.len().min(MAX_RESPONSE_FRAGMENT);
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (fragment, remaining) = rest.split_at(split);
+        RconPacket::new(id, SERVERDATA_RESPONSE_VALUE, fragment)
+            .write(writer)
+            .await?;
+        rest = remaining;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_packet_roundtrip() {
+        let packet = RconPacket::new(7, SERVERDATA_EXECCOMMAND, "echo hi");
+        let mut buf = Vec::new();
+        packet.write(&mut buf).await.unwrap();
+        assert_eq!(&buf[..4], &17i32.to_le_bytes());
+
+        let read = RconPacket::read(&mut buf.as_slice()).await.unwrap();
+        assert_eq!(read, Some(packet));
+        assert_eq!(RconPacket::read(&mut [].as_slice()).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_passwords_match() {
+        assert!(passwords_match("hunter2", "hunter2"));
+        assert!(!passwords_match("hunter", "hunter2"));
+        assert!(!passwords_match("hunter22", "hunter2"));
+        assert!(!passwords_match("hunter3", "hunter2"));
+        assert!(!passwords_match("", "hunter2"));
+    }
+
+    #[tokio::test]
+    async fn test_session() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        let (commands, command_recv) = crossbeam_channel::unbounded();
+        let session =
+            tokio::spawn(
+                async move { handle_rcon_session(&mut server, "hunter2", commands).await },
+            );
+
+        // Commands are refused until authenticated
+        RconPacket::new(1, SERVERDATA_EXECCOMMAND, "echo hi")
+            .write(&mut client)
+            .await
+            .unwrap();
+        let response = RconPacket::read(&mut client).await.unwrap().unwrap();
+        assert_eq!(response.id, -1);
+
+        RconPacket::new(2, SERVERDATA_AUTH, "wrong")
+            .write(&mut client)
+            .await
+            .unwrap();
+        let response = RconPacket::read(&mut client).await.unwrap().unwrap();
+        assert_eq!((response.id, response.kind), (-1, SERVERDATA_AUTH_RESPONSE));
+
+        RconPacket::new(3, SERVERDATA_AUTH, "hunter2")
+            .write(&mut client)
+            .await
+            .unwrap();
+        let response = RconPacket::read(&mut client).await.unwrap().unwrap();
+        assert_eq!((response.id, response.kind), (3, SERVERDATA_AUTH_RESPONSE));
+
+        RconPacket::new(4, SERVERDATA_EXECCOMMAND, "/echo hi")
+            .write(&mut client)
+            .await
+            .unwrap();
+        let command = tokio::task::spawn_blocking(move || command_recv.recv().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(command.command, "echo hi");
+        command.response.send("Rcon said: hi".to_string()).unwrap();
+        let response = RconPacket::read(&mut client).await.unwrap().unwrap();
+        assert_eq!(
+            response,
+            RconPacket::new(4, SERVERDATA_RESPONSE_VALUE, "Rcon said: hi")
+        );
+
+        drop(client);
+        session.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+use crate::errors::PluginsError;
+use bevy_ecs::event::{Event, EventRegistry};
+use bevy_ecs::prelude::{Resource, World};
+use bevy_ecs::schedule::{IntoScheduleConfigs, Schedule, ScheduleConfigs};
+use bevy_ecs::system::ScheduleSystem;
+use ferrumc_commands::Command;
+use ferrumc_scheduler::{Scheduler, TimedSchedule};
+use std::sync::Arc;
+
+/// Gives a plugin access to the server while it goes through its lifecycle hooks.
+///
+/// Schedules are looked up by the name they were registered with in the game loop
+/// (e.g. `"tick"`, `"world_sync"`, `"keepalive"`).
+///
+/// Systems and schedules are only added once the hook returns successfully, so a plugin that
+/// fails halfway doesn't leave any of them behind. Resources, events and commands are registered
+/// right away and stay registered even if the hook fails.
+pub struct PluginBuilder<'a> {
+    plugin: String,
+    world: &'a mut World,
+    scheduler: &'a mut Scheduler,
+    shutdown_schedule: &'a mut Schedule,
+    timed_schedules: Vec<TimedSchedule>,
+    systems: Vec<(String, ScheduleConfigs<ScheduleSystem>)>,
+    shutdown_systems: Vec<ScheduleConfigs<ScheduleSystem>>,
+}
+
+impl<'a> PluginBuilder<'a> {
+    pub(crate) fn new(
+        plugin: impl Into<String>,
+        world: &'a mut World,
+        scheduler: &'a mut Scheduler,
+        shutdown_schedule: &'a mut Schedule,
+    ) -> Self {
+        Self {
+            plugin: plugin.into(),
+            world,
+            scheduler,
+            shutdown_schedule,
+            timed_schedules: Vec::new(),
+            systems: Vec::new(),
+            shutdown_systems: Vec::new(),
+        }
+    }
+
+    /// Adds the schedules and systems collected by the hook to the server. Called once the hook
+    /// has returned successfully.
+    pub(crate) fn finish(self) {
+        for schedule in self.timed_schedules {
+            self.scheduler.register(schedule);
+        }
+        for (name, systems) in self.systems {
+            if let Some(timed) = self
+                .scheduler
+                .schedules
+                .iter_mut()
+                .find(|timed| timed.name == name)
+            {
+                timed.schedule.add_systems(systems);
+            }
+        }
+        for systems in self.shutdown_systems {
+            self.shutdown_schedule.add_systems(systems);
+        }
+    }
+
+    /// Name of the plugin this builder was created for.
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin
+    }
+
+    // ---- Schedules ----
+
+    /// Whether a timed schedule called `name` exists, or is added by this plugin.
+    pub fn has_schedule(&self, name: &str) -> bool {
+        self.scheduler
+            .schedules
+            .iter()
+            .chain(&self.timed_schedules)
+            .any(|timed| timed.name == name)
+    }
+
+    /// Adds systems to an existing timed schedule, such as `"tick"`.
+    pub fn add_systems<M>(
+        &mut self,
+        schedule: &str,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> Result<&mut Self, PluginsError> {
+        if !self.has_schedule(schedule) {
+            return Err(PluginsError::UnknownSchedule {
+                plugin: self.plugin.clone(),
+                schedule: schedule.to_string(),
+            });
+        }
+        self.systems
+            .push((schedule.to_string(), systems.into_configs()));
+        Ok(self)
+    }
+
+    /// Adds systems that run once when the server shuts down.
+    pub fn add_shutdown_systems<M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.shutdown_systems.push(systems.into_configs());
+        self
+    }
+
+    /// Registers a new timed schedule owned by the plugin.
+    pub fn add_timed_schedule(&mut self, schedule: TimedSchedule) -> &mut Self {
+        self.timed_schedules.push(schedule);
+        self
+    }
+
+    // ---- Events ----
+
+    /// Registers an event type so systems can read and write it.
+    pub fn register_event<E: Event>(&mut self) -> &mut Self {
+        EventRegistry::register_event::<E>(self.world);
+        self
+    }
+
+    // ---- Commands ----
+
+    /// Registers a command so it shows up in the command graph and can be dispatched.
+    ///
+    /// Commands declared with the `#[command]` macro are registered automatically, this is for
+    /// commands built at runtime.
+    pub fn register_command(&mut self, command: Arc<Command>) -> &mut Self {
+        ferrumc_commands::infrastructure::register_command(command);
+        self
+    }
+
+    // ---- Resources ----
+
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    pub fn get_resource<R: Resource>(&self) -> Option<&R> {
+        self.world.get_resource::<R>()
+    }
+
+    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<bevy_ecs::prelude::Mut<'_, R>> {
+        self.world.get_resource_mut::<R>()
+    }
+
+    /// Direct access to the ECS world, for anything not covered above.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.world
+    }
+}
@@ -1,7 +1,22 @@
+use crate::plugin::LifecycleStage;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Error)]
 pub enum PluginsError {
-    #[error("Something failed lol")]
-    SomeError,
+    #[error("Plugin '{plugin}' tried to use unknown schedule '{schedule}'")]
+    UnknownSchedule { plugin: String, schedule: String },
+    #[error("A plugin named '{0}' is already registered")]
+    DuplicatePlugin(String),
+    #[error("Plugin '{plugin}' failed to {stage}: {reason}")]
+    LifecycleFailed {
+        plugin: String,
+        stage: LifecycleStage,
+        reason: String,
+    },
+    #[error("IO error: {0}")]
+    Io(String),
+    #[error("Invalid plugin limits: {0}")]
+    InvalidLimits(String),
+    #[error("WASM plugin '{plugin}' failed: {reason}")]
+    Wasm { plugin: String, reason: String },
 }
@@ -1,16 +1,26 @@
-pub mod errors;
-
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+//! # FerrumC plugins
+//!
+//! Lets gameplay code hook into the server without having to fork `src/bin`.
+//!
+//! A plugin implements [`Plugin`] and is registered with [`register_plugin`] before the server
+//! starts. The game loop then drives every registered plugin through its lifecycle:
+//! 1. [`Plugin::on_load`]: Once the ECS world and the timed schedules have been built. This is
+//!    where systems, events, commands and resources should be registered.
+//! 2. [`Plugin::on_enable`]: After every plugin has been loaded, right before the first tick.
+//! 3. [`Plugin::on_disable`]: On shutdown, in reverse load order.
+//!
+//! Every hook receives a [`PluginBuilder`] giving access to the world and the schedules.
+//!
+//! With the `wasm` feature enabled, sandboxed plugins can also be loaded from `.wasm` files,
+//! see the `wasm` module.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub mod builder;
+pub mod errors;
+pub mod manager;
+pub mod plugin;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub use builder::PluginBuilder;
+pub use manager::{drain_registered_plugins, register_plugin, PluginManager};
+pub use plugin::{LifecycleStage, Plugin};
@@ -0,0 +1,256 @@
+use crate::builder::PluginBuilder;
+use crate::errors::PluginsError;
+use crate::plugin::{LifecycleStage, Plugin};
+use bevy_ecs::prelude::World;
+use bevy_ecs::schedule::Schedule;
+use ferrumc_scheduler::Scheduler;
+use std::sync::{Mutex, OnceLock};
+use tracing::{error, info};
+
+static REGISTRY: OnceLock<Mutex<Vec<Box<dyn Plugin>>>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<Vec<Box<dyn Plugin>>> {
+    REGISTRY.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Registers a plugin to be loaded when the game loop starts.
+pub fn register_plugin(plugin: impl Plugin) {
+    let mut reg = registry().lock().expect("plugin registry poisoned");
+    reg.push(Box::new(plugin));
+}
+
+/// Takes every plugin registered with [`register_plugin`] so far.
+pub fn drain_registered_plugins() -> Vec<Box<dyn Plugin>> {
+    let mut reg = registry().lock().expect("plugin registry poisoned");
+    std::mem::take(&mut *reg)
+}
+
+/// Owns the loaded plugins and drives them through their lifecycle.
+#[derive(Default)]
+pub struct PluginManager {
+    plugins: Vec<Box<dyn Plugin>>,
+}
+
+impl PluginManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a plugin. Names have to be unique.
+    pub fn add(&mut self, plugin: Box<dyn Plugin>) -> Result<(), PluginsError> {
+        if self.plugins.iter().any(|p| p.name() == plugin.name()) {
+            return Err(PluginsError::DuplicatePlugin(plugin.name().to_string()));
+        }
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    /// Names of the plugins that are currently loaded.
+    pub fn plugin_names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name()).collect()
+    }
+
+    /// Calls `on_load` on every plugin. Plugins that fail are dropped, and their errors are
+    /// returned.
+    pub fn load_all(
+        &mut self,
+        world: &mut World,
+        scheduler: &mut Scheduler,
+        shutdown_schedule: &mut Schedule,
+    ) -> Vec<PluginsError> {
+        self.run_hook(
+            LifecycleStage::Load,
+            world,
+            scheduler,
+            shutdown_schedule,
+            |plugin, builder| plugin.on_load(builder),
+        )
+    }
+
+    /// Calls `on_enable` on every loaded plugin. Plugins that fail are dropped, and their
+    /// errors are returned.
+    pub fn enable_all(
+        &mut self,
+        world: &mut World,
+        scheduler: &mut Scheduler,
+        shutdown_schedule: &mut Schedule,
+    ) -> Vec<PluginsError> {
+        self.run_hook(
+            LifecycleStage::Enable,
+            world,
+            scheduler,
+            shutdown_schedule,
+            |plugin, builder| plugin.on_enable(builder),
+        )
+    }
+
+    /// Runs a fallible lifecycle `hook` on every plugin, keeping what it registered only for the
+    /// plugins it succeeded for.
+    fn run_hook(
+        &mut self,
+        stage: LifecycleStage,
+        world: &mut World,
+        scheduler: &mut Scheduler,
+        shutdown_schedule: &mut Schedule,
+        hook: impl Fn(&mut Box<dyn Plugin>, &mut PluginBuilder) -> Result<(), PluginsError>,
+    ) -> Vec<PluginsError> {
+        let mut failures = Vec::new();
+        self.plugins.retain_mut(|plugin| {
+            let mut builder =
+                PluginBuilder::new(plugin.name(), world, scheduler, shutdown_schedule);
+            match hook(plugin, &mut builder) {
+                Ok(()) => {
+                    builder.finish();
+                    if stage == LifecycleStage::Load {
+                        info!("Loaded plugin {}", plugin.name());
+                    }
+                    true
+                }
+                Err(e) => {
+                    let failure = PluginsError::LifecycleFailed {
+                        plugin: plugin.name().to_string(),
+                        stage,
+                        reason: e.to_string(),
+                    };
+                    error!("{failure}");
+                    failures.push(failure);
+                    false
+                }
+            }
+        });
+        failures
+    }
+
+    /// Calls `on_disable` on every enabled plugin, in reverse load order.
+    pub fn disable_all(
+        &mut self,
+        world: &mut World,
+        scheduler: &mut Scheduler,
+        shutdown_schedule: &mut Schedule,
+    ) {
+        for plugin in self.plugins.iter_mut().rev() {
+            let mut builder =
+                PluginBuilder::new(plugin.name(), world, scheduler, shutdown_schedule);
+            plugin.on_disable(&mut builder);
+            builder.finish();
+            info!("Disabled plugin {}", plugin.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::prelude::{Event, EventWriter, ResMut, Resource};
+    use ferrumc_scheduler::TimedSchedule;
+    use std::time::Duration;
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[derive(Event)]
+    struct Ping;
+
+    fn count(mut counter: ResMut<Counter>, mut pings: EventWriter<Ping>) {
+        counter.0 += 1;
+        pings.write(Ping);
+    }
+
+    struct CountingPlugin;
+
+    impl Plugin for CountingPlugin {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn on_load(&mut self, builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+            builder
+                .insert_resource(Counter::default())
+                .register_event::<Ping>()
+                .add_systems("tick", count)?;
+            Ok(())
+        }
+
+        fn on_enable(&mut self, builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+            // Resources inserted by any plugin during `on_load` are visible here.
+            builder.get_resource_mut::<Counter>().unwrap().0 = 10;
+            Ok(())
+        }
+    }
+
+    struct BrokenPlugin;
+
+    impl Plugin for BrokenPlugin {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        fn on_load(&mut self, builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+            builder.add_systems("does_not_exist", || {})?;
+            Ok(())
+        }
+    }
+
+    /// Adds a system, then fails.
+    struct HalfBrokenPlugin;
+
+    impl Plugin for HalfBrokenPlugin {
+        fn name(&self) -> &str {
+            "half_broken"
+        }
+
+        fn on_load(&mut self, builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+            builder.add_systems("tick", |mut counter: ResMut<Counter>| counter.0 += 100)?;
+            builder.add_systems("does_not_exist", || {})?;
+            Ok(())
+        }
+    }
+
+    fn setup() -> (World, Scheduler, Schedule) {
+        let mut scheduler = Scheduler::new();
+        scheduler.register(TimedSchedule::new(
+            "tick",
+            Duration::from_millis(50),
+            |_| {},
+        ));
+        (World::new(), scheduler, Schedule::default())
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let (mut world, mut scheduler, mut shutdown) = setup();
+        let mut manager = PluginManager::new();
+        manager.add(Box::new(CountingPlugin)).unwrap();
+        manager.add(Box::new(BrokenPlugin)).unwrap();
+
+        manager.add(Box::new(HalfBrokenPlugin)).unwrap();
+
+        let failures = manager.load_all(&mut world, &mut scheduler, &mut shutdown);
+        assert_eq!(manager.plugin_names(), vec!["counting"]);
+        assert!(failures.iter().all(|failure| matches!(
+            failure,
+            PluginsError::LifecycleFailed {
+                stage: LifecycleStage::Load,
+                ..
+            }
+        )));
+        assert_eq!(failures.len(), 2);
+
+        manager.enable_all(&mut world, &mut scheduler, &mut shutdown);
+        scheduler.schedules[0].schedule.run(&mut world);
+        scheduler.schedules[0].schedule.run(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 12);
+
+        manager.disable_all(&mut world, &mut scheduler, &mut shutdown);
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let mut manager = PluginManager::new();
+        manager.add(Box::new(BrokenPlugin)).unwrap();
+        assert!(matches!(
+            manager.add(Box::new(BrokenPlugin)),
+            Err(PluginsError::DuplicatePlugin(_))
+        ));
+    }
+}
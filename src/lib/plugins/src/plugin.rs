@@ -0,0 +1,44 @@
+use crate::builder::PluginBuilder;
+use crate::errors::PluginsError;
+use std::fmt;
+
+/// The lifecycle hooks a plugin goes through, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleStage {
+    Load,
+    Enable,
+    Disable,
+}
+
+impl fmt::Display for LifecycleStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LifecycleStage::Load => "load",
+            LifecycleStage::Enable => "enable",
+            LifecycleStage::Disable => "disable",
+        })
+    }
+}
+
+/// A server plugin.
+///
+/// All hooks have empty default implementations, so a plugin only needs to implement the
+/// ones it cares about. Returning an error from `on_load` or `on_enable` is logged and the plugin
+/// is skipped for the remaining stages; the server keeps running.
+pub trait Plugin: Send + 'static {
+    /// Unique name of the plugin, used in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Called once the ECS world and the timed schedules have been built.
+    fn on_load(&mut self, _builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+        Ok(())
+    }
+
+    /// Called after every plugin has been loaded, right before the game loop starts.
+    fn on_enable(&mut self, _builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+        Ok(())
+    }
+
+    /// Called when the server shuts down, before the shutdown schedule runs.
+    fn on_disable(&mut self, _builder: &mut PluginBuilder) {}
+}
@@ -0,0 +1,152 @@
+use bevy_ecs::entity::Entity;
+use ferrumc_core::mq;
+use ferrumc_state::GlobalState;
+use ferrumc_text::TextComponent;
+use ferrumc_world::block_state_id::BlockStateId;
+use std::str::FromStr;
+use wasmi::{Caller, Error, Extern, Linker, StoreLimits};
+
+/// Name of the module host functions are imported from.
+const HOST_MODULE: &str = "ferrumc";
+
+/// Packet events a plugin can subscribe to with `subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum PacketEvent {
+    /// Movement and rotation packets, delivered to `on_transform`.
+    Transform = 0,
+    /// Command packets, delivered to `on_command`.
+    Command = 1,
+}
+
+impl TryFrom<i32> for PacketEvent {
+    type Error = ();
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PacketEvent::Transform),
+            1 => Ok(PacketEvent::Command),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Data owned by a plugin's store.
+pub(super) struct HostState {
+    pub limits: StoreLimits,
+    /// Only available once the plugin has been loaded into a running server.
+    pub state: Option<GlobalState>,
+    pub transform_subscribed: bool,
+    pub command_subscribed: bool,
+}
+
+impl HostState {
+    pub fn new(limits: StoreLimits) -> Self {
+        Self {
+            limits,
+            state: None,
+            transform_subscribed: false,
+            command_subscribed: false,
+        }
+    }
+}
+
+/// Reads a UTF-8 string out of the guest's `memory` export.
+fn read_string(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let start = usize::try_from(ptr).ok()?;
+    let end = start.checked_add(usize::try_from(len).ok()?)?;
+    let bytes = memory.data(caller).get(start..end)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+fn send_message(
+    caller: Caller<'_, HostState>,
+    ptr: i32,
+    len: i32,
+    receiver: i64,
+    actionbar: i32,
+) -> i32 {
+    let Some(text) = read_string(&caller, ptr, len) else {
+        return -1;
+    };
+    let message = TextComponent::from_str(&text).unwrap_or_else(|_| TextComponent::from(text));
+
+    if receiver < 0 {
+        mq::broadcast(message, actionbar != 0);
+    } else {
+        let Ok(entity) = Entity::try_from_bits(receiver as u64) else {
+            return -1;
+        };
+        mq::queue(message, actionbar != 0, entity);
+    }
+    0
+}
+
+fn get_block(
+    caller: Caller<'_, HostState>,
+    x: i32,
+    y: i32,
+    z: i32,
+    dim_ptr: i32,
+    dim_len: i32,
+) -> i32 {
+    let Some(dimension) = read_string(&caller, dim_ptr, dim_len) else {
+        return -1;
+    };
+    let Some(state) = &caller.data().state else {
+        return -1;
+    };
+    match state.world.get_block_and_fetch(x, y, z, &dimension) {
+        Ok(block) => block.0 as i32,
+        Err(_) => -1,
+    }
+}
+
+fn set_block(
+    caller: Caller<'_, HostState>,
+    x: i32,
+    y: i32,
+    z: i32,
+    dim_ptr: i32,
+    dim_len: i32,
+    block: i32,
+) -> i32 {
+    let Some(dimension) = read_string(&caller, dim_ptr, dim_len) else {
+        return -1;
+    };
+    let Some(state) = &caller.data().state else {
+        return -1;
+    };
+    let Ok(block) = u32::try_from(block) else {
+        return -1;
+    };
+    match state
+        .world
+        .set_block_and_fetch(x, y, z, &dimension, BlockStateId(block))
+    {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+fn subscribe(mut caller: Caller<'_, HostState>, event: i32) -> i32 {
+    let Ok(event) = PacketEvent::try_from(event) else {
+        return -1;
+    };
+    let data = caller.data_mut();
+    match event {
+        PacketEvent::Transform => data.transform_subscribed = true,
+        PacketEvent::Command => data.command_subscribed = true,
+    }
+    0
+}
+
+/// Defines every host function in the linker.
+pub(super) fn link_host_functions(linker: &mut Linker<HostState>) -> Result<(), Error> {
+    linker.func_wrap(HOST_MODULE, "send_message", send_message)?;
+    linker.func_wrap(HOST_MODULE, "get_block", get_block)?;
+    linker.func_wrap(HOST_MODULE, "set_block", set_block)?;
+    linker.func_wrap(HOST_MODULE, "subscribe", subscribe)?;
+    Ok(())
+}
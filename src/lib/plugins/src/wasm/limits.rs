@@ -0,0 +1,37 @@
+use crate::errors::PluginsError;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Resource limits for a single WASM plugin.
+///
+/// Read from `plugins/<name>.toml` next to `<name>.wasm`; missing keys use the defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WasmLimits {
+    /// Fuel available to each call into the plugin. Roughly one unit per executed instruction.
+    pub fuel: u64,
+    /// Maximum size of the plugin's linear memory, in bytes.
+    pub max_memory: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            max_memory: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl WasmLimits {
+    /// Reads the limits file for the given plugin, falling back to the defaults if there is none.
+    pub fn for_plugin(wasm_path: &Path) -> Result<Self, PluginsError> {
+        let path = wasm_path.with_extension("toml");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            std::fs::read_to_string(&path).map_err(|e| PluginsError::Io(e.to_string()))?;
+        toml::from_str(&contents).map_err(|e| PluginsError::InvalidLimits(e.to_string()))
+    }
+}
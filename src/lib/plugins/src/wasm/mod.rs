@@ -0,0 +1,94 @@
+//! # WASM plugins
+//!
+//! Loads `.wasm` modules from the `plugins/` directory and runs them sandboxed behind the
+//! regular [`Plugin`](crate::Plugin) API. Only compiled with the `wasm` feature.
+//!
+//! ## Guest ABI
+//!
+//! Host functions, imported from the `ferrumc` module:
+//! - `send_message(ptr: i32, len: i32, receiver: i64, actionbar: i32) -> i32`:
+//!   Queues a message through `ferrumc_core::mq`. The string is a JSON text component, or plain
+//!   text if it isn't valid JSON. A negative `receiver` broadcasts to every player.
+//! - `get_block(x: i32, y: i32, z: i32, dim_ptr: i32, dim_len: i32) -> i32`:
+//!   Returns the block state id at the given position, or `-1` on failure.
+//! - `set_block(x: i32, y: i32, z: i32, dim_ptr: i32, dim_len: i32, block: i32) -> i32`:
+//!   Sets a block, returning `0` on success and `-1` on failure.
+//! - `subscribe(event: i32) -> i32`: Subscribes to a packet event (see [`PacketEvent`]).
+//!   Returns `0` on success and `-1` for unknown events.
+//!
+//! Exports, all optional:
+//! - `memory`: Required by any host function taking a string.
+//! - `on_load()`, `on_enable()`, `on_disable()`: Lifecycle hooks.
+//! - `on_transform(entity: i64, x: f64, y: f64, z: f64, yaw: f32, pitch: f32)`:
+//!   A player moved or rotated. Missing values are `NaN`.
+//! - `on_command(entity: i64, ptr: i32, len: i32)`: A player (or the console or RCON,
+//!   `entity = -1`) dispatched a command. Requires an `alloc(len: i32) -> i32` export for the command string.
+//!
+//! Entities are passed around as `Entity::to_bits`.
+//!
+//! ## Limits
+//!
+//! Every call into a plugin gets a fixed amount of fuel, and memory can't grow past a fixed size.
+//! Both can be set per plugin in `plugins/<name>.toml`, see [`WasmLimits`]. A plugin that traps
+//! (for example by running out of fuel) is disabled, so it can't stall the tick schedule.
+
+mod host;
+mod limits;
+mod plugin;
+
+pub use host::PacketEvent;
+pub use limits::WasmLimits;
+pub use plugin::WasmPlugin;
+
+use crate::manager::register_plugin;
+use ferrumc_general_purpose::paths::get_root_path;
+use std::path::Path;
+use tracing::{error, info};
+
+/// Loads every `.wasm` file in `dir`. Plugins that fail to load are logged and skipped.
+pub fn load_wasm_plugins(dir: &Path) -> Vec<WasmPlugin> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("Could not read plugin directory {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect::<Vec<_>>();
+    // Deterministic load order
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match WasmPlugin::from_file(&path) {
+            Ok(plugin) => Some(plugin),
+            Err(e) => {
+                error!("Failed to load WASM plugin {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Loads the WASM plugins in `<root>/plugins` and registers them with [`register_plugin`].
+///
+/// The directory is created if it doesn't exist yet.
+pub fn register_wasm_plugins() {
+    let dir = get_root_path().join("plugins");
+    if !dir.exists() {
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            error!("Could not create plugin directory {}: {}", dir.display(), e);
+            return;
+        }
+    }
+
+    let plugins = load_wasm_plugins(&dir);
+    info!("Found {} WASM plugin(s)", plugins.len());
+    for plugin in plugins {
+        register_plugin(plugin);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::builder::PluginBuilder;
+use crate::errors::PluginsError;
+use crate::plugin::Plugin;
+use crate::wasm::host::{link_host_functions, HostState};
+use crate::wasm::limits::WasmLimits;
+use bevy_ecs::prelude::EventReader;
+use ferrumc_commands::events::CommandDispatchEvent;
+use ferrumc_commands::Sender;
+use ferrumc_net::packets::packet_events::TransformEvent;
+use ferrumc_state::GlobalStateResource;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{error, warn};
+use wasmi::{
+    Config, Engine, Extern, Instance, Linker, Module, Store, StoreLimitsBuilder, WasmParams,
+};
+
+/// A plugin backed by a WASM module.
+pub struct WasmPlugin {
+    name: String,
+    runtime: Arc<Mutex<WasmRuntime>>,
+}
+
+struct WasmRuntime {
+    name: String,
+    store: Store<HostState>,
+    instance: Instance,
+    fuel: u64,
+    /// Set once the plugin traps. A disabled plugin is never called again.
+    disabled: bool,
+}
+
+impl WasmPlugin {
+    /// Loads a plugin from a `.wasm` file, reading its limits from the `.toml` file next to it.
+    pub fn from_file(path: &Path) -> Result<Self, PluginsError> {
+        let bytes = std::fs::read(path).map_err(|e| PluginsError::Io(e.to_string()))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Self::from_bytes(name, &bytes, WasmLimits::for_plugin(path)?)
+    }
+
+    /// Compiles and instantiates a plugin. The module's start function runs here, under the same
+    /// limits as every other call.
+    pub fn from_bytes(
+        name: impl Into<String>,
+        bytes: &[u8],
+        limits: WasmLimits,
+    ) -> Result<Self, PluginsError> {
+        let name = name.into();
+        let wasm_err = |e: wasmi::Error| PluginsError::Wasm {
+            plugin: name.clone(),
+            reason: e.to_string(),
+        };
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(wasm_err)?;
+
+        let store_limits = StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory)
+            .memories(1)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&engine, HostState::new(store_limits));
+        store.limiter(|data| &mut data.limits);
+        store.set_fuel(limits.fuel).map_err(wasm_err)?;
+
+        let mut linker = Linker::new(&engine);
+        link_host_functions(&mut linker).map_err(wasm_err)?;
+        let instance = linker
+            .instantiate_and_start(&mut store, &module)
+            .map_err(wasm_err)?;
+
+        Ok(Self {
+            name: name.clone(),
+            runtime: Arc::new(Mutex::new(WasmRuntime {
+                name,
+                store,
+                instance,
+                fuel: limits.fuel,
+                disabled: false,
+            })),
+        })
+    }
+
+    /// Whether the plugin has been disabled after trapping.
+    pub fn is_disabled(&self) -> bool {
+        self.runtime.lock().unwrap().disabled
+    }
+}
+
+impl WasmRuntime {
+    /// Calls an exported function. Missing exports are not an error since every export is optional.
+    fn call<P: WasmParams>(&mut self, export: &str, params: P) -> Result<(), PluginsError> {
+        if self.disabled {
+            return Ok(());
+        }
+        let Ok(func) = self.instance.get_typed_func::<P, ()>(&self.store, export) else {
+            return Ok(());
+        };
+        self.call_with_fuel(|store| func.call(store, params))
+    }
+
+    /// Copies `bytes` into guest memory using the guest's `alloc` export.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<Option<i32>, PluginsError> {
+        if self.disabled {
+            return Ok(None);
+        }
+        let Ok(alloc) = self
+            .instance
+            .get_typed_func::<i32, i32>(&self.store, "alloc")
+        else {
+            return Ok(None);
+        };
+        let Some(memory) = self
+            .instance
+            .get_export(&self.store, "memory")
+            .and_then(Extern::into_memory)
+        else {
+            return Ok(None);
+        };
+
+        let len = bytes.len() as i32;
+        let mut ptr = 0;
+        self.call_with_fuel(|store| {
+            ptr = alloc.call(&mut *store, len)?;
+            memory.write(store, ptr as usize, bytes)?;
+            Ok(())
+        })?;
+        Ok(Some(ptr))
+    }
+
+    fn call_with_fuel(
+        &mut self,
+        f: impl FnOnce(&mut Store<HostState>) -> Result<(), wasmi::Error>,
+    ) -> Result<(), PluginsError> {
+        let res = self
+            .store
+            .set_fuel(self.fuel)
+            .and_then(|_| f(&mut self.store));
+        res.map_err(|e| {
+            error!(
+                "WASM plugin {} trapped and has been disabled: {}",
+                self.name, e
+            );
+            self.disabled = true;
+            PluginsError::Wasm {
+                plugin: self.name.clone(),
+                reason: e.to_string(),
+            }
+        })
+    }
+
+    fn dispatch_transform(&mut self, event: &TransformEvent) {
+        if !self.store.data().transform_subscribed {
+            return;
+        }
+        let (x, y, z) = event
+            .position
+            .as_ref()
+            .map(|pos| (pos.x, pos.y, pos.z))
+            .unwrap_or((f64::NAN, f64::NAN, f64::NAN));
+        let (yaw, pitch) = event
+            .rotation
+            .as_ref()
+            .map(|rot| (rot.yaw, rot.pitch))
+            .unwrap_or((f32::NAN, f32::NAN));
+        let entity = event.entity.to_bits() as i64;
+        // Errors are already logged and disable the plugin
+        let _ = self.call("on_transform", (entity, x, y, z, yaw, pitch));
+    }
+
+    fn dispatch_command(&mut self, event: &CommandDispatchEvent) {
+        if !self.store.data().command_subscribed {
+            return;
+        }
+        let entity = match event.sender {
+            Sender::Player(entity) => entity.to_bits() as i64,
+            Sender::Server | Sender::Rcon(_) => -1,
+        };
+        match self.write_bytes(event.command.as_bytes()) {
+            Ok(Some(ptr)) => {
+                let _ = self.call("on_command", (entity, ptr, event.command.len() as i32));
+            }
+            Ok(None) => warn!(
+                "WASM plugin {} subscribed to commands but doesn't export `alloc` and `memory`",
+                self.name
+            ),
+            Err(_) => {}
+        }
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_load(&mut self, builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+        let state = builder
+            .get_resource::<GlobalStateResource>()
+            .map(|res| res.0.clone());
+        {
+            let mut runtime = self.runtime.lock().unwrap();
+            runtime.store.data_mut().state = state;
+            runtime.call("on_load", ())?;
+        }
+
+        let runtime = self.runtime.clone();
+        builder.add_systems(
+            "tick",
+            move |mut transforms: EventReader<TransformEvent>,
+                  mut commands: EventReader<CommandDispatchEvent>| {
+                let mut runtime = runtime.lock().unwrap();
+                for event in transforms.read() {
+                    runtime.dispatch_transform(event);
+                }
+                for event in commands.read() {
+                    runtime.dispatch_command(event);
+                }
+            },
+        )?;
+        Ok(())
+    }
+
+    fn on_enable(&mut self, _builder: &mut PluginBuilder) -> Result<(), PluginsError> {
+        self.runtime.lock().unwrap().call("on_enable", ())
+    }
+
+    fn on_disable(&mut self, _builder: &mut PluginBuilder) {
+        let _ = self.runtime.lock().unwrap().call("on_disable", ());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::event::EventRegistry;
+    use bevy_ecs::prelude::World;
+    use bevy_ecs::schedule::Schedule;
+    use ferrumc_core::mq;
+    use ferrumc_core::transform::position::Position;
+    use ferrumc_scheduler::{Scheduler, TimedSchedule};
+    use ferrumc_text::TextComponent;
+    use std::time::Duration;
+
+    fn setup() -> (World, Scheduler, Schedule) {
+        let mut world = World::new();
+        EventRegistry::register_event::<TransformEvent>(&mut world);
+        EventRegistry::register_event::<CommandDispatchEvent>(&mut world);
+        let mut scheduler = Scheduler::new();
+        scheduler.register(TimedSchedule::new(
+            "tick",
+            Duration::from_millis(50),
+            |_| {},
+        ));
+        (world, scheduler, Schedule::default())
+    }
+
+    fn plugin(wat: &str, limits: WasmLimits) -> Result<WasmPlugin, PluginsError> {
+        // wasmi parses the text format too, which keeps these readable
+        WasmPlugin::from_bytes("test", wat.as_bytes(), limits)
+    }
+
+    #[test]
+    fn test_transform_subscription_sends_message() {
+        let mut plugin = plugin(
+            r#"(module
+                (import "ferrumc" "subscribe" (func $subscribe (param i32) (result i32)))
+                (import "ferrumc" "send_message" (func $send (param i32 i32 i64 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "wasm moved")
+                (func (export "on_load") (drop (call $subscribe (i32.const 0))))
+                (func (export "on_transform") (param i64 f64 f64 f64 f32 f32)
+                    (drop (call $send (i32.const 0) (i32.const 10) (local.get 0) (i32.const 0)))))"#,
+            WasmLimits::default(),
+        )
+        .unwrap();
+
+        let (mut world, mut scheduler, mut shutdown) = setup();
+        let mut builder = PluginBuilder::new("test", &mut world, &mut scheduler, &mut shutdown);
+        plugin.on_load(&mut builder).unwrap();
+        builder.finish();
+
+        let entity = world.spawn_empty().id();
+        world.send_event(TransformEvent::new(entity).position(Position::new(1.0, 2.0, 3.0)));
+        scheduler.schedules[0].schedule.run(&mut world);
+
+        let entry = std::iter::from_fn(|| mq::QUEUE.pop())
+            .find(|entry| entry.message == TextComponent::from("wasm moved"))
+            .expect("plugin should have queued a message");
+        assert_eq!(entry.receiver, Some(entity));
+        assert!(!plugin.is_disabled());
+    }
+
+    #[test]
+    fn test_out_of_fuel_disables_plugin() {
+        let mut plugin = plugin(
+            r#"(module (func (export "on_enable") (loop (br 0))))"#,
+            WasmLimits {
+                fuel: 10_000,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let (mut world, mut scheduler, mut shutdown) = setup();
+        let mut builder = PluginBuilder::new("test", &mut world, &mut scheduler, &mut shutdown);
+        assert!(plugin.on_enable(&mut builder).is_err());
+        assert!(plugin.is_disabled());
+        // Further calls are no-ops
+        assert!(plugin.on_enable(&mut builder).is_ok());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let limits = WasmLimits {
+            max_memory: 64 * 1024,
+            ..Default::default()
+        };
+        assert!(plugin("(module (memory 1))", limits.clone()).is_ok());
+        assert!(plugin("(module (memory 2))", limits).is_err());
+    }
+
+    #[test]
+    fn test_blocks_without_world() {
+        let mut plugin = plugin(
+            r#"(module
+                (import "ferrumc" "get_block" (func $get (param i32 i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "overworld")
+                (func (export "on_enable")
+                    (if (i32.ne (call $get (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 9)) (i32.const -1))
+                        (then unreachable))))"#,
+            WasmLimits::default(),
+        )
+        .unwrap();
+
+        let (mut world, mut scheduler, mut shutdown) = setup();
+        let mut builder = PluginBuilder::new("test", &mut world, &mut scheduler, &mut shutdown);
+        assert!(plugin.on_enable(&mut builder).is_ok());
+    }
+}
@@ -0,0 +1,197 @@
+//! Recipes and item tags in vanilla's data pack format.
+//!
+//! The bundled recipes are only a starting set. Any directory laid out like a data pack's `data`
+//! folder can be added on top, such as the one in the vanilla server jar:
+//!
+//! ```text
+//! <namespace>/recipe/<path>.json     the recipe <namespace>:<path>
+//! <namespace>/tags/item/<path>.json  the item tag <namespace>:<path>
+//! ```
+
+use crate::errors::RecipeError;
+use ferrumc_inventories::item::ItemID;
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Recipes and item tags keyed by name, before they're parsed into a registry.
+#[derive(Default)]
+pub struct RecipeData {
+    pub recipes: BTreeMap<String, serde_json::Value>,
+    /// Items and other `#` tags, by tag name.
+    pub tags: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct RawTag {
+    #[serde(default)]
+    replace: bool,
+    values: Vec<RawTagEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTagEntry {
+    Id(String),
+    Entry {
+        id: String,
+        #[serde(default = "required")]
+        required: bool,
+    },
+}
+
+fn required() -> bool {
+    true
+}
+
+impl RecipeData {
+    /// Parses recipes and tags that are each one JSON object keyed by name, like the bundled ones.
+    pub fn from_json(recipes: &str, tags: &str) -> Result<Self, RecipeError> {
+        Ok(Self {
+            recipes: serde_json::from_str(recipes)?,
+            tags: serde_json::from_str(tags)?,
+        })
+    }
+
+    /// Adds every recipe and item tag in `dir`, replacing recipes with the same name. Tags are
+    /// added to unless they set `replace`. Files that can't be read are skipped with a warning.
+    pub fn read_dir(&mut self, dir: &Path) -> Result<(), RecipeError> {
+        let mut namespaces = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        namespaces.sort();
+        for namespace in namespaces.iter().filter(|path| path.is_dir()) {
+            let Some(name) = namespace.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            for (path, file) in json_files(&namespace.join("tags").join("item"))? {
+                match read::<RawTag>(&file) {
+                    Ok(tag) => self.add_tag(format!("{name}:{path}"), tag),
+                    Err(err) => warn!("Skipping item tag {}: {}", file.display(), err),
+                }
+            }
+            for (path, file) in json_files(&namespace.join("recipe"))? {
+                match read(&file) {
+                    Ok(recipe) => {
+                        self.recipes.insert(format!("{name}:{path}"), recipe);
+                    }
+                    Err(err) => warn!("Skipping recipe {}: {}", file.display(), err),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_tag(&mut self, name: String, tag: RawTag) {
+        let entries = self.tags.entry(name).or_default();
+        if tag.replace {
+            entries.clear();
+        }
+        for entry in tag.values {
+            match entry {
+                RawTagEntry::Id(id) => entries.push(id),
+                // Optional items that don't exist are left out instead of failing every recipe
+                // using the tag
+                RawTagEntry::Entry {
+                    id,
+                    required: false,
+                } if !id.starts_with('#') && ItemID::from_name(&id).is_none() => {}
+                RawTagEntry::Entry { id, .. } => entries.push(id),
+            }
+        }
+    }
+}
+
+fn read<T: serde::de::DeserializeOwned>(file: &Path) -> Result<T, RecipeError> {
+    Ok(serde_json::from_slice(&std::fs::read(file)?)?)
+}
+
+/// Every `.json` file under `dir` with its path relative to `dir`, without the extension.
+/// A missing directory has none.
+fn json_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, RecipeError> {
+    let mut files = Vec::new();
+    if dir.is_dir() {
+        collect_json_files(dir, "", &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_json_files(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<(), RecipeError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            let prefix = format!("{prefix}{name}/");
+            collect_json_files(&path, &prefix, files)?;
+        } else if let Some(stem) = name.strip_suffix(".json") {
+            files.push((format!("{prefix}{stem}"), path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RecipeRegistry;
+    use ferrumc_inventories::slot::InventorySlot;
+    use ferrumc_inventories::window::Recipes;
+
+    fn write(dir: &Path, path: &str, contents: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn item(name: &str) -> Option<InventorySlot> {
+        Some(InventorySlot::new(ItemID::from_name(name).unwrap(), 1))
+    }
+
+    #[test]
+    fn test_read_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "test/recipe/nested/dirt.json",
+            r##"{"type": "minecraft:crafting_shapeless", "ingredients": ["#test:soft"], "result": {"id": "minecraft:dirt", "count": 2}}"##,
+        );
+        write(
+            dir.path(),
+            "test/tags/item/soft.json",
+            r##"{"values": ["minecraft:sand", {"id": "minecraft:nothing", "required": false}, "#minecraft:planks"]}"##,
+        );
+        write(
+            dir.path(),
+            "minecraft/tags/item/planks.json",
+            r#"{"replace": true, "values": ["minecraft:oak_planks"]}"#,
+        );
+        write(dir.path(), "test/recipe/broken.json", "{");
+
+        let mut data = RecipeData::from_json(crate::RECIPES_JSON, crate::ITEM_TAGS_JSON).unwrap();
+        let bundled = data.recipes.len();
+        data.read_dir(dir.path()).unwrap();
+        assert_eq!(data.recipes.len(), bundled + 1);
+        assert_eq!(data.tags["minecraft:planks"], vec!["minecraft:oak_planks"]);
+
+        let registry = RecipeRegistry::from_data(data);
+        let (_, recipe) = registry.find("test:nested/dirt").unwrap();
+        assert_eq!(recipe.result.count.0, 2);
+        let result = registry.craft(&[item("oak_planks"), None, None, None], 2);
+        assert_eq!(result.unwrap().item_id, ItemID::from_name("dirt"));
+        // Only the tag's own planks are left
+        assert_ne!(
+            registry
+                .craft(&[item("birch_planks"), None, None, None], 2)
+                .and_then(|result| result.item_id),
+            ItemID::from_name("dirt")
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! How recipes are shown to the client, which works out nothing about them itself.
+
+use crate::recipe::{Ingredient, Recipe, RecipeKind};
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_net_codec::encode::errors::NetEncodeError;
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// What the client shows in one slot of a recipe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotDisplay {
+    Empty,
+    /// Cycles through everything that burns in a furnace.
+    AnyFuel,
+    Item(ItemID),
+    ItemStack(InventorySlot),
+    /// Cycles through an item tag the client knows.
+    Tag(String),
+    /// An item and what's left of it after crafting.
+    WithRemainder(Box<SlotDisplay>, Box<SlotDisplay>),
+    /// Cycles through several displays.
+    Composite(Vec<SlotDisplay>),
+}
+
+impl SlotDisplay {
+    /// The id of the display's type in the `slot_display` registry.
+    fn type_id(&self) -> i32 {
+        match self {
+            Self::Empty => 0,
+            Self::AnyFuel => 1,
+            Self::Item(_) => 2,
+            Self::ItemStack(_) => 3,
+            Self::Tag(_) => 4,
+            Self::WithRemainder(..) => 6,
+            Self::Composite(_) => 7,
+        }
+    }
+}
+
+impl From<&Ingredient> for SlotDisplay {
+    fn from(ingredient: &Ingredient) -> Self {
+        Self::Composite(
+            ingredient
+                .0
+                .iter()
+                .map(|&item| match item.crafting_remainder() {
+                    Some(remainder) => Self::WithRemainder(
+                        Box::new(Self::Item(item)),
+                        Box::new(Self::Item(remainder)),
+                    ),
+                    None => Self::Item(item),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl NetEncode for SlotDisplay {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        VarInt::new(self.type_id()).encode(writer, opts)?;
+        match self {
+            Self::Empty | Self::AnyFuel => Ok(()),
+            Self::Item(item) => item.encode(writer, opts),
+            Self::ItemStack(stack) => stack.encode(writer, opts),
+            Self::Tag(tag) => tag.encode(writer, opts),
+            Self::WithRemainder(input, remainder) => {
+                input.encode(writer, opts)?;
+                remainder.encode(writer, opts)
+            }
+            Self::Composite(contents) => encode_list(contents, writer, opts),
+        }
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> Result<(), NetEncodeError> {
+        // Composites nest, which async functions can't without boxing
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+/// What the client shows for a whole recipe, in the recipe book or as a ghost recipe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeDisplay {
+    Shapeless {
+        ingredients: Vec<SlotDisplay>,
+        result: SlotDisplay,
+        station: SlotDisplay,
+    },
+    Shaped {
+        width: i32,
+        height: i32,
+        ingredients: Vec<SlotDisplay>,
+        result: SlotDisplay,
+        station: SlotDisplay,
+    },
+    Furnace {
+        ingredient: SlotDisplay,
+        fuel: SlotDisplay,
+        result: SlotDisplay,
+        station: SlotDisplay,
+        duration: i32,
+        experience: f32,
+    },
+}
+
+impl RecipeDisplay {
+    /// The id of the display's type in the `recipe_display` registry.
+    fn type_id(&self) -> i32 {
+        match self {
+            Self::Shapeless { .. } => 0,
+            Self::Shaped { .. } => 1,
+            Self::Furnace { .. } => 2,
+        }
+    }
+}
+
+impl NetEncode for RecipeDisplay {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        VarInt::new(self.type_id()).encode(writer, opts)?;
+        match self {
+            Self::Shapeless {
+                ingredients,
+                result,
+                station,
+            } => {
+                encode_list(ingredients, writer, opts)?;
+                result.encode(writer, opts)?;
+                station.encode(writer, opts)
+            }
+            Self::Shaped {
+                width,
+                height,
+                ingredients,
+                result,
+                station,
+            } => {
+                VarInt::new(*width).encode(writer, opts)?;
+                VarInt::new(*height).encode(writer, opts)?;
+                encode_list(ingredients, writer, opts)?;
+                result.encode(writer, opts)?;
+                station.encode(writer, opts)
+            }
+            Self::Furnace {
+                ingredient,
+                fuel,
+                result,
+                station,
+                duration,
+                experience,
+            } => {
+                ingredient.encode(writer, opts)?;
+                fuel.encode(writer, opts)?;
+                result.encode(writer, opts)?;
+                station.encode(writer, opts)?;
+                VarInt::new(*duration).encode(writer, opts)?;
+                experience.encode(writer, opts)
+            }
+        }
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> Result<(), NetEncodeError> {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+fn encode_list<W: Write>(
+    displays: &[SlotDisplay],
+    writer: &mut W,
+    opts: &NetEncodeOpts,
+) -> Result<(), NetEncodeError> {
+    VarInt::new(displays.len() as i32).encode(writer, opts)?;
+    for display in displays {
+        display.encode(writer, opts)?;
+    }
+    Ok(())
+}
+
+/// A set of items sent by id, which is how the client learns what a recipe needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemSet(pub Vec<ItemID>);
+
+impl NetEncode for ItemSet {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> Result<(), NetEncodeError> {
+        // 0 would mean a tag name follows instead
+        VarInt::new(self.0.len() as i32 + 1).encode(writer, opts)?;
+        for item in &self.0 {
+            item.encode(writer, opts)?;
+        }
+        Ok(())
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> Result<(), NetEncodeError> {
+        VarInt::new(self.0.len() as i32 + 1)
+            .encode_async(writer, opts)
+            .await?;
+        for item in &self.0 {
+            item.encode_async(writer, opts).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Recipe {
+    /// How the client shows the recipe.
+    pub fn display(&self) -> RecipeDisplay {
+        let result = SlotDisplay::ItemStack(self.result.clone());
+        let crafting_table = || {
+            ItemID::from_name("minecraft:crafting_table")
+                .map_or(SlotDisplay::Empty, SlotDisplay::Item)
+        };
+        match &self.kind {
+            RecipeKind::Shaped {
+                width,
+                height,
+                pattern,
+            } => RecipeDisplay::Shaped {
+                width: *width as i32,
+                height: *height as i32,
+                ingredients: pattern
+                    .iter()
+                    .map(|ingredient| ingredient.as_ref().map_or(SlotDisplay::Empty, Into::into))
+                    .collect(),
+                result,
+                station: crafting_table(),
+            },
+            RecipeKind::Shapeless { ingredients } => RecipeDisplay::Shapeless {
+                ingredients: ingredients.iter().map(Into::into).collect(),
+                result,
+                station: crafting_table(),
+            },
+            RecipeKind::Cooking {
+                method,
+                ingredient,
+                experience,
+                time,
+            } => RecipeDisplay::Furnace {
+                ingredient: ingredient.into(),
+                fuel: SlotDisplay::AnyFuel,
+                result,
+                station: ItemID::from_name(method.station())
+                    .map_or(SlotDisplay::Empty, SlotDisplay::Item),
+                duration: *time,
+                experience: *experience,
+            },
+        }
+    }
+
+    /// The ingredients the recipe book checks the player has before showing the recipe as
+    /// craftable.
+    pub fn requirements(&self) -> Vec<ItemSet> {
+        let ingredients: Vec<&Ingredient> = match &self.kind {
+            RecipeKind::Shaped { pattern, .. } => pattern.iter().flatten().collect(),
+            RecipeKind::Shapeless { ingredients } => ingredients.iter().collect(),
+            RecipeKind::Cooking { ingredient, .. } => vec![ingredient],
+        };
+        ingredients
+            .into_iter()
+            .map(|ingredient| ItemSet(ingredient.0.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BUNDLED;
+
+    fn encoded(value: &impl NetEncode) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        value.encode(&mut buffer, &NetEncodeOpts::None).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_encode_slot_display() {
+        assert_eq!(encoded(&SlotDisplay::Item(ItemID::new(5))), vec![2, 5]);
+        let composite = SlotDisplay::Composite(vec![SlotDisplay::Empty, SlotDisplay::AnyFuel]);
+        assert_eq!(encoded(&composite), vec![7, 2, 0, 1]);
+        assert_eq!(encoded(&ItemSet(vec![ItemID::new(3)])), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_display() {
+        let (_, torch) = BUNDLED.find("minecraft:torch").unwrap();
+        let RecipeDisplay::Shaped {
+            width,
+            height,
+            ingredients,
+            ..
+        } = torch.display()
+        else {
+            panic!("Torches are shaped");
+        };
+        assert_eq!((width, height), (1, 2));
+        // Coal or charcoal
+        assert!(matches!(&ingredients[0], SlotDisplay::Composite(items) if items.len() == 2));
+
+        let (_, cake) = BUNDLED.find("minecraft:cake").unwrap();
+        let RecipeDisplay::Shaped { ingredients, .. } = cake.display() else {
+            panic!("Cakes are shaped");
+        };
+        // Milk buckets leave their buckets behind
+        assert!(matches!(
+            &ingredients[0],
+            SlotDisplay::Composite(items) if matches!(items[..], [SlotDisplay::WithRemainder(..)])
+        ));
+        assert_eq!(cake.requirements().len(), 9);
+    }
+}
@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RecipeError {
+    #[error("Could not read recipe data: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid recipe data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown item: {0}")]
+    UnknownItem(String),
+    #[error("Unknown item tag: {0}")]
+    UnknownTag(String),
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+}
@@ -0,0 +1,43 @@
+//! Crafting and cooking recipes, loaded from vanilla's recipe data.
+//!
+//! Vanilla's item tags are bundled with the server, along with its recipes when
+//! `scripts/vanilla_data.py` is run with the server jar, otherwise a small starting set. Any data
+//! pack's recipes are added by copying its `data` folder's contents into the `recipes` directory,
+//! see [`datapack`].
+//!
+//! Recipes are numbered by their place in the registry, which is also the id the recipe book
+//! knows them by.
+
+pub mod datapack;
+pub mod display;
+pub mod errors;
+pub mod recipe;
+pub mod registry;
+
+use crate::datapack::RecipeData;
+use crate::registry::RecipeRegistry;
+use ferrumc_general_purpose::paths::get_root_path;
+use once_cell::sync::Lazy;
+use tracing::error;
+
+const RECIPES_JSON: &str = include_str!("../../../../assets/data/recipes.json");
+const ITEM_TAGS_JSON: &str = include_str!("../../../../assets/data/item_tags.json");
+
+/// The directory next to the server that recipes and item tags are read from.
+pub const RECIPES_DIR: &str = "recipes";
+
+/// Every recipe the server knows: the bundled ones and those in [`RECIPES_DIR`].
+pub static RECIPES: Lazy<RecipeRegistry> = Lazy::new(|| {
+    let mut data =
+        RecipeData::from_json(RECIPES_JSON, ITEM_TAGS_JSON).expect("Failed to load recipes.json");
+    let dir = get_root_path().join(RECIPES_DIR);
+    if let Err(err) = dir.is_dir().then(|| data.read_dir(&dir)).transpose() {
+        error!("Failed to load recipes from {}: {}", dir.display(), err);
+    }
+    RecipeRegistry::from_data(data)
+});
+
+/// The bundled recipes on their own, so tests don't depend on what's in [`RECIPES_DIR`].
+#[cfg(test)]
+static BUNDLED: Lazy<RecipeRegistry> =
+    Lazy::new(|| RecipeRegistry::load(RECIPES_JSON, ITEM_TAGS_JSON).unwrap());
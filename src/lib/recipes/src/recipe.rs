@@ -0,0 +1,231 @@
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+
+/// The items that can go in one slot of a recipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ingredient(pub Vec<ItemID>);
+
+impl Ingredient {
+    pub fn test(&self, item: &InventorySlot) -> bool {
+        !item.is_empty() && item.item_id.is_some_and(|id| self.0.contains(&id))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookingMethod {
+    Smelting,
+    Blasting,
+    Smoking,
+    Campfire,
+}
+
+impl CookingMethod {
+    /// How long cooking takes when the recipe doesn't say, in ticks.
+    pub fn default_time(&self) -> i32 {
+        match self {
+            Self::Smelting => 200,
+            Self::Blasting | Self::Smoking | Self::Campfire => 100,
+        }
+    }
+
+    /// The block the recipe is cooked in.
+    pub fn station(&self) -> &'static str {
+        match self {
+            Self::Smelting => "minecraft:furnace",
+            Self::Blasting => "minecraft:blast_furnace",
+            Self::Smoking => "minecraft:smoker",
+            Self::Campfire => "minecraft:campfire",
+        }
+    }
+}
+
+/// The recipe book tabs, numbered like the `recipe_book_category` registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookCategory {
+    CraftingBuildingBlocks = 0,
+    CraftingRedstone = 1,
+    CraftingEquipment = 2,
+    CraftingMisc = 3,
+    FurnaceFood = 4,
+    FurnaceBlocks = 5,
+    FurnaceMisc = 6,
+    BlastFurnaceBlocks = 7,
+    BlastFurnaceMisc = 8,
+    SmokerFood = 9,
+    Stonecutter = 10,
+    Smithing = 11,
+    Campfire = 12,
+}
+
+impl BookCategory {
+    /// The tab a crafting recipe goes in, from the `category` in its data.
+    pub fn crafting(category: &str) -> Self {
+        match category {
+            "building" => Self::CraftingBuildingBlocks,
+            "redstone" => Self::CraftingRedstone,
+            "equipment" => Self::CraftingEquipment,
+            _ => Self::CraftingMisc,
+        }
+    }
+
+    /// The tab a cooking recipe goes in, from the `category` in its data.
+    pub fn cooking(method: CookingMethod, category: &str) -> Self {
+        match (method, category) {
+            (CookingMethod::Smelting, "food") => Self::FurnaceFood,
+            (CookingMethod::Smelting, "blocks") => Self::FurnaceBlocks,
+            (CookingMethod::Smelting, _) => Self::FurnaceMisc,
+            (CookingMethod::Blasting, "blocks") => Self::BlastFurnaceBlocks,
+            (CookingMethod::Blasting, _) => Self::BlastFurnaceMisc,
+            (CookingMethod::Smoking, _) => Self::SmokerFood,
+            (CookingMethod::Campfire, _) => Self::Campfire,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeKind {
+    /// Ingredients laid out in a pattern, which can also be mirrored.
+    Shaped {
+        width: usize,
+        height: usize,
+        /// Row by row, `None` for slots that must be empty.
+        pattern: Vec<Option<Ingredient>>,
+    },
+    /// Ingredients anywhere in the grid.
+    Shapeless { ingredients: Vec<Ingredient> },
+    /// One item cooked for `time` ticks.
+    Cooking {
+        method: CookingMethod,
+        ingredient: Ingredient,
+        experience: f32,
+        time: i32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub name: String,
+    /// Recipes the recipe book shows together, like all the planks.
+    pub group: Option<String>,
+    pub category: BookCategory,
+    pub kind: RecipeKind,
+    pub result: InventorySlot,
+}
+
+impl Recipe {
+    /// Whether `grid`, a crafting grid `width` slots wide, holds the recipe's ingredients.
+    pub fn matches(&self, grid: &[Option<InventorySlot>], width: usize) -> bool {
+        match &self.kind {
+            RecipeKind::Shaped {
+                width: pattern_width,
+                height,
+                pattern,
+            } => matches_shaped(pattern, *pattern_width, *height, grid, width),
+            RecipeKind::Shapeless { ingredients } => matches_shapeless(ingredients, grid),
+            RecipeKind::Cooking { .. } => false,
+        }
+    }
+
+    /// The items each slot takes to make the recipe in a crafting grid `grid_width` slots wide,
+    /// row by row in rows as long as the returned width. `None` if it isn't made in a crafting
+    /// grid, or doesn't fit in this one.
+    pub fn placement(&self, grid_width: usize) -> Option<(Vec<Option<Vec<ItemID>>>, usize)> {
+        match &self.kind {
+            RecipeKind::Shaped {
+                width,
+                height,
+                pattern,
+            } if *width <= grid_width && *height <= grid_width => Some((
+                pattern
+                    .iter()
+                    .map(|ingredient| ingredient.as_ref().map(|ingredient| ingredient.0.clone()))
+                    .collect(),
+                *width,
+            )),
+            RecipeKind::Shapeless { ingredients }
+                if ingredients.len() <= grid_width * grid_width =>
+            {
+                Some((
+                    ingredients
+                        .iter()
+                        .map(|ingredient| Some(ingredient.0.clone()))
+                        .collect(),
+                    grid_width,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn item_at(grid: &[Option<InventorySlot>], index: usize) -> Option<&InventorySlot> {
+    grid.get(index)?.as_ref().filter(|item| !item.is_empty())
+}
+
+fn matches_shaped(
+    pattern: &[Option<Ingredient>],
+    width: usize,
+    height: usize,
+    grid: &[Option<InventorySlot>],
+    grid_width: usize,
+) -> bool {
+    if grid_width == 0 {
+        return false;
+    }
+    // The pattern can go anywhere, so only the smallest box around the items counts
+    let filled = (0..grid.len())
+        .filter(|&index| item_at(grid, index).is_some())
+        .collect::<Vec<_>>();
+    let (Some(left), Some(right)) = (
+        filled.iter().map(|index| index % grid_width).min(),
+        filled.iter().map(|index| index % grid_width).max(),
+    ) else {
+        return false;
+    };
+    let (top, bottom) = (
+        filled[0] / grid_width,
+        filled[filled.len() - 1] / grid_width,
+    );
+    if right - left + 1 != width || bottom - top + 1 != height {
+        return false;
+    }
+    [false, true].into_iter().any(|mirrored| {
+        (0..height).all(|row| {
+            (0..width).all(|col| {
+                let pattern_col = if mirrored { width - 1 - col } else { col };
+                let item = item_at(grid, (top + row) * grid_width + left + col);
+                match (&pattern[row * width + pattern_col], item) {
+                    (None, None) => true,
+                    (Some(ingredient), Some(item)) => ingredient.test(item),
+                    _ => false,
+                }
+            })
+        })
+    })
+}
+
+fn matches_shapeless(ingredients: &[Ingredient], grid: &[Option<InventorySlot>]) -> bool {
+    let items = (0..grid.len())
+        .filter_map(|index| item_at(grid, index))
+        .collect::<Vec<_>>();
+    items.len() == ingredients.len()
+        && assign(&items, ingredients, &mut vec![false; ingredients.len()])
+}
+
+/// Whether every item can be matched to a different unused ingredient.
+fn assign(items: &[&InventorySlot], ingredients: &[Ingredient], used: &mut [bool]) -> bool {
+    let Some((item, rest)) = items.split_first() else {
+        return true;
+    };
+    for (index, ingredient) in ingredients.iter().enumerate() {
+        if used[index] || !ingredient.test(item) {
+            continue;
+        }
+        used[index] = true;
+        if assign(rest, ingredients, used) {
+            return true;
+        }
+        used[index] = false;
+    }
+    false
+}
@@ -0,0 +1,406 @@
+use crate::datapack::RecipeData;
+use crate::errors::RecipeError;
+use crate::recipe::{BookCategory, CookingMethod, Ingredient, Recipe, RecipeKind};
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_inventories::window::Recipes;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// How deep tags can nest inside each other.
+const MAX_TAG_DEPTH: usize = 16;
+
+/// A recipe as written in vanilla's data packs.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum RawRecipe {
+    #[serde(rename = "minecraft:crafting_shaped")]
+    Shaped {
+        #[serde(default)]
+        category: String,
+        group: Option<String>,
+        key: HashMap<String, RawIngredient>,
+        pattern: Vec<String>,
+        result: RawResult,
+    },
+    #[serde(rename = "minecraft:crafting_shapeless")]
+    Shapeless {
+        #[serde(default)]
+        category: String,
+        group: Option<String>,
+        ingredients: Vec<RawIngredient>,
+        result: RawResult,
+    },
+    #[serde(rename = "minecraft:smelting")]
+    Smelting(RawCooking),
+    #[serde(rename = "minecraft:blasting")]
+    Blasting(RawCooking),
+    #[serde(rename = "minecraft:smoking")]
+    Smoking(RawCooking),
+    #[serde(rename = "minecraft:campfire_cooking")]
+    Campfire(RawCooking),
+    /// Smithing, stonecutting and the special crafting recipes aren't supported.
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Deserialize)]
+struct RawCooking {
+    #[serde(default)]
+    category: String,
+    group: Option<String>,
+    ingredient: RawIngredient,
+    result: RawResult,
+    #[serde(default)]
+    experience: f32,
+    cookingtime: Option<i32>,
+}
+
+/// An item, a `#` tag, or a list of items.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawIngredient {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct RawResult {
+    id: String,
+    #[serde(default = "one")]
+    count: i32,
+}
+
+fn one() -> i32 {
+    1
+}
+
+pub struct RecipeRegistry {
+    recipes: Vec<Recipe>,
+    /// Recipe book group ids, by group name.
+    groups: HashMap<String, i32>,
+}
+
+impl RecipeRegistry {
+    /// Loads recipes in vanilla's data pack format, keyed by name. `#` ingredients are looked up
+    /// in `tags`, lists of items and other tags keyed by tag name. Recipes that can't be loaded
+    /// are skipped with a warning, like vanilla does.
+    pub fn load(recipes: &str, tags: &str) -> Result<Self, RecipeError> {
+        Ok(Self::from_data(RecipeData::from_json(recipes, tags)?))
+    }
+
+    /// Parses recipes read by [`RecipeData`], skipping the ones that can't be loaded.
+    pub fn from_data(data: RecipeData) -> Self {
+        let mut registry = Self {
+            recipes: Vec::with_capacity(data.recipes.len()),
+            groups: HashMap::new(),
+        };
+        for (name, value) in data.recipes {
+            let recipe = serde_json::from_value(value)
+                .map_err(RecipeError::from)
+                .and_then(|recipe| parse(&name, recipe, &data.tags));
+            match recipe {
+                Ok(Some(recipe)) => registry.add(recipe),
+                Ok(None) => {}
+                Err(err) => warn!("Skipping recipe {}: {}", name, err),
+            }
+        }
+        registry
+    }
+
+    fn add(&mut self, recipe: Recipe) {
+        if let Some(group) = &recipe.group {
+            let next = self.groups.len() as i32;
+            self.groups.entry(group.clone()).or_insert(next);
+        }
+        self.recipes.push(recipe);
+    }
+
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Recipe> {
+        self.recipes.get(id)
+    }
+
+    /// A recipe and its id by name, e.g. `minecraft:stick`.
+    pub fn find(&self, name: &str) -> Option<(usize, &Recipe)> {
+        self.iter().find(|(_, recipe)| recipe.name == name)
+    }
+
+    /// Every recipe with its id.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Recipe)> {
+        self.recipes.iter().enumerate()
+    }
+
+    /// The id the recipe book groups the recipe's group by.
+    pub fn group_id(&self, recipe: &Recipe) -> Option<i32> {
+        self.groups.get(recipe.group.as_ref()?).copied()
+    }
+
+    /// The recipe `grid`, a crafting grid `width` slots wide, makes.
+    pub fn find_crafting(&self, grid: &[Option<InventorySlot>], width: usize) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|recipe| recipe.matches(grid, width))
+    }
+
+    /// Every item that can be cooked with `method`.
+    pub fn cooking_inputs(&self, method: CookingMethod) -> Vec<ItemID> {
+        let mut items = self
+            .recipes
+            .iter()
+            .filter_map(|recipe| match &recipe.kind {
+                RecipeKind::Cooking {
+                    method: recipe_method,
+                    ingredient,
+                    ..
+                } if *recipe_method == method => Some(ingredient.0.iter().copied()),
+                _ => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        items.sort_by_key(|item| item.0.0);
+        items.dedup();
+        items
+    }
+}
+
+impl Recipes for RecipeRegistry {
+    fn craft(&self, grid: &[Option<InventorySlot>], width: usize) -> Option<InventorySlot> {
+        self.find_crafting(grid, width)
+            .map(|recipe| recipe.result.clone())
+    }
+}
+
+fn parse(
+    name: &str,
+    recipe: RawRecipe,
+    tags: &HashMap<String, Vec<String>>,
+) -> Result<Option<Recipe>, RecipeError> {
+    let (group, category, kind, result) = match recipe {
+        RawRecipe::Shaped {
+            category,
+            group,
+            key,
+            pattern,
+            result,
+        } => {
+            let height = pattern.len();
+            let width = pattern.first().map_or(0, |row| row.chars().count());
+            if width == 0 || pattern.iter().any(|row| row.chars().count() != width) {
+                return Err(RecipeError::InvalidPattern(name.to_string()));
+            }
+            let pattern = pattern
+                .iter()
+                .flat_map(|row| row.chars())
+                .map(|symbol| match symbol {
+                    ' ' => Ok(None),
+                    symbol => key
+                        .get(&symbol.to_string())
+                        .ok_or_else(|| RecipeError::InvalidPattern(name.to_string()))
+                        .and_then(|ingredient| parse_ingredient(ingredient, tags))
+                        .map(Some),
+                })
+                .collect::<Result<_, _>>()?;
+            let kind = RecipeKind::Shaped {
+                width,
+                height,
+                pattern,
+            };
+            (group, BookCategory::crafting(&category), kind, result)
+        }
+        RawRecipe::Shapeless {
+            category,
+            group,
+            ingredients,
+            result,
+        } => {
+            let ingredients = ingredients
+                .iter()
+                .map(|ingredient| parse_ingredient(ingredient, tags))
+                .collect::<Result<_, _>>()?;
+            let kind = RecipeKind::Shapeless { ingredients };
+            (group, BookCategory::crafting(&category), kind, result)
+        }
+        RawRecipe::Smelting(cooking) => parse_cooking(CookingMethod::Smelting, cooking, tags)?,
+        RawRecipe::Blasting(cooking) => parse_cooking(CookingMethod::Blasting, cooking, tags)?,
+        RawRecipe::Smoking(cooking) => parse_cooking(CookingMethod::Smoking, cooking, tags)?,
+        RawRecipe::Campfire(cooking) => parse_cooking(CookingMethod::Campfire, cooking, tags)?,
+        RawRecipe::Unsupported => return Ok(None),
+    };
+    let result_item = item(&result.id)?;
+    Ok(Some(Recipe {
+        name: name.to_string(),
+        group: group.filter(|group| !group.is_empty()),
+        category,
+        kind,
+        result: InventorySlot::new(result_item, result.count),
+    }))
+}
+
+fn parse_cooking(
+    method: CookingMethod,
+    cooking: RawCooking,
+    tags: &HashMap<String, Vec<String>>,
+) -> Result<(Option<String>, BookCategory, RecipeKind, RawResult), RecipeError> {
+    let kind = RecipeKind::Cooking {
+        method,
+        ingredient: parse_ingredient(&cooking.ingredient, tags)?,
+        experience: cooking.experience,
+        time: cooking.cookingtime.unwrap_or(method.default_time()),
+    };
+    let category = BookCategory::cooking(method, &cooking.category);
+    Ok((cooking.group, category, kind, cooking.result))
+}
+
+fn parse_ingredient(
+    ingredient: &RawIngredient,
+    tags: &HashMap<String, Vec<String>>,
+) -> Result<Ingredient, RecipeError> {
+    let mut items = Vec::new();
+    match ingredient {
+        RawIngredient::One(entry) => add_entry(entry, tags, &mut items, 0)?,
+        RawIngredient::Many(entries) => {
+            for entry in entries {
+                add_entry(entry, tags, &mut items, 0)?;
+            }
+        }
+    }
+    items.dedup();
+    Ok(Ingredient(items))
+}
+
+/// Adds an item, or every item in a `#` tag, to `items`.
+fn add_entry(
+    entry: &str,
+    tags: &HashMap<String, Vec<String>>,
+    items: &mut Vec<ItemID>,
+    depth: usize,
+) -> Result<(), RecipeError> {
+    let Some(tag) = entry.strip_prefix('#') else {
+        items.push(item(entry)?);
+        return Ok(());
+    };
+    let entries = tags
+        .get(tag)
+        .filter(|_| depth < MAX_TAG_DEPTH)
+        .ok_or_else(|| RecipeError::UnknownTag(tag.to_string()))?;
+    for entry in entries {
+        add_entry(entry, tags, items, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn item(name: &str) -> Result<ItemID, RecipeError> {
+    ItemID::from_name(name).ok_or_else(|| RecipeError::UnknownItem(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BUNDLED;
+
+    fn item(name: &str) -> Option<InventorySlot> {
+        Some(InventorySlot::new(ItemID::from_name(name).unwrap(), 1))
+    }
+
+    #[test]
+    fn test_load_bundled() {
+        let raw: std::collections::BTreeMap<String, serde_json::Value> =
+            serde_json::from_str(crate::RECIPES_JSON).unwrap();
+        // None of them should have been skipped, nor any added from the recipes directory
+        assert_eq!(BUNDLED.len(), raw.len());
+        let (_, stick) = BUNDLED.find("minecraft:stick").unwrap();
+        assert_eq!(stick.category, BookCategory::CraftingMisc);
+        assert_eq!(stick.result.count.0, 4);
+    }
+
+    #[test]
+    fn test_shaped() {
+        // Sticks anywhere in the grid
+        let mut grid = vec![None; 9];
+        grid[2] = item("oak_planks");
+        grid[5] = item("birch_planks");
+        let result = BUNDLED.craft(&grid, 3).unwrap();
+        assert_eq!(result.item_id, ItemID::from_name("stick"));
+        // But not with a gap
+        grid[5] = None;
+        grid[8] = item("birch_planks");
+        assert_eq!(BUNDLED.craft(&grid, 3), None);
+    }
+
+    #[test]
+    fn test_shaped_mirrored() {
+        let axe = [
+            "oak_planks",
+            "oak_planks",
+            "stick",
+            "oak_planks",
+            "stick",
+            "",
+        ];
+        let grid = axe
+            .iter()
+            .map(|name| (!name.is_empty()).then(|| item(name)).flatten())
+            .collect::<Vec<_>>();
+        let result = BUNDLED.craft(&grid, 2).unwrap();
+        assert_eq!(result.item_id, ItemID::from_name("wooden_axe"));
+        // Anywhere in a bigger grid
+        let mut table = vec![None; 9];
+        for (index, item) in grid.into_iter().enumerate() {
+            table[index / 2 * 3 + index % 2 + 1] = item;
+        }
+        let result = BUNDLED.craft(&table, 3).unwrap();
+        assert_eq!(result.item_id, ItemID::from_name("wooden_axe"));
+    }
+
+    #[test]
+    fn test_shapeless() {
+        let grid = vec![item("flint"), None, None, item("iron_ingot")];
+        let result = BUNDLED.craft(&grid, 2).unwrap();
+        assert_eq!(result.item_id, ItemID::from_name("flint_and_steel"));
+        let grid = vec![item("flint"), item("flint"), None, item("iron_ingot")];
+        assert_eq!(BUNDLED.craft(&grid, 2), None);
+    }
+
+    #[test]
+    fn test_cooking() {
+        let inputs = BUNDLED.cooking_inputs(CookingMethod::Smelting);
+        assert!(inputs.contains(&ItemID::from_name("raw_iron").unwrap()));
+        assert!(inputs.contains(&ItemID::from_name("stripped_cherry_wood").unwrap()));
+        let blasting = BUNDLED.cooking_inputs(CookingMethod::Blasting);
+        assert_eq!(blasting, vec![ItemID::from_name("raw_iron").unwrap()]);
+    }
+
+    #[test]
+    fn test_placement() {
+        let (_, torch) = BUNDLED.find("minecraft:torch").unwrap();
+        let (pattern, width) = torch.placement(2).unwrap();
+        assert_eq!(width, 1);
+        assert_eq!(pattern.len(), 2);
+        let (_, chest) = BUNDLED.find("minecraft:chest").unwrap();
+        assert_eq!(chest.placement(2), None);
+        assert!(chest.placement(3).is_some());
+    }
+
+    #[test]
+    fn test_skips_invalid() {
+        let recipes = r##"{
+            "a": {"type": "minecraft:crafting_shapeless", "ingredients": ["minecraft:nothing"], "result": {"id": "minecraft:stone"}},
+            "b": {"type": "minecraft:stonecutting", "ingredient": "minecraft:stone", "result": {"id": "minecraft:stone_slab"}},
+            "c": {"type": "minecraft:crafting_shapeless", "group": "g", "ingredients": ["#minecraft:t"], "result": {"id": "minecraft:stone"}}
+        }"##;
+        let registry =
+            RecipeRegistry::load(recipes, r#"{"minecraft:t": ["minecraft:dirt"]}"#).unwrap();
+        assert_eq!(registry.len(), 1);
+        let recipe = registry.get(0).unwrap();
+        assert_eq!(registry.group_id(recipe), Some(0));
+    }
+}
@@ -226,6 +226,20 @@ impl LmdbBackend {
         Ok(values)
     }
 
+    /// Returns all keys in `table`, in ascending order.
+    pub fn keys(&self, table: String) -> Result<Vec<u128>, StorageError> {
+        let env = self.env.lock();
+        let ro_txn = env.read_txn()?;
+        let db: Database<U128<BigEndian>, Bytes> = env
+            .open_database(&ro_txn, Some(&table))?
+            .ok_or(StorageError::TableError("Table not found".to_string()))?;
+        let mut keys = Vec::new();
+        for entry in db.iter(&ro_txn)? {
+            keys.push(entry?.0);
+        }
+        Ok(keys)
+    }
+
     pub fn flush(&self) -> Result<(), StorageError> {
         let env = self.env.lock();
         env.clear_stale_readers()?;
@@ -299,6 +313,23 @@ mod tests {
         remove_dir_all(path).unwrap();
     }
 
+    #[test]
+    fn test_keys() {
+        let path = tempdir().unwrap().keep();
+        {
+            let backend = LmdbBackend::initialize(Some(path.clone())).unwrap();
+            backend.create_table("test_table".to_string()).unwrap();
+            for key in [3u128, 1, u128::MAX] {
+                backend
+                    .insert("test_table".to_string(), key, vec![0])
+                    .unwrap();
+            }
+            let keys = backend.keys("test_table".to_string()).unwrap();
+            assert_eq!(keys, vec![1, 3, u128::MAX]);
+        }
+        remove_dir_all(path).unwrap();
+    }
+
     #[test]
     fn test_concurrent_write() {
         let path = tempdir().unwrap().keep();
@@ -0,0 +1,148 @@
+//! Rendering of text components as ANSI escaped strings, for terminals.
+
+use crate::*;
+use std::fmt::Write;
+
+/// The style in effect for a component, after inheriting from its parents.
+#[derive(Clone, Copy, Default)]
+struct AnsiStyle {
+    color: Option<(u8, u8, u8)>,
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+    strikethrough: bool,
+}
+
+impl AnsiStyle {
+    fn inherit(mut self, component: &TextComponent) -> Self {
+        if let Some(color) = component.color.as_ref().and_then(Color::to_rgb) {
+            self.color = Some(color);
+        }
+        self.bold = component.bold.unwrap_or(self.bold);
+        self.italic = component.italic.unwrap_or(self.italic);
+        self.underlined = component.underlined.unwrap_or(self.underlined);
+        self.strikethrough = component.strikethrough.unwrap_or(self.strikethrough);
+        self
+    }
+
+    fn write_escape(&self, out: &mut String) -> bool {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.italic {
+            codes.push("3".to_string());
+        }
+        if self.underlined {
+            codes.push("4".to_string());
+        }
+        if self.strikethrough {
+            codes.push("9".to_string());
+        }
+        if let Some((r, g, b)) = self.color {
+            codes.push(format!("38;2;{r};{g};{b}"));
+        }
+        if codes.is_empty() {
+            return false;
+        }
+        let _ = write!(out, "\x1b[{}m", codes.join(";"));
+        true
+    }
+}
+
+impl NamedColor {
+    /// The RGB value the vanilla client uses for this color.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            NamedColor::Black => (0x00, 0x00, 0x00),
+            NamedColor::DarkBlue => (0x00, 0x00, 0xAA),
+            NamedColor::DarkGreen => (0x00, 0xAA, 0x00),
+            NamedColor::DarkAqua => (0x00, 0xAA, 0xAA),
+            NamedColor::DarkRed => (0xAA, 0x00, 0x00),
+            NamedColor::DarkPurple => (0xAA, 0x00, 0xAA),
+            NamedColor::Gold => (0xFF, 0xAA, 0x00),
+            NamedColor::Gray => (0xAA, 0xAA, 0xAA),
+            NamedColor::DarkGray => (0x55, 0x55, 0x55),
+            NamedColor::Blue => (0x55, 0x55, 0xFF),
+            NamedColor::Green => (0x55, 0xFF, 0x55),
+            NamedColor::Aqua => (0x55, 0xFF, 0xFF),
+            NamedColor::Red => (0xFF, 0x55, 0x55),
+            NamedColor::LightPurple => (0xFF, 0x55, 0xFF),
+            NamedColor::Yellow => (0xFF, 0xFF, 0x55),
+            NamedColor::White => (0xFF, 0xFF, 0xFF),
+        }
+    }
+}
+
+impl Color {
+    /// The RGB value of this color, or `None` if it is an invalid hex color.
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Named(named) => Some(named.to_rgb()),
+            Color::Hex(hex) => {
+                let hex = hex.strip_prefix('#').unwrap_or(hex);
+                if hex.len() != 6 {
+                    return None;
+                }
+                let value = u32::from_str_radix(hex, 16).ok()?;
+                Some(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+            }
+        }
+    }
+}
+
+impl TextComponent {
+    /// Renders this component and its children as a string with ANSI escape codes for
+    /// colors and styles, using 24-bit colors.
+    ///
+    /// Translations and keybinds can't be resolved on the server, so their keys are shown instead.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        self.write_ansi(&mut out, AnsiStyle::default());
+        out
+    }
+
+    /// Renders this component and its children as plain text, without any styling.
+    ///
+    /// Translations and keybinds are shown the same way as in [`TextComponent::to_ansi`].
+    pub fn to_plain_text(&self) -> String {
+        let mut out = self.content_string(TextComponent::to_plain_text);
+        for child in &self.extra {
+            out.push_str(&child.to_plain_text());
+        }
+        out
+    }
+
+    /// The text of this component alone, rendering translation arguments with `render`.
+    fn content_string(&self, render: fn(&TextComponent) -> String) -> String {
+        match &self.content {
+            TextContent::Text { text } => text.clone(),
+            TextContent::Translate { translate, with } => {
+                if with.is_empty() {
+                    translate.clone()
+                } else {
+                    let args = with.iter().map(render).collect::<Vec<_>>().join(", ");
+                    format!("{translate}[{args}]")
+                }
+            }
+            TextContent::Keybind { keybind } => format!("[{keybind}]"),
+        }
+    }
+
+    fn write_ansi(&self, out: &mut String, parent: AnsiStyle) {
+        let style = parent.inherit(self);
+        let text = self.content_string(TextComponent::to_ansi);
+
+        if !text.is_empty() {
+            let styled = style.write_escape(out);
+            out.push_str(&text);
+            if styled {
+                out.push_str("\x1b[0m");
+            }
+        }
+
+        for child in &self.extra {
+            child.write_ansi(out, style);
+        }
+    }
+}
@@ -177,3 +177,19 @@ fn test_serialize_to_nbt() {
         bytes_to_readable_string(&cursor.get_ref()[cursor.position() as usize..])
     );
 }
+
+#[test]
+fn test_to_ansi() {
+    assert_eq!(TextComponent::from("plain").to_ansi(), "plain");
+
+    let component = TextComponentBuilder::new("red ")
+        .color(NamedColor::Red)
+        .bold()
+        .extra(ComponentBuilder::text("child").color(Color::Hex("#00ff00".to_string())))
+        .build();
+    assert_eq!(
+        component.to_ansi(),
+        "\x1b[1;38;2;255;85;85mred \x1b[0m\x1b[1;38;2;0;255;0mchild\x1b[0m"
+    );
+    assert_eq!(component.to_plain_text(), "red child");
+}
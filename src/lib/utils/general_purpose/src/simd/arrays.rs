@@ -60,7 +60,7 @@ unsafe fn u8_slice_to_u32_be_simd(input: &[u8]) -> Vec<u32> {
     }
 
     let input = input.remainder();
-    let input = input.chunks_exact(8);
+    let input = input.chunks_exact(4);
 
     for chunk in input {
         let bytes: [u8; 4] = chunk.try_into().unwrap();
@@ -0,0 +1,255 @@
+//! Biome storage.
+//!
+//! Biomes are stored per 4x4x4 cell of blocks, so each section has 64 cells. Like in vanilla
+//! region files, every section keeps a palette of biome names and packs the palette index of each
+//! cell into longs, in YZX order. The names are turned into registry ids when the chunk is sent.
+
+use crate::chunk_format::{pack_entries, unpack_entries, BiomeStates, Chunk};
+use crate::errors::WorldError;
+use crate::vanilla_chunk_format::Biomes;
+use crate::World;
+use ferrumc_core::dimension::Dimension;
+use std::sync::Arc;
+
+/// The number of biome cells in a section.
+const CELLS: usize = 64;
+
+/// The biome sections are filled with when nothing else is known about them.
+pub fn default_biome(dimension: Dimension) -> &'static str {
+    match dimension {
+        Dimension::Overworld => "minecraft:plains",
+        Dimension::Nether => "minecraft:nether_wastes",
+        Dimension::End => "minecraft:the_end",
+    }
+}
+
+/// The index of a cell from block coordinates, which can be relative to anything aligned to a
+/// section.
+fn cell_index(x: i32, y: i32, z: i32) -> usize {
+    let (x, y, z) = ((x & 0xF) >> 2, (y & 0xF) >> 2, (z & 0xF) >> 2);
+    ((y << 4) | (z << 2) | x) as usize
+}
+
+/// The number of bits needed to store an index into a palette of `len` entries.
+fn bits_for(len: usize) -> u8 {
+    if len <= 1 {
+        0
+    } else {
+        (usize::BITS - (len - 1).leading_zeros()) as u8
+    }
+}
+
+impl BiomeStates {
+    /// A section filled with a single biome.
+    pub fn single(biome: &str) -> Self {
+        Self {
+            bits_per_biome: 0,
+            data: vec![],
+            palette: vec![biome.to_string()],
+        }
+    }
+
+    /// Builds the biome states from the biome of each of the 64 cells, in YZX order.
+    pub fn from_cells(cells: &[&str]) -> Self {
+        let mut palette: Vec<String> = Vec::new();
+        let indices = cells
+            .iter()
+            .map(
+                |&biome| match palette.iter().position(|entry| entry == biome) {
+                    Some(index) => index as u32,
+                    None => {
+                        palette.push(biome.to_string());
+                        palette.len() as u32 - 1
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        let bits_per_biome = bits_for(palette.len());
+        if bits_per_biome == 0 {
+            return Self::single(
+                palette
+                    .first()
+                    .map_or(default_biome(Dimension::default()), String::as_str),
+            );
+        }
+        Self {
+            bits_per_biome,
+            data: pack_entries(&indices, bits_per_biome),
+            palette,
+        }
+    }
+
+    /// The palette index of the biome of every cell, in YZX order.
+    pub fn indices(&self) -> Vec<u32> {
+        if self.bits_per_biome == 0 || self.palette.len() <= 1 {
+            return vec![0; CELLS];
+        }
+        unpack_entries(&self.data, self.bits_per_biome, CELLS)
+    }
+
+    /// The biome of every cell, in YZX order. Broken palette indices read as the first biome in
+    /// the palette.
+    pub fn cells(&self) -> Vec<&str> {
+        let first = self
+            .palette
+            .first()
+            .map_or(default_biome(Dimension::default()), String::as_str);
+        self.indices()
+            .into_iter()
+            .map(|index| {
+                self.palette
+                    .get(index as usize)
+                    .map_or(first, String::as_str)
+            })
+            .collect()
+    }
+
+    /// The biome of the cell containing a block.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> &str {
+        self.cells()[cell_index(x, y, z)]
+    }
+
+    /// Sets the biome of the cell containing a block, dropping biomes that are no longer used
+    /// from the palette.
+    pub fn set(&mut self, x: i32, y: i32, z: i32, biome: &str) {
+        let mut cells = self.cells();
+        cells[cell_index(x, y, z)] = biome;
+        *self = Self::from_cells(&cells);
+    }
+
+    pub(crate) fn from_vanilla(biomes: &Biomes) -> Self {
+        let states = Self {
+            bits_per_biome: bits_for(biomes.palette.len()),
+            data: biomes.data.clone().unwrap_or_default(),
+            palette: biomes.palette.clone(),
+        };
+        // Rebuild it so unused palette entries are dropped
+        Self::from_cells(&states.cells())
+    }
+
+    pub(crate) fn to_vanilla(&self) -> Biomes {
+        Biomes {
+            data: (!self.data.is_empty()).then(|| self.data.clone()),
+            palette: self.palette.clone(),
+        }
+    }
+}
+
+impl Chunk {
+    /// The biome at a block, which is the biome of the 4x4x4 cell it's in.
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Result<&str, WorldError> {
+        let section = self
+            .sections
+            .iter()
+            .find(|section| section.y == (y >> 4) as i8)
+            .ok_or(WorldError::SectionOutOfBounds(y >> 4))?;
+        Ok(section.biome_states.get(x, y, z))
+    }
+
+    /// Sets the biome of the 4x4x4 cell containing a block.
+    pub fn set_biome(&mut self, x: i32, y: i32, z: i32, biome: &str) -> Result<(), WorldError> {
+        let section = self
+            .sections
+            .iter_mut()
+            .find(|section| section.y == (y >> 4) as i8)
+            .ok_or(WorldError::SectionOutOfBounds(y >> 4))?;
+        section.biome_states.set(x, y, z, biome);
+        Ok(())
+    }
+
+    /// Sets the biome of the whole chunk.
+    pub fn fill_biome(&mut self, biome: &str) {
+        for section in &mut self.sections {
+            section.biome_states = BiomeStates::single(biome);
+        }
+    }
+}
+
+impl World {
+    /// Retrieves the biome at the specified block coordinates in the given dimension.
+    /// Under the hood, this function just fetches the chunk containing the block and then calls
+    /// [`Chunk::get_biome`] on it.
+    pub fn get_biome_and_fetch(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        dimension: &str,
+    ) -> Result<String, WorldError> {
+        let chunk = self.load_chunk(x >> 4, z >> 4, dimension)?;
+        chunk.get_biome(x, y, z).map(str::to_string)
+    }
+
+    /// Sets the biome of the 4x4x4 cell containing the specified block in the given dimension,
+    /// and saves the chunk.
+    pub fn set_biome_and_fetch(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        dimension: &str,
+        biome: &str,
+    ) -> Result<(), WorldError> {
+        let mut chunk = self.load_chunk_owned(x >> 4, z >> 4, dimension)?;
+        chunk.set_biome(x, y, z, biome)?;
+        self.save_chunk(Arc::new(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_biome() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        assert_eq!(chunk.get_biome(0, 0, 0).unwrap(), "minecraft:plains");
+
+        chunk.set_biome(5, 70, 9, "minecraft:desert").unwrap();
+        assert_eq!(chunk.get_biome(4, 68, 8).unwrap(), "minecraft:desert");
+        assert_eq!(chunk.get_biome(7, 71, 11).unwrap(), "minecraft:desert");
+        assert_eq!(chunk.get_biome(3, 70, 9).unwrap(), "minecraft:plains");
+        assert_eq!(chunk.get_biome(5, 70 - 16, 9).unwrap(), "minecraft:plains");
+        assert!(chunk.set_biome(0, 400, 0, "minecraft:desert").is_err());
+    }
+
+    #[test]
+    fn test_palette() {
+        let mut states = BiomeStates::single("minecraft:plains");
+        states.set(0, 0, 0, "minecraft:desert");
+        assert_eq!(states.palette.len(), 2);
+        assert_eq!(states.bits_per_biome, 1);
+
+        let biomes = ["minecraft:forest", "minecraft:taiga", "minecraft:swamp"];
+        for (i, biome) in biomes.iter().enumerate() {
+            states.set(4 * i as i32 + 4, 0, 0, biome);
+        }
+        assert_eq!(states.bits_per_biome, 3);
+        assert_eq!(states.get(12, 0, 0), "minecraft:swamp");
+
+        // Overwriting the only desert cell drops it from the palette
+        states.set(0, 0, 0, "minecraft:plains");
+        assert_eq!(states.palette.len(), 4);
+        assert_eq!(states.bits_per_biome, 2);
+        assert_eq!(states.get(0, 0, 0), "minecraft:plains");
+        assert_eq!(states.get(8, 0, 0), "minecraft:taiga");
+    }
+
+    #[test]
+    fn test_vanilla_roundtrip() {
+        let mut states = BiomeStates::single("minecraft:ocean");
+        states.set(0, 12, 4, "minecraft:beach");
+        let states = BiomeStates::from_vanilla(&states.to_vanilla());
+        assert_eq!(states.get(0, 12, 4), "minecraft:beach");
+        assert_eq!(states.get(4, 12, 4), "minecraft:ocean");
+    }
+
+    #[test]
+    fn test_default_biome() {
+        let chunk = Chunk::new(0, 0, "the_nether".to_string());
+        assert_eq!(
+            chunk.get_biome(0, 64, 0).unwrap(),
+            "minecraft:nether_wastes"
+        );
+    }
+}
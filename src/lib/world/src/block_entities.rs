@@ -0,0 +1,515 @@
+//! Block entities, the extra data some blocks keep like the items in a chest or the text on a sign.
+//!
+//! The data is kept as NBT in the network format, a compound's tag type followed by its payload
+//! with no name, so it can be saved and sent on without being read. It doesn't include the id or
+//! the position of the block entity, those are stored next to it.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use crate::chunk_format::Chunk;
+use crate::errors::WorldError;
+use crate::vanilla_chunk_format::BlockData;
+use bitcode_derive::{Decode, Encode};
+use deepsize::DeepSizeOf;
+use ferrumc_inventories::components::ComponentPatch;
+use ferrumc_inventories::item::ItemID;
+use ferrumc_inventories::slot::InventorySlot;
+use ferrumc_macros::{NBTDeserialize, NBTSerialize};
+use ferrumc_nbt::{
+    FromNbt, NBTError, NBTSerializable, NBTSerializeOptions, NbtTape, NbtTapeElement,
+};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+use simd_json::base::ValueAsScalar;
+
+const TAG_END: u8 = 0;
+const TAG_COMPOUND: u8 = 10;
+
+/// Keys of a vanilla block entity compound that are stored outside its data.
+const POSITION_KEYS: [&str; 5] = ["id", "x", "y", "z", "keepPacked"];
+
+/// The key of the items a container block entity holds.
+const ITEMS_KEY: &str = "Items";
+
+/// A block entity's position in its chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Encode, Decode, DeepSizeOf)]
+pub struct BlockEntityPos {
+    pub x: u8,
+    pub y: i16,
+    pub z: u8,
+}
+
+impl BlockEntityPos {
+    /// The position of a block in its chunk, from block coordinates.
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self {
+            x: (x & 0xF) as u8,
+            y: y as i16,
+            z: (z & 0xF) as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, DeepSizeOf)]
+pub struct BlockEntity {
+    /// The block entity type, e.g. `minecraft:chest`.
+    pub id: String,
+    /// The NBT compound of the block entity, in the network format.
+    pub nbt: Vec<u8>,
+}
+
+impl BlockEntity {
+    /// A block entity of the given type without any data, like a freshly placed block has.
+    pub fn empty(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            nbt: vec![TAG_COMPOUND, TAG_END],
+        }
+    }
+
+    /// The id of the block entity type in the registry, which is what gets sent to clients.
+    pub fn network_id(&self) -> Option<VarInt> {
+        ferrumc_registry::lookup(&format!(
+            "minecraft:block_entity_type/entries/{}/protocol_id",
+            self.id
+        ))
+        .and_then(|id| id.as_i32())
+        .map(VarInt::new)
+    }
+
+    /// Parses the compound, which only works for the network format if it's given a root name.
+    fn with_tape<T>(&self, f: impl FnOnce(&NbtTape) -> T) -> T {
+        let mut named = vec![TAG_COMPOUND, 0, 0];
+        named.extend_from_slice(self.nbt.get(1..).unwrap_or_default());
+        let mut tape = NbtTape::new(&named);
+        tape.parse();
+        f(&tape)
+    }
+
+    /// The items of a container block entity, `size` slots of them.
+    pub fn items(&self, size: usize) -> Vec<Option<InventorySlot>> {
+        let stored = self.with_tape(|tape| {
+            tape.get(ITEMS_KEY)
+                .and_then(|items| items.as_list::<StoredItem>(tape))
+                .unwrap_or_default()
+        });
+        let mut items = vec![None; size];
+        for item in stored {
+            let Some(slot) = usize::try_from(item.slot)
+                .ok()
+                .and_then(|slot| items.get_mut(slot))
+            else {
+                continue;
+            };
+            *slot = ItemID::from_name(&item.id)
+                .map(|id| {
+                    let slot = InventorySlot::new(id, item.count.unwrap_or(1));
+                    match item.components {
+                        Some(components) => slot.with_component_patch(components),
+                        None => slot,
+                    }
+                })
+                .filter(|item| !item.is_empty());
+        }
+        items
+    }
+
+    /// Replaces the items of a container block entity, keeping the rest of its data.
+    pub fn set_items(&mut self, items: &[Option<InventorySlot>]) -> Result<(), WorldError> {
+        let mut nbt = vec![TAG_COMPOUND];
+        self.with_tape(|tape| {
+            let Some((_, root)) = &tape.root else {
+                return Ok(());
+            };
+            for (name, value) in root.as_compound().into_iter().flatten() {
+                if *name == ITEMS_KEY {
+                    continue;
+                }
+                tape.write_element(value, &mut nbt, &NBTSerializeOptions::WithHeader(name))
+                    .map_err(|_| NBTError::InvalidNBTData)?;
+            }
+            Ok::<_, NBTError>(())
+        })?;
+        let stored = items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, item)| {
+                let item = item.as_ref().filter(|item| !item.is_empty())?;
+                Some(StoredItem {
+                    slot: i8::try_from(slot).ok()?,
+                    id: item.item_id?.to_name()?,
+                    count: Some(item.count.0),
+                    components: Some(item.component_patch())
+                        .filter(|components| !components.is_empty()),
+                })
+            })
+            .collect::<Vec<_>>();
+        if !stored.is_empty() {
+            stored.serialize(&mut nbt, &NBTSerializeOptions::WithHeader(ITEMS_KEY));
+        }
+        nbt.push(TAG_END);
+        self.nbt = nbt;
+        Ok(())
+    }
+}
+
+/// An item in a container block entity, as vanilla stores it.
+#[derive(NBTSerialize, NBTDeserialize, Debug, Clone, PartialEq)]
+struct StoredItem {
+    #[nbt(rename = "Slot")]
+    slot: i8,
+    id: String,
+    count: Option<i32>,
+    components: Option<ComponentPatch>,
+}
+
+/// A block entity as it's stored in vanilla chunks, with the position in world coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize, DeepSizeOf)]
+pub(crate) struct VanillaBlockEntity {
+    pub id: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// The rest of the compound, in the network format.
+    pub data: Vec<u8>,
+}
+
+impl<'a> FromNbt<'a> for VanillaBlockEntity {
+    fn from_nbt(tapes: &NbtTape<'a>, element: &NbtTapeElement<'a>) -> ferrumc_nbt::Result<Self> {
+        let compound = element.as_compound().ok_or(NBTError::TypeMismatch {
+            expected: "Compound (from VanillaBlockEntity)",
+            found: element.nbt_type(),
+        })?;
+        let field = |name: &'static str| element.get(name).ok_or(NBTError::ElementNotFound(name));
+
+        let mut data = vec![TAG_COMPOUND];
+        for (name, value) in compound {
+            if POSITION_KEYS.contains(name) {
+                continue;
+            }
+            tapes
+                .write_element(value, &mut data, &NBTSerializeOptions::WithHeader(name))
+                .map_err(|_| NBTError::InvalidNBTData)?;
+        }
+        data.push(TAG_END);
+
+        Ok(Self {
+            id: String::from_nbt(tapes, field("id")?)?,
+            x: i32::from_nbt(tapes, field("x")?)?,
+            y: i32::from_nbt(tapes, field("y")?)?,
+            z: i32::from_nbt(tapes, field("z")?)?,
+            data,
+        })
+    }
+}
+
+impl NBTSerializable for VanillaBlockEntity {
+    fn serialize(&self, buf: &mut Vec<u8>, options: &NBTSerializeOptions<'_>) {
+        match options {
+            NBTSerializeOptions::None => {}
+            NBTSerializeOptions::WithHeader(name) => {
+                buf.push(TAG_COMPOUND);
+                name.serialize(buf, &NBTSerializeOptions::None);
+            }
+            NBTSerializeOptions::Network | NBTSerializeOptions::Flatten => {
+                buf.push(TAG_COMPOUND);
+            }
+        }
+        self.id
+            .serialize(buf, &NBTSerializeOptions::WithHeader("id"));
+        self.x.serialize(buf, &NBTSerializeOptions::WithHeader("x"));
+        self.y.serialize(buf, &NBTSerializeOptions::WithHeader("y"));
+        self.z.serialize(buf, &NBTSerializeOptions::WithHeader("z"));
+        // The entries of the data compound, without its tag type and end
+        if let Some(entries) = self.data.get(1..self.data.len().saturating_sub(1)) {
+            buf.extend_from_slice(entries);
+        }
+        buf.push(TAG_END);
+    }
+
+    fn id() -> u8 {
+        TAG_COMPOUND
+    }
+}
+
+impl VanillaBlockEntity {
+    pub(crate) fn from_block_entity(
+        chunk_x: i32,
+        chunk_z: i32,
+        pos: BlockEntityPos,
+        block_entity: &BlockEntity,
+    ) -> Self {
+        Self {
+            id: block_entity.id.clone(),
+            x: chunk_x * 16 + i32::from(pos.x),
+            y: i32::from(pos.y),
+            z: chunk_z * 16 + i32::from(pos.z),
+            data: block_entity.nbt.clone(),
+        }
+    }
+
+    pub(crate) fn into_block_entity(self) -> (BlockEntityPos, BlockEntity) {
+        (
+            BlockEntityPos::new(self.x, self.y, self.z),
+            BlockEntity {
+                id: self.id,
+                nbt: self.data,
+            },
+        )
+    }
+}
+
+lazy_static! {
+    static ref BLOCK_ENTITY_TYPES: Vec<Option<String>> =
+        ID2BLOCK.iter().map(block_entity_type_for).collect();
+}
+
+impl BlockStateId {
+    /// The type of block entity this block has, if it has one.
+    pub fn block_entity_type(&self) -> Option<&'static str> {
+        BLOCK_ENTITY_TYPES
+            .get(self.0 as usize)
+            .and_then(Option::as_deref)
+    }
+}
+
+/// Blocks whose block entity type has the same name.
+const SAME_NAME: &[&str] = &[
+    "furnace",
+    "chest",
+    "trapped_chest",
+    "ender_chest",
+    "jukebox",
+    "dispenser",
+    "dropper",
+    "creaking_heart",
+    "brewing_stand",
+    "enchanting_table",
+    "end_portal",
+    "beacon",
+    "daylight_detector",
+    "hopper",
+    "comparator",
+    "structure_block",
+    "end_gateway",
+    "conduit",
+    "barrel",
+    "smoker",
+    "blast_furnace",
+    "lectern",
+    "bell",
+    "jigsaw",
+    "command_block",
+    "campfire",
+    "beehive",
+    "sculk_sensor",
+    "calibrated_sculk_sensor",
+    "sculk_catalyst",
+    "sculk_shrieker",
+    "chiseled_bookshelf",
+    "decorated_pot",
+    "crafter",
+    "trial_spawner",
+    "vault",
+    "test_block",
+    "test_instance_block",
+];
+
+fn block_entity_type_for(block: &BlockData) -> Option<String> {
+    let name = block.name.strip_prefix("minecraft:").unwrap_or(&block.name);
+    let id = match name {
+        _ if SAME_NAME.contains(&name) => name,
+        "spawner" => "mob_spawner",
+        "moving_piston" => "piston",
+        "chain_command_block" | "repeating_command_block" => "command_block",
+        "soul_campfire" => "campfire",
+        "bee_nest" => "beehive",
+        "suspicious_sand" | "suspicious_gravel" => "brushable_block",
+        "piston_head" => return None,
+        _ if name.ends_with("_hanging_sign") => "hanging_sign",
+        _ if name.ends_with("_sign") => "sign",
+        _ if name.ends_with("_banner") => "banner",
+        _ if name.ends_with("_bed") => "bed",
+        _ if name.ends_with("shulker_box") => "shulker_box",
+        _ if name.ends_with("_head") || name.ends_with("_skull") => "skull",
+        _ => return None,
+    };
+    Some(format!("minecraft:{id}"))
+}
+
+impl Chunk {
+    /// The block entity at a block, if it has one.
+    pub fn get_block_entity(&self, x: i32, y: i32, z: i32) -> Option<&BlockEntity> {
+        self.block_entities.get(&BlockEntityPos::new(x, y, z))
+    }
+
+    /// The block entity at a block, for changing its data.
+    pub fn get_block_entity_mut(&mut self, x: i32, y: i32, z: i32) -> Option<&mut BlockEntity> {
+        self.block_entities.get_mut(&BlockEntityPos::new(x, y, z))
+    }
+
+    /// Sets the block entity of a block, replacing any it already had.
+    pub fn set_block_entity(&mut self, x: i32, y: i32, z: i32, block_entity: BlockEntity) {
+        self.block_entities
+            .insert(BlockEntityPos::new(x, y, z), block_entity);
+    }
+
+    /// Removes the block entity of a block, returning it if there was one.
+    pub fn remove_block_entity(&mut self, x: i32, y: i32, z: i32) -> Option<BlockEntity> {
+        self.block_entities.remove(&BlockEntityPos::new(x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vanilla_chunk_format::VanillaChunk;
+    use ferrumc_inventories::components::Component;
+    use ferrumc_macros::block;
+    use ferrumc_nbt::NetworkNbt;
+
+    #[test]
+    fn test_block_entity_type() {
+        assert_eq!(block!("stone").block_entity_type(), None);
+        assert_eq!(
+            block!("barrel", {facing: "up", open: false}).block_entity_type(),
+            Some("minecraft:barrel")
+        );
+        assert_eq!(
+            block!("oak_sign", {rotation: 0, waterlogged: false}).block_entity_type(),
+            Some("minecraft:sign")
+        );
+        assert_eq!(
+            block!("oak_hanging_sign", {attached: false, rotation: 0, waterlogged: false})
+                .block_entity_type(),
+            Some("minecraft:hanging_sign")
+        );
+    }
+
+    #[test]
+    fn test_network_id() {
+        assert_eq!(
+            BlockEntity::empty("minecraft:chest").network_id(),
+            Some(VarInt::new(1))
+        );
+        assert_eq!(BlockEntity::empty("minecraft:nothing").network_id(), None);
+    }
+
+    #[test]
+    fn test_vanilla_roundtrip() {
+        let mut chunk = Chunk::new(2, -1, "overworld".to_string());
+        // A sign's front text: {front_text: {messages: ['"hi"']}}
+        let mut nbt = vec![TAG_COMPOUND, TAG_COMPOUND];
+        "front_text".serialize(&mut nbt, &NBTSerializeOptions::None);
+        vec!["\"hi\"".to_string()]
+            .serialize(&mut nbt, &NBTSerializeOptions::WithHeader("messages"));
+        nbt.extend([TAG_END, TAG_END]);
+        let sign = BlockEntity {
+            id: "minecraft:sign".to_string(),
+            nbt,
+        };
+        chunk.set_block_entity(35, 70, -3, sign.clone());
+
+        let imported = VanillaChunk::from_bytes(&chunk.to_vanilla_nbt())
+            .unwrap()
+            .to_custom_format()
+            .unwrap();
+        assert_eq!(imported.get_block_entity(3, 70, 13), Some(&sign));
+        assert_eq!(imported.block_entities.len(), 1);
+    }
+
+    #[test]
+    fn test_items() {
+        let mut chest = BlockEntity::empty("minecraft:chest");
+        // Other data is kept when the items change
+        let mut nbt = vec![TAG_COMPOUND];
+        "Box".serialize(&mut nbt, &NBTSerializeOptions::WithHeader("CustomName"));
+        nbt.push(TAG_END);
+        chest.nbt = nbt.clone();
+        assert_eq!(chest.items(27), vec![None; 27]);
+
+        let mut items = vec![None; 27];
+        items[3] = Some(InventorySlot::new(ItemID::from_name("stone").unwrap(), 12));
+        items[26] = Some(InventorySlot::new(ItemID::from_name("dirt").unwrap(), 1));
+        // Components like names are kept too
+        let sword = InventorySlot::new(ItemID::from_name("iron_sword").unwrap(), 1);
+        items[5] = Some(sword.with_component_patch(ComponentPatch {
+            added: vec![
+                Component::CustomName(NetworkNbt::string("Sting")),
+                Component::Damage(40),
+            ],
+            removed: Vec::new(),
+        }));
+        chest.set_items(&items).unwrap();
+        assert_eq!(chest.items(27), items);
+
+        chest.set_items(&vec![None; 27]).unwrap();
+        assert_eq!(chest.nbt, nbt);
+    }
+
+    #[test]
+    fn test_shulker_box_roundtrip() {
+        const TAG_LIST: u8 = 9;
+        // A chest holding a named shulker box with 3 diamonds in it
+        let mut nbt = vec![TAG_COMPOUND, TAG_LIST];
+        ITEMS_KEY.serialize(&mut nbt, &NBTSerializeOptions::None);
+        nbt.push(TAG_COMPOUND);
+        1i32.serialize(&mut nbt, &NBTSerializeOptions::None);
+        4i8.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("Slot"));
+        "minecraft:shulker_box".serialize(&mut nbt, &NBTSerializeOptions::WithHeader("id"));
+        1i32.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("count"));
+        nbt.push(TAG_COMPOUND);
+        "components".serialize(&mut nbt, &NBTSerializeOptions::None);
+        nbt.push(TAG_LIST);
+        "minecraft:container".serialize(&mut nbt, &NBTSerializeOptions::None);
+        nbt.push(TAG_COMPOUND);
+        1i32.serialize(&mut nbt, &NBTSerializeOptions::None);
+        0i32.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("slot"));
+        nbt.push(TAG_COMPOUND);
+        "item".serialize(&mut nbt, &NBTSerializeOptions::None);
+        "minecraft:diamond".serialize(&mut nbt, &NBTSerializeOptions::WithHeader("id"));
+        3i32.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("count"));
+        nbt.extend([TAG_END, TAG_END]);
+        "Loot".serialize(
+            &mut nbt,
+            &NBTSerializeOptions::WithHeader("minecraft:custom_name"),
+        );
+        nbt.extend([TAG_END, TAG_END, TAG_END]);
+        let chest = BlockEntity {
+            id: "minecraft:chest".to_string(),
+            nbt,
+        };
+
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        chunk.set_block_entity(1, 64, 1, chest.clone());
+        let imported = VanillaChunk::from_bytes(&chunk.to_vanilla_nbt())
+            .unwrap()
+            .to_custom_format()
+            .unwrap();
+        let mut imported = imported.get_block_entity(1, 64, 1).unwrap().clone();
+        let items = imported.items(27);
+        let shulker_box = items[4].as_ref().unwrap();
+        assert_eq!(shulker_box.components().len(), 2);
+        // Saving the items again leaves the contents of the shulker box as they were
+        imported.set_items(&items).unwrap();
+        assert_eq!(imported, chest);
+        chunk.set_block_entity(1, 64, 1, imported);
+        let exported = VanillaChunk::from_bytes(&chunk.to_vanilla_nbt())
+            .unwrap()
+            .to_custom_format()
+            .unwrap();
+        assert_eq!(exported.get_block_entity(1, 64, 1), Some(&chest));
+    }
+
+    #[test]
+    fn test_set_block_removes_block_entity() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        let barrel = block!("barrel", {facing: "up", open: false});
+        chunk.set_block(1, 64, 1, barrel).unwrap();
+        chunk.set_block_entity(1, 64, 1, BlockEntity::empty("minecraft:barrel"));
+        chunk.set_block(1, 64, 1, barrel).unwrap();
+        assert!(chunk.get_block_entity(1, 64, 1).is_some());
+        chunk.set_block(1, 64, 1, block!("stone")).unwrap();
+        assert!(chunk.get_block_entity(1, 64, 1).is_none());
+    }
+}
@@ -0,0 +1,586 @@
+//! Mining properties of blocks: how hard they are, which tool breaks them faster and what they
+//! drop.
+//!
+//! Hardness and whether a block needs the right tool to drop anything come from vanilla's block
+//! data in `block_properties.json`, and the tool and its tier from the `mineable/*` and
+//! `needs_*_tool` block tags. Loot tables aren't part of the data, so blocks drop their own item
+//! unless they're listed in [`DROPS`] or drop nothing. Chance based drops like saplings from
+//! leaves or flint from gravel aren't modelled.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use crate::block_tags::is_tagged;
+use ferrumc_inventories::item::ItemID;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+
+/// The kinds of tools that speed up breaking blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Hoe,
+    Sword,
+    Shears,
+}
+
+/// The materials tools are made of, from lowest to highest mining level.
+///
+/// Gold tools are fast but only have the mining level of wooden ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ToolTier {
+    Wood,
+    Gold,
+    Stone,
+    Iron,
+    Diamond,
+    Netherite,
+}
+
+impl ToolTier {
+    /// The mining speed multiplier of a tool of this tier on blocks it's made for.
+    pub fn speed(self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+            ToolTier::Diamond => 8.0,
+            ToolTier::Netherite => 9.0,
+            ToolTier::Gold => 12.0,
+        }
+    }
+
+    /// Whether a tool of this tier can harvest blocks that need at least `required`.
+    fn meets(self, required: ToolTier) -> bool {
+        match (self, required) {
+            // Gold sorts above wood for its speed, but has the same mining level
+            (ToolTier::Gold, required) => required <= ToolTier::Wood || required == ToolTier::Gold,
+            (tier, required) => tier >= required,
+        }
+    }
+}
+
+/// A tool held while breaking a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tool {
+    pub kind: ToolKind,
+    /// The tier of the tool, shears don't have one.
+    pub tier: Option<ToolTier>,
+}
+
+impl Tool {
+    /// Parses a tool from an item name like `minecraft:iron_pickaxe`. Returns `None` for items
+    /// that aren't tools.
+    pub fn from_item_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        if name == "shears" {
+            return Some(Self {
+                kind: ToolKind::Shears,
+                tier: None,
+            });
+        }
+        let (tier, kind) = name.split_once('_')?;
+        let tier = match tier {
+            "wooden" => ToolTier::Wood,
+            "stone" => ToolTier::Stone,
+            "iron" => ToolTier::Iron,
+            "golden" => ToolTier::Gold,
+            "diamond" => ToolTier::Diamond,
+            "netherite" => ToolTier::Netherite,
+            _ => return None,
+        };
+        let kind = match kind {
+            "pickaxe" => ToolKind::Pickaxe,
+            "axe" => ToolKind::Axe,
+            "shovel" => ToolKind::Shovel,
+            "hoe" => ToolKind::Hoe,
+            "sword" => ToolKind::Sword,
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            tier: Some(tier),
+        })
+    }
+}
+
+/// What a block drops when it's harvested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockDrop {
+    Nothing,
+    /// Between `min` and `max` of `item`, inclusive.
+    Item {
+        item: ItemID,
+        min: u8,
+        max: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockProperties {
+    /// How long the block takes to break. 0 breaks instantly, negative values can't be broken.
+    pub hardness: f32,
+    /// The tool that breaks the block faster.
+    pub tool: Option<ToolKind>,
+    /// The lowest tier of `tool` needed for the block to drop anything. `None` if the block
+    /// drops without the right tool.
+    pub required_tier: Option<ToolTier>,
+    /// The speed multiplier of shears, 1 for blocks shears aren't made for.
+    pub shears_speed: f32,
+    pub drop: BlockDrop,
+}
+
+impl BlockProperties {
+    pub fn is_breakable(&self) -> bool {
+        self.hardness >= 0.0
+    }
+
+    /// Whether breaking the block with `tool` drops its items.
+    pub fn can_harvest(&self, tool: Option<Tool>) -> bool {
+        let Some(required) = self.required_tier else {
+            return true;
+        };
+        let Some(tool) = tool else {
+            return false;
+        };
+        if tool.kind == ToolKind::Shears {
+            return self.shears_speed > 1.0;
+        }
+        // Swords work on any tier of block they're made for
+        Some(tool.kind) == self.tool
+            && tool
+                .tier
+                .is_some_and(|tier| tool.kind == ToolKind::Sword || tier.meets(required))
+    }
+
+    fn tool_speed(&self, tool: Option<Tool>) -> f32 {
+        let Some(tool) = tool else {
+            return 1.0;
+        };
+        match tool.kind {
+            ToolKind::Shears => self.shears_speed,
+            ToolKind::Sword if self.tool == Some(ToolKind::Sword) => 15.0,
+            kind if Some(kind) == self.tool => tool.tier.map_or(1.0, ToolTier::speed),
+            _ => 1.0,
+        }
+    }
+
+    /// The fraction of the block that is broken each tick, using the vanilla formula. A value of
+    /// 1 or more breaks the block instantly.
+    pub fn destroy_progress(&self, tool: Option<Tool>, on_ground: bool) -> f32 {
+        if !self.is_breakable() {
+            return 0.0;
+        }
+        if self.hardness == 0.0 {
+            return 1.0;
+        }
+        let mut speed = self.tool_speed(tool);
+        if !on_ground {
+            speed /= 5.0;
+        }
+        let divisor = if self.can_harvest(tool) { 30.0 } else { 100.0 };
+        speed / self.hardness / divisor
+    }
+
+    /// The number of ticks it takes to break the block, or `None` if it can't be broken.
+    pub fn break_ticks(&self, tool: Option<Tool>, on_ground: bool) -> Option<u32> {
+        let progress = self.destroy_progress(tool, on_ground);
+        (progress > 0.0).then(|| (1.0 / progress).ceil() as u32)
+    }
+}
+
+const UNKNOWN: BlockProperties = BlockProperties {
+    hardness: -1.0,
+    tool: None,
+    required_tier: None,
+    shears_speed: 1.0,
+    drop: BlockDrop::Nothing,
+};
+
+/// A block's entry in `block_properties.json`.
+#[derive(Deserialize)]
+struct VanillaProperties {
+    hardness: f32,
+    requires_correct_tool: bool,
+    /// Whether the block is solid regardless of its collision shape.
+    force_solid: Option<bool>,
+}
+
+const BLOCK_PROPERTIES_FILE: &str = include_str!("../../../../assets/data/block_properties.json");
+
+lazy_static! {
+    static ref VANILLA_PROPERTIES: HashMap<String, VanillaProperties> =
+        serde_json::from_str(BLOCK_PROPERTIES_FILE)
+            .expect("Failed to parse block_properties.json");
+
+    /// The properties of every block state, indexed by the block state ID.
+    static ref BLOCK_PROPERTIES: Vec<BlockProperties> = {
+        let mut by_name = HashMap::new();
+        ID2BLOCK
+            .iter()
+            .map(|block| {
+                *by_name
+                    .entry(block.name.as_str())
+                    .or_insert_with(|| match VANILLA_PROPERTIES.get(&block.name) {
+                        Some(properties) => properties_for(&block.name, properties),
+                        None => UNKNOWN,
+                    })
+            })
+            .collect()
+    };
+}
+
+impl BlockStateId {
+    /// The mining properties of this block. Unknown IDs are treated as unbreakable.
+    pub fn properties(&self) -> &'static BlockProperties {
+        BLOCK_PROPERTIES.get(self.0 as usize).unwrap_or(&UNKNOWN)
+    }
+}
+
+/// Whether the block called `name` is solid no matter its collision shape, if vanilla overrides
+/// it.
+pub(crate) fn forced_solid(name: &str) -> Option<bool> {
+    VANILLA_PROPERTIES
+        .get(name)
+        .and_then(|properties| properties.force_solid)
+}
+
+/// The tags of the blocks each tool is made for.
+const TOOL_TAGS: [(&str, ToolKind); 4] = [
+    ("minecraft:mineable/pickaxe", ToolKind::Pickaxe),
+    ("minecraft:mineable/axe", ToolKind::Axe),
+    ("minecraft:mineable/shovel", ToolKind::Shovel),
+    ("minecraft:mineable/hoe", ToolKind::Hoe),
+];
+
+/// The tags of the blocks that need more than a wooden tool to drop anything, best tier first.
+const TIER_TAGS: [(&str, ToolTier); 3] = [
+    ("minecraft:needs_diamond_tool", ToolTier::Diamond),
+    ("minecraft:needs_iron_tool", ToolTier::Iron),
+    ("minecraft:needs_stone_tool", ToolTier::Stone),
+];
+
+/// Fluids can't be targeted, so they can't be broken even though they have a hardness.
+const FLUIDS: &[&str] = &[
+    "minecraft:water",
+    "minecraft:lava",
+    "minecraft:bubble_column",
+];
+
+/// Blocks that drop something other than their own item, with the minimum and maximum count.
+const DROPS: &[(&str, &str, u8, u8)] = &[
+    ("minecraft:stone", "minecraft:cobblestone", 1, 1),
+    ("minecraft:deepslate", "minecraft:cobbled_deepslate", 1, 1),
+    ("minecraft:grass_block", "minecraft:dirt", 1, 1),
+    ("minecraft:podzol", "minecraft:dirt", 1, 1),
+    ("minecraft:mycelium", "minecraft:dirt", 1, 1),
+    ("minecraft:farmland", "minecraft:dirt", 1, 1),
+    ("minecraft:dirt_path", "minecraft:dirt", 1, 1),
+    ("minecraft:clay", "minecraft:clay_ball", 4, 4),
+    ("minecraft:snow_block", "minecraft:snowball", 4, 4),
+    ("minecraft:snow", "minecraft:snowball", 1, 1),
+    ("minecraft:glowstone", "minecraft:glowstone_dust", 2, 4),
+    (
+        "minecraft:sea_lantern",
+        "minecraft:prismarine_crystals",
+        2,
+        3,
+    ),
+    ("minecraft:ender_chest", "minecraft:obsidian", 8, 8),
+    ("minecraft:water_cauldron", "minecraft:cauldron", 1, 1),
+    ("minecraft:lava_cauldron", "minecraft:cauldron", 1, 1),
+    ("minecraft:powder_snow_cauldron", "minecraft:cauldron", 1, 1),
+    ("minecraft:cobweb", "minecraft:string", 1, 1),
+    ("minecraft:tripwire", "minecraft:string", 1, 1),
+    ("minecraft:dead_bush", "minecraft:stick", 0, 2),
+    ("minecraft:redstone_wire", "minecraft:redstone", 1, 1),
+    ("minecraft:wheat", "minecraft:wheat_seeds", 1, 1),
+    ("minecraft:carrots", "minecraft:carrot", 1, 1),
+    ("minecraft:potatoes", "minecraft:potato", 1, 1),
+    ("minecraft:beetroots", "minecraft:beetroot_seeds", 1, 1),
+    ("minecraft:kelp_plant", "minecraft:kelp", 1, 1),
+    ("minecraft:coal_ore", "minecraft:coal", 1, 1),
+    ("minecraft:deepslate_coal_ore", "minecraft:coal", 1, 1),
+    ("minecraft:copper_ore", "minecraft:raw_copper", 2, 5),
+    (
+        "minecraft:deepslate_copper_ore",
+        "minecraft:raw_copper",
+        2,
+        5,
+    ),
+    ("minecraft:iron_ore", "minecraft:raw_iron", 1, 1),
+    ("minecraft:deepslate_iron_ore", "minecraft:raw_iron", 1, 1),
+    ("minecraft:gold_ore", "minecraft:raw_gold", 1, 1),
+    ("minecraft:deepslate_gold_ore", "minecraft:raw_gold", 1, 1),
+    ("minecraft:nether_gold_ore", "minecraft:gold_nugget", 2, 6),
+    ("minecraft:lapis_ore", "minecraft:lapis_lazuli", 4, 9),
+    (
+        "minecraft:deepslate_lapis_ore",
+        "minecraft:lapis_lazuli",
+        4,
+        9,
+    ),
+    ("minecraft:redstone_ore", "minecraft:redstone", 4, 5),
+    (
+        "minecraft:deepslate_redstone_ore",
+        "minecraft:redstone",
+        4,
+        5,
+    ),
+    ("minecraft:diamond_ore", "minecraft:diamond", 1, 1),
+    ("minecraft:deepslate_diamond_ore", "minecraft:diamond", 1, 1),
+    ("minecraft:emerald_ore", "minecraft:emerald", 1, 1),
+    ("minecraft:deepslate_emerald_ore", "minecraft:emerald", 1, 1),
+    ("minecraft:nether_quartz_ore", "minecraft:quartz", 1, 1),
+];
+
+/// Blocks that only drop with Silk Touch or shears, on top of leaves and glass.
+const DROPS_NOTHING: &[&str] = &[
+    "minecraft:ice",
+    "minecraft:packed_ice",
+    "minecraft:blue_ice",
+    "minecraft:powder_snow",
+    "minecraft:budding_amethyst",
+    "minecraft:spawner",
+    "minecraft:piston_head",
+    "minecraft:short_grass",
+    "minecraft:tall_grass",
+    "minecraft:fern",
+    "minecraft:large_fern",
+    "minecraft:seagrass",
+    "minecraft:tall_seagrass",
+    "minecraft:vine",
+    "minecraft:glow_lichen",
+    "minecraft:hanging_roots",
+    "minecraft:sculk_vein",
+    "minecraft:infested_stone",
+    "minecraft:infested_cobblestone",
+    "minecraft:infested_stone_bricks",
+    "minecraft:infested_mossy_stone_bricks",
+    "minecraft:infested_cracked_stone_bricks",
+    "minecraft:infested_chiseled_stone_bricks",
+    "minecraft:infested_deepslate",
+];
+
+fn properties_for(name: &str, vanilla: &VanillaProperties) -> BlockProperties {
+    // Air has a hardness of 0 in vanilla, it just can't be targeted
+    let hardness = if is_tagged(name, "minecraft:air") || FLUIDS.contains(&name) {
+        -1.0
+    } else {
+        vanilla.hardness
+    };
+    let tool = if name == "minecraft:cobweb" {
+        Some(ToolKind::Sword)
+    } else {
+        TOOL_TAGS
+            .iter()
+            .find(|(tag, _)| is_tagged(name, tag))
+            .map(|&(_, kind)| kind)
+    };
+    let required_tier = vanilla.requires_correct_tool.then(|| {
+        TIER_TAGS
+            .iter()
+            .find(|(tag, _)| is_tagged(name, tag))
+            .map_or(ToolTier::Wood, |&(_, tier)| tier)
+    });
+    BlockProperties {
+        hardness,
+        tool,
+        required_tier,
+        shears_speed: shears_speed(name),
+        drop: if hardness < 0.0 {
+            BlockDrop::Nothing
+        } else {
+            drop_for(name)
+        },
+    }
+}
+
+/// The vanilla speeds of shears.
+fn shears_speed(name: &str) -> f32 {
+    match name {
+        "minecraft:cobweb" => 15.0,
+        _ if is_tagged(name, "minecraft:leaves") => 15.0,
+        _ if is_tagged(name, "minecraft:wool") => 5.0,
+        "minecraft:vine" | "minecraft:glow_lichen" => 2.0,
+        _ => 1.0,
+    }
+}
+
+fn drops_nothing(name: &str) -> bool {
+    // Tinted glass is the only glass that drops itself
+    let is_glass =
+        |name| name != "minecraft:tinted_glass" && is_tagged(name, "minecraft:impermeable");
+    DROPS_NOTHING.contains(&name)
+        || is_tagged(name, "minecraft:leaves")
+        || is_glass(name)
+        || name.strip_suffix("_pane").is_some_and(is_glass)
+}
+
+fn drop_for(name: &str) -> BlockDrop {
+    let (item, min, max) = match DROPS.iter().find(|(block, ..)| *block == name) {
+        Some(&(_, item, min, max)) => (ItemID::from_name(item), min, max),
+        None if drops_nothing(name) => return BlockDrop::Nothing,
+        // Wall variants like `oak_wall_sign` share the item of the standing block
+        None => (ItemID::from_name(&name.replace("wall_", "")), 1, 1),
+    };
+    match item {
+        Some(item) => BlockDrop::Item { item, min, max },
+        None => BlockDrop::Nothing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_macros::block;
+
+    #[test]
+    fn test_tool_from_item_name() {
+        assert_eq!(
+            Tool::from_item_name("minecraft:diamond_pickaxe"),
+            Some(Tool {
+                kind: ToolKind::Pickaxe,
+                tier: Some(ToolTier::Diamond)
+            })
+        );
+        assert_eq!(
+            Tool::from_item_name("shears").map(|tool| tool.kind),
+            Some(ToolKind::Shears)
+        );
+        assert_eq!(Tool::from_item_name("minecraft:stone"), None);
+        assert_eq!(Tool::from_item_name("minecraft:iron_ingot"), None);
+    }
+
+    #[test]
+    fn test_vanilla_properties() {
+        let stone = block!("stone").properties();
+        assert_eq!(stone.hardness, 1.5);
+        assert_eq!(stone.tool, Some(ToolKind::Pickaxe));
+        assert_eq!(stone.required_tier, Some(ToolTier::Wood));
+
+        // Neither is stone, or a plant with a hardness of 1
+        let lamp = block!("redstone_lamp", {lit: false}).properties();
+        assert_eq!(
+            (lamp.hardness, lamp.tool, lamp.required_tier),
+            (0.3, None, None)
+        );
+        assert_eq!(block!("potted_poppy").properties().hardness, 0.0);
+
+        let obsidian = block!("obsidian").properties();
+        assert_eq!(obsidian.hardness, 50.0);
+        assert_eq!(obsidian.required_tier, Some(ToolTier::Diamond));
+        assert_eq!(
+            block!("iron_ore").properties().required_tier,
+            Some(ToolTier::Stone)
+        );
+        assert_eq!(
+            block!("diamond_ore").properties().required_tier,
+            Some(ToolTier::Iron)
+        );
+
+        let log = block!("oak_log", {axis: "y"}).properties();
+        assert_eq!(
+            (log.hardness, log.tool, log.required_tier),
+            (2.0, Some(ToolKind::Axe), None)
+        );
+        assert_eq!(block!("gravel").properties().tool, Some(ToolKind::Shovel));
+
+        let leaves =
+            block!("oak_leaves", {distance: 7, persistent: false, waterlogged: false}).properties();
+        assert_eq!(leaves.tool, Some(ToolKind::Hoe));
+        assert_eq!(leaves.shears_speed, 15.0);
+        assert_eq!(leaves.drop, BlockDrop::Nothing);
+        assert_eq!(block!("white_wool").properties().shears_speed, 5.0);
+        assert!(!block!("water", {level: 0}).properties().is_breakable());
+    }
+
+    #[test]
+    fn test_break_ticks() {
+        let stone = block!("stone").properties();
+        let wooden_pickaxe = Tool::from_item_name("wooden_pickaxe");
+        // Vanilla: 7.5 seconds by hand, 1.15 seconds with a wooden pickaxe
+        assert_eq!(stone.break_ticks(None, true), Some(150));
+        assert_eq!(stone.break_ticks(wooden_pickaxe, true), Some(23));
+        // 5 times slower in the air
+        assert_eq!(stone.break_ticks(wooden_pickaxe, false), Some(113));
+
+        let dirt = block!("dirt").properties();
+        assert_eq!(dirt.break_ticks(None, true), Some(15));
+        assert_eq!(block!("air").properties().break_ticks(None, true), None);
+        assert_eq!(block!("bedrock").properties().break_ticks(None, true), None);
+        assert_eq!(
+            block!("short_grass").properties().break_ticks(None, true),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_harvesting() {
+        let iron_ore = block!("iron_ore").properties();
+        assert!(!iron_ore.can_harvest(None));
+        assert!(!iron_ore.can_harvest(Tool::from_item_name("wooden_pickaxe")));
+        assert!(!iron_ore.can_harvest(Tool::from_item_name("golden_pickaxe")));
+        assert!(iron_ore.can_harvest(Tool::from_item_name("stone_pickaxe")));
+        assert!(!iron_ore.can_harvest(Tool::from_item_name("diamond_shovel")));
+        assert_eq!(
+            iron_ore.drop,
+            BlockDrop::Item {
+                item: ItemID::from_name("raw_iron").unwrap(),
+                min: 1,
+                max: 1
+            }
+        );
+
+        let cobweb = block!("cobweb").properties();
+        assert!(cobweb.can_harvest(Tool::from_item_name("wooden_sword")));
+        assert!(cobweb.can_harvest(Tool::from_item_name("shears")));
+        assert!(!cobweb.can_harvest(Tool::from_item_name("diamond_pickaxe")));
+    }
+
+    #[test]
+    fn test_drops() {
+        let item = |name| ItemID::from_name(name).unwrap();
+        assert_eq!(
+            block!("stone").properties().drop,
+            BlockDrop::Item {
+                item: item("cobblestone"),
+                min: 1,
+                max: 1
+            }
+        );
+        assert_eq!(
+            block!("oak_planks").properties().drop,
+            BlockDrop::Item {
+                item: item("oak_planks"),
+                min: 1,
+                max: 1
+            }
+        );
+        assert_eq!(
+            block!("wall_torch", {facing: "north"}).properties().drop,
+            BlockDrop::Item {
+                item: item("torch"),
+                min: 1,
+                max: 1
+            }
+        );
+        assert_eq!(block!("glass").properties().drop, BlockDrop::Nothing);
+        assert_eq!(
+            block!("red_stained_glass_pane", {east: false, north: false, south: false, waterlogged: false, west: false})
+                .properties()
+                .drop,
+            BlockDrop::Nothing
+        );
+        assert_eq!(
+            block!("tinted_glass").properties().drop,
+            BlockDrop::Item {
+                item: item("tinted_glass"),
+                min: 1,
+                max: 1
+            }
+        );
+        assert_eq!(block!("air").properties().drop, BlockDrop::Nothing);
+    }
+}
@@ -0,0 +1,50 @@
+//! Vanilla's block tags, like `minecraft:leaves` or `minecraft:mineable/pickaxe`.
+//!
+//! `block_tags.json` is generated from the game data by `scripts/vanilla_data.py`, with nested
+//! tags already resolved. Use these instead of matching on block names when grouping blocks.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+
+const BLOCK_TAGS_FILE: &str = include_str!("../../../../assets/data/block_tags.json");
+
+lazy_static! {
+    /// The names of the blocks in each tag, by tag name.
+    static ref BLOCK_TAGS: HashMap<String, HashSet<String>> =
+        serde_json::from_str(BLOCK_TAGS_FILE).expect("Failed to parse block_tags.json");
+}
+
+/// Whether the block called `name`, e.g. `minecraft:oak_leaves`, is in `tag`.
+pub fn is_tagged(name: &str, tag: &str) -> bool {
+    BLOCK_TAGS
+        .get(tag)
+        .is_some_and(|blocks| blocks.contains(name))
+}
+
+impl BlockStateId {
+    /// Whether this block is in the block tag `tag`, e.g. `minecraft:leaves`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        ID2BLOCK
+            .get(self.0 as usize)
+            .is_some_and(|block| is_tagged(&block.name, tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::block_state_id::BlockStateId;
+    use ferrumc_macros::block;
+
+    #[test]
+    fn test_has_tag() {
+        assert!(
+            block!("oak_leaves", {distance: 7, persistent: false, waterlogged: false})
+                .has_tag("minecraft:leaves")
+        );
+        assert!(block!("stone").has_tag("minecraft:mineable/pickaxe"));
+        assert!(block!("cave_air").has_tag("minecraft:air"));
+        assert!(!block!("redstone_lamp", {lit: false}).has_tag("minecraft:mineable/pickaxe"));
+        assert!(!block!("stone").has_tag("minecraft:not_a_tag"));
+    }
+}
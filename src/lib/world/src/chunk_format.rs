@@ -1,14 +1,18 @@
-use crate::block_state_id::{BlockStateId, BLOCK2ID};
+use crate::biomes::default_biome;
+use crate::block_entities::{BlockEntity, BlockEntityPos, VanillaBlockEntity};
+use crate::block_state_id::{BlockStateId, BLOCK2ID, ID2BLOCK};
+use crate::lighting::LIGHT_ARRAY_SIZE;
 use crate::vanilla_chunk_format;
-use crate::vanilla_chunk_format::VanillaChunk;
+use crate::vanilla_chunk_format::{References, Starts, Structures, VanillaChunk};
 use crate::{errors::WorldError, vanilla_chunk_format::VanillaHeightmaps};
 use bitcode_derive::{Decode, Encode};
 use deepsize::DeepSizeOf;
-use ferrumc_general_purpose::data_packing::i32::read_nbit_i32;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_macros::{block, NBTDeserialize, NBTSerialize};
+use ferrumc_nbt::{NBTSerializable, NBTSerializeOptions};
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::error;
 // #[cfg(test)]
 // const BLOCKSFILE: &[u8] = &[0];
@@ -28,6 +32,7 @@ pub struct Chunk {
     pub dimension: String,
     pub sections: Vec<Section>,
     pub heightmaps: Heightmaps,
+    pub block_entities: BTreeMap<BlockEntityPos, BlockEntity>,
 }
 
 #[derive(Encode, Decode, NBTDeserialize, NBTSerialize, Clone, DeepSizeOf, Debug)]
@@ -36,6 +41,10 @@ pub struct Chunk {
 pub struct Heightmaps {
     #[nbt(rename = "MOTION_BLOCKING")]
     pub motion_blocking: Vec<i64>,
+    #[nbt(rename = "MOTION_BLOCKING_NO_LEAVES")]
+    pub motion_blocking_no_leaves: Vec<i64>,
+    #[nbt(rename = "OCEAN_FLOOR")]
+    pub ocean_floor: Vec<i64>,
     #[nbt(rename = "WORLD_SURFACE")]
     pub world_surface: Vec<i64>,
 }
@@ -72,7 +81,8 @@ pub enum PaletteType {
 pub struct BiomeStates {
     pub bits_per_biome: u8,
     pub data: Vec<i64>,
-    pub palette: Vec<VarInt>,
+    /// The biome names, like `minecraft:plains`.
+    pub palette: Vec<String>,
 }
 
 fn convert_to_net_palette(
@@ -97,6 +107,8 @@ impl Heightmaps {
     pub fn new() -> Self {
         Heightmaps {
             motion_blocking: vec![],
+            motion_blocking_no_leaves: vec![],
+            ocean_floor: vec![],
             world_surface: vec![],
         }
     }
@@ -112,6 +124,8 @@ impl From<VanillaHeightmaps> for Heightmaps {
     fn from(value: VanillaHeightmaps) -> Self {
         Self {
             motion_blocking: value.motion_blocking.unwrap_or_default(),
+            motion_blocking_no_leaves: value.motion_blocking_no_leaves.unwrap_or_default(),
+            ocean_floor: value.ocean_floor.unwrap_or_default(),
             world_surface: value.world_surface.unwrap_or_default(),
         }
     }
@@ -119,6 +133,11 @@ impl From<VanillaHeightmaps> for Heightmaps {
 
 impl VanillaChunk {
     pub fn to_custom_format(&self) -> Result<Chunk, WorldError> {
+        let dimension_type = self
+            .dimension
+            .as_deref()
+            .and_then(Dimension::from_name)
+            .unwrap_or_default();
         let mut sections = Vec::new();
         for section in self.sections.as_ref().unwrap() {
             let y = section.y;
@@ -132,48 +151,25 @@ impl VanillaChunk {
                 .as_ref()
                 .and_then(|bs| bs.palette.clone())
                 .unwrap_or_default();
-            let bits_per_block = max((palette.len() as f32).log2().ceil() as u8, 4);
-            let mut block_counts = HashMap::new();
-            for chunk in &raw_block_data {
-                let mut i = 0;
-                while i + bits_per_block < 64 {
-                    let palette_index = read_nbit_i32(chunk, bits_per_block as usize, i as u32)?;
-                    let block = match palette.get(palette_index as usize) {
-                        Some(block) => block.to_block_state_id(),
+            let palette = convert_to_net_palette(palette)?;
+            let block_states = if raw_block_data.is_empty() {
+                // Sections with a single block in the palette don't store any data
+                let block = palette.first().copied().unwrap_or_default();
+                BlockStates::from_block_ids(&[block.0 as u32; 4096])
+            } else {
+                let ids = unpack_entries(&raw_block_data, indirect_bits(palette.len()), 4096)
+                    .into_iter()
+                    .map(|index| match palette.get(index as usize) {
+                        Some(id) => id.0 as u32,
                         None => {
-                            error!("Could not find block for palette index: {}", palette_index);
-                            BlockStateId::default()
+                            error!("Could not find block for palette index: {}", index);
+                            0
                         }
-                    };
-
-                    if let Some(count) = block_counts.get_mut(&block) {
-                        *count += 1;
-                    } else {
-                        block_counts.insert(block, 0);
-                    }
-
-                    i += bits_per_block;
-                }
-            }
-            let block_data = if raw_block_data.is_empty() {
-                block_counts.insert(BlockStateId::default(), 4096);
-                PaletteType::Single(VarInt::from(0))
-            } else {
-                PaletteType::Indirect {
-                    bits_per_block,
-                    data: raw_block_data,
-                    palette: convert_to_net_palette(palette)?,
-                }
-            };
-            // Count the number of blocks that are either air, void air, or cave air
-            let mut air_blocks = *block_counts.get(&BlockStateId::default()).unwrap_or(&0) as u16;
-            air_blocks += *block_counts.get(&block!("void_air")).unwrap_or(&0) as u16;
-            air_blocks += *block_counts.get(&block!("cave_air")).unwrap_or(&0) as u16;
-            let non_air_blocks = 4096 - air_blocks;
-            let block_states = BlockStates {
-                block_counts,
-                non_air_blocks,
-                block_data,
+                    })
+                    .collect::<Vec<_>>();
+                // Noisy sections can have too many block states for a palette, in which case
+                // they're stored with the global ids
+                BlockStates::from_block_ids(&ids)
             };
             let block_light = section
                 .block_light
@@ -189,12 +185,14 @@ impl VanillaChunk {
                 .iter()
                 .map(|&x| x as u8)
                 .collect();
-            let biome_states = BiomeStates {
-                // TODO: Implement biome states properly
-                bits_per_biome: 4,
-                data: vec![],
-                palette: vec![VarInt::from(0); 1],
-            };
+            let biome_states = section
+                .biomes
+                .as_ref()
+                .filter(|biomes| !biomes.palette.is_empty())
+                .map_or_else(
+                    || BiomeStates::single(default_biome(dimension_type)),
+                    BiomeStates::from_vanilla,
+                );
             let section = Section {
                 y,
                 block_states,
@@ -205,50 +203,312 @@ impl VanillaChunk {
             sections.push(section);
         }
 
-        let dimension = self.dimension.clone().unwrap_or("overworld".to_string());
+        let dimension = self
+            .dimension
+            .clone()
+            .unwrap_or(Dimension::default().name().to_string());
 
         let heightmaps: Heightmaps = self.heightmaps.clone().map(Into::into).unwrap_or_default();
 
-        Ok(Chunk {
+        let block_entities = self
+            .block_entities
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(VanillaBlockEntity::into_block_entity)
+            .collect();
+
+        let mut chunk = Chunk {
             x: self.x_pos,
             z: self.z_pos,
             dimension,
             sections,
             heightmaps,
+            block_entities,
+        };
+        // Chunks the game hasn't calculated the light of yet don't have any saved
+        if self.is_light_on != Some(1) {
+            chunk.relight();
+        }
+        // Older chunks and unfinished ones don't have every heightmap
+        if !chunk.heightmaps.is_complete(dimension_type) {
+            chunk.recalculate_heightmaps();
+        }
+        Ok(chunk)
+    }
+}
+
+/// The data version of the chunks written by [`Chunk::to_vanilla_nbt`], 1.21.8.
+pub const DATA_VERSION: i32 = 4440;
+
+/// Reads `count` entries of `bits` bits each, which don't span across longs.
+pub(crate) fn unpack_entries(data: &[i64], bits: u8, count: usize) -> Vec<u32> {
+    let per_long = 64 / bits as usize;
+    let mask = (1u64 << bits) - 1;
+    (0..count)
+        .map(|index| {
+            let long = data.get(index / per_long).copied().unwrap_or_default() as u64;
+            ((long >> ((index % per_long) * bits as usize)) & mask) as u32
+        })
+        .collect()
+}
+
+/// The inverse of [`unpack_entries`].
+pub(crate) fn pack_entries(entries: &[u32], bits: u8) -> Vec<i64> {
+    let per_long = 64 / bits as usize;
+    entries
+        .chunks(per_long)
+        .map(|entries| {
+            entries
+                .iter()
+                .enumerate()
+                .fold(0u64, |long, (index, &entry)| {
+                    long | (u64::from(entry) << (index * bits as usize))
+                }) as i64
         })
+        .collect()
+}
+
+/// The most bits per block a section's palette can use. Sections with more block states than
+/// that fit store the global block state ids directly, like vanilla.
+pub const MAX_INDIRECT_BITS: u8 = 8;
+
+/// The bits per block of sections that store global block state ids, enough for every state.
+pub fn direct_bits() -> u8 {
+    (usize::BITS - (ID2BLOCK.len() - 1).leading_zeros()) as u8
+}
+
+/// The bits per block of a palette with `len` entries. Vanilla never uses less than 4.
+pub(crate) fn indirect_bits(len: usize) -> u8 {
+    max((len as f32).log2().ceil() as u8, 4)
+}
+
+impl BlockStates {
+    /// Builds the block states of a section from the global ids of its 4096 blocks, in YZX order,
+    /// using the smallest palette type they fit in.
+    pub fn from_block_ids(ids: &[u32]) -> Self {
+        let mut palette = Vec::new();
+        let mut palette_indices = HashMap::new();
+        let mut block_counts = HashMap::new();
+        let indices = ids
+            .iter()
+            .map(|&id| {
+                *block_counts.entry(BlockStateId(id)).or_insert(0) += 1;
+                *palette_indices.entry(id).or_insert_with(|| {
+                    palette.push(id);
+                    palette.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let air = [
+            BlockStateId::default(),
+            block!("void_air"),
+            block!("cave_air"),
+        ];
+        let non_air_blocks = block_counts
+            .iter()
+            .filter(|(block, _)| !air.contains(block))
+            .map(|(_, &count)| count as u16)
+            .sum();
+
+        let bits_per_block = indirect_bits(palette.len());
+        let block_data = if palette.len() == 1 {
+            PaletteType::Single(BlockStateId(palette[0]).to_varint())
+        } else if bits_per_block <= MAX_INDIRECT_BITS {
+            PaletteType::Indirect {
+                bits_per_block,
+                data: pack_entries(&indices, bits_per_block),
+                palette: palette
+                    .into_iter()
+                    .map(|id| BlockStateId(id).to_varint())
+                    .collect(),
+            }
+        } else {
+            PaletteType::Direct {
+                bits_per_block: direct_bits(),
+                data: pack_entries(ids, direct_bits()),
+            }
+        };
+        BlockStates {
+            non_air_blocks,
+            block_data,
+            block_counts,
+        }
+    }
+}
+
+impl PaletteType {
+    /// The global block state ids of all 4096 blocks in the section, in YZX order.
+    pub(crate) fn block_ids(&self) -> Vec<u32> {
+        match self {
+            PaletteType::Single(id) => vec![id.0 as u32; 4096],
+            PaletteType::Indirect {
+                bits_per_block,
+                data,
+                palette,
+            } => {
+                if palette.len() == 1 || *bits_per_block == 0 {
+                    return vec![palette[0].0 as u32; 4096];
+                }
+                unpack_entries(data, *bits_per_block, 4096)
+                    .into_iter()
+                    .map(|index| {
+                        palette
+                            .get(index as usize)
+                            .map(|id| id.0 as u32)
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            }
+            PaletteType::Direct {
+                bits_per_block,
+                data,
+            } => unpack_entries(data, *bits_per_block, 4096),
+        }
+    }
+}
+
+impl Section {
+    fn to_vanilla_format(&self) -> vanilla_chunk_format::Section {
+        // Rebuild the palette from scratch, so unused entries left behind by edits are dropped
+        let mut palette = Vec::new();
+        let mut palette_indices = HashMap::new();
+        let indices = self
+            .block_states
+            .block_data
+            .block_ids()
+            .into_iter()
+            .map(|id| {
+                *palette_indices.entry(id).or_insert_with(|| {
+                    palette.push(id);
+                    palette.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let data = if palette.len() > 1 {
+            Some(pack_entries(&indices, indirect_bits(palette.len())))
+        } else {
+            None
+        };
+        let palette = palette
+            .into_iter()
+            .map(|id| {
+                BlockStateId(id).to_block_data().unwrap_or_else(|| {
+                    error!("Could not find block data for block state id: {}", id);
+                    vanilla_chunk_format::BlockData::default()
+                })
+            })
+            .collect();
+
+        vanilla_chunk_format::Section {
+            block_states: Some(vanilla_chunk_format::BlockStates {
+                data,
+                palette: Some(palette),
+            }),
+            biomes: Some(self.biome_states.to_vanilla()),
+            y: self.y,
+            block_light: Some(self.block_light.iter().map(|&x| x as i8).collect()),
+            sky_light: Some(self.sky_light.iter().map(|&x| x as i8).collect()),
+        }
     }
 }
 
 impl Chunk {
+    /// Converts the chunk to the format used in vanilla region files.
+    pub(crate) fn to_vanilla_format(&self) -> VanillaChunk {
+        let non_empty = |heightmap: &Vec<i64>| (!heightmap.is_empty()).then(|| heightmap.clone());
+        VanillaChunk {
+            dimension: Some(self.dimension.clone()),
+            status: "minecraft:full".to_string(),
+            data_version: DATA_VERSION,
+            heightmaps: Some(VanillaHeightmaps {
+                motion_blocking: non_empty(&self.heightmaps.motion_blocking),
+                motion_blocking_no_leaves: non_empty(&self.heightmaps.motion_blocking_no_leaves),
+                ocean_floor: non_empty(&self.heightmaps.ocean_floor),
+                world_surface: non_empty(&self.heightmaps.world_surface),
+            }),
+            // Light is only calculated per chunk when chunks are generated, so let the game
+            // redo it with the neighbours loaded
+            is_light_on: Some(0),
+            inhabited_time: Some(0),
+            y_pos: self
+                .sections
+                .iter()
+                .map(|section| section.y)
+                .min()
+                .unwrap_or(-4) as i32,
+            x_pos: self.x,
+            z_pos: self.z,
+            structures: Some(Structures {
+                starts: Starts {},
+                references: References {},
+            }),
+            last_update: Some(0),
+            sections: Some(
+                self.sections
+                    .iter()
+                    .map(Section::to_vanilla_format)
+                    .collect(),
+            ),
+            block_entities: Some(
+                self.block_entities
+                    .iter()
+                    .map(|(&pos, block_entity)| {
+                        VanillaBlockEntity::from_block_entity(self.x, self.z, pos, block_entity)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Serializes the chunk to the uncompressed NBT stored in vanilla region files.
+    pub fn to_vanilla_nbt(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.to_vanilla_format()
+            .serialize(&mut buf, &NBTSerializeOptions::WithHeader(""));
+        buf
+    }
+
+    /// Creates an empty chunk, with the sections of `dimension`. Unknown dimensions get the
+    /// sections of the overworld.
     pub fn new(x: i32, z: i32, dimension: String) -> Self {
-        let mut sections: Vec<Section> = (-4..20)
+        let dimension_type = Dimension::from_name(&dimension).unwrap_or_default();
+        // An empty chunk is fully lit by the sky, if there is one
+        let sky_light = if dimension_type.has_skylight() {
+            0xFF
+        } else {
+            0
+        };
+        let mut sections: Vec<Section> = dimension_type
+            .sections()
             .map(|y| Section {
-                y: y as i8,
+                y,
                 block_states: BlockStates {
                     non_air_blocks: 0,
                     block_data: PaletteType::Single(VarInt::from(0)),
                     block_counts: HashMap::from([(BlockStateId::default(), 4096)]),
                 },
-                biome_states: BiomeStates {
-                    bits_per_biome: 0,
-                    data: vec![],
-                    palette: vec![VarInt::from(0)],
-                },
-                block_light: vec![255; 2048],
-                sky_light: vec![255; 2048],
+                biome_states: BiomeStates::single(default_biome(dimension_type)),
+                block_light: vec![0; LIGHT_ARRAY_SIZE],
+                sky_light: vec![sky_light; LIGHT_ARRAY_SIZE],
             })
             .collect();
         for section in &mut sections {
             section.optimise().expect("Failed to optimise section");
         }
         block!("stone");
-        Chunk {
+        let mut chunk = Chunk {
             x,
             z,
             dimension,
             sections,
             heightmaps: Heightmaps::new(),
-        }
+            block_entities: BTreeMap::new(),
+        };
+        chunk.recalculate_heightmaps();
+        chunk
     }
 }
 
@@ -287,11 +547,7 @@ mod tests {
                 block_data: PaletteType::Single(VarInt::from(0)),
                 block_counts: HashMap::from([(BlockStateId::default(), 4096)]),
             },
-            biome_states: BiomeStates {
-                bits_per_biome: 0,
-                data: vec![],
-                palette: vec![VarInt::from(0)],
-            },
+            biome_states: BiomeStates::single("minecraft:plains"),
             block_light: vec![255; 2048],
             sky_light: vec![255; 2048],
         };
@@ -307,6 +563,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dimension_sections() {
+        let chunk = Chunk::new(0, 0, Dimension::Nether.name().to_string());
+        assert_eq!(chunk.sections.len(), 16);
+        assert_eq!(chunk.sections[0].y, 0);
+        assert_eq!(chunk.to_vanilla_format().y_pos, 0);
+    }
+
     #[test]
     fn test_false_positive() {
         let mut chunk = Chunk::new(0, 0, "overworld".to_string());
@@ -315,6 +579,152 @@ mod tests {
         assert_ne!(chunk.get_block(0, 1, 0).unwrap(), block);
     }
 
+    #[test]
+    fn test_vanilla_roundtrip() {
+        let mut chunk = Chunk::new(3, -7, "overworld".to_string());
+        chunk.set_block(1, 2, 3, block!("stone")).unwrap();
+        chunk.set_block(4, 70, 5, block!("dirt")).unwrap();
+
+        let vanilla = VanillaChunk::from_bytes(&chunk.to_vanilla_nbt()).unwrap();
+        assert_eq!((vanilla.x_pos, vanilla.z_pos, vanilla.y_pos), (3, -7, -4));
+        assert_eq!(vanilla.data_version, DATA_VERSION);
+
+        let imported = vanilla.to_custom_format().unwrap();
+        assert_eq!(imported.get_block(1, 2, 3).unwrap(), block!("stone"));
+        assert_eq!(imported.get_block(4, 70, 5).unwrap(), block!("dirt"));
+        assert_eq!(
+            imported.get_block(0, 0, 0).unwrap(),
+            BlockStateId::default()
+        );
+        assert_eq!(
+            imported.get_block(4, 71, 5).unwrap(),
+            BlockStateId::default()
+        );
+
+        let mut filled = Chunk::new(0, 0, "overworld".to_string());
+        filled.fill(block!("stone")).unwrap();
+        let imported = VanillaChunk::from_bytes(&filled.to_vanilla_nbt())
+            .unwrap()
+            .to_custom_format()
+            .unwrap();
+        assert_eq!(imported.get_block(8, 8, 8).unwrap(), block!("stone"));
+    }
+
+    #[test]
+    fn test_pack_entries() {
+        let entries = (0..4096).map(|i| i % 13).collect::<Vec<_>>();
+        let packed = pack_entries(&entries, 5);
+        // 12 entries per long
+        assert_eq!(packed.len(), 4096usize.div_ceil(12));
+        assert_eq!(unpack_entries(&packed, 5, 4096), entries);
+    }
+
+    /// A section with more block states than fit in a palette, like the ones in creative worlds
+    /// full of every block.
+    fn noisy_ids() -> Vec<u32> {
+        (0..4096).map(|i| (i * 7) % 600).collect()
+    }
+
+    #[test]
+    fn test_direct_palette() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        for (i, id) in (1..300).enumerate() {
+            let i = i as i32;
+            chunk
+                .set_block(i & 0xF, (i >> 4) & 0xF, i >> 8, BlockStateId(id))
+                .unwrap();
+        }
+        let section = chunk.sections.iter().find(|s| s.y == 0).unwrap();
+        assert!(matches!(
+            section.block_states.block_data,
+            PaletteType::Direct { .. }
+        ));
+        assert_eq!(
+            chunk.get_block(5, 2, 1).unwrap(),
+            BlockStateId(1 + 256 + 32 + 5)
+        );
+        assert_eq!(chunk.get_block(0, 0, 0).unwrap(), BlockStateId(1));
+        assert_eq!(
+            chunk.get_block(15, 15, 15).unwrap(),
+            BlockStateId::default()
+        );
+
+        // Clearing most of the blocks moves the section back to a palette
+        for i in 0..250 {
+            chunk
+                .set_block(i & 0xF, (i >> 4) & 0xF, i >> 8, BlockStateId::default())
+                .unwrap();
+        }
+        let section = chunk.sections.iter().find(|s| s.y == 0).unwrap();
+        let PaletteType::Indirect {
+            bits_per_block,
+            palette,
+            ..
+        } = &section.block_states.block_data
+        else {
+            panic!("Section should have a palette again");
+        };
+        assert_eq!((*bits_per_block, palette.len()), (6, 50));
+        assert_eq!(
+            chunk.get_block(250 & 0xF, 250 >> 4, 0).unwrap(),
+            BlockStateId(251)
+        );
+    }
+
+    #[test]
+    fn test_noisy_section_import() {
+        let ids = noisy_ids();
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        chunk.sections[0].block_states = BlockStates::from_block_ids(&ids);
+
+        // Vanilla stores these with a 10 bit palette
+        let imported = VanillaChunk::from_bytes(&chunk.to_vanilla_nbt())
+            .unwrap()
+            .to_custom_format()
+            .unwrap();
+        let block_states = &imported.sections[0].block_states;
+        assert!(matches!(
+            block_states.block_data,
+            PaletteType::Direct {
+                bits_per_block: 15,
+                ..
+            }
+        ));
+        assert_eq!(block_states.block_data.block_ids(), ids);
+        assert_eq!(block_states.block_counts.len(), 600);
+        assert_eq!(block_states.block_counts[&BlockStateId(7)], 7);
+        assert_eq!(block_states.non_air_blocks, 4096 - 7);
+        assert_eq!(
+            imported
+                .get_block(3, imported.sections[0].y as i32 * 16, 0)
+                .unwrap(),
+            BlockStateId(21)
+        );
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut block_states =
+            BlockStates::from_block_ids(&(0..4096).map(|i| i % 20).collect::<Vec<_>>());
+        block_states.resize(7).unwrap();
+        assert!(matches!(
+            block_states.block_data,
+            PaletteType::Indirect {
+                bits_per_block: 7,
+                ..
+            }
+        ));
+        assert!(block_states.resize(4).is_err());
+        block_states.resize(9).unwrap();
+        assert!(matches!(
+            block_states.block_data,
+            PaletteType::Direct { .. }
+        ));
+        assert_eq!(block_states.block_data.block_ids()[4095], 4095 % 20);
+        block_states.resize(5).unwrap();
+        assert_eq!(block_states.block_data.block_ids()[77], 77 % 20);
+    }
+
     #[test]
     fn test_doesnt_fail() {
         let mut chunk = Chunk::new(0, 0, "overworld".to_string());
@@ -0,0 +1,178 @@
+//! The collision and outline shapes of blocks.
+//!
+//! `block_shapes.json` is generated from the game data by `scripts/vanilla_data.py`. It has every
+//! distinct shape once, as a list of boxes, and the index of the collision and outline shape of
+//! each block state. Collision shapes are what entities bump into, outline shapes are what
+//! players aim at, which is more than that for blocks like open fence gates and flowers.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use crate::block_tags::is_tagged;
+use ferrumc_core::collisions::bounds::CollisionBounds;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+
+type Shape = Vec<CollisionBounds>;
+
+/// Where the shapes of a block state are in [`ShapeTable::shapes`].
+#[derive(Clone, Copy)]
+struct StateShapes {
+    collision: u16,
+    outline: u16,
+    climbable: bool,
+}
+
+struct ShapeTable {
+    shapes: Vec<Shape>,
+    states: Vec<StateShapes>,
+}
+
+/// The layout of `block_shapes.json`. Boxes are `[x1, y1, z1, x2, y2, z2]`.
+#[derive(Deserialize)]
+struct VanillaShapes {
+    shapes: Vec<Vec<[f64; 6]>>,
+    collision: Vec<u16>,
+    outline: Vec<u16>,
+}
+
+const BLOCK_SHAPES_FILE: &str = include_str!("../../../../assets/data/block_shapes.json");
+
+lazy_static! {
+    static ref SHAPES: ShapeTable = {
+        let vanilla: VanillaShapes =
+            serde_json::from_str(BLOCK_SHAPES_FILE).expect("Failed to parse block_shapes.json");
+        let shapes = vanilla
+            .shapes
+            .iter()
+            .map(|boxes| {
+                boxes
+                    .iter()
+                    .map(|&[x1, y1, z1, x2, y2, z2]| CollisionBounds {
+                        x_offset_start: x1,
+                        x_offset_end: x2,
+                        y_offset_start: y1,
+                        y_offset_end: y2,
+                        z_offset_start: z1,
+                        z_offset_end: z2,
+                    })
+                    .collect()
+            })
+            .collect();
+        let states = ID2BLOCK
+            .iter()
+            .zip(vanilla.collision.iter().zip(&vanilla.outline))
+            .map(|(block, (&collision, &outline))| StateShapes {
+                collision,
+                outline,
+                climbable: is_tagged(&block.name, "minecraft:climbable"),
+            })
+            .collect();
+        ShapeTable { shapes, states }
+    };
+}
+
+const FULL: &[CollisionBounds] = &[CollisionBounds::FULL_BLOCK];
+
+impl BlockStateId {
+    /// The boxes entities collide with in this block, relative to its lowest corner. Unknown IDs
+    /// are treated as full blocks.
+    pub fn collision_shape(&self) -> &'static [CollisionBounds] {
+        SHAPES
+            .states
+            .get(self.0 as usize)
+            .map_or(FULL, |state| &SHAPES.shapes[state.collision as usize])
+    }
+
+    /// The boxes players aim at in this block, relative to its lowest corner. Unknown IDs are
+    /// treated as full blocks.
+    pub fn outline_shape(&self) -> &'static [CollisionBounds] {
+        SHAPES
+            .states
+            .get(self.0 as usize)
+            .map_or(FULL, |state| &SHAPES.shapes[state.outline as usize])
+    }
+
+    /// Whether entities can climb up this block, like ladders and vines.
+    pub fn is_climbable(&self) -> bool {
+        SHAPES
+            .states
+            .get(self.0 as usize)
+            .is_some_and(|state| state.climbable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_macros::block;
+
+    fn height(shape: &[CollisionBounds]) -> f64 {
+        shape
+            .iter()
+            .map(|bounds| bounds.y_offset_end)
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_full_and_empty_blocks() {
+        assert_eq!(block!("stone").collision_shape(), FULL);
+        assert_eq!(block!("stone").outline_shape(), FULL);
+        assert_eq!(
+            block!("oak_leaves", {distance: 1, persistent: true, waterlogged: false})
+                .collision_shape(),
+            FULL
+        );
+        assert_eq!(block!("sea_lantern").collision_shape(), FULL);
+        assert_eq!(
+            block!("jack_o_lantern", {facing: "north"}).collision_shape(),
+            FULL
+        );
+        assert!(BlockStateId::default().collision_shape().is_empty());
+        assert!(BlockStateId::default().outline_shape().is_empty());
+        assert!(block!("water", {level: 0}).outline_shape().is_empty());
+        assert_eq!(BlockStateId(u32::MAX).collision_shape(), FULL);
+    }
+
+    #[test]
+    fn test_partial_blocks() {
+        let cactus = block!("cactus", {age: 0});
+        assert_eq!(height(cactus.collision_shape()), 15.0 / 16.0);
+        assert_eq!(height(cactus.outline_shape()), 1.0);
+        assert!(block!("snow", {layers: 1}).collision_shape().is_empty());
+        assert_eq!(height(block!("snow", {layers: 3}).collision_shape()), 0.25);
+        assert_eq!(height(block!("snow", {layers: 3}).outline_shape()), 0.375);
+
+        let ladder = block!("ladder", {facing: "east", waterlogged: false});
+        assert_eq!(
+            ladder.collision_shape(),
+            &[CollisionBounds {
+                x_offset_end: 0.1875,
+                ..CollisionBounds::FULL_BLOCK
+            }]
+        );
+        assert!(ladder.is_climbable());
+        assert!(!block!("stone").is_climbable());
+
+        let lantern = block!("lantern", {hanging: false, waterlogged: false});
+        assert_eq!(lantern.collision_shape().len(), 2);
+        assert_eq!(height(lantern.collision_shape()), 0.5625);
+    }
+
+    #[test]
+    fn test_walk_through_blocks() {
+        let torch = block!("torch");
+        assert!(torch.collision_shape().is_empty());
+        assert_eq!(
+            torch.outline_shape(),
+            &[CollisionBounds {
+                x_offset_start: 0.375,
+                x_offset_end: 0.625,
+                y_offset_start: 0.0,
+                y_offset_end: 0.625,
+                z_offset_start: 0.375,
+                z_offset_end: 0.625,
+            }]
+        );
+        assert!(block!("dandelion").collision_shape().is_empty());
+        assert!(!block!("dandelion").outline_shape().is_empty());
+    }
+}
@@ -117,6 +117,43 @@ impl World {
     }
 }
 
+/// The version of the layout of [`Chunk`] in the database. Bitcode isn't self-describing, so
+/// chunks saved with another layout can't be read back, bump this whenever the layout changes.
+///
+/// Version 1 stores biome palettes per section as biome names.
+pub(crate) const CHUNK_FORMAT_VERSION: u32 = 1;
+const META_TABLE: &str = "meta";
+const FORMAT_VERSION_KEY: u128 = 0;
+
+/// Checks that the stored chunks are in the current format, and records the format of new worlds.
+/// Worlds saved before the format was recorded count as version 0.
+pub(crate) fn check_format_version(world: &World) -> Result<(), WorldError> {
+    let backend = &world.storage_backend;
+    if !backend.table_exists(META_TABLE.to_string())? {
+        backend.create_table(META_TABLE.to_string())?;
+    }
+    let stored = backend
+        .get(META_TABLE.to_string(), FORMAT_VERSION_KEY)?
+        .and_then(|bytes| Some(u32::from_le_bytes(bytes.try_into().ok()?)));
+    let has_chunks = || -> Result<bool, WorldError> {
+        Ok(backend.table_exists("chunks".to_string())?
+            && !backend.keys("chunks".to_string())?.is_empty())
+    };
+    match stored {
+        Some(CHUNK_FORMAT_VERSION) => Ok(()),
+        Some(version) => Err(WorldError::ChunkFormatMismatch(version)),
+        None if has_chunks()? => Err(WorldError::ChunkFormatMismatch(0)),
+        None => {
+            backend.upsert(
+                META_TABLE.to_string(),
+                FORMAT_VERSION_KEY,
+                CHUNK_FORMAT_VERSION.to_le_bytes().to_vec(),
+            )?;
+            Ok(())
+        }
+    }
+}
+
 pub(crate) fn save_chunk_internal(world: &World, chunk: &Chunk) -> Result<(), WorldError> {
     if !world.storage_backend.table_exists("chunks".to_string())? {
         world.storage_backend.create_table("chunks".to_string())?;
@@ -224,14 +261,27 @@ pub(crate) fn sync_internal(world: &World) -> Result<(), WorldError> {
     Ok(())
 }
 
-fn create_key(dimension: &str, x: i32, z: i32) -> u128 {
-    let mut key = 0u128;
+/// The hash of `dimension` as stored in the first 32 bits of a chunk key.
+pub(crate) fn dimension_hash(dimension: &str) -> u32 {
     let mut hasher = wyhash::WyHash::with_seed(0);
     hasher.write(dimension.as_bytes());
     hasher.write_u8(0xFF);
-    let dim_hash = hasher.finish();
+    hasher.finish() as u32
+}
+
+/// Splits a chunk key back into the dimension hash and the x and z coordinates.
+pub(crate) fn split_key(key: u128) -> (u32, i32, i32) {
+    (
+        (key >> 96) as u32,
+        (key >> 48) as u32 as i32,
+        key as u32 as i32,
+    )
+}
+
+fn create_key(dimension: &str, x: i32, z: i32) -> u128 {
+    let mut key = 0u128;
     // Insert the dimension hash into the key as the first 32 bits
-    key |= (dim_hash as u128) << 96;
+    key |= (dimension_hash(dimension) as u128) << 96;
     // Convert the x coordinate to a 48 bit integer and insert it into the key
     key |= ((x as u128) & 0x0000_0000_FFFF_FFFF) << 48;
     // Convert the z coordinate to a 48 bit integer and insert it into the key
@@ -239,3 +289,50 @@ fn create_key(dimension: &str, x: i32, z: i32) -> u128 {
 
     key
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_key() {
+        for (x, z) in [(0, 0), (-1, 1), (i32::MAX, i32::MIN), (1234, -5678)] {
+            let key = create_key("overworld", x, z);
+            assert_eq!(split_key(key), (dimension_hash("overworld"), x, z));
+        }
+    }
+
+    #[test]
+    fn test_format_version() {
+        let dir = tempfile::tempdir().unwrap();
+        // Records the version of the new world
+        let world = World::new(dir.path().join("db"));
+        assert!(check_format_version(&world).is_ok());
+
+        let backend = &world.storage_backend;
+        backend
+            .upsert(
+                META_TABLE.to_string(),
+                FORMAT_VERSION_KEY,
+                0u32.to_le_bytes().to_vec(),
+            )
+            .unwrap();
+        assert!(matches!(
+            check_format_version(&world),
+            Err(WorldError::ChunkFormatMismatch(0))
+        ));
+
+        // A world with chunks but no version is from before versions were recorded
+        backend
+            .delete(META_TABLE.to_string(), FORMAT_VERSION_KEY)
+            .unwrap();
+        backend.create_table("chunks".to_string()).unwrap();
+        backend
+            .upsert("chunks".to_string(), create_key("overworld", 0, 0), vec![0])
+            .unwrap();
+        assert!(matches!(
+            check_format_version(&world),
+            Err(WorldError::ChunkFormatMismatch(0))
+        ));
+    }
+}
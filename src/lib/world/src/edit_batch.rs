@@ -1,7 +1,9 @@
+use crate::biomes::default_biome;
 use crate::block_state_id::BlockStateId;
 use crate::chunk_format::{BiomeStates, BlockStates, Chunk, PaletteType};
 use crate::WorldError;
 use ahash::{AHashMap, AHashSet, AHasher};
+use ferrumc_core::dimension::Dimension;
 use ferrumc_general_purpose::data_packing::i32::read_nbit_i32;
 use ferrumc_general_purpose::data_packing::u32::write_nbit_u32;
 use ferrumc_net_codec::net_types::var_int::VarInt;
@@ -77,9 +79,15 @@ impl<'a> EditBatch<'a> {
         self.edits.push(Edit { x, y, z, block });
     }
 
+    /// Whether no edits have been added to the batch.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
     /// Applies all edits in the batch to the chunk.
     ///
-    /// This will modify the chunk in place and clear the batch.
+    /// This will modify the chunk in place, recalculate its light and heightmaps, remove the block
+    /// entities of replaced blocks and clear the batch.
     /// Will return an error if the batch has already been used or if there are no edits.
     pub fn apply(&mut self) -> Result<(), WorldError> {
         if self.used {
@@ -93,6 +101,19 @@ impl<'a> EditBatch<'a> {
             ));
         }
 
+        // Block entities belong to the blocks being replaced
+        if !self.chunk.block_entities.is_empty() {
+            for edit in &self.edits {
+                if self
+                    .chunk
+                    .get_block(edit.x, edit.y, edit.z)
+                    .is_ok_and(|block| block != edit.block)
+                {
+                    self.chunk.remove_block_entity(edit.x, edit.y, edit.z);
+                }
+            }
+        }
+
         let mut section_edits: AHashMap<i8, Vec<Option<&Edit>>> = AHashMap::new();
         let mut all_blocks = AHashSet::new();
 
@@ -137,12 +158,9 @@ impl<'a> EditBatch<'a> {
                             block_data: PaletteType::Single(VarInt::default()),
                             block_counts: HashMap::from([(BlockStateId::default(), 4096)]),
                         },
-                        // Biomes don't really matter for this, so we can just use empty data
-                        biome_states: BiomeStates {
-                            bits_per_biome: 0,
-                            data: vec![],
-                            palette: vec![],
-                        },
+                        biome_states: BiomeStates::single(default_biome(
+                            Dimension::from_name(&self.chunk.dimension).unwrap_or_default(),
+                        )),
                         block_light: vec![255; 2048],
                         sky_light: vec![255; 2048],
                     };
@@ -185,15 +203,41 @@ impl<'a> EditBatch<'a> {
                 };
             }
 
+            // Sections without a palette, or whose palette would overflow, are rebuilt from their
+            // block ids instead, which also moves them to or from global ids as needed
+            let fits = match &section.block_states.block_data {
+                PaletteType::Indirect {
+                    bits_per_block,
+                    palette,
+                    ..
+                } => {
+                    let mut blocks: AHashSet<BlockStateId> = palette
+                        .iter()
+                        .map(|p| BlockStateId::from_varint(*p))
+                        .collect();
+                    blocks.extend(edits_vec.iter().flatten().map(|edit| edit.block));
+                    blocks.len() <= 1 << *bits_per_block
+                }
+                _ => false,
+            };
+            if !fits {
+                let mut ids = section.block_states.block_data.block_ids();
+                for edit in edits_vec.iter().flatten() {
+                    let index =
+                        ((edit.y & 0xf) * 256 + (edit.z & 0xf) * 16 + (edit.x & 0xf)) as usize;
+                    ids[index] = edit.block.0;
+                }
+                section.block_states = BlockStates::from_block_ids(&ids);
+                continue;
+            }
+
             let PaletteType::Indirect {
                 bits_per_block,
                 data,
                 palette,
             } = &mut section.block_states.block_data
             else {
-                return Err(WorldError::InvalidBlockStateData(
-                    "Unsupported palette type".to_string(),
-                ));
+                unreachable!("Only sections with a palette are edited in place");
             };
 
             // Hash current palette so we can detect changes after edits
@@ -304,6 +348,11 @@ impl<'a> EditBatch<'a> {
         self.edits.clear();
         self.used = true;
 
+        // Relighting the whole chunk once is cheaper than updating it for each edit, and the same
+        // goes for the heightmaps
+        self.chunk.relight();
+        self.chunk.recalculate_heightmaps();
+
         Ok(())
     }
 }
@@ -362,4 +411,39 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_palette_overflow() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        let mut batch = EditBatch::new(&mut chunk);
+        for i in 0..300 {
+            batch.set_block(i & 0xF, (i >> 4) & 0xF, i >> 8, BlockStateId(i as u32 + 1));
+        }
+        batch.apply().unwrap();
+        let section = chunk.sections.iter().find(|s| s.y == 0).unwrap();
+        assert!(matches!(
+            section.block_states.block_data,
+            PaletteType::Direct { .. }
+        ));
+        assert_eq!(section.block_states.non_air_blocks, 300);
+        assert_eq!(
+            chunk.get_block(4, 2, 1).unwrap(),
+            BlockStateId(256 + 32 + 4 + 1)
+        );
+
+        let mut batch = EditBatch::new(&mut chunk);
+        for i in 20..300 {
+            batch.set_block(i & 0xF, (i >> 4) & 0xF, i >> 8, BlockStateId::default());
+        }
+        batch.apply().unwrap();
+        let section = chunk.sections.iter().find(|s| s.y == 0).unwrap();
+        assert!(matches!(
+            section.block_states.block_data,
+            PaletteType::Indirect {
+                bits_per_block: 5,
+                ..
+            }
+        ));
+        assert_eq!(chunk.get_block(19 & 0xF, 1, 0).unwrap(), BlockStateId(20));
+    }
 }
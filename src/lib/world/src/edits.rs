@@ -1,8 +1,12 @@
 use crate::block_state_id::{BlockStateId, ID2BLOCK};
-use crate::chunk_format::{BlockStates, Chunk, PaletteType, Section};
+use crate::chunk_format::{
+    direct_bits, indirect_bits, pack_entries, BlockStates, Chunk, PaletteType, Section,
+    MAX_INDIRECT_BITS,
+};
 use crate::errors::WorldError;
+use crate::lighting::LightEngine;
 use crate::World;
-use ferrumc_general_purpose::data_packing::i32::read_nbit_i32;
+use ferrumc_net_codec::net_types::var_int::VarInt;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -89,101 +93,53 @@ impl World {
 }
 
 impl BlockStates {
+    /// Repacks the section with `new_bit_size` bits per block. Past [`MAX_INDIRECT_BITS`] the
+    /// section switches to global block state ids, and back to a palette below it.
     pub fn resize(&mut self, new_bit_size: usize) -> Result<(), WorldError> {
-        match &mut self.block_data {
-            PaletteType::Single(val) => {
-                self.block_data = PaletteType::Indirect {
-                    bits_per_block: new_bit_size as u8,
-                    data: vec![],
-                    palette: vec![*val; 1],
-                }
-            }
-            PaletteType::Indirect {
-                bits_per_block,
-                data,
-                palette,
-            } => {
-                // Step 1: Read existing packed data into a list of normal integers
-                let mut normalised_ints = Vec::with_capacity(4096);
-                let mut values_read = 0;
-
-                for long in data {
-                    let mut bit_offset = 0;
-
-                    while bit_offset + *bits_per_block as usize <= 64 {
-                        if values_read >= 4096 {
-                            break;
-                        }
-
-                        // Extract value at the current bit offset
-                        let value =
-                            read_nbit_i32(long, *bits_per_block as usize, bit_offset as u32)?;
-                        let max_int_value = (1 << new_bit_size) - 1;
-                        if value > max_int_value {
-                            return Err(WorldError::InvalidBlockStateData(format!(
-                                "Value {value} exceeds maximum value for {new_bit_size}-bit block state"
-                            )));
-                        }
-                        normalised_ints.push(value);
-                        values_read += 1;
-
-                        bit_offset += *bits_per_block as usize;
-                    }
-
-                    // Stop reading if we’ve already hit 4096 values
-                    if values_read >= 4096 {
-                        break;
-                    }
-                }
-
-                // Check if we read exactly 4096 block states
-                if normalised_ints.len() != 4096 {
-                    return Err(WorldError::InvalidBlockStateData(format!(
-                        "Expected 4096 block states, but got {}",
-                        normalised_ints.len()
-                    )));
-                }
-
-                // Step 2: Write the normalised integers into the new packed format
-                let mut new_data = Vec::new();
-                let mut current_long: i64 = 0;
-                let mut bit_position = 0;
-
-                for &value in &normalised_ints {
-                    current_long |= (value as i64) << bit_position;
-                    bit_position += new_bit_size;
-
-                    if bit_position >= 64 {
-                        new_data.push(current_long);
-                        current_long = (value as i64) >> (new_bit_size - (bit_position - 64));
-                        bit_position -= 64;
-                    }
-                }
-
-                // Push any remaining bits in the final long
-                if bit_position > 0 {
-                    new_data.push(current_long);
-                }
+        if new_bit_size == 0 {
+            return Err(WorldError::InvalidBlockStateData(
+                "Can't resize block states to 0 bits".to_string(),
+            ));
+        }
+        let ids = self.block_data.block_ids();
+        if new_bit_size > MAX_INDIRECT_BITS as usize {
+            self.block_data = PaletteType::Direct {
+                bits_per_block: direct_bits(),
+                data: pack_entries(&ids, direct_bits()),
+            };
+            return Ok(());
+        }
 
-                // Verify the size of the new data matches expectations
-                let expected_size = (4096 * new_bit_size).div_ceil(64);
-                if new_data.len() != expected_size {
-                    return Err(WorldError::InvalidBlockStateData(format!(
-                        "Expected packed data size of {}, but got {}",
-                        expected_size,
-                        new_data.len()
-                    )));
-                }
-                // Update the chunk with the new packed data and a bit size
-                self.block_data = PaletteType::Indirect {
-                    bits_per_block: new_bit_size as u8,
-                    data: new_data,
-                    palette: palette.clone(),
-                }
-            }
-            _ => {
-                todo!("Implement resizing for direct palette")
-            }
+        // Keep the existing palette order, so the palette indices don't change
+        let mut palette = match &self.block_data {
+            PaletteType::Single(val) => vec![*val],
+            PaletteType::Indirect { palette, .. } => palette.clone(),
+            PaletteType::Direct { .. } => vec![],
+        };
+        let mut palette_indices: HashMap<u32, u32> = palette
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.0 as u32, index as u32))
+            .collect();
+        let indices = ids
+            .iter()
+            .map(|&id| {
+                *palette_indices.entry(id).or_insert_with(|| {
+                    palette.push(BlockStateId(id).to_varint());
+                    palette.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        if palette.len() > 1 << new_bit_size {
+            return Err(WorldError::InvalidBlockStateData(format!(
+                "{} block states don't fit in {new_bit_size} bits",
+                palette.len()
+            )));
+        }
+        self.block_data = PaletteType::Indirect {
+            bits_per_block: new_bit_size as u8,
+            data: pack_entries(&indices, new_bit_size as u8),
+            palette,
         };
         Ok(())
     }
@@ -211,12 +167,36 @@ impl Chunk {
     /// The positions are modulo'd by 16 to get the block index in the section anyway, so converting
     /// the coordinates to section coordinates isn't really necessary, but you should probably do it
     /// anyway for readability's sake.
+    ///
+    /// The light in the chunk is updated to match, but light isn't spread into neighbouring
+    /// chunks. Use a [`LightEngine`] with the neighbours loaded for that.
+    ///
+    /// The heightmaps of the column are updated too, and the block entity of the old block is
+    /// removed.
     pub fn set_block(
         &mut self,
         x: i32,
         y: i32,
         z: i32,
         block: BlockStateId,
+    ) -> Result<(), WorldError> {
+        let old_block = self.get_block(x, y, z)?;
+        self.set_block_unlit(x, y, z, block)?;
+        if old_block != block {
+            let (x, z) = (self.x * 16 + (x & 0xF), self.z * 16 + (z & 0xF));
+            LightEngine::new(vec![self]).update_block(x, y, z, old_block);
+        }
+        Ok(())
+    }
+
+    /// Sets a block like [`Chunk::set_block`], without updating the light. The heightmaps are
+    /// still updated.
+    pub(crate) fn set_block_unlit(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        block: BlockStateId,
     ) -> Result<(), WorldError> {
         // Get old block
         let old_block = self.get_block(x, y, z)?;
@@ -224,6 +204,8 @@ impl Chunk {
             // debug!("Block is the same as the old block");
             return Ok(());
         }
+        // The block entity belonged to the block being replaced
+        self.remove_block_entity(x, y, z);
         // Get section
         let section = self
             .sections
@@ -251,8 +233,53 @@ impl Chunk {
             section.block_states.block_data = new_contents;
         }
 
-        // Do different things based on the palette type
-        match &mut section.block_states.block_data {
+        // Grow the palette if the new block doesn't fit in it, which moves the section over to
+        // global ids once the palette gets too big
+        if let PaletteType::Indirect {
+            bits_per_block,
+            palette,
+            ..
+        } = &section.block_states.block_data
+        {
+            if !palette.contains(&block.to_varint()) && palette.len() >= 1 << *bits_per_block {
+                let new_bit_size = *bits_per_block as usize + 1;
+                section.block_states.resize(new_bit_size)?;
+            }
+        }
+
+        match section.block_states.block_counts.entry(old_block) {
+            Entry::Occupied(mut occ_entry) => {
+                let count = occ_entry.get_mut();
+                if *count <= 0 {
+                    return match old_block.to_block_data() {
+                        Some(block_data) => {
+                            error!("Block count is zero for block: {:?}", block_data);
+                            Err(WorldError::InvalidBlockStateData(format!(
+                                "Block count is zero for block: {block_data:?}"
+                            )))
+                        }
+                        None => {
+                            error!(
+                                "Block count is zero for unknown block state ID: {}",
+                                old_block.0
+                            );
+                            Err(WorldError::InvalidBlockStateId(old_block.0))
+                        }
+                    };
+                }
+                *count -= 1;
+            }
+            Entry::Vacant(empty_entry) => {
+                warn!("Block not found in block counts: {:?}", old_block);
+                empty_entry.insert(0);
+            }
+        }
+        // Add new block
+        *section.block_states.block_counts.entry(block).or_insert(0) += 1;
+
+        let index = ((y & 0xf) * 256 + (z & 0xf) * 16 + (x & 0xf)) as usize;
+        // The palette index of the block, or its global id for sections without a palette
+        let (value, bits_per_block, data) = match &mut section.block_states.block_data {
             PaletteType::Single(_val) => {
                 panic!("Single palette type should have been converted to indirect palette type");
             }
@@ -261,80 +288,39 @@ impl Chunk {
                 data,
                 palette,
             } => {
-                // debug!("Indirect mode");
-                match section.block_states.block_counts.entry(old_block) {
-                    Entry::Occupied(mut occ_entry) => {
-                        let count = occ_entry.get_mut();
-                        if *count <= 0 {
-                            return match old_block.to_block_data() {
-                                Some(block_data) => {
-                                    error!("Block count is zero for block: {:?}", block_data);
-                                    Err(WorldError::InvalidBlockStateData(format!(
-                                        "Block count is zero for block: {block_data:?}"
-                                    )))
-                                }
-                                None => {
-                                    error!(
-                                        "Block count is zero for unknown block state ID: {}",
-                                        old_block.0
-                                    );
-                                    Err(WorldError::InvalidBlockStateId(old_block.0))
-                                }
-                            };
-                        }
-                        *count -= 1;
-                    }
-                    Entry::Vacant(empty_entry) => {
-                        warn!("Block not found in block counts: {:?}", old_block);
-                        empty_entry.insert(0);
-                    }
-                }
-                // Add new block
-                if let Some(e) = section.block_states.block_counts.get(&block) {
-                    section.block_states.block_counts.insert(block, e + 1);
-                } else {
-                    // debug!("Adding block to block counts");
-                    section.block_states.block_counts.insert(block, 1);
-                }
-                // let required_bits = max((palette.len() as f32).log2().ceil() as u8, 4);
-                // if *bits_per_block != required_bits {
-                //     section.block_states.resize(required_bits as usize)?;
-                // }
-                // Get block index
                 let block_palette_index = palette
                     .iter()
                     .position(|p| *p == block.to_varint())
                     .unwrap_or_else(|| {
                         // Add block to palette if it doesn't exist
-                        let index = palette.len() as i16;
                         palette.push(block.to_varint());
-                        index as usize
+                        palette.len() - 1
                     });
-                // Set block
-                let blocks_per_i64 = (64f64 / *bits_per_block as f64).floor() as usize;
-                let index =
-                    ((y.abs() & 0xf) * 256 + (z.abs() & 0xf) * 16 + (x.abs() & 0xf)) as usize;
-                let i64_index = index / blocks_per_i64;
-                let packed_u64 =
-                    data.get_mut(i64_index)
-                        .ok_or(WorldError::InvalidBlockStateData(format!(
-                            "Invalid block state data at index {i64_index}"
-                        )))?;
-                let offset = (index % blocks_per_i64) * *bits_per_block as usize;
-                if let Err(e) = ferrumc_general_purpose::data_packing::u32::write_nbit_u32(
-                    packed_u64,
-                    offset as u32,
-                    block_palette_index as u32,
-                    *bits_per_block,
-                ) {
-                    return Err(WorldError::InvalidBlockStateData(format!(
-                        "Failed to write block: {e}"
-                    )));
-                }
-            }
-            PaletteType::Direct { .. } => {
-                todo!("Implement direct palette for set_block");
+                (block_palette_index as u32, *bits_per_block, data)
             }
+            PaletteType::Direct {
+                bits_per_block,
+                data,
+            } => (block.0, *bits_per_block, data),
+        };
+        // Set block
+        let blocks_per_i64 = 64 / bits_per_block as usize;
+        let i64_index = index / blocks_per_i64;
+        let packed_u64 = data
+            .get_mut(i64_index)
+            .ok_or(WorldError::InvalidBlockStateData(format!(
+                "Invalid block state data at index {i64_index}"
+            )))?;
+        let offset = (index % blocks_per_i64) * bits_per_block as usize;
+        if let Err(e) = ferrumc_general_purpose::data_packing::u32::write_nbit_u32(
+            packed_u64,
+            offset as u32,
+            value,
+            bits_per_block,
+        ) {
+            return Err(WorldError::InvalidBlockStateData(format!(
+                "Failed to write block: {e}"
+            )));
         }
 
         section.block_states.non_air_blocks = section
@@ -351,6 +337,7 @@ impl Chunk {
         self.sections
             .iter_mut()
             .for_each(|section| section.optimise().unwrap());
+        self.update_heightmaps(x, y, z, block);
         Ok(())
     }
 
@@ -375,7 +362,7 @@ impl Chunk {
         let section = self
             .sections
             .iter()
-            .find(|section| section.y == (y / 16) as i8)
+            .find(|section| section.y == (y >> 4) as i8)
             .ok_or(WorldError::SectionOutOfBounds(y >> 4))?;
         match &section.block_states.block_data {
             PaletteType::Single(val) => Ok(BlockStateId::from_varint(*val)),
@@ -404,13 +391,35 @@ impl Chunk {
                 let palette_id = palette.get(id as usize).ok_or(WorldError::ChunkNotFound)?;
                 Ok(BlockStateId::from_varint(*palette_id))
             }
-            &PaletteType::Direct { .. } => todo!("Implement direct palette for get_block"),
+            PaletteType::Direct {
+                bits_per_block,
+                data,
+            } => {
+                let blocks_per_i64 = 64 / *bits_per_block as usize;
+                let index = ((y & 0xf) * 256 + (z & 0xf) * 16 + (x & 0xf)) as usize;
+                let i64_index = index / blocks_per_i64;
+                let packed_u64 = data
+                    .get(i64_index)
+                    .ok_or(WorldError::InvalidBlockStateData(format!(
+                        "Invalid block state data at index {i64_index}"
+                    )))?;
+                let offset = (index % blocks_per_i64) * *bits_per_block as usize;
+                let id = ferrumc_general_purpose::data_packing::u32::read_nbit_u32(
+                    packed_u64,
+                    *bits_per_block,
+                    offset as u32,
+                )?;
+                Ok(BlockStateId(id))
+            }
         }
     }
 
     /// Sets the section at the specified index to the specified block data.
     /// If the section is out of bounds, an error is returned.
     ///
+    /// This doesn't update the light or heightmaps, call [`Chunk::relight`] and
+    /// [`Chunk::recalculate_heightmaps`] once the chunk is built.
+    ///
     /// # Arguments
     ///
     /// * `section` - The index of the section to set.
@@ -426,6 +435,8 @@ impl Chunk {
             .iter_mut()
             .find(|section| section.y == section_y)
         {
+            self.block_entities
+                .retain(|pos, _| (pos.y >> 4) as i8 != section_y);
             section.fill(block)
         } else {
             Err(WorldError::SectionOutOfBounds(section_y as i32))
@@ -434,6 +445,9 @@ impl Chunk {
 
     /// Fills the chunk with the specified block.
     ///
+    /// This doesn't update the light or heightmaps, call [`Chunk::relight`] and
+    /// [`Chunk::recalculate_heightmaps`] once the chunk is built.
+    ///
     /// # Arguments
     ///
     /// * `block` - The block data to fill the chunk with.
@@ -443,6 +457,7 @@ impl Chunk {
     /// * `Ok(())` - If the chunk was successfully filled.
     /// * `Err(WorldError)` - If an error occurs while filling the chunk.
     pub fn fill(&mut self, block: BlockStateId) -> Result<(), WorldError> {
+        self.block_entities.clear();
         for section in &mut self.sections {
             section.fill(block)?;
         }
@@ -473,73 +488,41 @@ impl Section {
         Ok(())
     }
 
-    /// This function trims out unnecessary data from the section. Primarily it does 2 things:
+    /// This function trims out unnecessary data from the section. Primarily it does 3 things:
     ///
     /// 1. Removes any palette entries that are not used in the block states data.
     ///
     /// 2. If there is only one block in the palette, it converts the palette to single block mode.
+    ///
+    /// 3. If a section storing global ids has few enough block states left to fit in a palette,
+    ///    it converts it back to palette'd mode.
     pub fn optimise(&mut self) -> Result<(), WorldError> {
-        match &mut self.block_states.block_data {
-            PaletteType::Single(_) => {
-                // If the section is already in single block mode, there's nothing to optimise
-                return Ok(());
-            }
-            PaletteType::Indirect {
-                bits_per_block,
-                data,
-                palette,
-            } => {
-                // Remove empty blocks from palette
-                let mut remove_indexes = Vec::new();
-                for (block, count) in &self.block_states.block_counts {
-                    if *count <= 0 {
-                        let index = palette.iter().position(|p| *p == block.to_varint());
-                        if let Some(index) = index {
-                            remove_indexes.push(index);
-                        } else {
-                            return Err(WorldError::InvalidBlockStateId(block.0));
-                        }
-                    }
-                }
-                for index in remove_indexes {
-                    // Decrement any data entries that are higher than the removed index
-                    for data_point in &mut *data {
-                        let mut i = 0;
-                        while (i + *bits_per_block as usize) < 64 {
-                            let block_index =
-                                ferrumc_general_purpose::data_packing::u32::read_nbit_u32(
-                                    data_point,
-                                    *bits_per_block,
-                                    i as u32,
-                                )?;
-                            if block_index > index as u32 {
-                                ferrumc_general_purpose::data_packing::u32::write_nbit_u32(
-                                    data_point,
-                                    i as u32,
-                                    block_index - 1,
-                                    *bits_per_block,
-                                )?;
-                            }
-                            i += *bits_per_block as usize;
-                        }
-                    }
-                }
-
-                {
-                    // If there is only one block in the palette, convert to single block mode
-                    if palette.len() == 1 {
-                        let block = BlockStateId::from(palette[0]);
-                        self.block_states.block_data = PaletteType::Single(palette[0]);
-                        self.block_states.block_counts.clear();
-                        self.block_states.block_counts.insert(block, 4096);
-                    }
-                }
+        let block_states = &self.block_states;
+        let used = |id: &VarInt| {
+            block_states
+                .block_counts
+                .get(&BlockStateId::from_varint(*id))
+                .is_some_and(|&count| count > 0)
+        };
+        let rebuild = match &block_states.block_data {
+            // If the section is already in single block mode, there's nothing to optimise
+            PaletteType::Single(_) => false,
+            PaletteType::Indirect { palette, .. } => {
+                palette.len() <= 1 || !palette.iter().all(used)
             }
             PaletteType::Direct { .. } => {
-                todo!("Implement optimisation for direct palette");
+                let states = block_states
+                    .block_counts
+                    .values()
+                    .filter(|&&count| count > 0)
+                    .count();
+                indirect_bits(states) <= MAX_INDIRECT_BITS
             }
         };
-
+        if rebuild {
+            self.block_states =
+                BlockStates::from_block_ids(&self.block_states.block_data.block_ids());
+        }
         Ok(())
     }
 }
@@ -20,6 +20,8 @@ pub enum WorldError {
     InvalidCacheSize(String),
     #[error("Invalid Import Path: {0}")]
     InvalidImportPath(String),
+    #[error("Invalid Export Path: {0}")]
+    InvalidExportPath(String),
     #[error("No region files")]
     NoRegionFiles,
     #[error("Unable to obtain permission to access file/folder: {0}")]
@@ -60,6 +62,12 @@ pub enum WorldError {
     DecompressionError(String),
     #[error("Corrupted chunk data: got checksum {0}, expected checksum {1}")]
     CorruptedChunkData(u32, u32),
+    #[error(
+        "The world's chunks are saved in format version {0}, but only version {current} can be \
+         read. Delete the world to regenerate it, or import it again",
+        current = crate::db_functions::CHUNK_FORMAT_VERSION
+    )]
+    ChunkFormatMismatch(u32),
     #[error("NBT data error: {0}")]
     NBTError(#[from] ferrumc_nbt::errors::NBTError),
 }
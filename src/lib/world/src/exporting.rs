@@ -0,0 +1,130 @@
+use crate::db_functions::{dimension_hash, load_chunk_batch_internal, split_key};
+use crate::errors::WorldError;
+use crate::importing::region_dir;
+use crate::World;
+use ferrumc_anvil::writer::{AnvilRegionWriter, CompressionType};
+use ferrumc_core::dimension::Dimension;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+/// The chunk coordinates in each region, keyed by the dimension and the region coordinates.
+type RegionChunks = BTreeMap<(Dimension, i32, i32), Vec<(i32, i32)>>;
+
+impl World {
+    /// Exports every chunk in the database to region files in `export_dir`, laid out like a
+    /// vanilla world folder.
+    ///
+    /// Only the region files are written, so vanilla tools can read them but the game needs an
+    /// existing `level.dat` to open the world.
+    pub fn export(
+        &self,
+        export_dir: PathBuf,
+        compression: CompressionType,
+    ) -> Result<(), WorldError> {
+        check_export_path(&export_dir)?;
+
+        if !self.storage_backend.table_exists("chunks".to_string())? {
+            warn!("There are no chunks to export");
+            return Ok(());
+        }
+
+        let mut regions = RegionChunks::new();
+        let mut total_chunks = 0;
+        for key in self.storage_backend.keys("chunks".to_string())? {
+            let (hash, x, z) = split_key(key);
+            let Some(dimension) = Dimension::ALL
+                .into_iter()
+                .find(|dimension| dimension_hash(dimension.name()) == hash)
+            else {
+                warn!("Skipping chunk {}, {} in an unknown dimension", x, z);
+                continue;
+            };
+            regions
+                .entry((dimension, x >> 5, z >> 5))
+                .or_default()
+                .push((x, z));
+            total_chunks += 1;
+        }
+
+        let progress_style = ProgressStyle::default_bar()
+            .template("[{elapsed_precise}/{eta_precise} eta] {bar:40.cyan/blue} {percent}%, {pos:>7}/{len:7}, {msg}")
+            .unwrap();
+        let progress = ProgressBar::new(total_chunks);
+        progress.set_style(progress_style);
+        progress.set_message("Exporting chunks...");
+
+        let start = std::time::Instant::now();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs() as u32)
+            .unwrap_or_default();
+        let failed = AtomicU64::new(0);
+
+        regions
+            .into_par_iter()
+            .try_for_each(|((dimension, region_x, region_z), coords)| {
+                let dir = export_dir.join(region_dir(dimension));
+                std::fs::create_dir_all(&dir)?;
+
+                let coords = coords
+                    .iter()
+                    .map(|&(x, z)| (x, z, dimension.name()))
+                    .collect::<Vec<_>>();
+                let mut writer = AnvilRegionWriter::new(compression);
+                for chunk in load_chunk_batch_internal(self, &coords)? {
+                    if let Err(e) = writer.insert_chunk(
+                        chunk.x as u32,
+                        chunk.z as u32,
+                        &chunk.to_vanilla_nbt(),
+                        timestamp,
+                    ) {
+                        error!("Failed to export chunk {}, {}: {}", chunk.x, chunk.z, e);
+                        failed.fetch_add(1, Ordering::Relaxed);
+                    }
+                    progress.inc(1);
+                }
+
+                writer.save(dir.join(format!("r.{region_x}.{region_z}.mca")))?;
+                Ok::<_, WorldError>(())
+            })?;
+
+        progress.finish_with_message("Export complete");
+
+        let failed = failed.load(Ordering::Relaxed);
+        info!(
+            "Exported {} chunks in {:?}",
+            total_chunks - failed,
+            start.elapsed()
+        );
+        if failed > 0 {
+            warn!("{} chunks could not be exported", failed);
+        }
+
+        Ok(())
+    }
+}
+
+/// Refuses to export into a folder that already has region files, so an existing world isn't
+/// overwritten by accident.
+fn check_export_path(export_dir: &Path) -> Result<(), WorldError> {
+    if export_dir.is_file() {
+        return Err(WorldError::InvalidExportPath(
+            export_dir.display().to_string(),
+        ));
+    }
+    for dimension in Dimension::ALL {
+        let dir = export_dir.join(region_dir(dimension));
+        if dir
+            .read_dir()
+            .is_ok_and(|mut entries| entries.next().is_some())
+        {
+            return Err(WorldError::InvalidExportPath(dir.display().to_string()));
+        }
+    }
+    Ok(())
+}
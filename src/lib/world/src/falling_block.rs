@@ -0,0 +1,76 @@
+//! Blocks that fall when there's nothing under them, like sand and gravel.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use bevy_ecs::prelude::Component;
+use lazy_static::lazy_static;
+
+/// How long a block can fall before it gives up and drops as an item, like vanilla's 600 ticks.
+pub const MAX_FALL_TICKS: u32 = 600;
+
+/// Blocks that fall. Suspicious sand and gravel only fall in vanilla once they've been brushed,
+/// so they're left out.
+const FALLING: &[&str] = &[
+    "sand",
+    "red_sand",
+    "gravel",
+    "anvil",
+    "chipped_anvil",
+    "damaged_anvil",
+    "dragon_egg",
+];
+
+lazy_static! {
+    static ref FALLS: Vec<bool> = ID2BLOCK
+        .iter()
+        .map(|block| {
+            let name = block.name.strip_prefix("minecraft:").unwrap_or(&block.name);
+            FALLING.contains(&name) || name.ends_with("_concrete_powder")
+        })
+        .collect();
+}
+
+impl BlockStateId {
+    /// Whether this block falls when the block under it doesn't hold it up.
+    pub fn falls(&self) -> bool {
+        FALLS.get(self.0 as usize).copied().unwrap_or(false)
+    }
+}
+
+/// A block falling as an entity. It's placed back in the world where it lands.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FallingBlock {
+    pub block: BlockStateId,
+    /// How many ticks the block has been falling for.
+    pub ticks: u32,
+}
+
+impl FallingBlock {
+    pub fn new(block: BlockStateId) -> Self {
+        Self { block, ticks: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_macros::block;
+
+    #[test]
+    fn test_falls() {
+        assert!(block!("sand").falls());
+        assert!(block!("gravel").falls());
+        assert!(block!("lime_concrete_powder").falls());
+        assert!(block!("anvil", {facing: "north"}).falls());
+        assert!(!block!("stone").falls());
+        assert!(!block!("lime_concrete").falls());
+        assert!(!BlockStateId::default().falls());
+    }
+
+    #[test]
+    fn test_blocks_motion() {
+        assert!(block!("sand").blocks_motion());
+        assert!(!BlockStateId::default().blocks_motion());
+        assert!(!block!("torch").blocks_motion());
+        assert!(!block!("water", {level: 0}).blocks_motion());
+    }
+}
@@ -0,0 +1,398 @@
+//! Heightmaps, the highest block of each column that matches some condition.
+//!
+//! Like vanilla, each heightmap stores 256 entries, one per column in ZX order, of
+//! `ceil(log2(height + 1))` bits each. An entry is the number of blocks from the bottom of the
+//! world up to and including the highest matching block, or 0 if no block in the column matches.
+//!
+//! Whether a block blocks motion isn't part of the block state mappings, so like vanilla it's
+//! worked out from the size of the block's collision shape, unless `block_properties.json` says the
+//! block is always or never solid.
+
+use crate::block_properties::forced_solid;
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use crate::block_tags::is_tagged;
+use crate::chunk_format::{pack_entries, unpack_entries, Chunk, Heightmaps};
+use crate::vanilla_chunk_format::BlockData;
+use ferrumc_core::collisions::bounds::CollisionBounds;
+use ferrumc_core::dimension::Dimension;
+use lazy_static::lazy_static;
+use std::cmp::Reverse;
+
+/// The number of columns in a chunk.
+const COLUMNS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeightmapKind {
+    /// The highest block that isn't air.
+    WorldSurface,
+    /// The highest block that blocks motion.
+    OceanFloor,
+    /// The highest block that blocks motion or contains a fluid.
+    MotionBlocking,
+    /// Like [`HeightmapKind::MotionBlocking`], but ignoring leaves.
+    MotionBlockingNoLeaves,
+}
+
+impl HeightmapKind {
+    pub const ALL: [HeightmapKind; 4] = [
+        HeightmapKind::WorldSurface,
+        HeightmapKind::OceanFloor,
+        HeightmapKind::MotionBlocking,
+        HeightmapKind::MotionBlockingNoLeaves,
+    ];
+
+    /// The heightmaps the client uses, which are sent with chunks.
+    pub const CLIENT: [HeightmapKind; 3] = [
+        HeightmapKind::WorldSurface,
+        HeightmapKind::MotionBlocking,
+        HeightmapKind::MotionBlockingNoLeaves,
+    ];
+
+    /// The id of the heightmap type in the chunk data packet.
+    pub fn network_id(self) -> i32 {
+        match self {
+            HeightmapKind::WorldSurface => 1,
+            HeightmapKind::OceanFloor => 3,
+            HeightmapKind::MotionBlocking => 4,
+            HeightmapKind::MotionBlockingNoLeaves => 5,
+        }
+    }
+
+    /// The name of the heightmap in vanilla chunk NBT.
+    pub fn name(self) -> &'static str {
+        match self {
+            HeightmapKind::WorldSurface => "WORLD_SURFACE",
+            HeightmapKind::OceanFloor => "OCEAN_FLOOR",
+            HeightmapKind::MotionBlocking => "MOTION_BLOCKING",
+            HeightmapKind::MotionBlockingNoLeaves => "MOTION_BLOCKING_NO_LEAVES",
+        }
+    }
+
+    /// Whether a block counts towards this heightmap.
+    pub fn matches(self, block: BlockStateId) -> bool {
+        let flags = block.heightmap_flags();
+        match self {
+            HeightmapKind::WorldSurface => flags & NOT_AIR != 0,
+            HeightmapKind::OceanFloor => flags & BLOCKS_MOTION != 0,
+            HeightmapKind::MotionBlocking => flags & (BLOCKS_MOTION | FLUID) != 0,
+            HeightmapKind::MotionBlockingNoLeaves => {
+                flags & (BLOCKS_MOTION | FLUID) != 0 && flags & LEAVES == 0
+            }
+        }
+    }
+}
+
+const NOT_AIR: u8 = 1;
+const BLOCKS_MOTION: u8 = 1 << 1;
+const FLUID: u8 = 1 << 2;
+const LEAVES: u8 = 1 << 3;
+const WATER: u8 = 1 << 4;
+
+lazy_static! {
+    static ref HEIGHTMAP_FLAGS: Vec<u8> = ID2BLOCK
+        .iter()
+        .enumerate()
+        .map(|(id, block)| heightmap_flags_for(BlockStateId(id as u32), block))
+        .collect();
+}
+
+impl BlockStateId {
+    /// Whether entities collide with this block rather than passing through it.
+    pub fn blocks_motion(&self) -> bool {
+        self.heightmap_flags() & BLOCKS_MOTION != 0
+    }
+
+    /// Whether this block is full of water, so entities in it can't breathe.
+    pub fn is_water(&self) -> bool {
+        self.heightmap_flags() & WATER != 0
+    }
+
+    /// Whether this block holds water or lava, which entities swim in.
+    pub fn is_fluid(&self) -> bool {
+        self.heightmap_flags() & FLUID != 0
+    }
+
+    /// Unknown IDs are treated as solid blocks.
+    fn heightmap_flags(&self) -> u8 {
+        HEIGHTMAP_FLAGS
+            .get(self.0 as usize)
+            .copied()
+            .unwrap_or(NOT_AIR | BLOCKS_MOTION)
+    }
+}
+
+/// Blocks that are always full of water.
+const FLUIDS: &[&str] = &[
+    "minecraft:water",
+    "minecraft:lava",
+    "minecraft:bubble_column",
+    "minecraft:seagrass",
+    "minecraft:tall_seagrass",
+    "minecraft:kelp",
+    "minecraft:kelp_plant",
+];
+
+/// Solid blocks that entities still pass through.
+const NOT_BLOCKING: &[&str] = &["minecraft:cobweb", "minecraft:bamboo_sapling"];
+
+/// Whether vanilla counts a block as solid: its collision shape is either about as big as a
+/// block or as tall as one.
+fn is_solid(id: BlockStateId, name: &str) -> bool {
+    if let Some(solid) = forced_solid(name) {
+        return solid;
+    }
+    let shape = id.collision_shape();
+    if shape.is_empty() {
+        return false;
+    }
+    let min = |bounds: fn(&CollisionBounds) -> f64| shape.iter().map(bounds).fold(1.0, f64::min);
+    let max = |bounds: fn(&CollisionBounds) -> f64| shape.iter().map(bounds).fold(0.0, f64::max);
+    let x = max(|bounds| bounds.x_offset_end) - min(|bounds| bounds.x_offset_start);
+    let y = max(|bounds| bounds.y_offset_end) - min(|bounds| bounds.y_offset_start);
+    let z = max(|bounds| bounds.z_offset_end) - min(|bounds| bounds.z_offset_start);
+    (x + y + z) / 3.0 >= 0.7291666666666666 || y >= 1.0
+}
+
+fn heightmap_flags_for(id: BlockStateId, block: &BlockData) -> u8 {
+    let name = block.name.as_str();
+    let waterlogged = block
+        .properties
+        .as_ref()
+        .and_then(|properties| properties.get("waterlogged"))
+        .is_some_and(|value| value == "true");
+
+    if is_tagged(name, "minecraft:air") {
+        return 0;
+    }
+    let mut flags = NOT_AIR;
+    let fluid = FLUIDS.contains(&name);
+    if fluid || waterlogged {
+        flags |= FLUID;
+    }
+    if (fluid && name != "minecraft:lava") || waterlogged {
+        flags |= WATER;
+    }
+    if !NOT_BLOCKING.contains(&name) && is_solid(id, name) {
+        flags |= BLOCKS_MOTION;
+    }
+    if is_tagged(name, "minecraft:leaves") {
+        flags |= LEAVES;
+    }
+    flags
+}
+
+/// The bits per entry of the heightmaps of a dimension.
+fn heightmap_bits(dimension: Dimension) -> u8 {
+    (u32::BITS - (dimension.height() as u32).leading_zeros()) as u8
+}
+
+fn column_index(x: i32, z: i32) -> usize {
+    (((z & 0xF) << 4) | (x & 0xF)) as usize
+}
+
+impl Heightmaps {
+    pub fn get(&self, kind: HeightmapKind) -> &Vec<i64> {
+        match kind {
+            HeightmapKind::WorldSurface => &self.world_surface,
+            HeightmapKind::OceanFloor => &self.ocean_floor,
+            HeightmapKind::MotionBlocking => &self.motion_blocking,
+            HeightmapKind::MotionBlockingNoLeaves => &self.motion_blocking_no_leaves,
+        }
+    }
+
+    fn get_mut(&mut self, kind: HeightmapKind) -> &mut Vec<i64> {
+        match kind {
+            HeightmapKind::WorldSurface => &mut self.world_surface,
+            HeightmapKind::OceanFloor => &mut self.ocean_floor,
+            HeightmapKind::MotionBlocking => &mut self.motion_blocking,
+            HeightmapKind::MotionBlockingNoLeaves => &mut self.motion_blocking_no_leaves,
+        }
+    }
+
+    /// Whether every heightmap has the right length for the dimension. Chunks saved before a
+    /// heightmap was tracked have it empty.
+    pub fn is_complete(&self, dimension: Dimension) -> bool {
+        let per_long = 64 / heightmap_bits(dimension) as usize;
+        let len = COLUMNS.div_ceil(per_long);
+        HeightmapKind::ALL
+            .iter()
+            .all(|&kind| self.get(kind).len() == len)
+    }
+}
+
+impl Chunk {
+    fn dimension_type(&self) -> Dimension {
+        Dimension::from_name(&self.dimension).unwrap_or_default()
+    }
+
+    /// The y coordinate just above the highest block in a column that matches the heightmap, or
+    /// the bottom of the world if no block in the column does.
+    pub fn get_height(&self, kind: HeightmapKind, x: i32, z: i32) -> i32 {
+        let dimension = self.dimension_type();
+        let heights = unpack_entries(
+            self.heightmaps.get(kind),
+            heightmap_bits(dimension),
+            COLUMNS,
+        );
+        dimension.min_y() + heights[column_index(x, z)] as i32
+    }
+
+    /// Recalculates every heightmap from the blocks in the chunk.
+    pub fn recalculate_heightmaps(&mut self) {
+        let dimension = self.dimension_type();
+        let mut sections = self
+            .sections
+            .iter()
+            .map(|section| (section.y, section.block_states.block_data.block_ids()))
+            .collect::<Vec<_>>();
+        sections.sort_by_key(|(y, _)| Reverse(*y));
+
+        let mut heights = [[0u32; COLUMNS]; HeightmapKind::ALL.len()];
+        for column in 0..COLUMNS {
+            let mut remaining = HeightmapKind::ALL.len();
+            'column: for (section_y, ids) in &sections {
+                for y in (0..16).rev() {
+                    let block = BlockStateId(ids[(y << 8) | column]);
+                    for (heights, kind) in heights.iter_mut().zip(HeightmapKind::ALL) {
+                        if heights[column] == 0 && kind.matches(block) {
+                            let y = i32::from(*section_y) * 16 + y as i32;
+                            heights[column] = (y - dimension.min_y() + 1) as u32;
+                            remaining -= 1;
+                        }
+                    }
+                    if remaining == 0 {
+                        break 'column;
+                    }
+                }
+            }
+        }
+
+        let bits = heightmap_bits(dimension);
+        for (heights, kind) in heights.iter().zip(HeightmapKind::ALL) {
+            *self.heightmaps.get_mut(kind) = pack_entries(heights, bits);
+        }
+    }
+
+    /// Updates the heightmaps of the column after the block at `x`, `y`, `z` was set to `block`.
+    pub(crate) fn update_heightmaps(&mut self, x: i32, y: i32, z: i32, block: BlockStateId) {
+        let dimension = self.dimension_type();
+        if !self.heightmaps.is_complete(dimension) {
+            self.recalculate_heightmaps();
+            return;
+        }
+        let bits = heightmap_bits(dimension);
+        let column = column_index(x, z);
+        let height = (y - dimension.min_y() + 1) as u32;
+        for kind in HeightmapKind::ALL {
+            let mut heights = unpack_entries(self.heightmaps.get(kind), bits, COLUMNS);
+            let new_height = if kind.matches(block) {
+                if height <= heights[column] {
+                    continue;
+                }
+                height
+            } else if height == heights[column] {
+                // The highest block was removed, so find the next one down
+                (dimension.min_y()..y)
+                    .rev()
+                    .find(|&y| {
+                        self.get_block(x, y, z)
+                            .is_ok_and(|block| kind.matches(block))
+                    })
+                    .map_or(0, |y| (y - dimension.min_y() + 1) as u32)
+            } else {
+                continue;
+            };
+            heights[column] = new_height;
+            *self.heightmaps.get_mut(kind) = pack_entries(&heights, bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit_batch::EditBatch;
+    use ferrumc_macros::block;
+
+    fn oak_leaves() -> BlockStateId {
+        block!("oak_leaves", {distance: 1, persistent: true, waterlogged: false})
+    }
+
+    #[test]
+    fn test_block_flags() {
+        assert!(!HeightmapKind::WorldSurface.matches(BlockStateId::default()));
+        assert!(HeightmapKind::OceanFloor.matches(block!("stone")));
+        assert!(!HeightmapKind::OceanFloor.matches(block!("water", {level: 0})));
+        assert!(HeightmapKind::MotionBlocking.matches(block!("water", {level: 0})));
+        assert!(HeightmapKind::MotionBlocking.matches(oak_leaves()));
+        assert!(!HeightmapKind::MotionBlockingNoLeaves.matches(oak_leaves()));
+        assert!(HeightmapKind::WorldSurface.matches(block!("torch")));
+        assert!(!HeightmapKind::MotionBlocking.matches(block!("torch")));
+        let campfire = block!("campfire", {facing: "north", lit: true, signal_fire: false, waterlogged: false});
+        assert!(campfire.blocks_motion());
+        assert!(!block!("fire", {age: 0, east: false, north: false, south: false, up: false, west: false}).blocks_motion());
+        assert!(!block!("cobweb").blocks_motion());
+        assert!(block!("farmland", {moisture: 0}).blocks_motion());
+        assert!(!block!("snow", {layers: 8}).blocks_motion());
+        assert!(block!("water", {level: 0}).is_water());
+        assert!(block!("kelp", {age: 0}).is_water());
+        assert!(!block!("lava", {level: 0}).is_water());
+        assert!(block!("lava", {level: 0}).is_fluid());
+        assert!(!oak_leaves().is_water());
+    }
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(heightmap_bits(Dimension::Overworld), 9);
+        assert_eq!(heightmap_bits(Dimension::Nether), 9);
+    }
+
+    #[test]
+    fn test_set_block_updates_heightmaps() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        assert_eq!(chunk.get_height(HeightmapKind::WorldSurface, 3, 5), -64);
+
+        chunk.set_block(3, 10, 5, block!("stone")).unwrap();
+        chunk
+            .set_block(3, 11, 5, block!("water", {level: 0}))
+            .unwrap();
+        chunk.set_block(3, 12, 5, oak_leaves()).unwrap();
+        chunk.set_block(3, 13, 5, block!("torch")).unwrap();
+        assert_eq!(chunk.get_height(HeightmapKind::WorldSurface, 3, 5), 14);
+        assert_eq!(chunk.get_height(HeightmapKind::MotionBlocking, 3, 5), 13);
+        assert_eq!(
+            chunk.get_height(HeightmapKind::MotionBlockingNoLeaves, 3, 5),
+            12
+        );
+        // Leaves block motion, water doesn't
+        assert_eq!(chunk.get_height(HeightmapKind::OceanFloor, 3, 5), 13);
+        assert_eq!(chunk.get_height(HeightmapKind::OceanFloor, 4, 5), -64);
+
+        // Removing the top blocks drops the heights to the next matching block down
+        chunk.set_block(3, 13, 5, BlockStateId::default()).unwrap();
+        chunk.set_block(3, 12, 5, BlockStateId::default()).unwrap();
+        assert_eq!(chunk.get_height(HeightmapKind::WorldSurface, 3, 5), 12);
+        assert_eq!(chunk.get_height(HeightmapKind::MotionBlocking, 3, 5), 12);
+        assert_eq!(chunk.get_height(HeightmapKind::OceanFloor, 3, 5), 11);
+        chunk.set_block(3, 10, 5, BlockStateId::default()).unwrap();
+        assert_eq!(chunk.get_height(HeightmapKind::OceanFloor, 3, 5), -64);
+    }
+
+    #[test]
+    fn test_edit_batch_matches_recalculation() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        let mut batch = EditBatch::new(&mut chunk);
+        for x in 0..16 {
+            for z in 0..16 {
+                batch.set_block(x, x * 3 + z - 20, z, block!("stone"));
+            }
+        }
+        batch.apply().unwrap();
+        assert_eq!(chunk.get_height(HeightmapKind::OceanFloor, 4, 2), -5);
+
+        let heightmaps = chunk.heightmaps.clone();
+        chunk.heightmaps = Heightmaps::new();
+        chunk.recalculate_heightmaps();
+        assert_eq!(chunk.heightmaps, heightmaps);
+    }
+}
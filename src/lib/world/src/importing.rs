@@ -2,6 +2,7 @@ use crate::errors::WorldError;
 use crate::vanilla_chunk_format::VanillaChunk;
 use crate::World;
 use ferrumc_anvil::load_anvil_file;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_threadpool::ThreadPool;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
@@ -13,23 +14,27 @@ use tracing::{error, info};
 impl World {
     fn get_chunk_count(&self, import_dir: &Path) -> Result<u64, WorldError> {
         info!("Counting chunks in import directory...");
-        let regions_dir = import_dir.join("region").read_dir()?;
         let chunk_count = AtomicU64::new(0);
 
-        regions_dir
-            .par_bridge()
-            .try_for_each(|region_file| -> Result<(), WorldError> {
-                let entry = region_file?;
-                if entry.path().is_dir() {
-                    return Ok(());
-                }
+        for dimension in Dimension::ALL {
+            let Ok(regions_dir) = import_dir.join(region_dir(dimension)).read_dir() else {
+                continue;
+            };
+            regions_dir
+                .par_bridge()
+                .try_for_each(|region_file| -> Result<(), WorldError> {
+                    let entry = region_file?;
+                    if entry.path().is_dir() {
+                        return Ok(());
+                    }
 
-                if let Ok(anvil_file) = load_anvil_file(entry.path()) {
-                    chunk_count
-                        .fetch_add(anvil_file.get_locations().len() as u64, Ordering::Relaxed);
-                }
-                Ok(())
-            })?;
+                    if let Ok(anvil_file) = load_anvil_file(entry.path()) {
+                        chunk_count
+                            .fetch_add(anvil_file.get_locations().len() as u64, Ordering::Relaxed);
+                    }
+                    Ok(())
+                })?;
+        }
 
         Ok(chunk_count.load(Ordering::Relaxed))
     }
@@ -55,15 +60,23 @@ impl World {
 
         let start = std::time::Instant::now();
 
-        let regions_dir = import_dir.join("region").read_dir()?;
-
         let mut batch = threadpool.batch();
 
         let arc_self = Arc::new(self.clone());
 
         progress.set_message("Importing chunks...");
 
-        for region_result in regions_dir {
+        // The nether and end are optional, only the overworld's region directory is required
+        let regions = Dimension::ALL.into_iter().flat_map(|dimension| {
+            import_dir
+                .join(region_dir(dimension))
+                .read_dir()
+                .into_iter()
+                .flatten()
+                .map(move |entry| (dimension, entry))
+        });
+
+        for (dimension, region_result) in regions {
             let region_entry = region_result?;
             if region_entry.path().is_dir() {
                 continue;
@@ -86,7 +99,9 @@ impl World {
 
             for (index, location) in locations.iter().enumerate() {
                 if let Ok(Some(chunk_data)) = anvil_file.get_chunk_from_location(*location) {
-                    if let Ok(vanilla_chunk) = VanillaChunk::from_bytes(&chunk_data) {
+                    if let Ok(mut vanilla_chunk) = VanillaChunk::from_bytes(&chunk_data) {
+                        // Vanilla doesn't store the dimension in the chunk itself
+                        vanilla_chunk.dimension = Some(dimension.name().to_string());
                         batch.execute({
                             let self_clone = arc_self.clone();
                             let progress = progress.clone();
@@ -124,10 +139,22 @@ impl World {
             start.elapsed()
         );
 
+        let players = arc_self.import_player_data(&import_dir)?;
+        info!("Imported data of {} players", players);
+
         Ok(())
     }
 }
 
+/// The directory, relative to the world folder, vanilla keeps the region files of `dimension` in.
+pub(crate) fn region_dir(dimension: Dimension) -> &'static str {
+    match dimension {
+        Dimension::Overworld => "region",
+        Dimension::Nether => "DIM-1/region",
+        Dimension::End => "DIM1/region",
+    }
+}
+
 fn check_paths_validity(import_dir: &Path) -> Result<(), WorldError> {
     if !import_dir.exists() {
         return Err(WorldError::InvalidImportPath(
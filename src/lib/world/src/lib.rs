@@ -1,10 +1,20 @@
+pub mod biomes;
+pub mod block_entities;
+pub mod block_properties;
 pub mod block_state_id;
+pub mod block_tags;
 pub mod chunk_format;
+pub mod collision;
 mod db_functions;
 pub mod edit_batch;
 pub mod edits;
 pub mod errors;
+mod exporting;
+pub mod falling_block;
+pub mod heightmaps;
 mod importing;
+pub mod lighting;
+pub mod player_data;
 pub mod vanilla_chunk_format;
 
 use crate::chunk_format::Chunk;
@@ -109,10 +119,15 @@ impl World {
             .max_capacity(get_global_config().database.cache_capacity * 1024)
             .build();
 
-        World {
+        let world = World {
             storage_backend,
             cache,
+        };
+        if let Err(e) = db_functions::check_format_version(&world) {
+            error!("Fatal error opening the world: {}", e);
+            exit(1);
         }
+        world
     }
 }
 
@@ -0,0 +1,551 @@
+//! Sky and block light.
+//!
+//! Light is stored per section as two arrays of 4096 nibbles, like vanilla. Block light spreads
+//! out from blocks that emit light, and sky light shines straight down from the top of the world
+//! until it hits a block that isn't see-through, then spreads out like block light. Both lose a
+//! level for every block travelled, plus the opacity of the blocks they pass through.
+//!
+//! [`LightEngine`] works on a set of neighbouring chunks so light can cross chunk borders. Chunks
+//! outside of the set are treated as if they were dark and can't be lit. Chunks so far out that
+//! their block coordinates would overflow are never lit.
+
+pub mod properties;
+
+use crate::block_state_id::BlockStateId;
+use crate::chunk_format::{Chunk, Section};
+use crate::errors::WorldError;
+use ferrumc_core::dimension::Dimension;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The size of a section's light array, half a byte per block.
+pub const LIGHT_ARRAY_SIZE: usize = 2048;
+const MAX_LIGHT: u8 = 15;
+/// The furthest chunk coordinate that can be lit, leaving plenty of room before block
+/// coordinates overflow. This is far past the world border.
+const MAX_CHUNK_COORDINATE: u32 = 1 << 26;
+
+/// The neighbours of a block, starting with the one below it.
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (0, -1, 0),
+    (0, 1, 0),
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightKind {
+    Sky,
+    Block,
+}
+
+/// The index of the nibble of a block in a light array, from section-relative coordinates.
+fn nibble_index(x: i32, y: i32, z: i32) -> usize {
+    (((y & 0xF) << 8) | ((z & 0xF) << 4) | (x & 0xF)) as usize
+}
+
+impl Section {
+    pub fn light(&self, kind: LightKind) -> &Vec<u8> {
+        match kind {
+            LightKind::Sky => &self.sky_light,
+            LightKind::Block => &self.block_light,
+        }
+    }
+
+    fn light_mut(&mut self, kind: LightKind) -> &mut Vec<u8> {
+        let light = match kind {
+            LightKind::Sky => &mut self.sky_light,
+            LightKind::Block => &mut self.block_light,
+        };
+        if light.len() != LIGHT_ARRAY_SIZE {
+            *light = vec![0; LIGHT_ARRAY_SIZE];
+        }
+        light
+    }
+
+    /// The light level of the block at the section-relative coordinates. Sections without light
+    /// data are dark.
+    pub fn get_light(&self, kind: LightKind, x: i32, y: i32, z: i32) -> u8 {
+        let index = nibble_index(x, y, z);
+        self.light(kind)
+            .get(index / 2)
+            .map_or(0, |byte| (byte >> ((index % 2) * 4)) & 0xF)
+    }
+
+    fn set_light(&mut self, kind: LightKind, x: i32, y: i32, z: i32, level: u8) {
+        let index = nibble_index(x, y, z);
+        let byte = &mut self.light_mut(kind)[index / 2];
+        let shift = (index % 2) * 4;
+        *byte = (*byte & !(0xF << shift)) | (level << shift);
+    }
+}
+
+impl Chunk {
+    /// Recalculates the light of the whole chunk, without looking at its neighbours.
+    pub fn relight(&mut self) {
+        let (x, z) = (self.x, self.z);
+        LightEngine::new(vec![self]).light_chunk(x, z);
+    }
+}
+
+/// Calculates and updates light across a group of neighbouring chunks.
+///
+/// The chunks should all be in the same dimension, and are changed in place.
+///
+/// # Example
+/// ```
+/// # use ferrumc_macros::block;
+/// # use ferrumc_world::block_state_id::BlockStateId;
+/// # use ferrumc_world::chunk_format::Chunk;
+/// # use ferrumc_world::lighting::{LightEngine, LightKind};
+/// let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+/// let mut neighbour = Chunk::new(1, 0, "overworld".to_string());
+/// let mut engine = LightEngine::new(vec![&mut chunk, &mut neighbour]);
+/// engine.set_block(15, 64, 0, block!("glowstone")).unwrap();
+/// assert_eq!(engine.get_light(LightKind::Block, 16, 64, 0), Some(14));
+/// ```
+pub struct LightEngine<'a> {
+    chunks: Vec<&'a mut Chunk>,
+    index: HashMap<(i32, i32), usize>,
+    dimension: Dimension,
+    /// The index into `Chunk::sections` of each section, from the bottom of the world up.
+    sections: Vec<Vec<Option<usize>>>,
+    /// The block states of each section, decoded when they're first needed.
+    blocks: Vec<Vec<Option<Vec<u32>>>>,
+    changed: HashSet<(i32, i32)>,
+}
+
+impl<'a> LightEngine<'a> {
+    pub fn new(chunks: Vec<&'a mut Chunk>) -> Self {
+        let dimension = chunks
+            .first()
+            .and_then(|chunk| Dimension::from_name(&chunk.dimension))
+            .unwrap_or_default();
+        let index = chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| {
+                chunk.x.unsigned_abs() < MAX_CHUNK_COORDINATE
+                    && chunk.z.unsigned_abs() < MAX_CHUNK_COORDINATE
+            })
+            .map(|(index, chunk)| ((chunk.x, chunk.z), index))
+            .collect();
+        let sections = chunks
+            .iter()
+            .map(|chunk| {
+                dimension
+                    .sections()
+                    .map(|y| chunk.sections.iter().position(|section| section.y == y))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let blocks = sections
+            .iter()
+            .map(|sections| vec![None; sections.len()])
+            .collect();
+        Self {
+            chunks,
+            index,
+            dimension,
+            sections,
+            blocks,
+            changed: HashSet::new(),
+        }
+    }
+
+    /// The chunks whose light changed, by their chunk coordinates.
+    pub fn changed_chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.changed.iter().copied()
+    }
+
+    fn max_y(&self) -> i32 {
+        self.dimension.min_y() + self.dimension.height()
+    }
+
+    fn kinds(&self) -> &'static [LightKind] {
+        if self.dimension.has_skylight() {
+            &[LightKind::Sky, LightKind::Block]
+        } else {
+            &[LightKind::Block]
+        }
+    }
+
+    /// The chunk and the index of the section slot containing the block, if it's loaded.
+    fn locate(&self, x: i32, y: i32, z: i32) -> Option<(usize, usize)> {
+        let chunk = *self.index.get(&(x >> 4, z >> 4))?;
+        let slot = (y >> 4) - (self.dimension.min_y() >> 4);
+        let slot = usize::try_from(slot).ok()?;
+        self.sections[chunk].get(slot)?.map(|_| (chunk, slot))
+    }
+
+    fn section(&mut self, chunk: usize, slot: usize) -> &mut Section {
+        let index = self.sections[chunk][slot].expect("Section slot should be filled");
+        &mut self.chunks[chunk].sections[index]
+    }
+
+    fn block(&mut self, x: i32, y: i32, z: i32) -> Option<BlockStateId> {
+        let (chunk, slot) = self.locate(x, y, z)?;
+        if self.blocks[chunk][slot].is_none() {
+            let ids = self
+                .section(chunk, slot)
+                .block_states
+                .block_data
+                .block_ids();
+            self.blocks[chunk][slot] = Some(ids);
+        }
+        let ids = self.blocks[chunk][slot].as_ref()?;
+        Some(BlockStateId(ids[nibble_index(x, y, z)]))
+    }
+
+    /// The light level of a block, or `None` if it isn't loaded. Blocks above the world are lit
+    /// by the sky.
+    pub fn get_light(&self, kind: LightKind, x: i32, y: i32, z: i32) -> Option<u8> {
+        if y >= self.max_y() {
+            self.index.get(&(x >> 4, z >> 4))?;
+            let sky = kind == LightKind::Sky && self.dimension.has_skylight();
+            return Some(if sky { MAX_LIGHT } else { 0 });
+        }
+        let (chunk, slot) = self.locate(x, y, z)?;
+        let index = self.sections[chunk][slot]?;
+        Some(self.chunks[chunk].sections[index].get_light(kind, x, y, z))
+    }
+
+    fn set_light(&mut self, kind: LightKind, x: i32, y: i32, z: i32, level: u8) {
+        let Some((chunk, slot)) = self.locate(x, y, z) else {
+            return;
+        };
+        let section = self.section(chunk, slot);
+        if section.get_light(kind, x, y, z) != level {
+            section.set_light(kind, x, y, z, level);
+            let chunk = &self.chunks[chunk];
+            self.changed.insert((chunk.x, chunk.z));
+        }
+    }
+
+    /// Sets a block and updates the light around it. `x`, `y` and `z` are world coordinates.
+    pub fn set_block(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        block: BlockStateId,
+    ) -> Result<(), WorldError> {
+        let chunk = *self
+            .index
+            .get(&(x >> 4, z >> 4))
+            .ok_or(WorldError::ChunkNotFound)?;
+        let old = self.chunks[chunk].get_block(x, y, z)?;
+        self.chunks[chunk].set_block_unlit(x, y, z, block)?;
+        if let Some((chunk, slot)) = self.locate(x, y, z) {
+            self.blocks[chunk][slot] = None;
+        }
+        self.update_block(x, y, z, old);
+        Ok(())
+    }
+
+    /// Updates the light around a block that was changed from `old` to whatever is there now.
+    pub fn update_block(&mut self, x: i32, y: i32, z: i32, old: BlockStateId) {
+        let Some(new) = self.block(x, y, z) else {
+            return;
+        };
+        if old.light() == new.light() {
+            return;
+        }
+        for &kind in self.kinds() {
+            let mut removals = VecDeque::new();
+            let mut relight = VecDeque::new();
+            if let Some(level) = self.get_light(kind, x, y, z).filter(|&level| level > 0) {
+                self.set_light(kind, x, y, z, 0);
+                removals.push_back((x, y, z, level));
+            }
+            self.unpropagate(kind, &mut removals, &mut relight);
+
+            let emission = new.light().emission;
+            if kind == LightKind::Block && emission > 0 {
+                self.set_light(kind, x, y, z, emission);
+                relight.push_back((x, y, z));
+            }
+            // Let the light around the block flow back in
+            for (dx, dy, dz) in DIRECTIONS {
+                relight.push_back((x + dx, y + dy, z + dz));
+            }
+            self.propagate(kind, &mut relight);
+        }
+    }
+
+    /// Calculates the light of a chunk from scratch, taking in the light of its loaded
+    /// neighbours and spreading its own light into them.
+    pub fn light_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        let Some(&chunk) = self.index.get(&(chunk_x, chunk_z)) else {
+            return;
+        };
+        let sky = self.dimension.has_skylight();
+        for section in &mut self.chunks[chunk].sections {
+            section.sky_light = vec![if sky { 0xFF } else { 0 }; LIGHT_ARRAY_SIZE];
+            section.block_light = vec![0; LIGHT_ARRAY_SIZE];
+        }
+        self.changed.insert((chunk_x, chunk_z));
+
+        let (base_x, base_z) = (chunk_x * 16, chunk_z * 16);
+        let (min_y, max_y) = (self.dimension.min_y(), self.max_y());
+
+        // Block light starts at the blocks that emit it
+        let mut block_queue = VecDeque::new();
+        for slot in 0..self.sections[chunk].len() {
+            if self.sections[chunk][slot].is_none() {
+                continue;
+            }
+            let section_y = min_y + slot as i32 * 16;
+            for index in 0..4096 {
+                let (x, y, z) = (
+                    base_x + (index & 0xF),
+                    section_y + (index >> 8),
+                    base_z + ((index >> 4) & 0xF),
+                );
+                let emission = self
+                    .block(x, y, z)
+                    .map_or(0, |block| block.light().emission);
+                if emission > 0 {
+                    self.set_light(LightKind::Block, x, y, z, emission);
+                    block_queue.push_back((x, y, z));
+                }
+            }
+        }
+
+        // Sky light starts fully lit and is cut off below the first block that isn't see-through
+        // in each column, so only the edges of the lit area have to spread
+        let mut sky_queue = VecDeque::new();
+        if sky {
+            let mut heights = [[min_y; 16]; 16];
+            for (x, column) in heights.iter_mut().enumerate() {
+                for (z, height) in column.iter_mut().enumerate() {
+                    let (x, z) = (base_x + x as i32, base_z + z as i32);
+                    *height = (min_y..max_y)
+                        .rev()
+                        .find(|&y| self.block(x, y, z).is_some_and(|b| b.light().opacity > 0))
+                        .map_or(min_y, |y| y + 1);
+                    for y in min_y..*height {
+                        self.set_light(LightKind::Sky, x, y, z, 0);
+                    }
+                }
+            }
+            for x in 0..16 {
+                for z in 0..16 {
+                    let height = heights[x][z];
+                    let edge = x == 0 || x == 15 || z == 0 || z == 15;
+                    let top = if edge {
+                        max_y
+                    } else {
+                        [(x - 1, z), (x + 1, z), (x, z - 1), (x, z + 1)]
+                            .into_iter()
+                            .map(|(x, z)| heights[x][z])
+                            .max()
+                            .unwrap_or(height)
+                    };
+                    for y in height..top.max(height + 1).min(max_y) {
+                        sky_queue.push_back((base_x + x as i32, y, base_z + z as i32));
+                    }
+                }
+            }
+        }
+
+        // Light coming in from the neighbours
+        for (dx, dz) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            if !self.index.contains_key(&(chunk_x + dx, chunk_z + dz)) {
+                continue;
+            }
+            for i in 0..16 {
+                let (x, z) = match (dx, dz) {
+                    (-1, _) => (base_x - 1, base_z + i),
+                    (1, _) => (base_x + 16, base_z + i),
+                    (_, -1) => (base_x + i, base_z - 1),
+                    _ => (base_x + i, base_z + 16),
+                };
+                for y in min_y..max_y {
+                    block_queue.push_back((x, y, z));
+                    if sky {
+                        sky_queue.push_back((x, y, z));
+                    }
+                }
+            }
+        }
+
+        self.propagate(LightKind::Block, &mut block_queue);
+        if sky {
+            self.propagate(LightKind::Sky, &mut sky_queue);
+        }
+    }
+
+    /// The level light spreading from a block at `level` has when it reaches a block with
+    /// `opacity`, going in the direction `dy` vertically.
+    fn spread(kind: LightKind, level: u8, opacity: u8, dy: i32) -> u8 {
+        if kind == LightKind::Sky && dy == -1 && level == MAX_LIGHT && opacity == 0 {
+            // Direct sunlight doesn't get weaker going down
+            MAX_LIGHT
+        } else {
+            level.saturating_sub(opacity.max(1))
+        }
+    }
+
+    /// Spreads light out from the blocks in `queue` to their neighbours.
+    fn propagate(&mut self, kind: LightKind, queue: &mut VecDeque<(i32, i32, i32)>) {
+        while let Some((x, y, z)) = queue.pop_front() {
+            let Some(level) = self.get_light(kind, x, y, z) else {
+                continue;
+            };
+            if level <= 1 {
+                continue;
+            }
+            for (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                let Some(neighbour) = self.block(nx, ny, nz) else {
+                    continue;
+                };
+                let new = Self::spread(kind, level, neighbour.light().opacity, dy);
+                if new > self.get_light(kind, nx, ny, nz).unwrap_or(MAX_LIGHT) {
+                    self.set_light(kind, nx, ny, nz, new);
+                    queue.push_back((nx, ny, nz));
+                }
+            }
+        }
+    }
+
+    /// Darkens the blocks that were lit by the blocks in `removals`, which have already been
+    /// set to 0 and are paired with the level they had. Blocks that are lit by something else
+    /// are added to `relight` to spread their light back into the darkened area.
+    fn unpropagate(
+        &mut self,
+        kind: LightKind,
+        removals: &mut VecDeque<(i32, i32, i32, u8)>,
+        relight: &mut VecDeque<(i32, i32, i32)>,
+    ) {
+        while let Some((x, y, z, level)) = removals.pop_front() {
+            for (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                let Some(current) = self.get_light(kind, nx, ny, nz) else {
+                    continue;
+                };
+                let Some(neighbour) = self.block(nx, ny, nz) else {
+                    // Above the world, where the sky light comes from
+                    relight.push_back((nx, ny, nz));
+                    continue;
+                };
+                if current == 0 {
+                    continue;
+                }
+                let direct_sunlight = kind == LightKind::Sky
+                    && dy == -1
+                    && level == MAX_LIGHT
+                    && current == MAX_LIGHT;
+                if current < level || direct_sunlight {
+                    self.set_light(kind, nx, ny, nz, 0);
+                    removals.push_back((nx, ny, nz, current));
+                    let emission = neighbour.light().emission;
+                    if kind == LightKind::Block && emission > 0 {
+                        self.set_light(kind, nx, ny, nz, emission);
+                        relight.push_back((nx, ny, nz));
+                    }
+                } else {
+                    relight.push_back((nx, ny, nz));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_macros::block;
+
+    fn flat_chunk(x: i32, z: i32) -> Chunk {
+        let mut chunk = Chunk::new(x, z, Dimension::Overworld.name().to_string());
+        for y in -4..4 {
+            chunk.set_section(y, block!("stone")).unwrap();
+        }
+        chunk.relight();
+        chunk
+    }
+
+    fn light(chunk: &Chunk, kind: LightKind, x: i32, y: i32, z: i32) -> u8 {
+        chunk
+            .sections
+            .iter()
+            .find(|section| section.y == (y >> 4) as i8)
+            .unwrap()
+            .get_light(kind, x, y, z)
+    }
+
+    #[test]
+    fn test_sky_light() {
+        let chunk = flat_chunk(0, 0);
+        assert_eq!(light(&chunk, LightKind::Sky, 3, 64, 3), 15);
+        assert_eq!(light(&chunk, LightKind::Sky, 3, 300, 3), 15);
+        assert_eq!(light(&chunk, LightKind::Sky, 3, 63, 3), 0);
+        assert_eq!(light(&chunk, LightKind::Block, 3, 64, 3), 0);
+    }
+
+    #[test]
+    fn test_block_light() {
+        let mut chunk = flat_chunk(0, 0);
+        chunk.set_block(8, 70, 8, block!("glowstone")).unwrap();
+        assert_eq!(light(&chunk, LightKind::Block, 8, 70, 8), 15);
+        assert_eq!(light(&chunk, LightKind::Block, 8, 71, 8), 14);
+        assert_eq!(light(&chunk, LightKind::Block, 10, 70, 10), 11);
+        // The stone floor doesn't let light through
+        assert_eq!(light(&chunk, LightKind::Block, 8, 63, 8), 0);
+
+        chunk.set_block(8, 70, 8, BlockStateId::default()).unwrap();
+        assert_eq!(light(&chunk, LightKind::Block, 8, 70, 8), 0);
+        assert_eq!(light(&chunk, LightKind::Block, 10, 70, 10), 0);
+    }
+
+    #[test]
+    fn test_shadow() {
+        let mut chunk = flat_chunk(0, 0);
+        chunk.set_block(8, 66, 8, block!("stone")).unwrap();
+        // Under the block, lit from the sides
+        assert_eq!(light(&chunk, LightKind::Sky, 8, 65, 8), 14);
+        assert_eq!(light(&chunk, LightKind::Sky, 8, 64, 8), 14);
+
+        // A covered hole is only lit through the opening next to it
+        chunk.set_block(8, 63, 8, BlockStateId::default()).unwrap();
+        chunk.set_block(8, 62, 8, BlockStateId::default()).unwrap();
+        assert_eq!(light(&chunk, LightKind::Sky, 8, 62, 8), 12);
+
+        chunk.set_block(8, 66, 8, BlockStateId::default()).unwrap();
+        assert_eq!(light(&chunk, LightKind::Sky, 8, 62, 8), 15);
+    }
+
+    #[test]
+    fn test_cross_chunk() {
+        let mut chunk = flat_chunk(0, 0);
+        let mut neighbour = flat_chunk(-1, 0);
+        let mut engine = LightEngine::new(vec![&mut chunk, &mut neighbour]);
+        engine.set_block(1, 64, 5, block!("glowstone")).unwrap();
+        assert_eq!(engine.get_light(LightKind::Block, -1, 64, 5), Some(13));
+        assert_eq!(engine.get_light(LightKind::Block, -12, 64, 5), Some(2));
+        assert_eq!(engine.changed_chunks().count(), 2);
+
+        engine.set_block(1, 64, 5, BlockStateId::default()).unwrap();
+        assert_eq!(engine.get_light(LightKind::Block, -1, 64, 5), Some(0));
+    }
+
+    #[test]
+    fn test_light_chunk_from_neighbour() {
+        let mut chunk = flat_chunk(0, 0);
+        chunk.set_block(15, 64, 0, block!("glowstone")).unwrap();
+        let mut neighbour = flat_chunk(1, 0);
+        let mut engine = LightEngine::new(vec![&mut chunk, &mut neighbour]);
+        engine.light_chunk(1, 0);
+        assert_eq!(engine.get_light(LightKind::Block, 16, 64, 0), Some(14));
+    }
+
+    #[test]
+    fn test_no_sky_light_in_nether() {
+        let mut chunk = Chunk::new(0, 0, Dimension::Nether.name().to_string());
+        chunk.relight();
+        assert_eq!(light(&chunk, LightKind::Sky, 0, 100, 0), 0);
+    }
+}
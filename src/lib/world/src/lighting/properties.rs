@@ -0,0 +1,229 @@
+//! How much light each block state emits and how much it blocks.
+//!
+//! Like the mining properties, these aren't part of the block state mappings. Blocks that don't
+//! fill their space let light through, going by their outline shape like vanilla does, and the
+//! few full blocks that are see-through anyway are picked out by tag or name. Emission uses the
+//! vanilla values for each block.
+
+use crate::block_state_id::{BlockStateId, ID2BLOCK};
+use crate::block_tags::is_tagged;
+use crate::vanilla_chunk_format::BlockData;
+use ferrumc_core::collisions::bounds::CollisionBounds;
+use lazy_static::lazy_static;
+
+/// The light a block gives off and the light it takes away from light passing through it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightProperties {
+    pub emission: u8,
+    /// 0 for see-through blocks, 15 for blocks that stop light completely.
+    pub opacity: u8,
+}
+
+lazy_static! {
+    static ref LIGHT_PROPERTIES: Vec<LightProperties> = ID2BLOCK
+        .iter()
+        .enumerate()
+        .map(|(id, block)| light_properties_for(BlockStateId(id as u32), block))
+        .collect();
+}
+
+impl BlockStateId {
+    /// The light properties of this block. Unknown IDs are treated as opaque.
+    pub fn light(&self) -> LightProperties {
+        LIGHT_PROPERTIES
+            .get(self.0 as usize)
+            .copied()
+            .unwrap_or(LightProperties {
+                emission: 0,
+                opacity: 15,
+            })
+    }
+}
+
+/// Full blocks that only let some light through, lowering it by 1 more than air does.
+const TRANSLUCENT: &[&str] = &[
+    "minecraft:ice",
+    "minecraft:frosted_ice",
+    "minecraft:slime_block",
+    "minecraft:honey_block",
+    "minecraft:cobweb",
+];
+
+/// Tags of full blocks that let light through.
+const SEE_THROUGH_TAGS: &[&str] = &[
+    "minecraft:impermeable",
+    "minecraft:shulker_boxes",
+    "minecraft:replaceable_by_trees",
+    "minecraft:crops",
+];
+
+/// Other full blocks that let light through.
+const SEE_THROUGH: &[&str] = &[
+    "minecraft:barrier",
+    "minecraft:beacon",
+    "minecraft:end_gateway",
+    "minecraft:spawner",
+    "minecraft:trial_spawner",
+    "minecraft:vault",
+    "minecraft:sculk_vein",
+    "minecraft:sweet_berry_bush",
+    "minecraft:mangrove_roots",
+];
+
+fn light_properties_for(id: BlockStateId, block: &BlockData) -> LightProperties {
+    let name = block.name.as_str();
+    let property = |key: &str| {
+        block
+            .properties
+            .as_ref()
+            .and_then(|properties| properties.get(key))
+            .map(String::as_str)
+    };
+    let waterlogged = property("waterlogged") == Some("true");
+
+    let opacity = if name == "minecraft:tinted_glass" {
+        15
+    } else if id.is_fluid() || is_tagged(name, "minecraft:leaves") || TRANSLUCENT.contains(&name) {
+        1
+    } else if id.outline_shape() != [CollisionBounds::FULL_BLOCK]
+        || SEE_THROUGH.contains(&name)
+        || SEE_THROUGH_TAGS.iter().any(|tag| is_tagged(name, tag))
+        || name.ends_with("copper_grate")
+    {
+        0
+    } else {
+        15
+    };
+
+    LightProperties {
+        emission: emission(name, &property, waterlogged),
+        opacity,
+    }
+}
+
+fn emission<'a>(name: &str, property: &impl Fn(&str) -> Option<&'a str>, waterlogged: bool) -> u8 {
+    let lit = property("lit") == Some("true");
+    let number = |key: &str| {
+        property(key)
+            .and_then(|value| value.parse::<u8>().ok())
+            .unwrap_or(0)
+    };
+    if is_tagged(name, "minecraft:candle_cakes") {
+        return if lit { 3 } else { 0 };
+    }
+    if is_tagged(name, "minecraft:candles") {
+        return if lit { number("candles") * 3 } else { 0 };
+    }
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    match name {
+        "lava"
+        | "fire"
+        | "glowstone"
+        | "sea_lantern"
+        | "jack_o_lantern"
+        | "beacon"
+        | "conduit"
+        | "shroomlight"
+        | "end_gateway"
+        | "end_portal"
+        | "lantern"
+        | "ochre_froglight"
+        | "verdant_froglight"
+        | "pearlescent_froglight"
+        | "lava_cauldron" => 15,
+        "torch" | "wall_torch" | "end_rod" => 14,
+        "nether_portal" => 11,
+        "soul_fire" | "soul_torch" | "soul_wall_torch" | "soul_lantern" | "crying_obsidian" => 10,
+        "glow_lichen" | "enchanting_table" | "ender_chest" => 7,
+        "sculk_catalyst" => 6,
+        "amethyst_cluster" => 5,
+        "large_amethyst_bud" => 4,
+        "magma_block" => 3,
+        "medium_amethyst_bud" => 2,
+        "small_amethyst_bud"
+        | "brewing_stand"
+        | "brown_mushroom"
+        | "dragon_egg"
+        | "end_portal_frame"
+        | "sculk_sensor"
+        | "calibrated_sculk_sensor" => 1,
+        "light" => number("level"),
+        "respawn_anchor" => (number("charges") * 4).saturating_sub(1),
+        "sea_pickle" if waterlogged => (number("pickles") + 1) * 3,
+        "cave_vines" | "cave_vines_plant" if property("berries") == Some("true") => 14,
+        _ if !lit => 0,
+        "redstone_lamp" | "campfire" => 15,
+        "furnace" | "blast_furnace" | "smoker" => 13,
+        "soul_campfire" => 10,
+        "redstone_ore" | "deepslate_redstone_ore" => 9,
+        "redstone_torch" | "redstone_wall_torch" => 7,
+        _ if name.ends_with("copper_bulb") => {
+            let name = name.trim_start_matches("waxed_");
+            if name.starts_with("exposed_") {
+                12
+            } else if name.starts_with("weathered_") {
+                8
+            } else if name.starts_with("oxidized_") {
+                4
+            } else {
+                15
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_macros::block;
+
+    #[test]
+    fn test_opacity() {
+        assert_eq!(BlockStateId::default().light().opacity, 0);
+        assert_eq!(block!("stone").light().opacity, 15);
+        assert_eq!(block!("glass").light().opacity, 0);
+        assert_eq!(block!("water", {level: 0}).light().opacity, 1);
+        assert_eq!(
+            block!("oak_leaves", {distance: 1, persistent: false, waterlogged: false})
+                .light()
+                .opacity,
+            1
+        );
+        assert_eq!(block!("torch").light().opacity, 0);
+        assert_eq!(block!("tinted_glass").light().opacity, 15);
+        assert_eq!(block!("sea_lantern").light().opacity, 15);
+        assert_eq!(block!("tall_grass", {half: "lower"}).light().opacity, 0);
+        assert_eq!(
+            block!("oak_stairs", {facing: "north", half: "bottom", shape: "straight", waterlogged: true})
+                .light()
+                .opacity,
+            1
+        );
+    }
+
+    #[test]
+    fn test_emission() {
+        assert_eq!(block!("stone").light().emission, 0);
+        assert_eq!(block!("torch").light().emission, 14);
+        assert_eq!(block!("lava", {level: 0}).light().emission, 15);
+        assert_eq!(
+            block!("furnace", {facing: "north", lit: true})
+                .light()
+                .emission,
+            13
+        );
+        assert_eq!(
+            block!("furnace", {facing: "north", lit: false})
+                .light()
+                .emission,
+            0
+        );
+        assert_eq!(
+            block!("light", {level: 9, waterlogged: false})
+                .light()
+                .emission,
+            9
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Persistent player state, stored per player UUID in the `playerdata` table.
+//!
+//! Also reads vanilla `playerdata/<uuid>.dat` files so imported worlds keep their players'
+//! positions, inventories and game modes.
+
+use crate::errors::WorldError;
+use crate::World;
+use bitcode::{Decode, Encode};
+use ferrumc_config::server_config::get_global_config;
+use ferrumc_core::dimension::Dimension;
+use ferrumc_core::game_mode::GameMode;
+use ferrumc_inventories::components::ComponentPatch;
+use ferrumc_inventories::defined_slots::player::{CHEST_SLOT, FEET_SLOT, HEAD_SLOT, LEGS_SLOT};
+use ferrumc_inventories::item::ItemID;
+use ferrumc_macros::NBTDeserialize;
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::Path;
+use tracing::{error, info, warn};
+
+const TABLE: &str = "playerdata";
+/// The offhand slot in the player inventory window.
+const OFFHAND_SLOT: u8 = 45;
+
+/// The state of a player that is kept across reconnects.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+pub struct PlayerData {
+    /// The name of the dimension the player is in, see `Dimension::name`.
+    pub dimension: String,
+    pub position: (f64, f64, f64),
+    /// Yaw and pitch.
+    pub rotation: (f32, f32),
+    /// The selected hotbar slot, 0-8.
+    pub selected_slot: u8,
+    /// The non-empty inventory slots.
+    pub inventory: Vec<SavedItem>,
+    /// The ID of the player's game mode, see `GameMode::id`. `None` for players that play in the
+    /// server's default game mode.
+    pub game_mode: Option<u8>,
+}
+
+/// An item stack in a player's inventory.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct SavedItem {
+    /// The slot index in the player inventory window, see
+    /// `ferrumc_inventories::defined_slots::player`.
+    pub slot: u8,
+    pub item_id: i32,
+    pub count: i32,
+    /// The item's components, see `ComponentPatch::to_bytes`. Empty if it has none.
+    pub components: Vec<u8>,
+}
+
+/// The game mode of a player with the saved `data`, or the server's default for new players.
+pub fn game_mode(data: Option<&PlayerData>) -> GameMode {
+    data.and_then(|data| data.game_mode)
+        .and_then(GameMode::from_id)
+        .unwrap_or_else(default_game_mode)
+}
+
+/// The game mode set as `default_gamemode` in the config, or survival if that isn't one.
+pub fn default_game_mode() -> GameMode {
+    let name = &get_global_config().default_gamemode;
+    GameMode::from_name(name).unwrap_or_else(|| {
+        warn!("Unknown default game mode {name}, using survival");
+        GameMode::Survival
+    })
+}
+
+impl World {
+    /// Saves the state of the player with the given `uuid`, replacing any previous state.
+    pub fn save_player_data(&self, uuid: u128, data: &PlayerData) -> Result<(), WorldError> {
+        self.save_player_data_batch(vec![(uuid, data.clone())])
+    }
+
+    /// Saves the state of several players in a single transaction.
+    pub fn save_player_data_batch(&self, data: Vec<(u128, PlayerData)>) -> Result<(), WorldError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let encoded = data
+            .iter()
+            .map(|(uuid, data)| (*uuid, bitcode::encode(data)))
+            .collect();
+        self.storage_backend
+            .batch_upsert(TABLE.to_string(), encoded)?;
+        Ok(())
+    }
+
+    /// Loads the saved state of the player with the given `uuid`, if there is any.
+    pub fn load_player_data(&self, uuid: u128) -> Result<Option<PlayerData>, WorldError> {
+        if !self.storage_backend.table_exists(TABLE.to_string())? {
+            return Ok(None);
+        }
+        let Some(bytes) = self.storage_backend.get(TABLE.to_string(), uuid)? else {
+            return Ok(None);
+        };
+        let data =
+            bitcode::decode(&bytes).map_err(|e| WorldError::BitcodeDecodeError(e.to_string()))?;
+        Ok(Some(data))
+    }
+
+    /// Imports every `<uuid>.dat` file from the `playerdata` directory of a vanilla world.
+    ///
+    /// Returns the number of imported players. Files that can't be read are logged and skipped.
+    pub(crate) fn import_player_data(&self, import_dir: &Path) -> Result<usize, WorldError> {
+        let player_data_dir = import_dir.join("playerdata");
+        if !player_data_dir.is_dir() {
+            info!("No playerdata directory found, skipping player import");
+            return Ok(0);
+        }
+
+        let mut imported = Vec::new();
+        for entry in player_data_dir.read_dir()? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "dat") {
+                continue;
+            }
+            let Some(uuid) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| uuid::Uuid::parse_str(stem).ok())
+            else {
+                warn!("Skipping player data file {}: not a UUID", path.display());
+                continue;
+            };
+
+            match read_vanilla_player_data(&path) {
+                Ok(data) => imported.push((uuid.as_u128(), data)),
+                Err(e) => error!("Failed to import player data {}: {}", path.display(), e),
+            }
+        }
+
+        let count = imported.len();
+        self.save_player_data_batch(imported)?;
+        Ok(count)
+    }
+}
+
+#[derive(NBTDeserialize, Debug)]
+#[nbt(is_root)]
+#[nbt(rename = "")]
+struct VanillaPlayerData {
+    #[nbt(rename = "Dimension")]
+    dimension: Option<String>,
+    #[nbt(rename = "Pos")]
+    pos: Option<Vec<f64>>,
+    #[nbt(rename = "Rotation")]
+    rotation: Option<Vec<f32>>,
+    #[nbt(rename = "SelectedItemSlot")]
+    selected_item_slot: Option<i32>,
+    #[nbt(rename = "Inventory")]
+    inventory: Option<Vec<VanillaItem>>,
+    /// Armor and the offhand item, since 1.21.5. Older versions keep them in `Inventory`.
+    equipment: Option<VanillaEquipment>,
+    #[nbt(rename = "playerGameType")]
+    game_type: Option<i32>,
+}
+
+#[derive(NBTDeserialize, Debug)]
+struct VanillaItem {
+    #[nbt(rename = "Slot")]
+    slot: Option<i8>,
+    id: String,
+    /// Since 1.20.5.
+    count: Option<i32>,
+    /// Before 1.20.5.
+    #[nbt(rename = "Count")]
+    legacy_count: Option<i8>,
+    /// Since 1.20.5.
+    components: Option<ComponentPatch>,
+}
+
+#[derive(NBTDeserialize, Debug)]
+struct VanillaEquipment {
+    head: Option<VanillaItem>,
+    chest: Option<VanillaItem>,
+    legs: Option<VanillaItem>,
+    feet: Option<VanillaItem>,
+    offhand: Option<VanillaItem>,
+}
+
+/// Maps a vanilla inventory `Slot` to its index in the player inventory window.
+fn window_slot(vanilla_slot: i8) -> Option<u8> {
+    match vanilla_slot {
+        0..=8 => Some(vanilla_slot as u8 + 36),
+        9..=35 => Some(vanilla_slot as u8),
+        // Armor, feet to head
+        100..=103 => Some(108 - vanilla_slot as u8),
+        -106 => Some(OFFHAND_SLOT),
+        _ => None,
+    }
+}
+
+impl VanillaItem {
+    fn to_saved(&self, slot: u8) -> Option<SavedItem> {
+        let Some(item_id) = ItemID::from_name(&self.id) else {
+            warn!("Skipping unknown item {}", self.id);
+            return None;
+        };
+        let count = self.count.or(self.legacy_count.map(i32::from)).unwrap_or(1);
+        Some(SavedItem {
+            slot,
+            item_id: item_id.0 .0,
+            count,
+            components: self
+                .components
+                .as_ref()
+                .filter(|components| !components.is_empty())
+                .map(ComponentPatch::to_bytes)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl VanillaPlayerData {
+    fn into_player_data(self) -> PlayerData {
+        let position = match self.pos.as_deref() {
+            Some(&[x, y, z]) => (x, y, z),
+            _ => (0.0, 0.0, 0.0),
+        };
+        let rotation = match self.rotation.as_deref() {
+            Some(&[yaw, pitch]) => (yaw, pitch),
+            _ => (0.0, 0.0),
+        };
+
+        let mut inventory = self
+            .inventory
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| item.to_saved(window_slot(item.slot?)?))
+            .collect::<Vec<_>>();
+        if let Some(equipment) = self.equipment {
+            let slots = [
+                (equipment.head, HEAD_SLOT),
+                (equipment.chest, CHEST_SLOT),
+                (equipment.legs, LEGS_SLOT),
+                (equipment.feet, FEET_SLOT),
+                (equipment.offhand, OFFHAND_SLOT),
+            ];
+            for (item, slot) in slots {
+                if let Some(saved) = item.and_then(|item| item.to_saved(slot)) {
+                    inventory.push(saved);
+                }
+            }
+        }
+
+        let dimension = self
+            .dimension
+            .as_deref()
+            .and_then(Dimension::from_name)
+            .unwrap_or_default();
+
+        PlayerData {
+            dimension: dimension.name().to_string(),
+            position,
+            rotation,
+            selected_slot: self.selected_item_slot.unwrap_or(0).clamp(0, 8) as u8,
+            inventory,
+            game_mode: self
+                .game_type
+                .and_then(|id| u8::try_from(id).ok())
+                .filter(|&id| GameMode::from_id(id).is_some()),
+        }
+    }
+}
+
+fn read_vanilla_player_data(path: &Path) -> Result<PlayerData, WorldError> {
+    let compressed = std::fs::read(path)?;
+    let mut data = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut data)?;
+    Ok(VanillaPlayerData::from_bytes(&data)?.into_player_data())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_inventories::components::Component;
+    use ferrumc_macros::NBTSerialize;
+    use ferrumc_nbt::{NBTSerializable, NBTSerializeOptions, NetworkNbt};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn test_data() -> PlayerData {
+        PlayerData {
+            dimension: "the_nether".to_string(),
+            position: (1.5, 64.0, -3.25),
+            rotation: (90.0, -10.0),
+            selected_slot: 4,
+            inventory: vec![SavedItem {
+                slot: 36,
+                item_id: 1,
+                count: 64,
+                components: Vec::new(),
+            }],
+            game_mode: Some(GameMode::Creative.id()),
+        }
+    }
+
+    #[test]
+    fn test_window_slot() {
+        assert_eq!(window_slot(0), Some(36));
+        assert_eq!(window_slot(8), Some(44));
+        assert_eq!(window_slot(9), Some(9));
+        assert_eq!(window_slot(35), Some(35));
+        assert_eq!(window_slot(100), Some(8));
+        assert_eq!(window_slot(103), Some(5));
+        assert_eq!(window_slot(-106), Some(45));
+        assert_eq!(window_slot(36), None);
+    }
+
+    #[test]
+    fn test_player_data_roundtrip() {
+        let data = test_data();
+        let decoded: PlayerData = bitcode::decode(&bitcode::encode(&data)).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_world_player_data_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = World::new(dir.path().join("db"));
+        assert_eq!(world.load_player_data(42).unwrap(), None);
+
+        let data = test_data();
+        world.save_player_data(42, &data).unwrap();
+        assert_eq!(world.load_player_data(42).unwrap(), Some(data));
+        assert_eq!(world.load_player_data(43).unwrap(), None);
+    }
+
+    #[derive(NBTSerialize, Debug)]
+    #[allow(non_snake_case)]
+    struct TestVanillaItem {
+        Slot: i8,
+        id: String,
+        count: i32,
+    }
+
+    #[test]
+    fn test_import_vanilla_player_data() {
+        let uuid = uuid::Uuid::from_u128(0x1234);
+        let mut nbt = vec![10, 0, 0];
+        "minecraft:the_nether".serialize(&mut nbt, &NBTSerializeOptions::WithHeader("Dimension"));
+        vec![1.5f64, 70.0, -8.25].serialize(&mut nbt, &NBTSerializeOptions::WithHeader("Pos"));
+        vec![90.0f32, 15.0].serialize(&mut nbt, &NBTSerializeOptions::WithHeader("Rotation"));
+        3i32.serialize(
+            &mut nbt,
+            &NBTSerializeOptions::WithHeader("SelectedItemSlot"),
+        );
+        1i32.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("playerGameType"));
+        vec![
+            TestVanillaItem {
+                Slot: 0,
+                id: "minecraft:stone".to_string(),
+                count: 32,
+            },
+            TestVanillaItem {
+                Slot: 100,
+                id: "minecraft:iron_boots".to_string(),
+                count: 1,
+            },
+        ]
+        .serialize(&mut nbt, &NBTSerializeOptions::WithHeader("Inventory"));
+        nbt.push(0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let import_dir = dir.path().join("import");
+        std::fs::create_dir_all(import_dir.join("playerdata")).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&nbt).unwrap();
+        std::fs::write(
+            import_dir.join("playerdata").join(format!("{uuid}.dat")),
+            encoder.finish().unwrap(),
+        )
+        .unwrap();
+        // Not a player, skipped
+        std::fs::write(import_dir.join("playerdata").join("level.dat"), []).unwrap();
+
+        let world = World::new(dir.path().join("db"));
+        assert_eq!(world.import_player_data(&import_dir).unwrap(), 1);
+
+        let data = world.load_player_data(uuid.as_u128()).unwrap().unwrap();
+        assert_eq!(data.dimension, "the_nether");
+        assert_eq!(data.position, (1.5, 70.0, -8.25));
+        assert_eq!(data.rotation, (90.0, 15.0));
+        assert_eq!(data.selected_slot, 3);
+        assert_eq!(data.game_mode, Some(GameMode::Creative.id()));
+        let slots = data
+            .inventory
+            .iter()
+            .map(|item| (item.slot, item.item_id, item.count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            slots,
+            vec![
+                (36, ItemID::from_name("minecraft:stone").unwrap().0 .0, 32),
+                (
+                    FEET_SLOT,
+                    ItemID::from_name("minecraft:iron_boots").unwrap().0 .0,
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vanilla_item_components() {
+        let name = NetworkNbt::string("Sting");
+        let components = ComponentPatch {
+            added: vec![Component::CustomName(name.clone())],
+            removed: Vec::new(),
+        };
+        let mut nbt = vec![10, 0, 0];
+        "minecraft:iron_sword".serialize(&mut nbt, &NBTSerializeOptions::WithHeader("id"));
+        1i32.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("count"));
+        components.serialize(&mut nbt, &NBTSerializeOptions::WithHeader("components"));
+        nbt.push(0);
+
+        let saved = VanillaItem::from_bytes(&nbt).unwrap().to_saved(36).unwrap();
+        assert_eq!(
+            ComponentPatch::from_bytes(&saved.components).unwrap(),
+            components
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::block_entities::VanillaBlockEntity;
 use bitcode::{Decode, Encode};
 use ferrumc_macros::NBTDeserialize;
 use ferrumc_macros::NBTSerialize;
@@ -45,18 +46,19 @@ pub(crate) struct VanillaChunk {
     #[nbt(rename = "LastUpdate")]
     pub last_update: Option<i64>,
     pub sections: Option<Vec<Section>>,
+    pub block_entities: Option<Vec<VanillaBlockEntity>>,
 }
 
 #[apply(ChunkDerives)]
 #[derive(deepsize::DeepSizeOf)]
 #[nbt(net_encode)]
 pub(crate) struct VanillaHeightmaps {
-    // #[nbt(rename = "MOTION_BLOCKING_NO_LEAVES")]
-    // pub motion_blocking_no_leaves: Option<Vec<i64>>,
+    #[nbt(rename = "MOTION_BLOCKING_NO_LEAVES")]
+    pub motion_blocking_no_leaves: Option<Vec<i64>>,
     #[nbt(rename = "MOTION_BLOCKING")]
     pub motion_blocking: Option<Vec<i64>>,
-    // #[nbt(rename = "OCEAN_FLOOR")]
-    // pub ocean_floor: Option<Vec<i64>>,
+    #[nbt(rename = "OCEAN_FLOOR")]
+    pub ocean_floor: Option<Vec<i64>>,
     #[nbt(rename = "WORLD_SURFACE")]
     pub world_surface: Option<Vec<i64>>,
 }
@@ -0,0 +1,102 @@
+use crate::errors::WorldGenError;
+use crate::{BiomeGenerator, NoiseGenerator};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_macros::block;
+use ferrumc_world::block_state_id::BlockStateId;
+use ferrumc_world::chunk_format::Chunk;
+use ferrumc_world::edit_batch::EditBatch;
+
+/// The highest block of the nether roof.
+const ROOF_Y: i32 = 127;
+/// Lava fills everything below the floor up to this height.
+const LAVA_LEVEL: i32 = 31;
+
+pub(crate) struct NetherWastesBiome;
+
+impl BiomeGenerator for NetherWastesBiome {
+    fn _biome_id(&self) -> u8 {
+        1
+    }
+
+    fn _biome_name(&self) -> String {
+        "nether_wastes".to_string()
+    }
+
+    fn generate_chunk(
+        &self,
+        x: i32,
+        z: i32,
+        noise: &NoiseGenerator,
+    ) -> Result<Chunk, WorldGenError> {
+        let mut chunk = Chunk::new(x, z, Dimension::Nether.name().to_string());
+        let netherrack = block!("netherrack");
+        let bedrock = block!("bedrock");
+
+        // The bottom section is always solid and the one above it is a lava sea
+        chunk.set_section(0, netherrack)?;
+        chunk.set_section(1, block!("lava", {level: 0}))?;
+
+        let mut batch = EditBatch::new(&mut chunk);
+        for chunk_x in 0..16 {
+            for chunk_z in 0..16 {
+                let global_x = i64::from(x) * 16 + i64::from(chunk_x);
+                let global_z = i64::from(z) * 16 + i64::from(chunk_z);
+                let floor = noise.get_noise(global_x as f64, global_z as f64);
+                let floor = (floor * 12.0) as i32 + 36;
+                // Offset the ceiling noise so it doesn't mirror the floor
+                let ceiling = noise.get_noise(global_z as f64 + 4096.0, global_x as f64);
+                let ceiling = (ceiling * 8.0) as i32 + 108;
+
+                batch.set_block(chunk_x, 0, chunk_z, bedrock);
+                for y in 16..=floor.max(16) {
+                    batch.set_block(chunk_x, y, chunk_z, netherrack);
+                }
+                for y in floor.max(LAVA_LEVEL) + 1..=LAVA_LEVEL {
+                    batch.set_block(chunk_x, y, chunk_z, block!("lava", {level: 0}));
+                }
+                for y in ceiling..ROOF_Y {
+                    batch.set_block(chunk_x, y, chunk_z, netherrack);
+                }
+                batch.set_block(chunk_x, ROOF_Y, chunk_z, bedrock);
+            }
+        }
+        batch.apply()?;
+
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_ok() {
+        let generator = NetherWastesBiome {};
+        let noise = NoiseGenerator::new(0);
+        let chunk = generator.generate_chunk(0, 0, &noise).unwrap();
+        assert_eq!(chunk.dimension, Dimension::Nether.name());
+        assert_eq!(chunk.get_block(0, 0, 0).unwrap(), block!("bedrock"));
+        assert_eq!(chunk.get_block(0, ROOF_Y, 0).unwrap(), block!("bedrock"));
+        assert_eq!(
+            chunk.get_block(0, ROOF_Y + 1, 0).unwrap(),
+            BlockStateId::default()
+        );
+    }
+
+    #[test]
+    fn test_very_high_coordinates() {
+        let generator = NetherWastesBiome {};
+        let noise = NoiseGenerator::new(0);
+        assert!(
+            generator
+                .generate_chunk(1610612735, 1610612735, &noise)
+                .is_ok()
+        );
+        assert!(
+            generator
+                .generate_chunk(-1610612735, -1610612735, &noise)
+                .is_ok()
+        );
+    }
+}
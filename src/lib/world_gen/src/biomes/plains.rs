@@ -1,5 +1,6 @@
 use crate::errors::WorldGenError;
 use crate::{BiomeGenerator, NoiseGenerator};
+use ferrumc_core::dimension::Dimension;
 use ferrumc_macros::block;
 use ferrumc_world::block_state_id::BlockStateId;
 use ferrumc_world::chunk_format::Chunk;
@@ -22,7 +23,7 @@ impl BiomeGenerator for PlainsBiome {
         z: i32,
         noise: &NoiseGenerator,
     ) -> Result<Chunk, WorldGenError> {
-        let mut chunk = Chunk::new(x, z, "overworld".to_string());
+        let mut chunk = Chunk::new(x, z, Dimension::Overworld.name().to_string());
         let mut heights = vec![];
         let stone = block!("stone"); // just to test the macro
 
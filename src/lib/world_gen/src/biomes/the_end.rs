@@ -0,0 +1,119 @@
+use crate::errors::WorldGenError;
+use crate::{BiomeGenerator, NoiseGenerator};
+use ferrumc_core::dimension::Dimension;
+use ferrumc_macros::block;
+use ferrumc_world::block_state_id::BlockStateId;
+use ferrumc_world::chunk_format::Chunk;
+use ferrumc_world::edit_batch::EditBatch;
+
+/// The radius in blocks of the main island around the origin, before the noise is applied.
+const ISLAND_RADIUS: f64 = 96.0;
+/// The height of the top of the island at its centre.
+const ISLAND_TOP: f64 = 64.0;
+
+pub(crate) struct TheEndBiome;
+
+impl BiomeGenerator for TheEndBiome {
+    fn _biome_id(&self) -> u8 {
+        2
+    }
+
+    fn _biome_name(&self) -> String {
+        "the_end".to_string()
+    }
+
+    fn generate_chunk(
+        &self,
+        x: i32,
+        z: i32,
+        noise: &NoiseGenerator,
+    ) -> Result<Chunk, WorldGenError> {
+        let mut chunk = Chunk::new(x, z, Dimension::End.name().to_string());
+
+        // Everything outside the main island is void. The noise is in -2..2, so no part of the
+        // island reaches further than the radius plus twice the noise scale.
+        let edge = ISLAND_RADIUS + 16.0 * 2.0;
+        let closest_x = 0.clamp(i64::from(x) * 16, i64::from(x) * 16 + 15);
+        let closest_z = 0.clamp(i64::from(z) * 16, i64::from(z) * 16 + 15);
+        if (closest_x as f64).hypot(closest_z as f64) > edge {
+            return Ok(chunk);
+        }
+
+        let end_stone = block!("end_stone");
+        let mut batch = EditBatch::new(&mut chunk);
+        for chunk_x in 0..16 {
+            for chunk_z in 0..16 {
+                let global_x = i64::from(x) * 16 + i64::from(chunk_x);
+                let global_z = i64::from(z) * 16 + i64::from(chunk_z);
+                let radius =
+                    ISLAND_RADIUS + noise.get_noise(global_x as f64, global_z as f64) * 16.0;
+                // 0 at the edge of the island and 1 at its centre
+                let falloff = 1.0 - (global_x as f64).hypot(global_z as f64) / radius;
+                if falloff <= 0.0 {
+                    continue;
+                }
+                let top = (ISLAND_TOP - (1.0 - falloff) * 8.0) as i32;
+                let bottom = (ISLAND_TOP - falloff.sqrt() * 48.0) as i32;
+                for y in bottom..=top {
+                    batch.set_block(chunk_x, y, chunk_z, end_stone);
+                }
+            }
+        }
+        // Chunks just inside the edge can still miss the island entirely
+        if !batch.is_empty() {
+            batch.apply()?;
+        }
+
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_main_island() {
+        let generator = TheEndBiome {};
+        let noise = NoiseGenerator::new(0);
+        let chunk = generator.generate_chunk(0, 0, &noise).unwrap();
+        assert_eq!(chunk.dimension, Dimension::End.name());
+        assert_eq!(
+            chunk.get_block(0, ISLAND_TOP as i32, 0).unwrap(),
+            block!("end_stone")
+        );
+        assert_eq!(
+            chunk.get_block(0, ISLAND_TOP as i32 + 1, 0).unwrap(),
+            BlockStateId::default()
+        );
+    }
+
+    #[test]
+    fn test_void() {
+        let generator = TheEndBiome {};
+        let noise = NoiseGenerator::new(0);
+        for (x, z) in [(20, 0), (-20, 3), (1610612735, -1610612735)] {
+            let chunk = generator.generate_chunk(x, z, &noise).unwrap();
+            for y in 0..256 {
+                assert_eq!(chunk.get_block(0, y, 0).unwrap(), BlockStateId::default());
+            }
+        }
+    }
+
+    #[test]
+    fn test_island_edge() {
+        let generator = TheEndBiome {};
+        let noise = NoiseGenerator::new(0);
+        for x in -10..10 {
+            for z in -10..10 {
+                assert!(generator.generate_chunk(x, z, &noise).is_ok(), "({x}, {z})");
+            }
+        }
+        // Close enough to the island to be generated, but without any end stone
+        let chunk = generator.generate_chunk(7, 0, &noise).unwrap();
+        assert_eq!(
+            chunk.get_block(15, ISLAND_TOP as i32 - 8, 0).unwrap(),
+            BlockStateId::default()
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Picks overworld biomes from climate noise.
+//!
+//! Like vanilla's multi-noise biome source, every position gets a temperature, humidity and
+//! continentalness, and the biome is looked up from those. Continentalness is the same noise the
+//! terrain height comes from, so oceans and beaches line up with the water and sand.
+
+use crate::NoiseGenerator;
+use ferrumc_world::chunk_format::{BiomeStates, Chunk};
+use noise::{NoiseFn, OpenSimplex};
+
+/// How many blocks wide the temperature and humidity noise features are.
+const CLIMATE_SCALE: f64 = 1024.0;
+/// The climate values that split temperature and humidity into 5 levels.
+const LEVELS: [f64; 4] = [-0.3, -0.1, 0.1, 0.3];
+/// At or below this continentalness the terrain is under the sea.
+const OCEAN: f64 = -1.0 / 64.0;
+/// Below this continentalness the ocean is deep.
+const DEEP_OCEAN: f64 = -0.3;
+/// Up to this continentalness the terrain is sand just above the sea.
+const BEACH: f64 = 2.0 / 64.0;
+
+/// Oceans by temperature.
+const OCEANS: [&str; 5] = [
+    "minecraft:frozen_ocean",
+    "minecraft:cold_ocean",
+    "minecraft:ocean",
+    "minecraft:lukewarm_ocean",
+    "minecraft:warm_ocean",
+];
+/// Deep oceans by temperature. There's no deep warm ocean.
+const DEEP_OCEANS: [&str; 5] = [
+    "minecraft:deep_frozen_ocean",
+    "minecraft:deep_cold_ocean",
+    "minecraft:deep_ocean",
+    "minecraft:deep_lukewarm_ocean",
+    "minecraft:warm_ocean",
+];
+/// Beaches by temperature.
+const BEACHES: [&str; 5] = [
+    "minecraft:snowy_beach",
+    "minecraft:beach",
+    "minecraft:beach",
+    "minecraft:beach",
+    "minecraft:desert",
+];
+/// Inland biomes by temperature, then humidity.
+const INLAND: [[&str; 5]; 5] = [
+    [
+        "minecraft:snowy_plains",
+        "minecraft:snowy_plains",
+        "minecraft:snowy_plains",
+        "minecraft:snowy_taiga",
+        "minecraft:taiga",
+    ],
+    [
+        "minecraft:plains",
+        "minecraft:plains",
+        "minecraft:forest",
+        "minecraft:taiga",
+        "minecraft:old_growth_spruce_taiga",
+    ],
+    [
+        "minecraft:flower_forest",
+        "minecraft:plains",
+        "minecraft:forest",
+        "minecraft:birch_forest",
+        "minecraft:dark_forest",
+    ],
+    [
+        "minecraft:savanna",
+        "minecraft:savanna",
+        "minecraft:forest",
+        "minecraft:jungle",
+        "minecraft:jungle",
+    ],
+    [
+        "minecraft:desert",
+        "minecraft:desert",
+        "minecraft:desert",
+        "minecraft:desert",
+        "minecraft:desert",
+    ],
+];
+
+/// The climate at a position.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ClimatePoint {
+    pub(crate) temperature: f64,
+    pub(crate) humidity: f64,
+    pub(crate) continentalness: f64,
+}
+
+impl ClimatePoint {
+    /// The biome with this climate.
+    pub(crate) fn biome(&self) -> &'static str {
+        let temperature = level(self.temperature);
+        if self.continentalness < DEEP_OCEAN {
+            DEEP_OCEANS[temperature]
+        } else if self.continentalness <= OCEAN {
+            OCEANS[temperature]
+        } else if self.continentalness < BEACH {
+            BEACHES[temperature]
+        } else {
+            INLAND[temperature][level(self.humidity)]
+        }
+    }
+}
+
+/// Which of the 5 levels a temperature or humidity is.
+fn level(value: f64) -> usize {
+    LEVELS.iter().filter(|&&level| value >= level).count()
+}
+
+pub(crate) struct Climate {
+    temperature: OpenSimplex,
+    humidity: OpenSimplex,
+}
+
+impl Climate {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            temperature: OpenSimplex::new(seed.wrapping_add(100) as u32),
+            humidity: OpenSimplex::new(seed.wrapping_add(101) as u32),
+        }
+    }
+
+    pub(crate) fn sample(&self, x: i64, z: i64, noise: &NoiseGenerator) -> ClimatePoint {
+        let (x, z) = (x as f64, z as f64);
+        ClimatePoint {
+            temperature: self.temperature.get([x / CLIMATE_SCALE, z / CLIMATE_SCALE]),
+            humidity: self.humidity.get([x / CLIMATE_SCALE, z / CLIMATE_SCALE]),
+            continentalness: noise.get_noise(x, z),
+        }
+    }
+
+    /// Sets the biomes of an overworld chunk from the climate in the middle of each 4x4 column
+    /// of biome cells.
+    pub(crate) fn apply_biomes(&self, chunk: &mut Chunk, noise: &NoiseGenerator) {
+        let mut cells = [""; 64];
+        for cell_x in 0..4 {
+            for cell_z in 0..4 {
+                let x = i64::from(chunk.x) * 16 + cell_x as i64 * 4 + 2;
+                let z = i64::from(chunk.z) * 16 + cell_z as i64 * 4 + 2;
+                let biome = self.sample(x, z, noise).biome();
+                for cell_y in 0..4 {
+                    cells[(cell_y << 4) | (cell_z << 2) | cell_x] = biome;
+                }
+            }
+        }
+        let biomes = BiomeStates::from_cells(&cells);
+        for section in &mut chunk.sections {
+            section.biome_states = biomes.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_core::dimension::Dimension;
+
+    fn biome(temperature: f64, humidity: f64, continentalness: f64) -> &'static str {
+        ClimatePoint {
+            temperature,
+            humidity,
+            continentalness,
+        }
+        .biome()
+    }
+
+    #[test]
+    fn test_biome() {
+        assert_eq!(biome(0.0, 0.0, -0.5), "minecraft:deep_ocean");
+        assert_eq!(biome(-0.5, 0.0, -0.1), "minecraft:frozen_ocean");
+        assert_eq!(biome(0.0, 0.0, 0.01), "minecraft:beach");
+        assert_eq!(biome(0.0, 0.0, 0.5), "minecraft:forest");
+        assert_eq!(biome(-0.5, -0.5, 0.5), "minecraft:snowy_plains");
+        assert_eq!(biome(0.5, 0.5, 0.5), "minecraft:desert");
+        assert_eq!(biome(0.2, 0.5, 0.5), "minecraft:jungle");
+    }
+
+    #[test]
+    fn test_oceans_are_under_water() {
+        let noise = NoiseGenerator::new(0);
+        let climate = Climate::new(0);
+        for x in (-2048..2048).step_by(64) {
+            for z in (-2048..2048).step_by(64) {
+                let point = climate.sample(x, z, &noise);
+                // The plains terrain puts the surface at this height
+                let height = (point.continentalness * 64.0) as i32 + 64;
+                assert_eq!(point.biome().ends_with("ocean"), height < 64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_biomes() {
+        let noise = NoiseGenerator::new(0);
+        let mut chunk = Chunk::new(3, -7, Dimension::Overworld.name().to_string());
+        Climate::new(0).apply_biomes(&mut chunk, &noise);
+        let expected = Climate::new(0)
+            .sample(3 * 16 + 6, -7 * 16 + 10, &noise)
+            .biome();
+        assert_eq!(
+            chunk.get_biome(3 * 16 + 5, 100, -7 * 16 + 9).unwrap(),
+            expected
+        );
+        assert_eq!(
+            chunk.get_biome(3 * 16 + 5, -60, -7 * 16 + 9).unwrap(),
+            expected
+        );
+    }
+}
@@ -1,7 +1,10 @@
 mod biomes;
+mod climate;
 pub mod errors;
 
+use crate::climate::Climate;
 use crate::errors::WorldGenError;
+use ferrumc_core::dimension::Dimension;
 use ferrumc_world::chunk_format::Chunk;
 use noise::{Clamp, NoiseFn, OpenSimplex};
 
@@ -26,6 +29,7 @@ pub(crate) struct NoiseGenerator {
 pub struct WorldGenerator {
     _seed: u64,
     noise_generator: NoiseGenerator,
+    climate: Climate,
 }
 
 impl NoiseGenerator {
@@ -54,16 +58,31 @@ impl WorldGenerator {
         Self {
             _seed: seed,
             noise_generator: NoiseGenerator::new(seed),
+            climate: Climate::new(seed),
         }
     }
 
-    fn get_biome(&self, _x: i32, _z: i32) -> Box<dyn BiomeGenerator> {
-        // Implement biome selection here
-        Box::new(biomes::plains::PlainsBiome)
+    /// The generator for the terrain of a chunk. There's only plains terrain in the overworld,
+    /// whatever biome the climate picks for it.
+    fn get_biome(&self, _x: i32, _z: i32, dimension: Dimension) -> Box<dyn BiomeGenerator> {
+        match dimension {
+            Dimension::Overworld => Box::new(biomes::plains::PlainsBiome),
+            Dimension::Nether => Box::new(biomes::nether_wastes::NetherWastesBiome),
+            Dimension::End => Box::new(biomes::the_end::TheEndBiome),
+        }
     }
 
-    pub fn generate_chunk(&self, x: i32, z: i32) -> Result<Chunk, WorldGenError> {
-        let biome = self.get_biome(x, z);
-        biome.generate_chunk(x, z, &self.noise_generator)
+    pub fn generate_chunk(
+        &self,
+        x: i32,
+        z: i32,
+        dimension: Dimension,
+    ) -> Result<Chunk, WorldGenError> {
+        let biome = self.get_biome(x, z, dimension);
+        let mut chunk = biome.generate_chunk(x, z, &self.noise_generator)?;
+        if dimension == Dimension::Overworld {
+            self.climate.apply_biomes(&mut chunk, &self.noise_generator);
+        }
+        Ok(chunk)
     }
 }